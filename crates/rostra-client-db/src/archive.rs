@@ -0,0 +1,298 @@
+//! Portable archives of signed events and their content.
+//!
+//! An archive is a header followed by a stream of records, each a signed event
+//! with its content if stored locally, all bincode-encoded with
+//! [`STD_BINCODE_CONFIG`]. The stream ends with an explicit end marker, so a
+//! truncated archive is detected. Archives don't depend on the database
+//! layout: importing re-verifies every event and its content, and processes
+//! them like events received from peers.
+//!
+//! [`Database::merge_from`] imports all events of another database the same
+//! way, without going through an archive file.
+
+use std::io;
+use std::ops::Bound;
+
+use bincode::{Decode, Encode};
+use rostra_core::bincode::STD_BINCODE_CONFIG;
+use rostra_core::event::{
+    EventContentRaw, EventExt as _, SignedEvent, VerifiedEvent, VerifiedEventContent,
+};
+use rostra_core::id::RostraId;
+use rostra_core::{ShortEventId, Timestamp};
+use serde::Serialize;
+use snafu::{ResultExt as _, Snafu};
+use tracing::debug;
+
+use crate::{
+    Database, DbError, DbResult, LOG_TARGET, content_store, events, events_by_author,
+    events_content_state,
+};
+
+/// Magic bytes at the start of every archive
+pub const ARCHIVE_MAGIC: [u8; 8] = *b"rostraar";
+
+/// Current archive format version
+pub const ARCHIVE_VERSION: u32 = 0;
+
+/// Number of events read from the database per transaction when exporting
+const EXPORT_BATCH_SIZE: usize = 1024;
+
+#[derive(Encode, Decode)]
+struct ArchiveHeader {
+    magic: [u8; 8],
+    version: u32,
+}
+
+/// Key of [`events_by_author`], used as the cursor of batched reads
+type EventsByAuthorKey = (RostraId, Timestamp, ShortEventId);
+
+#[derive(Encode, Decode)]
+struct ArchiveRecord {
+    event: SignedEvent,
+    content: Option<EventContentRaw>,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ArchiveError {
+    #[snafu(display("Archive write error"))]
+    Encode { source: bincode::error::EncodeError },
+    #[snafu(display("Archive read error"))]
+    Decode { source: bincode::error::DecodeError },
+    #[snafu(display("Archive flush error"))]
+    Flush { source: io::Error },
+    #[snafu(display("Not a Rostra archive"))]
+    NotAnArchive,
+    #[snafu(display("Unsupported archive version {version}"))]
+    UnsupportedVersion { version: u32 },
+    #[snafu(display("Database error"))]
+    Db { source: DbError },
+}
+
+pub type ArchiveResult<T> = std::result::Result<T, ArchiveError>;
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct ArchiveExportStats {
+    /// Number of events written
+    pub events: u64,
+    /// Number of events written along with their content
+    pub contents: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize)]
+pub struct ArchiveImportStats {
+    /// Number of verified events processed
+    pub events: u64,
+    /// Number of verified contents processed along with their events
+    pub contents: u64,
+    /// Number of events with an invalid signature, skipped
+    pub invalid_events: u64,
+    /// Number of contents not matching their event, skipped
+    pub invalid_contents: u64,
+}
+
+impl Database {
+    /// Write all stored events of `authors` to an archive
+    ///
+    /// Events are written per author, in timestamp order. Content is included
+    /// whenever it is stored, so deleted and pruned content is left out.
+    pub async fn export_archive(
+        &self,
+        authors: impl IntoIterator<Item = RostraId>,
+        out: &mut impl io::Write,
+    ) -> ArchiveResult<ArchiveExportStats> {
+        self.export_archive_in_batches(authors, out, EXPORT_BATCH_SIZE)
+            .await
+    }
+
+    pub(crate) async fn export_archive_in_batches(
+        &self,
+        authors: impl IntoIterator<Item = RostraId>,
+        out: &mut impl io::Write,
+        batch_size: usize,
+    ) -> ArchiveResult<ArchiveExportStats> {
+        let mut stats = ArchiveExportStats::default();
+
+        let header = ArchiveHeader {
+            magic: ARCHIVE_MAGIC,
+            version: ARCHIVE_VERSION,
+        };
+        bincode::encode_into_std_write(header, out, STD_BINCODE_CONFIG).context(EncodeSnafu)?;
+
+        for author in authors {
+            let mut start = (author, Timestamp::ZERO, ShortEventId::ZERO);
+            let end = (author, Timestamp::MAX, ShortEventId::MAX);
+            loop {
+                let (records, next) = self
+                    .read_archive_records(Bound::Included(start), Bound::Included(end), batch_size)
+                    .await
+                    .context(DbSnafu)?;
+                for record in records {
+                    stats.events += 1;
+                    stats.contents += u64::from(record.content.is_some());
+                    bincode::encode_into_std_write(Some(record), out, STD_BINCODE_CONFIG)
+                        .context(EncodeSnafu)?;
+                }
+                let Some(next) = next else {
+                    break;
+                };
+                start = next;
+            }
+        }
+
+        bincode::encode_into_std_write(None::<ArchiveRecord>, out, STD_BINCODE_CONFIG)
+            .context(EncodeSnafu)?;
+        out.flush().context(FlushSnafu)?;
+        debug!(target: LOG_TARGET, events = stats.events, contents = stats.contents, "Exported archive");
+        Ok(stats)
+    }
+
+    /// Read up to `batch_size` archive records of events in the given range of
+    /// [`events_by_author`], returning the key to continue from, if any
+    async fn read_archive_records(
+        &self,
+        start: Bound<EventsByAuthorKey>,
+        end: Bound<EventsByAuthorKey>,
+        batch_size: usize,
+    ) -> DbResult<(Vec<ArchiveRecord>, Option<EventsByAuthorKey>)> {
+        self.read_with(|tx| {
+            let events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
+            let events_table = tx.open_table(&events::TABLE)?;
+            let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+            let content_store_table = tx.open_table(&content_store::TABLE)?;
+
+            let range = events_by_author_table.range((start.as_ref(), end.as_ref()))?;
+
+            let mut records = vec![];
+            for entry in range {
+                let key = entry?.0.value();
+                if batch_size <= records.len() {
+                    return Ok((records, Some(key)));
+                }
+                let (_, _, event_id) = key;
+                let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                    continue;
+                };
+                let content = Database::get_event_content_full_tx(
+                    event_id,
+                    event.content_hash(),
+                    &events_content_state_table,
+                    &content_store_table,
+                )?
+                .and_then(|result| result.content().cloned());
+                records.push(ArchiveRecord {
+                    event: event.signed,
+                    content,
+                });
+            }
+            Ok((records, None))
+        })
+        .await
+    }
+
+    /// Import all events of an archive, verifying each of them
+    ///
+    /// Events with an invalid signature are skipped, and so is content not
+    /// matching its event (the event itself is still imported). Importing is
+    /// idempotent, so an archive can be imported again or into a database
+    /// that already has some of its events. Records are imported as they are
+    /// read, so on error the ones before it stay imported.
+    pub async fn import_archive(
+        &self,
+        input: &mut impl io::Read,
+    ) -> ArchiveResult<ArchiveImportStats> {
+        let mut stats = ArchiveImportStats::default();
+
+        let header: ArchiveHeader =
+            bincode::decode_from_std_read(input, STD_BINCODE_CONFIG).context(DecodeSnafu)?;
+        if header.magic != ARCHIVE_MAGIC {
+            return NotAnArchiveSnafu.fail();
+        }
+        if header.version != ARCHIVE_VERSION {
+            return UnsupportedVersionSnafu {
+                version: header.version,
+            }
+            .fail();
+        }
+
+        while let Some(record) =
+            bincode::decode_from_std_read::<Option<ArchiveRecord>, _, _>(input, STD_BINCODE_CONFIG)
+                .context(DecodeSnafu)?
+        {
+            self.import_archive_record(record, &mut stats)
+                .await
+                .context(DbSnafu)?;
+        }
+
+        debug!(target: LOG_TARGET, events = stats.events, contents = stats.contents, "Imported archive");
+        Ok(stats)
+    }
+
+    /// Merge all events and content stored in `other` into this database
+    ///
+    /// Meant for syncing databases without network access, e.g. between
+    /// air-gapped machines or from a backup. Events are verified and processed
+    /// exactly like an imported archive (see [`Self::import_archive`]), so
+    /// `other` can be a database of any identity. Merging is idempotent.
+    pub async fn merge_from(&self, other: &Database) -> DbResult<ArchiveImportStats> {
+        self.merge_from_in_batches(other, EXPORT_BATCH_SIZE).await
+    }
+
+    pub(crate) async fn merge_from_in_batches(
+        &self,
+        other: &Database,
+        batch_size: usize,
+    ) -> DbResult<ArchiveImportStats> {
+        let mut stats = ArchiveImportStats::default();
+
+        let mut start = Bound::Unbounded;
+        loop {
+            let (records, next) = other
+                .read_archive_records(start, Bound::Unbounded, batch_size)
+                .await?;
+            for record in records {
+                self.import_archive_record(record, &mut stats).await?;
+            }
+            let Some(next) = next else {
+                break;
+            };
+            start = Bound::Included(next);
+        }
+
+        debug!(target: LOG_TARGET, events = stats.events, contents = stats.contents, "Merged database");
+        Ok(stats)
+    }
+
+    async fn import_archive_record(
+        &self,
+        ArchiveRecord { event, content }: ArchiveRecord,
+        stats: &mut ArchiveImportStats,
+    ) -> DbResult<()> {
+        let delegates = self.get_device_delegates(event.event.author).await;
+        let Ok(event) = VerifiedEvent::verify_received_delegated(event, &delegates) else {
+            debug!(target: LOG_TARGET, "Skipping archived event with invalid signature");
+            stats.invalid_events += 1;
+            return Ok(());
+        };
+        stats.events += 1;
+
+        // Content of empty events is implied, even if not archived
+        if content.is_none() && event.content_len() != 0 {
+            self.try_process_event(&event).await?;
+            return Ok(());
+        }
+        let has_content = content.is_some();
+        match VerifiedEventContent::verify(event, content) {
+            Ok(event_content) => {
+                stats.contents += u64::from(has_content);
+                self.try_process_event_with_content(&event_content).await?;
+            }
+            Err(_) => {
+                debug!(target: LOG_TARGET, event_id = %event.event_id, "Skipping archived content not matching its event");
+                stats.invalid_contents += 1;
+                self.try_process_event(&event).await?;
+            }
+        }
+        Ok(())
+    }
+}
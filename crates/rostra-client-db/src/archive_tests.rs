@@ -0,0 +1,149 @@
+use rostra_core::ShortEventId;
+use rostra_core::event::VerifiedEventContent;
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::archive::ArchiveError;
+use crate::tests::fixtures::{deletion, social_post};
+use crate::tests::{temp_db, temp_db_rng};
+use crate::{Database, EventContentState};
+
+async fn post_bodies(db: &Database) -> Vec<String> {
+    let mut bodies: Vec<String> = db
+        .paginate_social_posts_rev(None, 100, |_| true)
+        .await
+        .0
+        .into_iter()
+        .filter_map(|post| post.content.djot_content)
+        .collect();
+    bodies.sort();
+    bodies
+}
+
+/// Test: an exported archive seeds a fresh database with the same events,
+/// content and deletions, and importing it again changes nothing.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn archive_round_trips_events_and_content() -> BoxedErrorResult<()> {
+    let author = RostraIdSecretKey::generate();
+    let other = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(author.id()).await?;
+
+    let first = social_post(author, 100, None, None, "First");
+    let second = social_post(author, 200, Some(first.event_id()), None, "Second");
+    let deleted = social_post(author, 300, Some(second.event_id()), None, "Deleted");
+    let delete = VerifiedEventContent::assume_verified(
+        deletion(author, 400, deleted.event_id(), deleted.event_id()),
+        None,
+    );
+    let others = social_post(other, 150, None, None, "Not exported");
+    for event in [&first, &second, &deleted, &delete, &others] {
+        db.process_event_with_content(event).await;
+    }
+
+    let mut archive = vec![];
+    // A small batch size exercises resuming the export between batches
+    let stats = db
+        .export_archive_in_batches([author.id()], &mut archive, 3)
+        .await?;
+    assert_eq!(stats.events, 4);
+    assert_eq!(stats.contents, 3);
+
+    let (_dir2, db2) = temp_db_rng().await?;
+    let stats = db2.import_archive(&mut archive.as_slice()).await?;
+    assert_eq!(stats.events, 4);
+    assert_eq!(stats.contents, 3);
+    assert_eq!(stats.invalid_events, 0);
+    assert_eq!(stats.invalid_contents, 0);
+
+    assert_eq!(post_bodies(&db2).await, vec!["First", "Second"]);
+    assert!(!db2.has_event(others.event_id()).await);
+    assert!(matches!(
+        db2.get_event_content_state(deleted.event_id()).await,
+        Some(EventContentState::Deleted { .. })
+    ));
+    let head: ShortEventId = delete.event_id().to_short();
+    assert_eq!(db2.get_heads(author.id()).await, [head].into());
+
+    db2.import_archive(&mut archive.as_slice()).await?;
+    assert_eq!(post_bodies(&db2).await, vec!["First", "Second"]);
+
+    Ok(())
+}
+
+/// Test: importing rejects non-archives and truncated archives, and skips
+/// content not matching its event.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn archive_import_verifies_its_input() -> BoxedErrorResult<()> {
+    let author = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(author.id()).await?;
+    let post = social_post(author, 100, None, None, "Hello");
+    db.process_event_with_content(&post).await;
+
+    let mut archive = vec![];
+    db.export_archive([author.id()], &mut archive).await?;
+
+    let (_dir2, db2) = temp_db_rng().await?;
+    assert!(matches!(
+        db2.import_archive(&mut &b"not an archive at all"[..]).await,
+        Err(ArchiveError::NotAnArchive)
+    ));
+
+    // Flip a bit of the content, which is right before the end marker
+    let mut tampered = archive.clone();
+    let content_byte = tampered.len() - 2;
+    tampered[content_byte] ^= 1;
+    let stats = db2.import_archive(&mut tampered.as_slice()).await?;
+    assert_eq!(stats.events, 1);
+    assert_eq!(stats.contents, 0);
+    assert_eq!(stats.invalid_contents, 1);
+    assert!(post_bodies(&db2).await.is_empty());
+
+    // Records before the truncation are still imported
+    assert!(matches!(
+        db2.import_archive(&mut &archive[..archive.len() - 1]).await,
+        Err(ArchiveError::Decode { .. })
+    ));
+    assert_eq!(post_bodies(&db2).await, vec!["Hello"]);
+
+    Ok(())
+}
+
+/// Test: merging two databases in both directions leaves both with all events
+/// and content, and a single head.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn merge_syncs_two_databases() -> BoxedErrorResult<()> {
+    let author = RostraIdSecretKey::generate();
+    let other = RostraIdSecretKey::generate();
+    let (_dir_a, db_a) = temp_db(author.id()).await?;
+    let (_dir_b, db_b) = temp_db(author.id()).await?;
+
+    let first = social_post(author, 100, None, None, "First");
+    let second = social_post(author, 200, Some(first.event_id()), None, "Second");
+    let third = social_post(author, 300, Some(second.event_id()), None, "Third");
+    let others = social_post(other, 150, None, None, "Other");
+    for event in [&first, &second] {
+        db_a.process_event_with_content(event).await;
+    }
+    // `db_b` only has the newest event, so misses its parent
+    for event in [&third, &others] {
+        db_b.process_event_with_content(event).await;
+    }
+
+    // A small batch size exercises resuming the merge between batches
+    let stats = db_a.merge_from_in_batches(&db_b, 1).await?;
+    assert_eq!(stats.events, 2);
+    assert_eq!(stats.contents, 2);
+    let stats = db_b.merge_from(&db_a).await?;
+    assert_eq!(stats.events, 4);
+
+    let head: ShortEventId = third.event_id().to_short();
+    for db in [&db_a, &db_b] {
+        assert_eq!(
+            post_bodies(db).await,
+            vec!["First", "Other", "Second", "Third"]
+        );
+        assert_eq!(db.get_heads(author.id()).await, [head].into());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! Identities blocked by the local identity.
+//!
+//! Only blocks published by the local identity are tracked, in
+//! [`crate::ids_self_blocks`]. They are excluded from the [`crate::WotData`],
+//! and the timelines skip their posts.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use rostra_core::event::content_kind;
+use rostra_core::id::RostraId;
+
+use crate::event_order::EventOrder;
+use crate::ids::IdsSelfBlockRecord;
+use crate::{Database, DbResult, WriteTransactionCtx, ids_followees, ids_self_blocks};
+
+impl Database {
+    pub(crate) fn insert_self_block_tx(
+        &self,
+        event_order: EventOrder,
+        content: &content_kind::Block,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+        let updated = Database::insert_latest_value_tx(
+            event_order.timestamp(),
+            &content.target,
+            IdsSelfBlockRecord {
+                event_id: event_order.event_id(),
+                blocked: content.blocked,
+            },
+            &mut ids_self_blocks_table,
+        )?;
+
+        if updated && tx.commit_hooks_enabled() {
+            let ids_followees_table = tx.open_table(&ids_followees::TABLE)?;
+            let self_followees = Database::read_followees_tx(self.self_id, &ids_followees_table)?;
+            let self_wot = Arc::new(Database::compute_wot_tx(
+                self.self_id,
+                &self_followees,
+                Database::read_self_blocks_tx(&ids_self_blocks_table)?,
+                &ids_followees_table,
+            )?);
+            let wot_sender = self.self_wot_updated.clone();
+            tx.on_commit(move || {
+                wot_sender.send_replace(self_wot);
+            });
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read_self_blocks_tx(
+        ids_self_blocks_table: &impl ids_self_blocks::ReadableTable,
+    ) -> DbResult<HashSet<RostraId>> {
+        let mut blocked = HashSet::new();
+        for entry in ids_self_blocks_table.range(..)? {
+            let (k, v) = entry?;
+            if v.value().inner.blocked {
+                blocked.insert(k.value());
+            }
+        }
+        Ok(blocked)
+    }
+
+    pub(crate) fn is_self_blocked_tx(
+        id: RostraId,
+        ids_self_blocks_table: &impl ids_self_blocks::ReadableTable,
+    ) -> DbResult<bool> {
+        Ok(ids_self_blocks_table
+            .get(&id)?
+            .is_some_and(|record| record.value().inner.blocked))
+    }
+
+    /// Identities currently blocked by the local identity
+    pub async fn get_self_blocks(&self) -> HashSet<RostraId> {
+        self.read_with(|tx| Self::read_self_blocks_tx(&tx.open_table(&ids_self_blocks::TABLE)?))
+            .await
+            .expect("Storage error")
+    }
+
+    /// Is `id` currently blocked by the local identity
+    pub async fn is_self_blocked(&self, id: RostraId) -> bool {
+        self.read_with(|tx| Self::is_self_blocked_tx(id, &tx.open_table(&ids_self_blocks::TABLE)?))
+            .await
+            .expect("Storage error")
+    }
+}
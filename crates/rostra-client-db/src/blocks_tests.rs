@@ -0,0 +1,180 @@
+use std::collections::{BTreeSet, HashSet};
+
+use rostra_core::event::content_kind::{self, EventContentKind as _};
+use rostra_core::event::{Event, EventContentRaw, EventKind, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraId, RostraIdSecretKey, ToShort as _};
+use rostra_core::{ExternalEventId, ShortEventId};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::tests::temp_db;
+
+fn event_with_content(
+    secret: RostraIdSecretKey,
+    kind: EventKind,
+    timestamp: i64,
+    parent_marker: u8,
+    content: EventContentRaw,
+) -> VerifiedEventContent {
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(kind)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(ShortEventId::from_bytes([parent_marker; 16]))
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn follow(
+    secret: RostraIdSecretKey,
+    followee: RostraId,
+    timestamp: i64,
+    parent_marker: u8,
+) -> VerifiedEventContent {
+    let content = content_kind::Follow {
+        followee,
+        persona: None,
+        selector: None,
+        persona_tags_selector: Some(Default::default()),
+    }
+    .serialize_cbor()
+    .expect("valid follow");
+    event_with_content(secret, EventKind::FOLLOW, timestamp, parent_marker, content)
+}
+
+fn block(
+    secret: RostraIdSecretKey,
+    target: RostraId,
+    blocked: bool,
+    timestamp: i64,
+    parent_marker: u8,
+) -> VerifiedEventContent {
+    let content = content_kind::Block { target, blocked }
+        .serialize_cbor()
+        .expect("valid block");
+    event_with_content(secret, EventKind::BLOCK, timestamp, parent_marker, content)
+}
+
+fn post(
+    secret: RostraIdSecretKey,
+    reply_to: Option<ExternalEventId>,
+    timestamp: i64,
+    parent_marker: u8,
+) -> VerifiedEventContent {
+    let content = content_kind::SocialPost::new("Hello".to_owned(), reply_to, BTreeSet::new())
+        .serialize_cbor()
+        .expect("valid post");
+    event_with_content(
+        secret,
+        EventKind::SOCIAL_POST,
+        timestamp,
+        parent_marker,
+        content,
+    )
+}
+
+async fn timeline(db: &Database) -> Vec<ShortEventId> {
+    db.paginate_social_posts_rev(None, 100, |_| true)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+async fn comments(db: &Database, post_id: ShortEventId) -> Vec<ShortEventId> {
+    db.paginate_social_post_comments_rev(post_id, None, 100)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+/// Test: blocked identities and their followees leave the web of trust, and
+/// the latest block event of a target wins regardless of delivery order.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn blocks_are_excluded_from_wot() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let carol = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+    let wot = db.self_wot_subscribe();
+
+    for event in [
+        follow(own, alice.id(), 10, 1),
+        follow(alice, carol.id(), 10, 2),
+    ] {
+        db.process_event_with_content(&event).await;
+    }
+    assert!(wot.snapshot().contains(carol.id(), own.id()));
+
+    db.process_event_with_content(&block(own, carol.id(), true, 20, 3))
+        .await;
+    let snapshot = wot.snapshot();
+    assert!(!snapshot.contains(carol.id(), own.id()));
+    assert!(snapshot.contains(alice.id(), own.id()));
+    assert!(snapshot.is_blocked(carol.id()));
+
+    // A newer unblock wins over an older block delivered later
+    db.process_event_with_content(&block(own, alice.id(), false, 40, 4))
+        .await;
+    db.process_event_with_content(&block(own, alice.id(), true, 30, 5))
+        .await;
+    assert!(wot.snapshot().contains(alice.id(), own.id()));
+    assert!(!db.is_self_blocked(alice.id()).await);
+
+    db.process_event_with_content(&block(own, alice.id(), true, 50, 6))
+        .await;
+    let snapshot = wot.snapshot();
+    assert!(!snapshot.contains(alice.id(), own.id()));
+    assert!(snapshot.extended.is_empty());
+    assert_eq!(
+        db.get_self_blocks().await,
+        HashSet::from([alice.id(), carol.id()])
+    );
+
+    // Blocks published by others don't affect the local identity
+    db.process_event_with_content(&block(alice, carol.id(), false, 60, 7))
+        .await;
+    assert!(db.is_self_blocked(carol.id()).await);
+
+    Ok(())
+}
+
+/// Test: posts and replies of blocked identities are hidden from the timeline
+/// and comments until unblocked.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn blocked_posts_are_hidden() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let root = post(own, None, 10, 1);
+    let root_id = root.event_id().to_short();
+    let reply = post(alice, Some(ExternalEventId::new(own.id(), root_id)), 20, 2);
+    let reply_id = reply.event_id().to_short();
+    for event in [&root, &reply] {
+        db.process_event_with_content(event).await;
+    }
+    assert_eq!(timeline(&db).await, vec![reply_id, root_id]);
+    assert_eq!(comments(&db, root_id).await, vec![reply_id]);
+
+    db.process_event_with_content(&block(own, alice.id(), true, 30, 3))
+        .await;
+    assert_eq!(timeline(&db).await, vec![root_id]);
+    assert!(comments(&db, root_id).await.is_empty());
+    let hi = content_kind::SocialPost::new("Hi".to_owned(), None, BTreeSet::new());
+    assert!(db.is_social_post_hidden(alice.id(), &hi).await);
+    assert!(!db.is_social_post_hidden(own.id(), &hi).await);
+
+    db.process_event_with_content(&block(own, alice.id(), false, 40, 4))
+        .await;
+    assert_eq!(timeline(&db).await, vec![reply_id, root_id]);
+    assert_eq!(comments(&db, root_id).await, vec![reply_id]);
+
+    Ok(())
+}
@@ -0,0 +1,271 @@
+//! Local content filters of the local identity.
+//!
+//! Filters are a local preference, never published as events. They are kept
+//! in the [`local_content_filters`] extension table and applied by the
+//! timeline, comment, reaction, repost, news and search queries, so muted content
+//! never leaves the database. Posts of the local identity are never muted.
+
+use std::collections::BTreeSet;
+
+use bincode::{Decode, Encode};
+use rostra_core::event::{PersonaTag, SocialPost};
+use rostra_core::id::RostraId;
+use serde::{Deserialize, Serialize};
+use snafu::{ResultExt as _, Snafu};
+use tracing::debug;
+
+use crate::{
+    Database, DbResult, ExtensionReadTransaction, ExtensionWriteTransaction, LOG_TARGET,
+    ReadTransaction, ids_self_blocks,
+};
+
+/// User-configured local content filters
+#[derive(Encode, Decode, Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentFilters {
+    /// Words and phrases muted anywhere in the post text, case-insensitive
+    #[serde(default)]
+    pub muted_words: Vec<String>,
+    /// Regular expressions (`regex-lite` syntax) muted in the post text
+    #[serde(default)]
+    pub muted_patterns: Vec<String>,
+    /// Posts carrying any of these persona tags are muted
+    #[serde(default)]
+    pub muted_persona_tags: BTreeSet<PersonaTag>,
+    /// Posts linking to these domains, or their subdomains, are muted
+    #[serde(default)]
+    pub muted_domains: Vec<String>,
+    /// Hide all reactions
+    #[serde(default)]
+    pub hide_reactions: bool,
+    /// Hide all reposts
+    #[serde(default)]
+    pub hide_reposts: bool,
+}
+
+#[derive(Debug, Snafu)]
+pub enum ContentFiltersError {
+    #[snafu(display("Invalid muted pattern `{pattern}`: {source}"))]
+    InvalidPattern {
+        pattern: String,
+        source: regex_lite::Error,
+    },
+}
+
+impl ContentFilters {
+    /// Check that all the muted patterns compile
+    pub fn validate(&self) -> Result<(), ContentFiltersError> {
+        for pattern in &self.muted_patterns {
+            regex_lite::Regex::new(pattern).context(InvalidPatternSnafu { pattern })?;
+        }
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Normalize user input: trim and lowercase words and domains, and drop
+    /// empty and duplicate entries
+    pub fn normalized(self) -> Self {
+        fn normalize(items: Vec<String>, f: impl Fn(&str) -> String) -> Vec<String> {
+            let mut seen = BTreeSet::new();
+            items
+                .iter()
+                .map(|item| f(item.trim()))
+                .filter(|item| !item.is_empty() && seen.insert(item.clone()))
+                .collect()
+        }
+        Self {
+            muted_words: normalize(self.muted_words, str::to_lowercase),
+            muted_patterns: normalize(self.muted_patterns, str::to_owned),
+            muted_persona_tags: self.muted_persona_tags,
+            muted_domains: normalize(self.muted_domains, |domain| {
+                domain
+                    .trim_start_matches("*.")
+                    .trim_matches('.')
+                    .to_lowercase()
+            }),
+            hide_reactions: self.hide_reactions,
+            hide_reposts: self.hide_reposts,
+        }
+    }
+}
+
+crate::define_extension_table!(
+    /// Local content filters, under the unit key
+    local_content_filters, "rostra/local_content_filters": () => ContentFilters
+);
+
+/// [`ContentFilters`] prepared for matching posts
+pub(crate) struct ContentFilterMatcher {
+    self_id: RostraId,
+    filters: ContentFilters,
+    patterns: Vec<regex_lite::Regex>,
+}
+
+impl ContentFilterMatcher {
+    pub(crate) fn new(self_id: RostraId, filters: ContentFilters) -> Self {
+        let patterns = filters
+            .muted_patterns
+            .iter()
+            .filter_map(|pattern| match regex_lite::Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(err) => {
+                    debug!(target: LOG_TARGET, %pattern, %err, "Ignoring invalid muted pattern");
+                    None
+                }
+            })
+            .collect();
+        Self {
+            self_id,
+            filters,
+            patterns,
+        }
+    }
+
+    /// Is `post` of `author` hidden by the filters
+    pub(crate) fn is_muted(&self, author: RostraId, post: &SocialPost) -> bool {
+        if author == self.self_id || self.filters.is_empty() {
+            return false;
+        }
+        if post.reaction.is_some() && self.filters.hide_reactions {
+            return true;
+        }
+        if !self.filters.muted_persona_tags.is_empty()
+            && post
+                .persona_tags()
+                .iter()
+                .any(|tag| self.filters.muted_persona_tags.contains(tag))
+        {
+            return true;
+        }
+
+        let texts = [
+            post.djot_content.as_deref(),
+            post.title.as_deref(),
+            post.reaction.as_deref(),
+        ];
+        for text in texts.into_iter().flatten() {
+            if self.is_text_muted(text) {
+                return true;
+            }
+        }
+
+        if !self.filters.muted_domains.is_empty() {
+            let hosts = post
+                .url
+                .iter()
+                .filter_map(|url| url.host_str().map(str::to_owned))
+                .chain(
+                    post.djot_content
+                        .as_deref()
+                        .into_iter()
+                        .flat_map(link_hosts),
+                );
+            for host in hosts {
+                if self.is_domain_muted(&host) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Is a repost by `author`, with an optional quote `comment`, hidden by the
+    /// filters
+    ///
+    /// The reposted post itself is checked separately with [`Self::is_muted`].
+    pub(crate) fn is_repost_muted(&self, author: RostraId, comment: Option<&str>) -> bool {
+        if author == self.self_id {
+            return false;
+        }
+        self.filters.hide_reposts || comment.is_some_and(|comment| self.is_text_muted(comment))
+    }
+
+    fn is_text_muted(&self, text: &str) -> bool {
+        if !self.filters.muted_words.is_empty() {
+            let text = text.to_lowercase();
+            if self
+                .filters
+                .muted_words
+                .iter()
+                .any(|word| text.contains(word.as_str()))
+            {
+                return true;
+            }
+        }
+        self.patterns.iter().any(|regex| regex.is_match(text))
+    }
+
+    fn is_domain_muted(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.filters.muted_domains.iter().any(|domain| {
+            host == *domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+}
+
+/// Hosts of the http(s) links in a djot text
+fn link_hosts(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split_whitespace().filter_map(|word| {
+        let start = word.find("https://").or_else(|| word.find("http://"))?;
+        let link = word[start..].trim_end_matches([')', ']', '>', '.', ',', '"', '\'', '!', '?']);
+        url::Url::parse(link).ok()?.host_str().map(str::to_owned)
+    })
+}
+
+impl Database {
+    pub(crate) fn read_content_filter_matcher_tx(
+        &self,
+        tx: &ReadTransaction,
+    ) -> DbResult<ContentFilterMatcher> {
+        let filters = Self::read_content_filters_tx(&ExtensionReadTransaction::new(tx))?;
+        Ok(ContentFilterMatcher::new(self.self_id, filters))
+    }
+
+    fn read_content_filters_tx(tx: &ExtensionReadTransaction<'_>) -> DbResult<ContentFilters> {
+        Ok(tx
+            .open_table(&local_content_filters::TABLE)?
+            .get(&())?
+            .map(|g| g.value())
+            .unwrap_or_default())
+    }
+
+    /// Current local content filters
+    pub async fn get_content_filters(&self) -> ContentFilters {
+        self.extension_read(Self::read_content_filters_tx)
+            .await
+            .expect("Storage error")
+    }
+
+    /// Is `post` of `author` hidden, by a block of `author` or by the content
+    /// filters
+    ///
+    /// The post queries apply the same checks, this is for posts handled as
+    /// they are received.
+    pub async fn is_social_post_hidden(&self, author: RostraId, post: &SocialPost) -> bool {
+        self.read_with(|tx| {
+            if Self::is_self_blocked_tx(author, &tx.open_table(&ids_self_blocks::TABLE)?)? {
+                return Ok(true);
+            }
+            Ok(self
+                .read_content_filter_matcher_tx(tx)?
+                .is_muted(author, post))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Replace the local content filters
+    pub async fn set_content_filters(&self, filters: ContentFilters) -> DbResult<()> {
+        self.extension_write(|tx: &ExtensionWriteTransaction<'_>| {
+            tx.open_table(&local_content_filters::TABLE)?
+                .insert(&(), &filters)?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::BTreeSet;
+
+use rostra_core::event::content_kind::{EventContentKind as _, SocialPost};
+use rostra_core::event::{Event, EventKind, PersonaTag, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_core::{ExternalEventId, ShortEventId};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::content_filters::ContentFilters;
+use crate::tests::temp_db;
+
+fn post_event(
+    secret: RostraIdSecretKey,
+    post: &SocialPost,
+    timestamp: i64,
+    parent_marker: u8,
+) -> VerifiedEventContent {
+    let content = post.serialize_cbor().expect("valid post");
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::SOCIAL_POST)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(ShortEventId::from_bytes([parent_marker; 16]))
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn text_post(text: &str, reply_to: Option<ExternalEventId>) -> SocialPost {
+    SocialPost::new(text.to_owned(), reply_to, BTreeSet::new())
+}
+
+async fn timeline(db: &Database) -> Vec<ShortEventId> {
+    db.paginate_social_posts_rev(None, 100, |_| true)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+/// Test: muted words, patterns, domains and persona tags hide posts of others
+/// from the timeline, but never posts of the local identity.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn muted_posts_are_hidden_from_timeline() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let spoiler = post_event(alice, &text_post("Big SPOILERS ahead", None), 10, 1);
+    let pattern = post_event(alice, &text_post("Episode 42 was great", None), 20, 2);
+    let link = post_event(
+        alice,
+        &text_post("Read [this](https://news.example.com/a).", None),
+        30,
+        3,
+    );
+    let news = post_event(
+        alice,
+        &text_post("", None).with_news_fields(
+            Some("https://example.com/b".parse()?),
+            Some("Headline".to_owned()),
+        ),
+        40,
+        4,
+    );
+    let tagged = post_event(
+        alice,
+        &SocialPost::new(
+            "Work stuff".to_owned(),
+            None,
+            BTreeSet::from([PersonaTag::professional()]),
+        ),
+        50,
+        5,
+    );
+    let plain = post_event(alice, &text_post("Hello", None), 60, 6);
+    let own_spoiler = post_event(own, &text_post("My own spoiler", None), 70, 7);
+    let events = [
+        &spoiler,
+        &pattern,
+        &link,
+        &news,
+        &tagged,
+        &plain,
+        &own_spoiler,
+    ];
+    for event in events {
+        db.process_event_with_content(event).await;
+    }
+    assert_eq!(timeline(&db).await.len(), events.len());
+
+    let filters = ContentFilters {
+        muted_words: vec![" Spoiler ".to_owned(), String::new()],
+        muted_patterns: vec![r"Episode \d+".to_owned()],
+        muted_persona_tags: BTreeSet::from([PersonaTag::professional()]),
+        muted_domains: vec!["*.Example.com".to_owned()],
+        hide_reactions: false,
+        hide_reposts: false,
+    }
+    .normalized();
+    filters.validate()?;
+    assert_eq!(filters.muted_words, vec!["spoiler".to_owned()]);
+    db.set_content_filters(filters.clone()).await?;
+    assert_eq!(db.get_content_filters().await, filters);
+    assert!(
+        db.is_social_post_hidden(alice.id(), &text_post("Big SPOILERS ahead", None))
+            .await
+    );
+    assert!(
+        !db.is_social_post_hidden(alice.id(), &text_post("Hello", None))
+            .await
+    );
+    assert!(
+        !db.is_social_post_hidden(own.id(), &text_post("My own spoiler", None))
+            .await
+    );
+
+    assert_eq!(
+        timeline(&db).await,
+        vec![
+            own_spoiler.event_id().to_short(),
+            plain.event_id().to_short()
+        ]
+    );
+
+    db.set_content_filters(ContentFilters::default()).await?;
+    assert_eq!(timeline(&db).await.len(), events.len());
+
+    Ok(())
+}
+
+/// Test: hidden reactions and muted replies are dropped from the comments and
+/// reactions of a post.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn muted_replies_and_reactions_are_hidden() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let root = post_event(own, &text_post("Root", None), 10, 1);
+    let root_id = root.event_id().to_short();
+    let reply_to = Some(ExternalEventId::new(own.id(), root_id));
+    let reply = post_event(alice, &text_post("A reply with spoilers", reply_to), 20, 2);
+    let reaction = post_event(alice, &text_post("👍", reply_to), 30, 3);
+    for event in [&root, &reply, &reaction] {
+        db.process_event_with_content(event).await;
+    }
+    assert_eq!(
+        db.paginate_social_post_comments_rev(root_id, None, 100)
+            .await
+            .0
+            .len(),
+        1
+    );
+    assert_eq!(
+        db.paginate_social_post_reactions_rev(root_id, None, 100)
+            .await
+            .0
+            .len(),
+        1
+    );
+
+    db.set_content_filters(ContentFilters {
+        muted_words: vec!["spoilers".to_owned()],
+        hide_reactions: true,
+        ..Default::default()
+    })
+    .await?;
+    assert!(
+        db.paginate_social_post_comments_rev(root_id, None, 100)
+            .await
+            .0
+            .is_empty()
+    );
+    assert!(
+        db.paginate_social_post_reactions_rev(root_id, None, 100)
+            .await
+            .0
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+/// Test: invalid patterns are rejected by validation.
+#[test]
+fn invalid_pattern_fails_validation() {
+    let filters = ContentFilters {
+        muted_patterns: vec!["(unclosed".to_owned()],
+        ..Default::default()
+    };
+    assert!(filters.validate().is_err());
+}
@@ -363,3 +363,47 @@ async fn public_content_ingestion_preserves_terminal_states() -> BoxedErrorResul
 
     Ok(())
 }
+
+/// Test: partially downloaded content resumes after the contiguous prefix of
+/// stored pieces, and the pieces are dropped when the content arrives.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn partial_content_pieces_are_resumed_and_cleared() -> BoxedErrorResult<()> {
+    let secret = RostraIdSecretKey::from_bytes([93; 32]);
+    let author = secret.id();
+    let content = verified_content(
+        secret,
+        EventKind::SOCIAL_MEDIA_CHUNK,
+        content_kind::SocialMediaChunk {
+            data: (0..=255).collect(),
+        }
+        .serialize_cbor()?,
+        None,
+    );
+    let event_id = content.event_id().to_short();
+    let bytes = content
+        .content
+        .as_ref()
+        .expect("content must be present")
+        .as_slice()
+        .to_vec();
+    let (_dir, db) = crate::tests::temp_db(author).await?;
+    db.process_event(&content.event).await;
+
+    assert!(db.get_event_content_partial(event_id).await.is_empty());
+
+    // A piece after a gap is not a part of the prefix
+    db.insert_event_content_partial(event_id, 0, bytes[..100].to_vec())
+        .await?;
+    db.insert_event_content_partial(event_id, 200, bytes[200..].to_vec())
+        .await?;
+    assert_eq!(db.get_event_content_partial(event_id).await, bytes[..100]);
+
+    db.insert_event_content_partial(event_id, 100, bytes[100..200].to_vec())
+        .await?;
+    assert_eq!(db.get_event_content_partial(event_id).await, bytes);
+
+    db.process_event_content(&content).await;
+    assert!(db.get_event_content_partial(event_id).await.is_empty());
+
+    Ok(())
+}
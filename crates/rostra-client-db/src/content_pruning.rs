@@ -0,0 +1,249 @@
+use std::ops::Bound;
+use std::time::Duration;
+
+use rostra_core::event::{EventExt as _, EventKind};
+use rostra_core::{ShortEventId, Timestamp};
+use tracing::debug;
+
+use crate::event::EventContentState;
+use crate::social::EventPaginationCursor;
+use crate::{
+    Database, DbResult, LOG_TARGET, content_rc, content_store, events, events_by_time,
+    events_content_missing, events_content_state, ids_data_usage, ids_followees, ids_self_blocks,
+    social_direct_messages, social_private_posts_by_time,
+};
+
+/// Local retention policy for event content.
+///
+/// Content pruning discards payloads only. Event envelopes stay in place, so
+/// DAG traversal and sync are unaffected, and a pruned payload is never
+/// scheduled for fetching again. Content-derived projections of already
+/// processed payloads are retained, except the full-text search index, which
+/// can't match text that is gone. Only [`Self::max_header_age`] removes
+/// envelopes as well.
+///
+/// Direct messages received by the local identity are never pruned. The
+/// default policy keeps the local identity's content and prunes nothing else.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentPruningPolicy {
+    /// Never prune content authored by the database owner.
+    pub keep_self: bool,
+    /// Prune content of events authored longer ago than this.
+    pub max_age: Option<Duration>,
+    /// Prune all content of identities further away in the web of trust.
+    ///
+    /// See [`crate::WotData::distance`].
+    pub max_wot_distance: Option<u8>,
+    /// Per-identity budget for current and missing payload bytes.
+    ///
+    /// Identities over budget have their oldest payloads pruned first.
+    pub max_content_size_per_id: Option<u64>,
+    /// Prune whole events authored longer ago than this, for identities that
+    /// are neither self nor direct followees.
+    ///
+    /// See [`Database::prune_event_headers`].
+    pub max_header_age: Option<Duration>,
+}
+
+impl Default for ContentPruningPolicy {
+    fn default() -> Self {
+        Self {
+            keep_self: true,
+            max_age: None,
+            max_wot_distance: None,
+            max_content_size_per_id: None,
+            max_header_age: None,
+        }
+    }
+}
+
+impl ContentPruningPolicy {
+    /// Returns true if this policy can never prune anything.
+    pub fn is_noop(&self) -> bool {
+        self.max_age.is_none()
+            && self.max_wot_distance.is_none()
+            && self.max_content_size_per_id.is_none()
+            && self.max_header_age.is_none()
+    }
+}
+
+/// Outcome of one [`Database::prune_content_batch`] call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContentPruningBatch {
+    /// Number of events whose content was moved to `Pruned`.
+    pub pruned_num: u64,
+    /// Payload bytes of the events moved to `Pruned`.
+    pub pruned_size: u64,
+    /// Bytes removed from `content_store` because no event references them
+    /// anymore.
+    pub reclaimed_size: u64,
+    /// Cursor to continue the pass from, or `None` when the pass is complete.
+    pub next: Option<EventPaginationCursor>,
+}
+
+impl Database {
+    /// Apply `policy` to up to `limit` events, oldest first.
+    ///
+    /// Scans `events_by_time` starting after `after` (or from the beginning),
+    /// moving content that the policy does not retain to `Pruned`. This
+    /// decrements `content_rc` and usage accounting exactly once per event,
+    /// removes pending fetches, and deletes `content_store` bytes whose
+    /// reference count reached zero. A full pass calls this repeatedly with
+    /// the returned cursor until it returns `next: None`.
+    ///
+    /// Because quota enforcement walks events in authored-time order, an
+    /// identity over its budget loses its oldest payloads first.
+    pub async fn prune_content_batch(
+        &self,
+        policy: &ContentPruningPolicy,
+        now: Timestamp,
+        after: Option<EventPaginationCursor>,
+        limit: usize,
+    ) -> DbResult<ContentPruningBatch> {
+        let age_cutoff = policy
+            .max_age
+            .map(|max_age| Timestamp::from(now.as_u64().saturating_sub(max_age.as_secs())));
+
+        self.write_with(|tx| {
+            let events_table = tx.open_table(&events::TABLE)?;
+            let events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+            let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+            let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
+            let mut content_store_table = tx.open_table(&content_store::TABLE)?;
+            let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
+            let mut ids_data_usage_table = tx.open_table(&ids_data_usage::TABLE)?;
+            let direct_messages_table = tx.open_table(&social_direct_messages::TABLE)?;
+            let mut private_posts_table = tx.open_table(&social_private_posts_by_time::TABLE)?;
+
+            let wot = if policy.max_wot_distance.is_some() {
+                let ids_followees_table = tx.open_table(&ids_followees::TABLE)?;
+                let followees = Database::read_followees_tx(self.self_id, &ids_followees_table)?;
+                Some(Database::compute_wot_tx(
+                    self.self_id,
+                    &followees,
+                    Database::read_self_blocks_tx(&tx.open_table(&ids_self_blocks::TABLE)?)?,
+                    &ids_followees_table,
+                )?)
+            } else {
+                None
+            };
+
+            let start = after.map_or(Bound::Unbounded, |cursor| {
+                Bound::Excluded((cursor.ts, cursor.event_id))
+            });
+            let keys = events_by_time_table
+                .range((start, Bound::Unbounded))?
+                .take(limit)
+                .map(|entry| entry.map(|(k, _)| k.value()))
+                .collect::<Result<Vec<(Timestamp, ShortEventId)>, _>>()?;
+
+            let mut batch = ContentPruningBatch {
+                next: (keys.len() == limit)
+                    .then(|| keys.last())
+                    .flatten()
+                    .map(|&(ts, event_id)| EventPaginationCursor { ts, event_id }),
+                ..Default::default()
+            };
+
+            for (ts, event_id) in keys {
+                let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                    continue;
+                };
+                let author = event.author();
+                let content_len = event.content_len();
+
+                if content_len == 0 || (policy.keep_self && author == self.self_id) {
+                    continue;
+                }
+
+                if event.kind() == EventKind::DIRECT_MESSAGE
+                    && direct_messages_table
+                        .get(&(author, ts, event_id))?
+                        .is_some()
+                {
+                    continue;
+                }
+
+                if matches!(
+                    Database::get_event_content_state_tx(event_id, &events_content_state_table)?,
+                    Some(
+                        EventContentState::Deleted { .. }
+                            | EventContentState::Pruned
+                            | EventContentState::Invalid
+                    )
+                ) {
+                    continue;
+                }
+
+                let outside_wot = match (policy.max_wot_distance, &wot) {
+                    (Some(max_distance), Some(wot)) => wot
+                        .distance(author, self.self_id)
+                        .is_none_or(|distance| max_distance < distance),
+                    _ => false,
+                };
+                let too_old = age_cutoff.is_some_and(|cutoff| ts < cutoff);
+                let over_quota = match policy.max_content_size_per_id {
+                    Some(max_size) => {
+                        let usage = Database::get_data_usage_tx(author, &ids_data_usage_table)?;
+                        max_size
+                            < usage
+                                .current_content_size
+                                .saturating_add(usage.missing_payload_size)
+                    }
+                    None => false,
+                };
+
+                if !(outside_wot || too_old || over_quota) {
+                    continue;
+                }
+
+                if event.kind() == EventKind::SOCIAL_POST {
+                    Database::unindex_pruned_social_post_tx(
+                        event_id,
+                        ts,
+                        &content_store_table,
+                        &event,
+                        tx,
+                    )?;
+                }
+                if event.kind() == EventKind::PRIVATE_SOCIAL_POST {
+                    private_posts_table.remove(&(ts, event_id))?;
+                }
+
+                let content_hash = event.content_hash();
+                if !Database::prune_event_content_tx(
+                    event_id,
+                    content_hash,
+                    &mut events_content_state_table,
+                    &mut content_rc_table,
+                    &mut events_content_missing_table,
+                    Some((author, content_len, &mut ids_data_usage_table)),
+                )? {
+                    continue;
+                }
+                batch.pruned_num += 1;
+                batch.pruned_size += u64::from(content_len);
+
+                if Database::get_content_rc_tx(content_hash, &content_rc_table)? == 0
+                    && content_store_table.remove(&content_hash)?.is_some()
+                {
+                    batch.reclaimed_size += u64::from(content_len);
+                }
+
+                debug!(
+                    target: LOG_TARGET,
+                    %event_id,
+                    %author,
+                    content_len,
+                    outside_wot,
+                    too_old,
+                    over_quota,
+                    "Pruned event content"
+                );
+            }
+
+            Ok(batch)
+        })
+        .await
+    }
+}
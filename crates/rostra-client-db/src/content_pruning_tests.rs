@@ -0,0 +1,231 @@
+use std::time::Duration;
+
+use rostra_core::event::{
+    Event, EventContentRaw, EventExt as _, EventKind, VerifiedEvent, VerifiedEventContent,
+};
+use rostra_core::id::{RostraId, RostraIdSecretKey, ToShort as _};
+use rostra_core::{ContentHash, ShortEventId, Timestamp};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::event::EventContentState;
+use crate::{
+    ContentPruningBatch, ContentPruningPolicy, Database, IdsDataUsageRecord, content_rc,
+    content_store, events_content_missing,
+};
+
+fn raw(secret: RostraIdSecretKey, bytes: Vec<u8>, secs: i64) -> VerifiedEventContent {
+    let content = EventContentRaw::new(bytes);
+    let signed = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::RAW)
+        .content(&content)
+        .timestamp(time::OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(secs))
+        .build()
+        .signed_by(secret);
+    let event =
+        VerifiedEvent::verify_signed(secret.id(), signed).expect("event signature must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn assert_usage_consistent(usage: IdsDataUsageRecord) {
+    assert_eq!(
+        usage.total_content_size,
+        usage.current_content_size
+            + usage.deleted_payload_size
+            + usage.pruned_payload_size
+            + usage.missing_payload_size
+            + usage.invalid_payload_size
+    );
+    assert_eq!(
+        usage.total_payload_num,
+        usage.current_payload_num
+            + usage.deleted_payload_num
+            + usage.pruned_payload_num
+            + usage.missing_payload_num
+            + usage.invalid_payload_num
+    );
+}
+
+async fn is_stored(db: &Database, content_hash: ContentHash) -> BoxedErrorResult<bool> {
+    Ok(db
+        .read_with(|tx| {
+            Ok(tx
+                .open_table(&content_store::TABLE)?
+                .get(&content_hash)?
+                .is_some())
+        })
+        .await?)
+}
+
+async fn content_rc(db: &Database, content_hash: ContentHash) -> BoxedErrorResult<u64> {
+    Ok(db
+        .read_with(|tx| {
+            Database::get_content_rc_tx(content_hash, &tx.open_table(&content_rc::TABLE)?)
+        })
+        .await?)
+}
+
+async fn prune_all(
+    db: &Database,
+    policy: &ContentPruningPolicy,
+    now: Timestamp,
+    limit: usize,
+) -> BoxedErrorResult<ContentPruningBatch> {
+    let mut total = ContentPruningBatch::default();
+    let mut after = None;
+    loop {
+        let batch = db.prune_content_batch(policy, now, after, limit).await?;
+        total.pruned_num += batch.pruned_num;
+        total.pruned_size += batch.pruned_size;
+        total.reclaimed_size += batch.reclaimed_size;
+        after = batch.next;
+        if after.is_none() {
+            return Ok(total);
+        }
+    }
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn quota_prunes_oldest_content_first_and_keeps_self() -> BoxedErrorResult<()> {
+    let self_secret = RostraIdSecretKey::from_bytes([1; 32]);
+    let other_secret = RostraIdSecretKey::from_bytes([2; 32]);
+    let self_id = self_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    let mut self_events = Vec::new();
+    let mut other_events = Vec::new();
+    for (i, secs) in [30, 10, 20].into_iter().enumerate() {
+        let i = u8::try_from(i)?;
+        let own = raw(self_secret, vec![i; 100], secs);
+        let other = raw(other_secret, vec![0x80 | i; 100], secs);
+        db.process_event_with_content(&own).await;
+        db.process_event_with_content(&other).await;
+        self_events.push(own);
+        other_events.push(other);
+    }
+
+    let policy = ContentPruningPolicy {
+        max_content_size_per_id: Some(150),
+        ..Default::default()
+    };
+    let batch = prune_all(&db, &policy, Timestamp::from(100), 2).await?;
+    assert_eq!(batch.pruned_num, 2);
+    assert_eq!(batch.pruned_size, 200);
+    assert_eq!(batch.reclaimed_size, 200);
+
+    for (event, expected) in other_events.iter().zip([
+        None,
+        Some(EventContentState::Pruned),
+        Some(EventContentState::Pruned),
+    ]) {
+        assert_eq!(db.get_event_content_state(event.event_id()).await, expected);
+        assert_eq!(
+            is_stored(&db, event.content_hash()).await?,
+            expected.is_none()
+        );
+    }
+    for event in &self_events {
+        assert_eq!(db.get_event_content_state(event.event_id()).await, None);
+        assert!(is_stored(&db, event.content_hash()).await?);
+    }
+
+    let other_usage = db.get_data_usage(other_secret.id()).await;
+    assert_usage_consistent(other_usage);
+    assert_eq!(other_usage.current_content_size, 100);
+    assert_eq!(other_usage.pruned_payload_size, 200);
+    assert_eq!(other_usage.pruned_payload_num, 2);
+    assert_eq!(db.get_data_usage(self_id).await.pruned_payload_num, 0);
+
+    assert_eq!(
+        prune_all(&db, &policy, Timestamp::from(100), 2).await?,
+        ContentPruningBatch::default()
+    );
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn wot_and_age_pruning_keep_shared_bytes_referenced() -> BoxedErrorResult<()> {
+    let self_secret = RostraIdSecretKey::from_bytes([3; 32]);
+    let other_secret = RostraIdSecretKey::from_bytes([4; 32]);
+    let self_id = self_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    let own = raw(self_secret, vec![7; 64], 10);
+    let shared = raw(other_secret, vec![7; 64], 500);
+    let missing = raw(other_secret, vec![8; 64], 600);
+    db.process_event_with_content(&own).await;
+    db.process_event_with_content(&shared).await;
+    db.process_event(&missing.event).await;
+    assert!(
+        db.is_event_content_missing(missing.event_id().to_short())
+            .await
+    );
+
+    let age_policy = ContentPruningPolicy {
+        keep_self: false,
+        max_age: Some(Duration::from_secs(100)),
+        ..Default::default()
+    };
+    let batch = prune_all(&db, &age_policy, Timestamp::from(200), 16).await?;
+    assert_eq!(batch.pruned_num, 1);
+    assert_eq!(batch.reclaimed_size, 0);
+    assert_eq!(
+        db.get_event_content_state(own.event_id()).await,
+        Some(EventContentState::Pruned)
+    );
+    assert_eq!(content_rc(&db, shared.content_hash()).await?, 1);
+    assert!(is_stored(&db, shared.content_hash()).await?);
+    assert!(db.get_event_content(shared.event_id()).await.is_some());
+
+    let wot_policy = ContentPruningPolicy {
+        max_wot_distance: Some(2),
+        ..Default::default()
+    };
+    let batch = prune_all(&db, &wot_policy, Timestamp::from(200), 16).await?;
+    assert_eq!(batch.pruned_num, 2);
+    assert_eq!(batch.reclaimed_size, 64);
+    assert_eq!(content_rc(&db, shared.content_hash()).await?, 0);
+    assert!(!is_stored(&db, shared.content_hash()).await?);
+    assert_eq!(
+        db.get_event_content_state(missing.event_id()).await,
+        Some(EventContentState::Pruned)
+    );
+    let queue: Vec<(Timestamp, ShortEventId)> = db
+        .read_with(|tx| {
+            Ok(tx
+                .open_table(&events_content_missing::TABLE)?
+                .range(..)?
+                .map(|entry| entry.map(|(key, _)| key.value()))
+                .collect::<Result<Vec<_>, _>>()?)
+        })
+        .await?;
+    assert!(queue.is_empty());
+
+    let other_usage = db.get_data_usage(other_secret.id()).await;
+    assert_usage_consistent(other_usage);
+    assert_eq!(other_usage.missing_payload_num, 0);
+    assert_eq!(other_usage.pruned_payload_num, 2);
+    assert_usage_consistent(db.get_data_usage(self_id).await);
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn default_policy_prunes_nothing() -> BoxedErrorResult<()> {
+    let secret = RostraIdSecretKey::from_bytes([5; 32]);
+    let author: RostraId = RostraIdSecretKey::from_bytes([6; 32]).id();
+    let (_dir, db) = crate::tests::temp_db(author).await?;
+    let event = raw(secret, vec![1; 32], 0);
+    db.process_event_with_content(&event).await;
+
+    let policy = ContentPruningPolicy::default();
+    assert!(policy.is_noop());
+    assert_eq!(
+        prune_all(&db, &policy, Timestamp::MAX, 1).await?,
+        ContentPruningBatch::default()
+    );
+    assert!(db.get_event_content(event.event_id()).await.is_some());
+
+    Ok(())
+}
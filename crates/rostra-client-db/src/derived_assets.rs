@@ -0,0 +1,72 @@
+//! Local cache of assets derived from event content.
+//!
+//! Resized image thumbnails are expensive to generate, so they are generated
+//! once, on first request, and kept in the [`local_derived_assets`] extension
+//! table. Entries are keyed by the event the source was published in, and a
+//! caller-defined variant (e.g. the thumbnail size). Assets of events whose
+//! content is no longer available (deleted, pruned, etc.) are never returned.
+
+use bincode::{Decode, Encode};
+use rostra_core::ShortEventId;
+
+use crate::{Database, DbResult, ExtensionReadTransaction, ExtensionWriteTransaction};
+use crate::{events, events_content_state};
+
+/// An asset derived from event content
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct DerivedAsset {
+    pub mime: String,
+    pub data: Vec<u8>,
+}
+
+crate::define_extension_table!(
+    /// Derived assets, by the source event id and the variant
+    local_derived_assets, "rostra/local_derived_assets": (ShortEventId, u32) => DerivedAsset
+);
+
+impl Database {
+    /// Cached `variant` of the asset derived from the content of `event_id`
+    ///
+    /// Returns `None` if there is none, or the source content is no longer
+    /// available.
+    pub async fn get_derived_asset(
+        &self,
+        event_id: impl Into<ShortEventId>,
+        variant: u32,
+    ) -> Option<DerivedAsset> {
+        let event_id = event_id.into();
+        self.read_with(|tx| {
+            if Database::get_event_tx(event_id, &tx.open_table(&events::TABLE)?)?.is_none()
+                || Database::get_event_content_state_tx(
+                    event_id,
+                    &tx.open_table(&events_content_state::TABLE)?,
+                )?
+                .is_some()
+            {
+                return Ok(None);
+            }
+            Ok(ExtensionReadTransaction::new(tx)
+                .open_table(&local_derived_assets::TABLE)?
+                .get(&(event_id, variant))?
+                .map(|g| g.value()))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Store `variant` of the asset derived from the content of `event_id`
+    pub async fn insert_derived_asset(
+        &self,
+        event_id: impl Into<ShortEventId>,
+        variant: u32,
+        asset: DerivedAsset,
+    ) -> DbResult<()> {
+        let event_id = event_id.into();
+        self.extension_write(|tx: &ExtensionWriteTransaction<'_>| {
+            tx.open_table(&local_derived_assets::TABLE)?
+                .insert(&(event_id, variant), &asset)?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,73 @@
+use rostra_core::EventId;
+use rostra_core::event::{Event, EventContentRaw, EventKind, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::RostraIdSecretKey;
+use rostra_util_error::BoxedErrorResult;
+
+use crate::derived_assets::DerivedAsset;
+use crate::tests::temp_db;
+
+fn media(secret: RostraIdSecretKey, timestamp: i64) -> VerifiedEventContent {
+    let content = EventContentRaw::new(b"image bytes".to_vec());
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::RAW)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn deletion(secret: RostraIdSecretKey, timestamp: i64, target: EventId) -> VerifiedEvent {
+    let content = EventContentRaw::new(vec![]);
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::RAW)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(target.into())
+        .delete(target.into())
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify")
+}
+
+/// Test: derived assets are cached per variant, and stop being served once
+/// the source content is deleted.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn derived_assets_follow_source_content() -> BoxedErrorResult<()> {
+    let secret = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(secret.id()).await?;
+
+    let source = media(secret, 10);
+    let small = DerivedAsset {
+        mime: "image/jpeg".to_owned(),
+        data: b"small".to_vec(),
+    };
+
+    // Nothing cached yet on a fresh database
+    db.process_event_with_content(&media(secret, 5)).await;
+    assert_eq!(
+        db.get_derived_asset(media(secret, 5).event_id(), 128).await,
+        None
+    );
+
+    // Not cached for unknown events
+    db.insert_derived_asset(source.event_id(), 128, small.clone())
+        .await?;
+    assert_eq!(db.get_derived_asset(source.event_id(), 128).await, None);
+
+    db.process_event_with_content(&source).await;
+    assert_eq!(
+        db.get_derived_asset(source.event_id(), 128).await,
+        Some(small)
+    );
+    assert_eq!(db.get_derived_asset(source.event_id(), 640).await, None);
+
+    db.process_event(&deletion(secret, 20, source.event_id()))
+        .await;
+    assert_eq!(db.get_derived_asset(source.event_id(), 128).await, None);
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+//! Device keys delegated to sign on behalf of identities.
+//!
+//! The latest [`content_kind::DeviceDelegation`] of every `(identity, device)`
+//! pair, signed by the identity's own key, is tracked in
+//! [`crate::ids_devices`]. Within the same second, a revocation wins over a
+//! delegation, whatever their event ids. Events signed by the active devices
+//! of their author are verified with [`Database::get_device_delegates`].
+
+use rostra_core::Timestamp;
+use rostra_core::event::{EventExt as _, SignedEventExt as _, VerifiedEvent, content_kind};
+use rostra_core::id::RostraId;
+use tracing::debug;
+
+use crate::event_order::EventOrder;
+use crate::ids::IdsDeviceRecord;
+use crate::{Database, DbResult, LOG_TARGET, Latest, WriteTransactionCtx, ids_devices};
+
+impl Database {
+    pub(crate) fn insert_device_delegation_tx(
+        event: &VerifiedEvent,
+        event_order: EventOrder,
+        content: &content_kind::DeviceDelegation,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let author = event.author();
+        // Devices can't delegate to further devices, nor revoke each other
+        if event.event.verify_signature(event.sig()).is_err() || content.device == author {
+            debug!(target: LOG_TARGET, %author, device = %content.device, "Ignoring device delegation not signed by its identity");
+            return Ok(());
+        }
+        let key = (author, content.device);
+        let ts = event_order.timestamp();
+        let record = IdsDeviceRecord {
+            event_id: event_order.event_id(),
+            revoked: content.revoked,
+            expires_at: content.expires_at,
+        };
+        let mut ids_devices_table = tx.open_table(&ids_devices::TABLE)?;
+        let existing = ids_devices_table.get(&key)?.map(|g| g.value());
+        if let Some(existing) = existing {
+            if existing.ts == ts && existing.inner.revoked != content.revoked {
+                if content.revoked {
+                    ids_devices_table.insert(&key, &Latest { ts, inner: record })?;
+                }
+                return Ok(());
+            }
+        }
+        Database::insert_latest_value_tx(ts, &key, record, &mut ids_devices_table)?;
+        Ok(())
+    }
+
+    pub(crate) fn read_device_delegates_tx(
+        id: RostraId,
+        ids_devices_table: &impl ids_devices::ReadableTable,
+    ) -> DbResult<Vec<RostraId>> {
+        let now = Timestamp::now();
+        let mut delegates = vec![];
+        for entry in ids_devices_table.range(&(id, RostraId::ZERO)..=&(id, RostraId::MAX))? {
+            let (k, v) = entry?;
+            if v.value().inner.is_active(now) {
+                delegates.push(k.value().1);
+            }
+        }
+        Ok(delegates)
+    }
+
+    /// Device keys currently delegated to sign on behalf of `id`
+    pub async fn get_device_delegates(&self, id: RostraId) -> Vec<RostraId> {
+        self.read_with(|tx| {
+            Self::read_device_delegates_tx(id, &tx.open_table(&ids_devices::TABLE)?)
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Is `device` currently delegated to sign on behalf of `id`
+    pub async fn is_device_delegate(&self, id: RostraId, device: RostraId) -> bool {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&ids_devices::TABLE)?
+                .get(&(id, device))?
+                .is_some_and(|record| record.value().inner.is_active(Timestamp::now())))
+        })
+        .await
+        .expect("Storage error")
+    }
+}
@@ -0,0 +1,177 @@
+use rostra_core::Timestamp;
+use rostra_core::event::content_kind;
+use rostra_core::event::{Event, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraId, RostraIdSecretKey};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::tests::temp_db_rng;
+
+fn delegation(
+    author: RostraId,
+    signer: RostraIdSecretKey,
+    device: RostraId,
+    revoked: bool,
+    timestamp: i64,
+) -> VerifiedEventContent {
+    delegation_event(
+        author,
+        signer,
+        content_kind::DeviceDelegation {
+            device,
+            revoked,
+            expires_at: None,
+        },
+        timestamp,
+    )
+}
+
+fn delegation_event(
+    author: RostraId,
+    signer: RostraIdSecretKey,
+    content: content_kind::DeviceDelegation,
+    timestamp: i64,
+) -> VerifiedEventContent {
+    let (event, content) = Event::builder(&content)
+        .author(author)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .build()
+        .expect("valid delegation");
+    let event =
+        VerifiedEvent::verify_signed_delegated(author, event.signed_by(signer), &[signer.id()])
+            .expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+/// Test: devices delegated by their identity are listed until revoked.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn device_delegations_can_be_revoked() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let root = RostraIdSecretKey::generate();
+    let laptop = RostraIdSecretKey::generate().id();
+    let phone = RostraIdSecretKey::generate().id();
+
+    db.process_event_with_content(&delegation(root.id(), root, laptop, false, 100))
+        .await;
+    db.process_event_with_content(&delegation(root.id(), root, phone, false, 100))
+        .await;
+    let mut delegates = db.get_device_delegates(root.id()).await;
+    delegates.sort();
+    let mut expected = vec![laptop, phone];
+    expected.sort();
+    assert_eq!(delegates, expected);
+
+    db.process_event_with_content(&delegation(root.id(), root, phone, true, 200))
+        .await;
+    // An older delegation doesn't lift a revocation
+    db.process_event_with_content(&delegation(root.id(), root, phone, false, 150))
+        .await;
+    assert_eq!(db.get_device_delegates(root.id()).await, vec![laptop]);
+    assert!(db.is_device_delegate(root.id(), laptop).await);
+    assert!(!db.is_device_delegate(root.id(), phone).await);
+
+    Ok(())
+}
+
+/// Test: a delegation stops counting once it expires.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn expired_delegations_are_not_delegates() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let root = RostraIdSecretKey::generate();
+    let expired = RostraIdSecretKey::generate().id();
+    let current = RostraIdSecretKey::generate().id();
+    let now = Timestamp::now();
+
+    for (device, expires_at) in [
+        (expired, Timestamp::from(now.as_u64() - 1)),
+        (current, now.saturating_add_secs(3600)),
+    ] {
+        db.process_event_with_content(&delegation_event(
+            root.id(),
+            root,
+            content_kind::DeviceDelegation {
+                device,
+                revoked: false,
+                expires_at: Some(expires_at),
+            },
+            100,
+        ))
+        .await;
+    }
+
+    assert_eq!(db.get_device_delegates(root.id()).await, vec![current]);
+    assert!(!db.is_device_delegate(root.id(), expired).await);
+    assert!(db.is_device_delegate(root.id(), current).await);
+
+    Ok(())
+}
+
+/// Test: a revocation wins over a delegation of the same second, whatever
+/// their event ids, and in any order.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn revocation_wins_within_the_same_second() -> BoxedErrorResult<()> {
+    let root = RostraIdSecretKey::generate();
+    let device = RostraIdSecretKey::generate().id();
+    let delegate = delegation(root.id(), root, device, false, 100);
+    let revoke = delegation(root.id(), root, device, true, 100);
+
+    for events in [[&delegate, &revoke], [&revoke, &delegate]] {
+        let (_dir, db) = temp_db_rng().await?;
+        for event in events {
+            db.process_event_with_content(event).await;
+        }
+        assert!(!db.is_device_delegate(root.id(), device).await);
+    }
+
+    Ok(())
+}
+
+/// Test: once revoked, events signed by the device earlier don't verify
+/// anymore, as their timestamps can't be trusted.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn revoked_device_events_are_rejected() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let root = RostraIdSecretKey::generate();
+    let device = RostraIdSecretKey::generate();
+
+    db.process_event_with_content(&delegation(root.id(), root, device.id(), false, 100))
+        .await;
+    let (event, _) = Event::builder(&content_kind::SocialPost::new(
+        "From the device".to_owned(),
+        None,
+        Default::default(),
+    ))
+    .author(root.id())
+    .timestamp(time::OffsetDateTime::from_unix_timestamp(150).expect("valid timestamp"))
+    .build()
+    .expect("valid post");
+    let signed = event.signed_by(device);
+    let delegates = db.get_device_delegates(root.id()).await;
+    assert!(VerifiedEvent::verify_signed_delegated(root.id(), signed, &delegates).is_ok());
+
+    db.process_event_with_content(&delegation(root.id(), root, device.id(), true, 200))
+        .await;
+    let delegates = db.get_device_delegates(root.id()).await;
+    assert!(VerifiedEvent::verify_signed_delegated(root.id(), signed, &delegates).is_err());
+
+    Ok(())
+}
+
+/// Test: delegations signed by a device, instead of the identity itself, are
+/// ignored.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn devices_cant_delegate_further() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let root = RostraIdSecretKey::generate();
+    let device = RostraIdSecretKey::generate();
+    let other = RostraIdSecretKey::generate().id();
+
+    db.process_event_with_content(&delegation(root.id(), root, device.id(), false, 100))
+        .await;
+    db.process_event_with_content(&delegation(root.id(), device, other, false, 200))
+        .await;
+    db.process_event_with_content(&delegation(root.id(), device, device.id(), true, 200))
+        .await;
+    assert_eq!(db.get_device_delegates(root.id()).await, vec![device.id()]);
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+//! Conversations of end-to-end encrypted direct messages.
+//!
+//! Only messages sent or received by the local identity are indexed, by
+//! counterparty, in [`crate::social_direct_messages`]. The database never sees
+//! the decryption key, so the records carry the still encrypted
+//! [`content_kind::DirectMessage`].
+
+use rostra_core::event::{EventExt as _, content_kind};
+use rostra_core::id::RostraId;
+use rostra_core::{ShortEventId, Timestamp};
+use tracing::debug;
+
+use crate::event::ContentStoreRecord;
+use crate::social::EventPaginationCursor;
+use crate::{
+    Database, DbResult, LOG_TARGET, WriteTransactionCtx, content_store, events,
+    events_content_state, social_direct_conversations, social_direct_messages,
+};
+
+/// A direct message in a conversation of the local identity
+#[derive(Clone, Debug)]
+pub struct DirectMessageRecord {
+    pub ts: Timestamp,
+    pub event_id: ShortEventId,
+    pub author: RostraId,
+    pub content: content_kind::DirectMessage,
+}
+
+/// A direct message conversation of the local identity
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DirectConversationRecord {
+    pub counterparty: RostraId,
+    /// Timestamp of the latest message
+    pub last_ts: Timestamp,
+    /// Latest message
+    pub last_event_id: ShortEventId,
+}
+
+impl Database {
+    pub(crate) fn insert_direct_message_tx(
+        counterparty: RostraId,
+        ts: Timestamp,
+        event_id: ShortEventId,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut messages_table = tx.open_table(&social_direct_messages::TABLE)?;
+        let mut conversations_table = tx.open_table(&social_direct_conversations::TABLE)?;
+
+        messages_table.insert(&(counterparty, ts, event_id), &())?;
+        let is_latest = conversations_table
+            .get(&counterparty)?
+            .map(|g| g.value())
+            .is_none_or(|latest| latest < (ts, event_id));
+        if is_latest {
+            conversations_table.insert(&counterparty, &(ts, event_id))?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_direct_message_tx(
+        counterparty: RostraId,
+        ts: Timestamp,
+        event_id: ShortEventId,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut messages_table = tx.open_table(&social_direct_messages::TABLE)?;
+        let mut conversations_table = tx.open_table(&social_direct_conversations::TABLE)?;
+
+        messages_table.remove(&(counterparty, ts, event_id))?;
+        let latest = messages_table
+            .range(
+                &(counterparty, Timestamp::ZERO, ShortEventId::ZERO)
+                    ..=&(counterparty, Timestamp::MAX, ShortEventId::MAX),
+            )?
+            .next_back()
+            .transpose()?
+            .map(|(k, _)| k.value());
+        match latest {
+            Some((_, ts, event_id)) => {
+                conversations_table.insert(&counterparty, &(ts, event_id))?;
+            }
+            None => {
+                conversations_table.remove(&counterparty)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// All direct message conversations, most recently active first
+    pub async fn get_direct_conversations(&self) -> Vec<DirectConversationRecord> {
+        self.read_with(|tx| {
+            let conversations_table = tx.open_table(&social_direct_conversations::TABLE)?;
+
+            let mut ret = conversations_table
+                .range(..)?
+                .map(|entry| {
+                    let (k, v) = entry?;
+                    let (last_ts, last_event_id) = v.value();
+                    Ok(DirectConversationRecord {
+                        counterparty: k.value(),
+                        last_ts,
+                        last_event_id,
+                    })
+                })
+                .collect::<DbResult<Vec<_>>>()?;
+            ret.sort_by_key(|record| std::cmp::Reverse((record.last_ts, record.last_event_id)));
+            Ok(ret)
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Paginate messages of the conversation with `counterparty`, newest first
+    ///
+    /// The returned cursor is the first message not returned yet.
+    pub async fn paginate_direct_messages_rev(
+        &self,
+        counterparty: RostraId,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+    ) -> (Vec<DirectMessageRecord>, Option<EventPaginationCursor>) {
+        self.read_with(|tx| {
+            let messages_table = tx.open_table(&social_direct_messages::TABLE)?;
+            let events_table = tx.open_table(&events::TABLE)?;
+            let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+            let content_store_table = tx.open_table(&content_store::TABLE)?;
+
+            let start = (counterparty, Timestamp::ZERO, ShortEventId::ZERO);
+            let end = match cursor {
+                Some(cursor) => (counterparty, cursor.ts, cursor.event_id),
+                None => (counterparty, Timestamp::MAX, ShortEventId::MAX),
+            };
+
+            let mut ret = vec![];
+            for entry in messages_table.range(&start..=&end)?.rev() {
+                let (_, ts, event_id) = entry?.0.value();
+                if limit <= ret.len() {
+                    return Ok((ret, Some(EventPaginationCursor { ts, event_id })));
+                }
+
+                let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                    continue;
+                };
+                if Database::get_event_content_state_tx(event_id, &events_content_state_table)?
+                    .is_some()
+                {
+                    continue;
+                }
+                let Some(ContentStoreRecord(content)) = content_store_table
+                    .get(&event.content_hash())?
+                    .map(|g| g.value())
+                else {
+                    continue;
+                };
+                let Ok(content) = content.deserialize_cbor::<content_kind::DirectMessage>() else {
+                    debug!(target: LOG_TARGET, %event_id, "Direct message content invalid");
+                    continue;
+                };
+
+                ret.push(DirectMessageRecord {
+                    ts,
+                    event_id,
+                    author: event.author(),
+                    content,
+                });
+            }
+
+            Ok((ret, None))
+        })
+        .await
+        .expect("Storage error")
+    }
+}
@@ -0,0 +1,171 @@
+use rostra_core::event::content_kind::{
+    self, DirectMessageBody, DirectMessageNonce, EventContentKind as _,
+};
+use rostra_core::event::{
+    Event, EventContentRaw, EventExt as _, EventKind, VerifiedEvent, VerifiedEventContent,
+};
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_core::{EventId, ShortEventId};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::direct_messages::DirectConversationRecord;
+use crate::tests::temp_db;
+
+fn direct_message(
+    secret: RostraIdSecretKey,
+    recipient: RostraIdSecretKey,
+    timestamp: i64,
+    parent_prev: Option<EventId>,
+    text: &str,
+) -> VerifiedEventContent {
+    let content = content_kind::DirectMessage::seal_with_nonce(
+        secret,
+        recipient.id(),
+        DirectMessageNonce::from_bytes([timestamp as u8; 24]),
+        &DirectMessageBody {
+            djot_content: text.to_owned(),
+        },
+    )
+    .expect("can seal")
+    .serialize_cbor()
+    .expect("direct message must serialize");
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::DIRECT_MESSAGE)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .content(&content)
+        .maybe_parent_prev(parent_prev.map(Into::into))
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn deletion(
+    secret: RostraIdSecretKey,
+    timestamp: i64,
+    parent: EventId,
+    target: EventId,
+) -> VerifiedEvent {
+    let content = EventContentRaw::new(vec![]);
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::DIRECT_MESSAGE)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(parent.into())
+        .delete(target.into())
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify")
+}
+
+async fn conversation(db: &Database, counterparty: RostraIdSecretKey) -> Vec<ShortEventId> {
+    db.paginate_direct_messages_rev(counterparty.id(), None, 100)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+/// Test: sent and received messages are indexed per counterparty, messages
+/// between other identities are not, and conversations are ordered by their
+/// latest message.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn direct_messages_are_indexed_per_counterparty() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let to_alice = direct_message(own, alice, 100, None, "Hi Alice");
+    let from_alice = direct_message(alice, own, 200, None, "Hi!");
+    let from_bob = direct_message(bob, own, 150, None, "Hey");
+    let bob_to_alice = direct_message(bob, alice, 300, Some(from_bob.event_id()), "Psst");
+    for msg in [&to_alice, &from_alice, &from_bob, &bob_to_alice] {
+        db.process_event_with_content(msg).await;
+    }
+
+    assert_eq!(
+        conversation(&db, alice).await,
+        vec![
+            from_alice.event_id().to_short(),
+            to_alice.event_id().to_short()
+        ]
+    );
+    assert_eq!(
+        conversation(&db, bob).await,
+        vec![from_bob.event_id().to_short()]
+    );
+    assert_eq!(
+        db.get_direct_conversations().await,
+        vec![
+            DirectConversationRecord {
+                counterparty: alice.id(),
+                last_ts: from_alice.timestamp(),
+                last_event_id: from_alice.event_id().to_short(),
+            },
+            DirectConversationRecord {
+                counterparty: bob.id(),
+                last_ts: from_bob.timestamp(),
+                last_event_id: from_bob.event_id().to_short(),
+            },
+        ]
+    );
+
+    let (page, cursor) = db.paginate_direct_messages_rev(alice.id(), None, 1).await;
+    assert_eq!(page[0].author, alice.id());
+    assert_eq!(
+        page[0]
+            .content
+            .open(own, alice.id())
+            .expect("can open")
+            .djot_content,
+        "Hi!"
+    );
+    let (page, cursor) = db.paginate_direct_messages_rev(alice.id(), cursor, 1).await;
+    assert_eq!(page[0].event_id, to_alice.event_id().to_short());
+    assert_eq!(cursor, None);
+
+    Ok(())
+}
+
+/// Test: deleting a message removes it from its conversation, and the
+/// conversation disappears with its last message.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn direct_message_deletion_updates_conversation() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let first = direct_message(own, alice, 100, None, "One");
+    let second = direct_message(own, alice, 200, Some(first.event_id()), "Two");
+    for msg in [&first, &second] {
+        db.process_event_with_content(msg).await;
+    }
+
+    let delete_second = deletion(own, 300, second.event_id(), second.event_id());
+    db.process_event(&delete_second).await;
+    assert_eq!(
+        conversation(&db, alice).await,
+        vec![first.event_id().to_short()]
+    );
+    assert_eq!(
+        db.get_direct_conversations().await[0].last_event_id,
+        first.event_id().to_short()
+    );
+
+    db.process_event(&deletion(
+        own,
+        400,
+        delete_second.event_id,
+        first.event_id(),
+    ))
+    .await;
+    assert!(conversation(&db, alice).await.is_empty());
+    assert!(db.get_direct_conversations().await.is_empty());
+
+    Ok(())
+}
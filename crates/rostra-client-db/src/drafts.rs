@@ -0,0 +1,188 @@
+//! Drafts and scheduled posts of the local identity.
+//!
+//! Drafts are a local convenience, never published as they are. They are kept
+//! in the [`local_post_drafts`] extension table until published, by hand or,
+//! for the scheduled ones, by the client when their time comes. Scheduled
+//! drafts are also indexed by their time in [`local_scheduled_post_drafts`].
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use bincode::{Decode, Encode};
+use rostra_core::event::PersonaTag;
+use rostra_core::{ExternalEventId, Timestamp};
+use tokio::sync::Notify;
+
+use crate::{Database, DbResult, ExtensionReadTransaction, ExtensionWriteTransaction};
+
+/// Local id of a [`PostDraft`]
+pub type PostDraftId = u64;
+
+/// A post not published yet
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct PostDraft {
+    pub content: String,
+    pub reply_to: Option<ExternalEventId>,
+    pub persona_tags: BTreeSet<PersonaTag>,
+    /// When to publish the post, `None` to keep it until published by hand
+    pub scheduled_at: Option<Timestamp>,
+    pub updated_at: Timestamp,
+    /// Why publishing it at the scheduled time failed, if it did
+    pub last_error: Option<String>,
+}
+
+crate::define_extension_table!(
+    /// Drafts, by their local id
+    local_post_drafts, "rostra/local_post_drafts": PostDraftId => PostDraft
+);
+
+crate::define_extension_table!(
+    /// Scheduled drafts, by their scheduled time and local id
+    local_scheduled_post_drafts, "rostra/local_scheduled_post_drafts": (Timestamp, PostDraftId) => ()
+);
+
+impl Database {
+    fn put_post_draft_tx(
+        id: PostDraftId,
+        draft: &PostDraft,
+        tx: &ExtensionWriteTransaction<'_>,
+    ) -> DbResult<()> {
+        let previous = tx
+            .open_table(&local_post_drafts::TABLE)?
+            .insert(&id, draft)?
+            .map(|g| g.value());
+        let mut scheduled_table = tx.open_table(&local_scheduled_post_drafts::TABLE)?;
+        if let Some(scheduled_at) = previous.and_then(|previous| previous.scheduled_at) {
+            scheduled_table.remove(&(scheduled_at, id))?;
+        }
+        if let Some(scheduled_at) = draft.scheduled_at {
+            scheduled_table.insert(&(scheduled_at, id), &())?;
+        }
+        Ok(())
+    }
+
+    /// Store a new draft, returning its id
+    pub async fn insert_post_draft(&self, draft: PostDraft) -> DbResult<PostDraftId> {
+        let scheduled = draft.scheduled_at.is_some();
+        let id = self
+            .extension_write(|tx: &ExtensionWriteTransaction<'_>| {
+                let id = tx
+                    .open_table(&local_post_drafts::TABLE)?
+                    .last()?
+                    .map(|(k, _)| k.value() + 1)
+                    .unwrap_or_default();
+                Self::put_post_draft_tx(id, &draft, tx)?;
+                Ok(id)
+            })
+            .await?;
+        if scheduled {
+            self.post_drafts_scheduled_notify.notify_one();
+        }
+        Ok(id)
+    }
+
+    /// Replace an existing draft
+    ///
+    /// Returns `false` if there is no draft with `id` (e.g. it was published
+    /// meanwhile).
+    pub async fn update_post_draft(&self, id: PostDraftId, draft: PostDraft) -> DbResult<bool> {
+        let scheduled = draft.scheduled_at.is_some();
+        let updated = self
+            .extension_write(|tx: &ExtensionWriteTransaction<'_>| {
+                if tx
+                    .open_table(&local_post_drafts::TABLE)?
+                    .get(&id)?
+                    .is_none()
+                {
+                    return Ok(false);
+                }
+                Self::put_post_draft_tx(id, &draft, tx)?;
+                Ok(true)
+            })
+            .await?;
+        if updated && scheduled {
+            self.post_drafts_scheduled_notify.notify_one();
+        }
+        Ok(updated)
+    }
+
+    /// Remove a draft, returning it if it existed
+    pub async fn remove_post_draft(&self, id: PostDraftId) -> DbResult<Option<PostDraft>> {
+        self.extension_write(|tx: &ExtensionWriteTransaction<'_>| {
+            let removed = tx
+                .open_table(&local_post_drafts::TABLE)?
+                .remove(&id)?
+                .map(|g| g.value());
+            if let Some(scheduled_at) = removed.as_ref().and_then(|draft| draft.scheduled_at) {
+                tx.open_table(&local_scheduled_post_drafts::TABLE)?
+                    .remove(&(scheduled_at, id))?;
+            }
+            Ok(removed)
+        })
+        .await
+    }
+
+    pub async fn get_post_draft(&self, id: PostDraftId) -> Option<PostDraft> {
+        self.extension_read(|tx: &ExtensionReadTransaction<'_>| {
+            Ok(tx
+                .open_table(&local_post_drafts::TABLE)?
+                .get(&id)?
+                .map(|g| g.value()))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// All the drafts, newest first
+    pub async fn list_post_drafts(&self) -> Vec<(PostDraftId, PostDraft)> {
+        self.extension_read(|tx: &ExtensionReadTransaction<'_>| {
+            tx.open_table(&local_post_drafts::TABLE)?
+                .range(..)?
+                .rev()
+                .map(|entry| {
+                    let (k, v) = entry?;
+                    Ok((k.value(), v.value()))
+                })
+                .collect()
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Scheduled drafts due at `now`, oldest first
+    pub async fn get_due_post_drafts(&self, now: Timestamp) -> Vec<(PostDraftId, PostDraft)> {
+        self.extension_read(|tx: &ExtensionReadTransaction<'_>| {
+            let drafts_table = tx.open_table(&local_post_drafts::TABLE)?;
+            let mut due = vec![];
+            for entry in tx
+                .open_table(&local_scheduled_post_drafts::TABLE)?
+                .range(..=(now, PostDraftId::MAX))?
+            {
+                let (_, id) = entry?.0.value();
+                if let Some(draft) = drafts_table.get(&id)?.map(|g| g.value()) {
+                    due.push((id, draft));
+                }
+            }
+            Ok(due)
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Time of the earliest scheduled draft
+    pub async fn get_next_post_draft_schedule(&self) -> Option<Timestamp> {
+        self.extension_read(|tx: &ExtensionReadTransaction<'_>| {
+            Ok(tx
+                .open_table(&local_scheduled_post_drafts::TABLE)?
+                .first()?
+                .map(|(k, _)| k.value().0))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Get a handle to the notification of newly scheduled drafts
+    pub fn post_drafts_scheduled_notify(&self) -> Arc<Notify> {
+        self.post_drafts_scheduled_notify.clone()
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeSet;
+
+use rostra_core::Timestamp;
+use rostra_core::id::RostraIdSecretKey;
+use rostra_util_error::BoxedErrorResult;
+
+use crate::drafts::PostDraft;
+use crate::tests::temp_db;
+
+fn draft(content: &str, scheduled_at: Option<u64>) -> PostDraft {
+    PostDraft {
+        content: content.to_owned(),
+        reply_to: None,
+        persona_tags: BTreeSet::new(),
+        scheduled_at: scheduled_at.map(Timestamp::from),
+        updated_at: Timestamp::from(1),
+        last_error: None,
+    }
+}
+
+/// Test: drafts are listed newest first, and only the scheduled ones become
+/// due, at their time, following reschedules and removals.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn scheduled_drafts_become_due_in_order() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    assert!(db.list_post_drafts().await.is_empty());
+    assert_eq!(db.get_next_post_draft_schedule().await, None);
+
+    let plain = db.insert_post_draft(draft("plain", None)).await?;
+    let later = db.insert_post_draft(draft("later", Some(300))).await?;
+    let sooner = db.insert_post_draft(draft("sooner", Some(200))).await?;
+
+    assert_eq!(
+        db.list_post_drafts()
+            .await
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![sooner, later, plain]
+    );
+    assert_eq!(
+        db.get_next_post_draft_schedule().await,
+        Some(Timestamp::from(200))
+    );
+    assert!(
+        db.get_due_post_drafts(Timestamp::from(199))
+            .await
+            .is_empty()
+    );
+    assert_eq!(
+        db.get_due_post_drafts(Timestamp::from(300))
+            .await
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>(),
+        vec![sooner, later]
+    );
+
+    // Rescheduling drops the previous time
+    assert!(
+        db.update_post_draft(sooner, draft("sooner", Some(400)))
+            .await?
+    );
+    assert_eq!(
+        db.get_next_post_draft_schedule().await,
+        Some(Timestamp::from(300))
+    );
+
+    // Unscheduling and removal drop the draft from the due ones
+    assert!(db.update_post_draft(later, draft("later", None)).await?);
+    assert_eq!(
+        db.remove_post_draft(sooner)
+            .await?
+            .map(|draft| draft.content),
+        Some("sooner".to_owned())
+    );
+    assert!(db.get_due_post_drafts(Timestamp::MAX).await.is_empty());
+    assert_eq!(db.get_next_post_draft_schedule().await, None);
+
+    // Removed drafts can't be updated
+    assert!(!db.update_post_draft(sooner, draft("sooner", None)).await?);
+    assert_eq!(db.get_post_draft(sooner).await, None);
+    assert_eq!(db.list_post_drafts().await.len(), 2);
+
+    Ok(())
+}
@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use rostra_core::event::{
+    EventAuxKey, EventExt as _, EventKind, VerifiedEvent, VerifiedEventContent,
+};
+use rostra_core::id::{RostraId, ToShort as _};
+use rostra_core::{ShortEventId, Timestamp};
+use rostra_util_error::FmtCompact as _;
+use tracing::debug;
+
+use crate::event::{ContentStoreRecord, EventsPrunedCheckpointRecord};
+use crate::process_event_content_ops::ProcessEventError;
+use crate::{
+    Database, DbResult, EventHeaderPruningNotAllowedSnafu, EventRecord, LOG_TARGET,
+    WriteTransactionCtx, content_rc, content_store, events, events_by_author, events_by_time,
+    events_content_missing, events_content_state, events_heads, events_missing, events_pruned,
+    events_pruned_checkpoints, events_singletons_new, ids_data_usage, ids_followees,
+    social_posts_pruned,
+};
+
+/// Outcome of one [`Database::prune_event_headers`] call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventPruningOutcome {
+    /// Number of event headers removed.
+    pub pruned_num: u64,
+    /// Number of pruned events that retained events still reference.
+    pub frontier_num: u64,
+    /// Bytes removed from `content_store` because no event references them
+    /// anymore.
+    pub reclaimed_size: u64,
+}
+
+impl Database {
+    /// Prune event headers of `author` authored before `before`.
+    ///
+    /// Unlike content pruning, this removes the events themselves. The DAG is
+    /// walked from the author's heads: heads, current singleton winners and
+    /// events authored at or after `before` are retained, everything else
+    /// reachable is pruned. Content of pruned events is pruned first, and
+    /// projections of processed social posts are reverted.
+    ///
+    /// Pruned parents of retained events are recorded as the pruned frontier,
+    /// and `before` as the author's checkpoint. Together they keep
+    /// `events_missing` and `events_heads` consistent: pruned parents are
+    /// never reported as missing, and pruned history received again is
+    /// rejected by event insertion instead of being downloaded.
+    ///
+    /// Only identities that are neither self nor directly followed can be
+    /// pruned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`crate::DbError::EventHeaderPruningNotAllowed`] for self and
+    /// direct followees, and storage errors.
+    pub async fn prune_event_headers(
+        &self,
+        author: RostraId,
+        before: Timestamp,
+    ) -> DbResult<EventPruningOutcome> {
+        self.write_with(|tx| self.prune_event_headers_tx(author, before, tx))
+            .await
+    }
+
+    /// Identities whose event headers can be pruned.
+    ///
+    /// These are all identities with stored events, except self and direct
+    /// followees.
+    pub async fn get_event_header_pruning_candidates(&self) -> DbResult<Vec<RostraId>> {
+        self.read_with(|tx| {
+            let ids_data_usage_table = tx.open_table(&ids_data_usage::TABLE)?;
+            let ids_followees_table = tx.open_table(&ids_followees::TABLE)?;
+
+            let mut candidates = vec![];
+            for entry in ids_data_usage_table.range(..)? {
+                let (id, usage) = entry?;
+                let id = id.value();
+                if id == self.self_id
+                    || usage.value().current_metadata_num == 0
+                    || ids_followees_table.get(&(self.self_id, id))?.is_some()
+                {
+                    continue;
+                }
+                candidates.push(id);
+            }
+            Ok(candidates)
+        })
+        .await
+    }
+
+    /// Check if `event_id` is a pruned parent of a retained event of
+    /// `author`.
+    ///
+    /// There is no need to fetch such events, as they would be rejected.
+    pub async fn is_event_pruned(&self, author: RostraId, event_id: ShortEventId) -> bool {
+        self.read_with(|tx| {
+            let events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+            Ok(events_pruned_table.get(&(author, event_id))?.is_some())
+        })
+        .await
+        .expect("Database panic")
+    }
+
+    /// Timestamp before which event headers of `author` were pruned, if any.
+    pub async fn get_events_pruned_checkpoint(&self, author: RostraId) -> Option<Timestamp> {
+        self.read_with(|tx| {
+            let table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
+            Ok(table.get(&author)?.map(|record| record.value().before))
+        })
+        .await
+        .expect("Database panic")
+    }
+
+    fn prune_event_headers_tx(
+        &self,
+        author: RostraId,
+        before: Timestamp,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<EventPruningOutcome> {
+        if author == self.self_id
+            || tx
+                .open_table(&ids_followees::TABLE)?
+                .get(&(self.self_id, author))?
+                .is_some()
+        {
+            return EventHeaderPruningNotAllowedSnafu { id: author }.fail();
+        }
+
+        let mut events_table = tx.open_table(&events::TABLE)?;
+        let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
+        let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let mut events_pruned_checkpoints_table =
+            tx.open_table(&events_pruned_checkpoints::TABLE)?;
+        let events_heads_table = tx.open_table(&events_heads::TABLE)?;
+
+        let heads = Database::get_heads_events_tx(author, &events_heads_table)?;
+        let singleton_winners = tx
+            .open_table(&events_singletons_new::TABLE)?
+            .range(
+                (author, EventKind::from_u16(0), EventAuxKey::ZERO)
+                    ..=(author, EventKind::from_u16(u16::MAX), EventAuxKey::MAX),
+            )?
+            .map(|entry| entry.map(|(_, record)| record.value().inner.event_id))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let retains = |event_id: ShortEventId, event: &EventRecord| {
+            before <= event.timestamp()
+                || heads.contains(&event_id)
+                || singleton_winners.contains(&event_id)
+        };
+
+        // Walk the whole DAG of the author from its heads, splitting it into
+        // retained and pruned events, and record which absent parents are
+        // referenced by which part.
+        let mut visited = HashSet::new();
+        let mut stack = heads.clone();
+        let mut to_prune = BTreeMap::new();
+        let mut frontier = BTreeSet::new();
+        let mut retained_missing = HashSet::new();
+        let mut pruned_missing = HashSet::new();
+
+        while let Some(event_id) = stack.pop() {
+            if !visited.insert(event_id) {
+                continue;
+            }
+            let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                continue;
+            };
+            let is_retained = retains(event_id, &event);
+
+            for parent_id in event.all_parents() {
+                match Database::get_event_tx(parent_id, &events_table)?
+                    .filter(|parent| parent.author() == author)
+                {
+                    Some(parent) => {
+                        if is_retained && !retains(parent_id, &parent) {
+                            frontier.insert(parent_id);
+                        }
+                        stack.push(parent_id);
+                    }
+                    None if is_retained => {
+                        if events_pruned_table.get(&(author, parent_id))?.is_some() {
+                            frontier.insert(parent_id);
+                        } else {
+                            retained_missing.insert(parent_id);
+                        }
+                    }
+                    None => {
+                        pruned_missing.insert(parent_id);
+                    }
+                }
+            }
+
+            if !is_retained {
+                to_prune.insert(event_id, event);
+            }
+        }
+
+        let mut outcome = EventPruningOutcome::default();
+
+        // Rewrite the frontier, and stop looking for parents that only pruned
+        // events were referencing.
+        let old_frontier = events_pruned_table
+            .range((author, ShortEventId::ZERO)..=(author, ShortEventId::MAX))?
+            .map(|entry| entry.map(|(key, _)| key.value().1))
+            .collect::<Result<Vec<_>, _>>()?;
+        for event_id in old_frontier {
+            events_pruned_table.remove(&(author, event_id))?;
+        }
+        for &event_id in &frontier {
+            events_pruned_table.insert(&(author, event_id), &())?;
+            outcome.frontier_num += 1;
+        }
+        for event_id in pruned_missing {
+            if !retained_missing.contains(&event_id) && !frontier.contains(&event_id) {
+                events_missing_table.remove(&(author, event_id))?;
+            }
+        }
+
+        // Projections of the pruned events, like social post feeds and repost
+        // counts, are reverted in their own tables, so only the pruned events
+        // themselves are needed at that point.
+        let mut processed_contents = vec![];
+        {
+            let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+            let content_store_table = tx.open_table(&content_store::TABLE)?;
+            for (&event_id, event) in &to_prune {
+                if !Database::REVERTED_EVENT_KINDS.contains(&event.kind())
+                    || Database::get_event_content_state_tx(event_id, &events_content_state_table)?
+                        .is_some()
+                {
+                    continue;
+                }
+                if let Some(ContentStoreRecord(content)) = content_store_table
+                    .get(&event.content_hash())?
+                    .map(|record| record.value())
+                {
+                    processed_contents.push(VerifiedEventContent::assume_verified(
+                        VerifiedEvent::assume_verified_from_signed(event.signed),
+                        content.into_owned(),
+                    ));
+                }
+            }
+        }
+        for event_content in &processed_contents {
+            match self.process_event_content_reverted_tx(event_content, tx) {
+                Ok(()) => {}
+                Err(ProcessEventError::Db { source }) => return Err(source),
+                Err(ProcessEventError::Invalid { source, location }) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        err = %source.as_ref().fmt_compact(),
+                        %location,
+                        event_id = %event_content.event_id().to_short(),
+                        "Could not revert content of a pruned event"
+                    );
+                }
+            }
+        }
+
+        let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+        let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
+        let mut content_store_table = tx.open_table(&content_store::TABLE)?;
+        let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
+        let mut ids_data_usage_table = tx.open_table(&ids_data_usage::TABLE)?;
+        let mut social_posts_pruned_table = tx.open_table(&social_posts_pruned::TABLE)?;
+
+        for (event_id, event) in to_prune {
+            let content_hash = event.content_hash();
+            let content_len = event.content_len();
+
+            // Moves payload accounting to `pruned` exactly once, unless the
+            // payload was already released as deleted or invalid.
+            Database::prune_event_content_tx(
+                event_id,
+                content_hash,
+                &mut events_content_state_table,
+                &mut content_rc_table,
+                &mut events_content_missing_table,
+                Some((author, content_len, &mut ids_data_usage_table)),
+            )?;
+            events_content_state_table.remove(&event_id)?;
+            if Database::get_content_rc_tx(content_hash, &content_rc_table)? == 0
+                && content_store_table.remove(&content_hash)?.is_some()
+            {
+                outcome.reclaimed_size += u64::from(content_len);
+            }
+
+            events_table.remove(&event_id)?;
+            events_by_time_table.remove(&(event.timestamp(), event_id))?;
+            events_by_author_table.remove(&(author, event.timestamp(), event_id))?;
+            if event.kind() == EventKind::SOCIAL_POST {
+                social_posts_pruned_table.insert(&event_id, &author)?;
+            }
+            Database::track_pruned_event_tx(author, &mut ids_data_usage_table)?;
+            outcome.pruned_num += 1;
+        }
+
+        let before = events_pruned_checkpoints_table
+            .get(&author)?
+            .map(|record| record.value().before)
+            .map_or(before, |old_before| old_before.max(before));
+        events_pruned_checkpoints_table
+            .insert(&author, &EventsPrunedCheckpointRecord { before })?;
+
+        debug!(
+            target: LOG_TARGET,
+            author = %author.to_short(),
+            %before,
+            pruned_num = outcome.pruned_num,
+            frontier_num = outcome.frontier_num,
+            "Pruned event headers"
+        );
+
+        Ok(outcome)
+    }
+}
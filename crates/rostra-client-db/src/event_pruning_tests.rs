@@ -0,0 +1,331 @@
+use std::num::NonZeroUsize;
+
+use rostra_core::event::content_kind::{self, EventContentKind as _};
+use rostra_core::event::{Event, EventContentRaw, EventKind, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_core::{EventId, ExternalEventId, ShortEventId, Timestamp};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::{
+    Database, DbError, EventPruningOutcome, InsertEventOutcome, ProcessEventState,
+    SocialPostMaterialization, events_by_time, social_posts_by_time, social_reposts_by_original,
+    social_reposts_by_time,
+};
+
+fn event(
+    secret: RostraIdSecretKey,
+    kind: EventKind,
+    content: EventContentRaw,
+    secs: i64,
+    parent: Option<EventId>,
+) -> VerifiedEventContent {
+    let signed = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(kind)
+        .content(&content)
+        .timestamp(time::OffsetDateTime::UNIX_EPOCH + time::Duration::seconds(secs))
+        .maybe_parent_prev(parent.map(Into::into))
+        .build()
+        .signed_by(secret);
+    let event =
+        VerifiedEvent::verify_signed(secret.id(), signed).expect("event signature must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+/// Build a linear chain of raw events, one per timestamp, oldest first.
+fn chain(secret: RostraIdSecretKey, timestamps: &[i64]) -> Vec<VerifiedEventContent> {
+    let mut events: Vec<VerifiedEventContent> = vec![];
+    for (i, &secs) in timestamps.iter().enumerate() {
+        let parent = events.last().map(|parent| parent.event_id());
+        events.push(event(
+            secret,
+            EventKind::RAW,
+            EventContentRaw::new(vec![u8::try_from(i).expect("short chain"); 16]),
+            secs,
+            parent,
+        ));
+    }
+    events
+}
+
+async fn events_by_time_len(db: &Database) -> BoxedErrorResult<usize> {
+    Ok(db
+        .read_with(|tx| Ok(tx.open_table(&events_by_time::TABLE)?.range(..)?.count()))
+        .await?)
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn pruned_history_keeps_frontier_and_is_not_accepted_again() -> BoxedErrorResult<()> {
+    let self_id = RostraIdSecretKey::from_bytes([1; 32]).id();
+    let far_secret = RostraIdSecretKey::from_bytes([2; 32]);
+    let far_id = far_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    let events = chain(far_secret, &[10, 20, 30, 40]);
+    for event in &events {
+        db.process_event_with_content(event).await;
+    }
+
+    let outcome = db.prune_event_headers(far_id, Timestamp::from(25)).await?;
+    assert_eq!(
+        outcome,
+        EventPruningOutcome {
+            pruned_num: 2,
+            frontier_num: 1,
+            reclaimed_size: 32,
+        }
+    );
+
+    for (event, retained) in events.iter().zip([false, false, true, true]) {
+        assert_eq!(db.get_event(event.event_id()).await.is_some(), retained);
+    }
+    assert_eq!(events_by_time_len(&db).await?, 2);
+    assert!(
+        db.is_event_pruned(far_id, events[1].event_id().to_short())
+            .await
+    );
+    assert!(
+        !db.is_event_pruned(far_id, events[0].event_id().to_short())
+            .await
+    );
+    assert_eq!(
+        db.get_events_pruned_checkpoint(far_id).await,
+        Some(Timestamp::from(25))
+    );
+    assert_eq!(
+        db.get_heads_events_for_id(far_id).await,
+        vec![events[3].event_id().to_short()]
+    );
+    assert!(db.get_missing_events_for_id(far_id).await.is_empty());
+
+    let usage = db.get_data_usage(far_id).await;
+    assert_eq!(usage.current_metadata_num, 2);
+    assert_eq!(usage.total_metadata_num, 4);
+    assert_eq!(
+        usage.current_metadata_size,
+        2 * Database::EVENT_METADATA_SIZE
+    );
+    assert_eq!(usage.total_metadata_size, 4 * Database::EVENT_METADATA_SIZE);
+    assert_eq!(usage.current_payload_num, 2);
+    assert_eq!(usage.pruned_payload_num, 2);
+    assert_eq!(usage.pruned_payload_size, 32);
+
+    // Pruned history sent again is rejected without side effects.
+    for event in &events[..2] {
+        let (outcome, state) = db.process_event_with_content(event).await;
+        assert!(matches!(outcome, InsertEventOutcome::Pruned));
+        assert_eq!(state, ProcessEventState::Pruned);
+        assert!(db.get_event(event.event_id()).await.is_none());
+    }
+    assert_eq!(db.get_data_usage(far_id).await.total_metadata_num, 4);
+    assert!(db.get_missing_events_for_id(far_id).await.is_empty());
+
+    // New events on top of the retained DAG are accepted as usual.
+    let next = event(
+        far_secret,
+        EventKind::RAW,
+        EventContentRaw::new(vec![9; 16]),
+        50,
+        Some(events[3].event_id()),
+    );
+    let (outcome, _) = db.process_event_with_content(&next).await;
+    assert!(matches!(outcome, InsertEventOutcome::Inserted { .. }));
+    assert_eq!(
+        db.get_heads_events_for_id(far_id).await,
+        vec![next.event_id().to_short()]
+    );
+
+    // Pruning again is idempotent, and the checkpoint never moves back.
+    assert_eq!(
+        db.prune_event_headers(far_id, Timestamp::from(5)).await?,
+        EventPruningOutcome {
+            frontier_num: 1,
+            ..Default::default()
+        }
+    );
+    assert_eq!(
+        db.get_events_pruned_checkpoint(far_id).await,
+        Some(Timestamp::from(25))
+    );
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn missing_parents_of_pruned_history_are_forgotten() -> BoxedErrorResult<()> {
+    let self_id = RostraIdSecretKey::from_bytes([3; 32]).id();
+    let far_secret = RostraIdSecretKey::from_bytes([4; 32]);
+    let far_id = far_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    let events = chain(far_secret, &[10, 20, 30, 40]);
+    // Only the newest two events arrive, so the second one is missing.
+    db.process_event_with_content(&events[2]).await;
+    db.process_event_with_content(&events[3]).await;
+    assert_eq!(
+        db.get_missing_events_for_id(far_id).await,
+        vec![events[1].event_id().to_short()]
+    );
+
+    db.prune_event_headers(far_id, Timestamp::from(35)).await?;
+    assert!(db.get_missing_events_for_id(far_id).await.is_empty());
+    assert!(
+        db.is_event_pruned(far_id, events[2].event_id().to_short())
+            .await
+    );
+    assert_eq!(
+        db.get_heads_events_for_id(far_id).await,
+        vec![events[3].event_id().to_short()]
+    );
+
+    // A new event referencing the pruned frontier does not make it missing.
+    let fork = event(
+        far_secret,
+        EventKind::RAW,
+        EventContentRaw::new(vec![7; 16]),
+        60,
+        Some(events[2].event_id()),
+    );
+    db.process_event_with_content(&fork).await;
+    assert!(db.get_missing_events_for_id(far_id).await.is_empty());
+
+    // A retained event waiting for a parent older than the checkpoint stops
+    // waiting once it arrives, and the parent joins the frontier.
+    let late_child = event(
+        far_secret,
+        EventKind::RAW,
+        EventContentRaw::new(vec![8; 16]),
+        70,
+        Some(events[0].event_id()),
+    );
+    db.process_event_with_content(&late_child).await;
+    assert_eq!(
+        db.get_missing_events_for_id(far_id).await,
+        vec![events[0].event_id().to_short()]
+    );
+    let (outcome, _) = db.process_event_with_content(&events[0]).await;
+    assert!(matches!(outcome, InsertEventOutcome::Pruned));
+    assert!(db.get_missing_events_for_id(far_id).await.is_empty());
+    assert!(
+        db.is_event_pruned(far_id, events[0].event_id().to_short())
+            .await
+    );
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn pruned_social_posts_are_removed_from_feeds() -> BoxedErrorResult<()> {
+    let self_id = RostraIdSecretKey::from_bytes([5; 32]).id();
+    let far_secret = RostraIdSecretKey::from_bytes([6; 32]);
+    let far_id = far_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    let post_content = content_kind::SocialPost::new("old".to_owned(), None, Default::default())
+        .serialize_cbor()?;
+    let post = event(far_secret, EventKind::SOCIAL_POST, post_content, 10, None);
+    let head = event(
+        far_secret,
+        EventKind::RAW,
+        EventContentRaw::new(vec![1; 16]),
+        40,
+        Some(post.event_id()),
+    );
+    db.process_event_with_content(&post).await;
+    db.process_event_with_content(&head).await;
+
+    db.prune_event_headers(far_id, Timestamp::from(25)).await?;
+
+    let posts_by_time: Vec<(Timestamp, ShortEventId)> = db
+        .read_with(|tx| {
+            Ok(tx
+                .open_table(&social_posts_by_time::TABLE)?
+                .range(..)?
+                .map(|entry| entry.map(|(key, _)| key.value()))
+                .collect::<Result<Vec<_>, _>>()?)
+        })
+        .await?;
+    assert!(posts_by_time.is_empty());
+
+    let page = db
+        .scan_social_post_materializations(
+            None,
+            NonZeroUsize::new(16).expect("positive test limit"),
+        )
+        .await?;
+    assert_eq!(
+        page.items,
+        vec![SocialPostMaterialization::Removed {
+            post_id: ExternalEventId::new(far_id, post.event_id().to_short()),
+        }]
+    );
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn self_event_headers_are_never_pruned() -> BoxedErrorResult<()> {
+    let self_secret = RostraIdSecretKey::from_bytes([7; 32]);
+    let self_id = self_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    for event in &chain(self_secret, &[10, 20]) {
+        db.process_event_with_content(event).await;
+    }
+
+    assert!(matches!(
+        db.prune_event_headers(self_id, Timestamp::MAX).await,
+        Err(DbError::EventHeaderPruningNotAllowed { .. })
+    ));
+    assert!(db.get_event_header_pruning_candidates().await?.is_empty());
+    assert_eq!(events_by_time_len(&db).await?, 2);
+
+    Ok(())
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn pruned_reposts_are_removed_from_counts_and_indexes() -> BoxedErrorResult<()> {
+    let self_id = RostraIdSecretKey::from_bytes([8; 32]).id();
+    let far_secret = RostraIdSecretKey::from_bytes([9; 32]);
+    let far_id = far_secret.id();
+    let (_dir, db) = crate::tests::temp_db(self_id).await?;
+
+    let original = ExternalEventId::new(self_id, ShortEventId::from_bytes([1; 16]));
+    let repost_content = content_kind::SocialRepost::new(original, None).serialize_cbor()?;
+    let repost = event(
+        far_secret,
+        EventKind::SOCIAL_REPOST,
+        repost_content,
+        10,
+        None,
+    );
+    let head = event(
+        far_secret,
+        EventKind::RAW,
+        EventContentRaw::new(vec![1; 16]),
+        40,
+        Some(repost.event_id()),
+    );
+    db.process_event_with_content(&repost).await;
+    db.process_event_with_content(&head).await;
+    assert_eq!(db.get_social_repost_count(original).await, 1);
+
+    db.prune_event_headers(far_id, Timestamp::from(25)).await?;
+
+    assert_eq!(db.get_social_repost_count(original).await, 0);
+    let (by_time_len, by_original_len) = db
+        .read_with(|tx| {
+            Ok((
+                tx.open_table(&social_reposts_by_time::TABLE)?
+                    .range(..)?
+                    .count(),
+                tx.open_table(&social_reposts_by_original::TABLE)?
+                    .range(..)?
+                    .count(),
+            ))
+        })
+        .await?;
+    assert_eq!((by_time_len, by_original_len), (0, 0));
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use rostra_core::ShortEventId;
+
+use crate::{Database, DbResult, events_content_partial};
+
+impl Database {
+    /// Verified prefix of the content of `event_id` downloaded so far.
+    ///
+    /// Only pieces continuing from the start of the content are returned.
+    pub async fn get_event_content_partial(&self, event_id: ShortEventId) -> Vec<u8> {
+        self.read_with(|tx| {
+            let table = tx.open_table(&events_content_partial::TABLE)?;
+            let mut prefix = vec![];
+            for piece in table.range(&(event_id, 0)..=&(event_id, u32::MAX))? {
+                let (k, v) = piece?;
+                let (_, offset) = k.value();
+                if usize::try_from(offset).ok() != Some(prefix.len()) {
+                    break;
+                }
+                prefix.extend_from_slice(&v.value());
+            }
+            Ok(prefix)
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Store a verified piece of the content of `event_id` starting at
+    /// `offset`.
+    pub async fn insert_event_content_partial(
+        &self,
+        event_id: ShortEventId,
+        offset: u32,
+        data: Vec<u8>,
+    ) -> DbResult<()> {
+        self.write_with(|tx| {
+            tx.open_table(&events_content_partial::TABLE)?
+                .insert(&(event_id, offset), &data)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Drop all the stored pieces of the content of `event_id`.
+    pub async fn remove_event_content_partial(&self, event_id: ShortEventId) -> DbResult<()> {
+        self.write_with(|tx| {
+            Self::remove_event_content_partial_tx(
+                event_id,
+                &mut tx.open_table(&events_content_partial::TABLE)?,
+            )
+        })
+        .await
+    }
+
+    pub(crate) fn remove_event_content_partial_tx(
+        event_id: ShortEventId,
+        table: &mut events_content_partial::Table,
+    ) -> DbResult<()> {
+        table.retain_in(&(event_id, 0)..=&(event_id, u32::MAX), |_, _| false)?;
+        Ok(())
+    }
+}
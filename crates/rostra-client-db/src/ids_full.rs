@@ -65,6 +65,18 @@ pub(crate) fn get(tx: &ReadTransaction, prefix: ShortRostraId) -> DbResult<Optio
         .map(|entry| entry.value()))
 }
 
+/// Resolve a shortened identity that has authored a retained event, within a
+/// write transaction.
+pub(crate) fn resolve_tx(
+    tx: &WriteTransactionCtx,
+    prefix: ShortRostraId,
+) -> DbResult<Option<RostraId>> {
+    Ok(tx
+        .open_table(&TABLE)?
+        .get(&prefix)?
+        .map(|entry| RostraId::assemble(prefix, entry.value())))
+}
+
 /// Deliberately bypass the collision guard to build corruption fixtures.
 #[cfg(test)]
 pub(crate) fn set_for_test(
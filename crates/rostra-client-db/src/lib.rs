@@ -1,25 +1,42 @@
+pub mod archive;
+mod blocks;
+pub mod content_filters;
+mod content_pruning;
 mod current_state;
+pub mod derived_assets;
+pub mod devices;
+pub mod direct_messages;
+pub mod drafts;
 mod event_order;
+mod event_pruning;
 mod events_content_missing_ops;
+mod events_content_partial_ops;
 mod extension;
 mod id_nodes_ops;
 mod ids_full;
+pub mod mentions;
 mod migration_ops;
 mod models;
 pub mod news;
 mod paginate;
+pub mod polls;
+pub mod private_posts;
 mod process_event_content_ops;
 mod process_event_ops;
 mod reception_order_ops;
+pub mod reposts;
+pub mod search;
 mod self_followee;
 pub mod social;
 mod social_post_materialization;
+pub mod succession;
 mod table_ops;
 mod tables;
+pub mod tags;
 mod tx_ops;
 
 use std::borrow::Cow;
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -42,7 +59,9 @@ use tokio::sync::{Notify, broadcast, watch};
 use tokio::task::JoinError;
 use tracing::{debug, error, info, instrument};
 
+pub use self::content_pruning::{ContentPruningBatch, ContentPruningPolicy};
 pub use self::current_state::{CurrentState, CurrentStateClosed};
+pub use self::event_pruning::EventPruningOutcome;
 pub use self::extension::{
     EXTENSION_RESERVED_TABLE_PREFIXES, ExtensionReadTransaction, ExtensionTableDefinition,
     ExtensionWriteTransaction,
@@ -56,21 +75,24 @@ pub(crate) use self::tables::*;
 pub use self::tables::{
     ContentStoreRecordOwned, EventContentResult, EventContentState, EventReceivedRecord,
     EventReceivedSource, EventRecord, EventsHeadsTableRecord, IdSocialProfileRecord,
-    IdsDataUsageRecord, IrohNodeRecord, IrohNodeStats, Latest, SocialNewsRankRecord,
-    SocialPostRecord, SocialPostsReactionsRecord, SocialPostsRepliesRecord, SocialVoteScore,
-    SocialVoteSumRecord,
+    IdsDataUsageRecord, IrohNodeRecord, IrohNodeStats, Latest, PrivateAudienceRecord,
+    SocialNewsRankRecord, SocialPollVoteRecord, SocialPollVoteSumRecord, SocialPostRecord,
+    SocialPostsReactionsRecord, SocialPostsRepliesRecord, SocialVoteScore, SocialVoteSumRecord,
 };
 
 /// Web of Trust data - contains direct followees and extended followees.
 ///
 /// Extended followees are the followees of your direct followees, excluding
-/// those you already follow directly.
+/// those you already follow directly. Identities blocked by self are never
+/// part of either.
 #[derive(Debug, Clone, Default)]
 pub struct WotData {
     /// Direct followees with their persona selectors
     pub followees: HashMap<RostraId, ids::IdsFolloweesRecord>,
     /// Extended followees (followees of followees), excluding direct followees
     pub extended: HashSet<RostraId>,
+    /// Identities blocked by self
+    pub blocked: HashSet<RostraId>,
 }
 
 impl WotData {
@@ -80,6 +102,27 @@ impl WotData {
         id == self_id || self.followees.contains_key(&id) || self.extended.contains(&id)
     }
 
+    /// Returns the web of trust distance of the given id
+    ///
+    /// `0` is self, `1` a direct followee, `2` an extended followee, and
+    /// `None` an id outside the web of trust.
+    pub fn distance(&self, id: RostraId, self_id: RostraId) -> Option<u8> {
+        if id == self_id {
+            Some(0)
+        } else if self.followees.contains_key(&id) {
+            Some(1)
+        } else if self.extended.contains(&id) {
+            Some(2)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the given id is blocked by self
+    pub fn is_blocked(&self, id: RostraId) -> bool {
+        self.blocked.contains(&id)
+    }
+
     /// Returns the total number of IDs in the web of trust (excluding self)
     pub fn len(&self) -> usize {
         self.followees.len() + self.extended.len()
@@ -368,6 +411,12 @@ pub enum DbError {
         #[snafu(implicit)]
         location: Location,
     },
+    #[snafu(display("Event headers of {id} are always retained"))]
+    EventHeaderPruningNotAllowed {
+        id: RostraId,
+        #[snafu(implicit)]
+        location: Location,
+    },
 }
 pub type DbResult<T> = std::result::Result<T, DbError>;
 
@@ -440,6 +489,21 @@ pub struct Database {
     /// The `MissingEventContentFetcher` task waits on this to wake up
     /// immediately when new missing content arrives, instead of polling.
     content_missing_notify: Arc<Notify>,
+
+    /// Notification for when new audience key grants wait to be unwrapped.
+    ///
+    /// A client holding the identity secret waits on this to unwrap them.
+    private_audience_grants_notify: Arc<Notify>,
+
+    /// Notification for when a reposted post needs to be fetched.
+    ///
+    /// The `RepostOriginalFetcher` task waits on this to fetch it from peers.
+    repost_originals_missing_notify: Arc<Notify>,
+
+    /// Notification for when a draft gets scheduled.
+    ///
+    /// The `ScheduledPostPublisher` task waits on this to reschedule itself.
+    post_drafts_scheduled_notify: Arc<Notify>,
 }
 
 impl Database {
@@ -522,8 +586,12 @@ impl Database {
             Self::read_with_inner(&inner, |tx| {
                 let ids_followees_table = tx.open_table(&ids_followees::TABLE)?;
                 let self_followees = Self::read_followees_tx(self_id, &ids_followees_table)?;
-                let self_wot =
-                    Self::compute_wot_tx(self_id, &self_followees, &ids_followees_table)?;
+                let self_wot = Self::compute_wot_tx(
+                    self_id,
+                    &self_followees,
+                    Self::read_self_blocks_tx(&tx.open_table(&ids_self_blocks::TABLE)?)?,
+                    &ids_followees_table,
+                )?;
                 let db_init_time = tx
                     .open_table(&db_init_time::TABLE)?
                     .get(&())?
@@ -566,6 +634,9 @@ impl Database {
             ids_with_missing_events_tx: dedup_chan::Sender::new(),
             news_score_updates_tx: dedup_chan::Sender::new(),
             content_missing_notify: Arc::new(Notify::new()),
+            private_audience_grants_notify: Arc::new(Notify::new()),
+            repost_originals_missing_notify: Arc::new(Notify::new()),
+            post_drafts_scheduled_notify: Arc::new(Notify::new()),
         };
 
         // If total migration stashed events, reprocess them now using the real
@@ -585,7 +656,12 @@ impl Database {
             .read_with(|tx| {
                 let followees = tx.open_table(&ids_followees::TABLE)?;
                 let self_followees = Self::read_followees_tx(self.self_id, &followees)?;
-                let self_wot = Self::compute_wot_tx(self.self_id, &self_followees, &followees)?;
+                let self_wot = Self::compute_wot_tx(
+                    self.self_id,
+                    &self_followees,
+                    Self::read_self_blocks_tx(&tx.open_table(&ids_self_blocks::TABLE)?)?,
+                    &followees,
+                )?;
                 Ok((
                     Self::read_head_tx(self.self_id, &tx.open_table(&events_heads::TABLE)?)?,
                     self_followees,
@@ -627,6 +703,15 @@ impl Database {
                     Self::dump_table_dbtx(tx, &tables::social_posts_reactions::TABLE)?
                 }
                 "social_vote_sums" => Self::dump_table_dbtx(tx, &tables::social_vote_sums::TABLE)?,
+                "social_poll_votes" => {
+                    Self::dump_table_dbtx(tx, &tables::social_poll_votes::TABLE)?
+                }
+                "social_poll_vote_sums" => {
+                    Self::dump_table_dbtx(tx, &tables::social_poll_vote_sums::TABLE)?
+                }
+                "social_repost_counts" => {
+                    Self::dump_table_dbtx(tx, &tables::social_repost_counts::TABLE)?
+                }
                 "social_news_rank_by_post_id" => {
                     Self::dump_table_dbtx(tx, &tables::social_news_rank_by_post_id::TABLE)?
                 }
@@ -912,6 +997,80 @@ impl Database {
         .expect("Database panic")
     }
 
+    /// Collect up to `limit` events of `author`, walking back from `heads`.
+    ///
+    /// The DAG is walked breadth-first, so newer events come first. `known`
+    /// events are neither returned nor traversed, and events not stored
+    /// locally end the walk along their branch. Every returned event is either
+    /// one of the `heads`, or a parent of an event returned before it.
+    pub async fn get_ancestors(
+        &self,
+        author: RostraId,
+        heads: &[ShortEventId],
+        known: &[ShortEventId],
+        limit: usize,
+    ) -> Vec<crate::event::EventRecord> {
+        self.read_with(|tx| {
+            let events_table = tx.open_table(&events::TABLE)?;
+
+            let mut visited: HashSet<ShortEventId> = known.iter().copied().collect();
+            let mut queue: VecDeque<ShortEventId> = heads.iter().copied().collect();
+            let mut ancestors = vec![];
+
+            while ancestors.len() < limit {
+                let Some(event_id) = queue.pop_front() else {
+                    break;
+                };
+                if !visited.insert(event_id) {
+                    continue;
+                }
+                let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                    continue;
+                };
+                if event.author() != author {
+                    continue;
+                }
+                queue.extend(event.all_parents());
+                ancestors.push(event);
+            }
+
+            Ok(ancestors)
+        })
+        .await
+        .expect("Database panic")
+    }
+
+    /// Up to `limit` events of `author` ordered by `(timestamp, event id)`,
+    /// from `start` (inclusive) to `end` (exclusive, or unbounded if `None`).
+    pub async fn get_events_by_author_range(
+        &self,
+        author: RostraId,
+        start: (Timestamp, ShortEventId),
+        end: Option<(Timestamp, ShortEventId)>,
+        limit: usize,
+    ) -> Vec<(Timestamp, ShortEventId)> {
+        self.read_with(|tx| {
+            let events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
+
+            let start = (author, start.0, start.1);
+            let range = match end {
+                Some((end_ts, end_id)) if start < (author, end_ts, end_id) => {
+                    events_by_author_table.range(start..(author, end_ts, end_id))?
+                }
+                Some(_) => return Ok(vec![]),
+                None => events_by_author_table
+                    .range(start..=(author, Timestamp::MAX, ShortEventId::MAX))?,
+            };
+
+            Ok(range
+                .take(limit)
+                .map(|entry| entry.map(|(key, _)| (key.value().1, key.value().2)))
+                .collect::<Result<Vec<_>, _>>()?)
+        })
+        .await
+        .expect("Database panic")
+    }
+
     pub async fn get_event_content(
         &self,
         event_id: impl Into<ShortEventId>,
@@ -1032,7 +1191,9 @@ impl Database {
         let now = Timestamp::now();
         self.write_with(|tx| {
             let res = self.process_event_tx(&content.event, now, tx)?;
-            self.process_event_content_tx(content, now, tx)?;
+            if !matches!(res.0, InsertEventOutcome::Pruned) {
+                self.process_event_content_tx(content, now, tx)?;
+            }
             Ok(res)
         })
         .await
@@ -1161,6 +1322,10 @@ impl Database {
                 {
                     events_content_missing_table.remove(&(next_fetch_attempt, event_short_id))?;
                 }
+                Self::remove_event_content_partial_tx(
+                    event_short_id,
+                    &mut tx.open_table(&tables::events_content_partial::TABLE)?,
+                )?;
             }
 
             if let Some(content) = event_content.content.as_ref() {
@@ -1684,6 +1849,11 @@ pub enum InsertEventOutcome {
     /// Identity registration is still validated and may restore an absent
     /// shortened/full mapping.
     AlreadyPresent,
+    /// The event belongs to history of its author that was pruned locally
+    /// (see [`Database::prune_event_headers`]), so it was not inserted.
+    ///
+    /// If a retained child was waiting for it, it is no longer missing.
+    Pruned,
     Inserted {
         /// An event already had a child reporting its existence.
         ///
@@ -1756,20 +1926,52 @@ impl ProcessEventState {
     }
 }
 #[cfg(test)]
+mod archive_tests;
+#[cfg(test)]
+mod blocks_tests;
+#[cfg(test)]
+mod content_filters_tests;
+#[cfg(test)]
 mod content_ingestion_tests;
 #[cfg(test)]
+mod content_pruning_tests;
+#[cfg(test)]
 mod deleted_replacement_tests;
 #[cfg(test)]
+mod derived_assets_tests;
+#[cfg(test)]
+mod devices_tests;
+#[cfg(test)]
+mod direct_messages_tests;
+#[cfg(test)]
+mod drafts_tests;
+#[cfg(test)]
+mod event_pruning_tests;
+#[cfg(test)]
 mod follow_epoch_tests;
 #[cfg(test)]
 mod identity_collision_tests;
 #[cfg(test)]
+mod mentions_tests;
+#[cfg(test)]
+mod polls_tests;
+#[cfg(test)]
+mod private_posts_tests;
+#[cfg(test)]
 mod reception_order_tests;
 #[cfg(test)]
+mod reposts_tests;
+#[cfg(test)]
+mod search_tests;
+#[cfg(test)]
 mod social_post_materialization_tests;
 #[cfg(test)]
 mod social_post_projection_tests;
 #[cfg(test)]
 mod social_post_receipt_tests;
 #[cfg(test)]
+mod succession_tests;
+#[cfg(test)]
+mod tags_tests;
+#[cfg(test)]
 mod tests;
@@ -0,0 +1,138 @@
+//! Mention index of social posts.
+//!
+//! Posts are indexed in [`crate::social_posts_by_mention`] when their content
+//! is processed, by every distinct identity their djot content @mentions, and
+//! unindexed along with the full-text index (see [`crate::search`]). The
+//! local notifications keep using [`crate::social_posts_self_mention`].
+
+use std::collections::BTreeSet;
+
+use rostra_core::event::content_kind;
+use rostra_core::id::RostraId;
+use rostra_core::{ShortEventId, Timestamp};
+use rostra_djot::links::RostraIdLink;
+use tracing::info;
+
+use crate::social::{EventPaginationCursor, SocialPostRecord};
+use crate::{
+    Database, DbResult, LOG_TARGET, WriteTransactionCtx, ids_full, social_posts_by_mention,
+};
+
+/// Max number of index rows scanned by one mention listing call
+pub const SOCIAL_POSTS_BY_MENTION_SCAN_MAX: usize = 4_096;
+
+/// Max number of distinct identities indexed per post
+const MAX_MENTIONS_PER_POST: usize = 32;
+
+/// Identities @mentioned by a post of `author`, other than `author` itself
+///
+/// Short references to identities that never authored a retained event can't
+/// be resolved and are left out.
+fn social_post_mentions_tx(
+    author: RostraId,
+    content: &content_kind::SocialPost,
+    tx: &WriteTransactionCtx,
+) -> DbResult<BTreeSet<RostraId>> {
+    let mut mentions = BTreeSet::new();
+    for mention in content
+        .djot_content
+        .as_deref()
+        .map(rostra_djot::mention::extract_mentions)
+        .unwrap_or_default()
+    {
+        let id = match mention {
+            RostraIdLink::Full(id) => Some(id),
+            RostraIdLink::Short(short_id) => ids_full::resolve_tx(tx, short_id)?,
+        };
+        if let Some(id) = id.filter(|id| *id != author) {
+            mentions.insert(id);
+        }
+    }
+    Ok(mentions.into_iter().take(MAX_MENTIONS_PER_POST).collect())
+}
+
+impl Database {
+    pub(crate) fn index_social_post_mentions_tx(
+        author: RostraId,
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content: &content_kind::SocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mentions = social_post_mentions_tx(author, content, tx)?;
+        let mut table = tx.open_table(&social_posts_by_mention::TABLE)?;
+        for id in mentions {
+            table.insert(&(id, ts, event_id), &())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unindex_social_post_mentions_tx(
+        author: RostraId,
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content: &content_kind::SocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mentions = social_post_mentions_tx(author, content, tx)?;
+        let mut table = tx.open_table(&social_posts_by_mention::TABLE)?;
+        for id in mentions {
+            table.remove(&(id, ts, event_id))?;
+        }
+        Ok(())
+    }
+
+    /// Index the mentions of all stored social posts that still have their
+    /// content
+    pub(crate) fn backfill_social_posts_by_mention_tx(tx: &WriteTransactionCtx) -> DbResult<()> {
+        let count =
+            Self::for_each_stored_social_post_tx(tx, |author, event_id, ts, social_post| {
+                Self::index_social_post_mentions_tx(author, event_id, ts, social_post, tx)
+            })?;
+        info!(target: LOG_TARGET, count, "Backfilled social post mention index");
+        Ok(())
+    }
+
+    /// List social posts @mentioning `id` passing `filter_fn`, newest first
+    ///
+    /// Replaced posts, posts without content and posts of blocked identities
+    /// are skipped. A page can have fewer than `limit` posts (even none) while
+    /// the returned cursor is `Some`, if [`SOCIAL_POSTS_BY_MENTION_SCAN_MAX`]
+    /// index rows were scanned first; pass the cursor back to continue.
+    pub async fn paginate_social_posts_mentioning_rev(
+        &self,
+        id: RostraId,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+        filter_fn: impl Fn(&SocialPostRecord<content_kind::SocialPost>) -> bool + Send + 'static,
+    ) -> (
+        Vec<SocialPostRecord<content_kind::SocialPost>>,
+        Option<EventPaginationCursor>,
+    ) {
+        self.read_with(|tx| {
+            let start = (id, Timestamp::ZERO, ShortEventId::ZERO);
+            let end = match cursor {
+                Some(cursor) => (id, cursor.ts, cursor.event_id),
+                None => (id, Timestamp::MAX, ShortEventId::MAX),
+            };
+
+            let social_posts_by_mention_table = tx.open_table(&social_posts_by_mention::TABLE)?;
+            let entries = social_posts_by_mention_table
+                .range(&start..=&end)?
+                .rev()
+                .map(|entry| {
+                    let (_, ts, event_id) = entry?.0.value();
+                    Ok((ts, event_id))
+                });
+            self.collect_indexed_social_posts_tx(
+                tx,
+                entries,
+                limit,
+                SOCIAL_POSTS_BY_MENTION_SCAN_MAX,
+                filter_fn,
+            )
+        })
+        .await
+        .expect("Storage error")
+    }
+}
@@ -0,0 +1,109 @@
+use rostra_core::ShortEventId;
+use rostra_core::id::{RostraId, RostraIdSecretKey, ToShort as _};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::tests::fixtures::{deletion, social_post};
+use crate::tests::temp_db_rng;
+
+async fn mentioning(db: &Database, id: RostraId) -> Vec<ShortEventId> {
+    db.paginate_social_posts_mentioning_rev(id, None, 100, |_| true)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+/// Test: posts are listed by the identities they mention, newest first, with
+/// short references resolved against known identities and the author's own
+/// mentions left out.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn posts_are_listed_by_mentioned_identity() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let author = RostraIdSecretKey::generate();
+    let known = RostraIdSecretKey::generate();
+    let unknown = RostraIdSecretKey::generate().id();
+
+    let known_post = social_post(known, 50, None, None, "Hello");
+    db.process_event_with_content(&known_post).await;
+
+    let old = social_post(author, 100, None, None, &format!("Hi <rostra:{unknown}>"));
+    let new = social_post(
+        author,
+        200,
+        Some(old.event_id()),
+        None,
+        &format!(
+            "Hi <rostra:{}> and <rostra:{}>, from <rostra:{}>",
+            known.id().to_short(),
+            unknown,
+            author.id(),
+        ),
+    );
+    let unresolved = social_post(
+        author,
+        300,
+        Some(new.event_id()),
+        None,
+        &format!("Hi <rostra:{}>", unknown.to_short()),
+    );
+    for post in [&old, &new, &unresolved] {
+        db.process_event_with_content(post).await;
+    }
+    let [old, new] = [&old, &new].map(|post| post.event_id().to_short());
+
+    assert_eq!(mentioning(&db, unknown).await, vec![new, old]);
+    assert_eq!(mentioning(&db, known.id()).await, vec![new]);
+    assert!(mentioning(&db, author.id()).await.is_empty());
+
+    let (page, cursor) = db
+        .paginate_social_posts_mentioning_rev(unknown, None, 1, |_| true)
+        .await;
+    assert_eq!(
+        page.iter()
+            .map(|record| record.event_id)
+            .collect::<Vec<_>>(),
+        vec![new]
+    );
+    let (page, cursor) = db
+        .paginate_social_posts_mentioning_rev(unknown, cursor, 1, |_| true)
+        .await;
+    assert_eq!(
+        page.iter()
+            .map(|record| record.event_id)
+            .collect::<Vec<_>>(),
+        vec![old]
+    );
+    assert_eq!(cursor, None);
+
+    Ok(())
+}
+
+/// Test: deleted posts are removed from the mention index.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn deleted_posts_are_unindexed_from_mentions() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let author = RostraIdSecretKey::generate();
+    let mentioned = RostraIdSecretKey::generate().id();
+
+    let post = social_post(author, 100, None, None, &format!("Hi <rostra:{mentioned}>"));
+    db.process_event_with_content(&post).await;
+    assert_eq!(
+        mentioning(&db, mentioned).await,
+        vec![post.event_id().to_short()]
+    );
+
+    db.process_event(&deletion(author, 200, post.event_id(), post.event_id()))
+        .await;
+    assert!(mentioning(&db, mentioned).await.is_empty());
+    assert!(
+        db.read_with(|tx| Ok(tx
+            .open_table(&crate::social_posts_by_mention::TABLE)?
+            .first()?
+            .is_none()))
+            .await?
+    );
+
+    Ok(())
+}
@@ -76,8 +76,20 @@ pub(crate) struct LegacyEventReceivedRecord {
 ///
 /// Version 25 performs the single final rebuild for the stacked version-24
 /// schema changes. Version 26 adds the empty append-only SocialPost
-/// materialization feed without backfill.
-const DB_VER: u64 = 26;
+/// materialization feed without backfill. Version 27 adds the empty event
+/// header pruning tables. Version 28 adds the per-author event index, backfilled
+/// from `events`. Version 29 adds the social post full-text index, backfilled
+/// from stored post content. Version 30 adds the empty direct message tables
+/// without backfill. Version 31 adds the empty private post tables without
+/// backfill. Version 32 adds the empty self-block table without backfill.
+/// Version 33 adds the empty repost tables without backfill. Version 34 adds
+/// the empty partial event content table without backfill. Version 35 adds the
+/// empty poll vote tables without backfill. Version 36 adds the social post
+/// hashtag index, backfilled from stored post content. Version 37 adds the
+/// social post mention index, backfilled from stored post content. Version 38
+/// adds the empty identity successor table without backfill. Version 39 adds
+/// the empty device delegation table without backfill.
+const DB_VER: u64 = 39;
 
 /// Versions older than this require a total migration.
 ///
@@ -120,6 +132,31 @@ const MIGRATION_EVENT_SOURCES_TEMP_TABLE: &str = "_total_migration_event_sources
 const MIGRATION_SOCIAL_POST_MATERIALIZATIONS_TEMP_TABLE: &str =
     "_total_migration_social_post_materializations";
 
+/// First version with event header pruning tables.
+const DB_VER_EVENT_PRUNING: u64 = 27;
+
+/// First version with the per-author event index.
+const DB_VER_EVENTS_BY_AUTHOR: u64 = 28;
+
+/// First version with the social post full-text index.
+const DB_VER_SOCIAL_POSTS_SEARCH: u64 = 29;
+
+/// First version with the social post hashtag index.
+const DB_VER_SOCIAL_POSTS_BY_TAG: u64 = 36;
+
+/// First version with the social post mention index.
+const DB_VER_SOCIAL_POSTS_BY_MENTION: u64 = 37;
+
+/// Name of the temp table preserving the pruned DAG frontier.
+const MIGRATION_EVENTS_PRUNED_TEMP_TABLE: &str = "_total_migration_events_pruned";
+
+/// Name of the temp table preserving per-identity pruning checkpoints.
+const MIGRATION_EVENTS_PRUNED_CHECKPOINTS_TEMP_TABLE: &str =
+    "_total_migration_events_pruned_checkpoints";
+
+/// Name of the temp table preserving authors of pruned social posts.
+const MIGRATION_SOCIAL_POSTS_PRUNED_TEMP_TABLE: &str = "_total_migration_social_posts_pruned";
+
 impl Database {
     /// Check if there's a pending migration stash that needs reprocessing.
     ///
@@ -154,6 +191,9 @@ impl Database {
         tx.open_table(&crate::ids_followees::TABLE)?;
         tx.open_table(&crate::ids_follow_events::TABLE)?;
         tx.open_table(&crate::ids_unfollowed::TABLE)?;
+        tx.open_table(&crate::ids_self_blocks::TABLE)?;
+        tx.open_table(&crate::ids_successors::TABLE)?;
+        tx.open_table(&crate::ids_devices::TABLE)?;
         tx.open_table(&crate::ids_personas::TABLE)?;
         tx.open_table(&crate::ids_data_usage::TABLE)?;
         tx.open_table(&crate::ids_nodes::TABLE)?;
@@ -162,9 +202,13 @@ impl Database {
         tx.open_table(&crate::events_singletons_new::TABLE)?;
         tx.open_table(&crate::events_missing::TABLE)?;
         tx.open_table(&crate::events_by_time::TABLE)?;
+        tx.open_table(&crate::events_by_author::TABLE)?;
         tx.open_table(&crate::events_content_missing::TABLE)?;
+        tx.open_table(&crate::events_content_partial::TABLE)?;
         tx.open_table(&crate::events_self::TABLE)?;
         tx.open_table(&crate::events_heads::TABLE)?;
+        tx.open_table(&crate::events_pruned::TABLE)?;
+        tx.open_table(&crate::events_pruned_checkpoints::TABLE)?;
 
         tx.open_table(&crate::content_store::TABLE)?;
         tx.open_table(&crate::content_rc::TABLE)?;
@@ -181,13 +225,36 @@ impl Database {
         tx.open_table(&crate::social_posts_reactions::TABLE)?;
         tx.open_table(&crate::social_posts_replaced_by::TABLE)?;
         tx.open_table(&crate::social_posts_replaces::TABLE)?;
+        tx.open_table(&crate::social_posts_pruned::TABLE)?;
         tx.open_table(&crate::social_vote_sums::TABLE)?;
+        tx.open_table(&crate::social_poll_votes::TABLE)?;
+        tx.open_table(&crate::social_poll_vote_sums::TABLE)?;
         tx.open_table(&crate::social_news_rank_by_post_id::TABLE)?;
         tx.open_table(&crate::social_news_rank_by_score::TABLE)?;
         tx.open_table(&crate::social_news_rank_by_time::TABLE)?;
         tx.open_table(&crate::social_posts_self_mention::TABLE)?;
+        tx.open_table(&crate::social_posts_search_terms::TABLE)?;
+        tx.open_table(&crate::social_posts_by_tag::TABLE)?;
+        tx.open_table(&crate::social_posts_by_mention::TABLE)?;
+        tx.open_table(&crate::social_reposts_by_time::TABLE)?;
+        tx.open_table(&crate::social_reposts_by_original::TABLE)?;
+        tx.open_table(&crate::social_repost_counts::TABLE)?;
+        tx.open_table(&crate::social_reposts_missing_original::TABLE)?;
+        tx.open_table(&crate::social_direct_messages::TABLE)?;
+        tx.open_table(&crate::social_direct_conversations::TABLE)?;
+        tx.open_table(&crate::social_private_audience_keys::TABLE)?;
+        tx.open_table(&crate::social_private_audience_grants_pending::TABLE)?;
+        tx.open_table(&crate::social_private_audience_self::TABLE)?;
+        tx.open_table(&crate::social_private_posts_pending_key::TABLE)?;
+        tx.open_table(&crate::social_private_posts_by_time::TABLE)?;
 
         tx.open_table(&crate::shoutbox_posts_by_received_at::TABLE)?;
+
+        let extension_tx = crate::ExtensionWriteTransaction::new(tx);
+        extension_tx.open_table(&crate::content_filters::local_content_filters::TABLE)?;
+        extension_tx.open_table(&crate::derived_assets::local_derived_assets::TABLE)?;
+        extension_tx.open_table(&crate::drafts::local_post_drafts::TABLE)?;
+        extension_tx.open_table(&crate::drafts::local_scheduled_post_drafts::TABLE)?;
         Ok(())
     }
 
@@ -266,6 +333,22 @@ impl Database {
                     init_time_table.insert(&(), &Timestamp::now())?;
                 }
             }
+
+            // A total migration rebuilds these indices while replaying events.
+            if (DB_VER_REQUIRES_TOTAL_MIGRATION..DB_VER_EVENTS_BY_AUTHOR).contains(&cur_db_ver) {
+                Self::backfill_events_by_author_tx(dbtx)?;
+            }
+            if (DB_VER_REQUIRES_TOTAL_MIGRATION..DB_VER_SOCIAL_POSTS_SEARCH).contains(&cur_db_ver) {
+                Self::backfill_social_posts_search_tx(dbtx)?;
+            }
+            if (DB_VER_REQUIRES_TOTAL_MIGRATION..DB_VER_SOCIAL_POSTS_BY_TAG).contains(&cur_db_ver) {
+                Self::backfill_social_posts_by_tag_tx(dbtx)?;
+            }
+            if (DB_VER_REQUIRES_TOTAL_MIGRATION..DB_VER_SOCIAL_POSTS_BY_MENTION)
+                .contains(&cur_db_ver)
+            {
+                Self::backfill_social_posts_by_mention_tx(dbtx)?;
+            }
         }
 
         // Update version
@@ -343,6 +426,25 @@ impl Database {
                 &materializations_temp,
             )?;
         }
+        // Pruned history is not part of the stashed events, so what is known
+        // about it has to survive the rebuild.
+        if DB_VER_EVENT_PRUNING <= source_ver {
+            Self::copy_table_raw(
+                dbtx,
+                &crate::events_pruned::TABLE,
+                &Self::events_pruned_temp(),
+            )?;
+            Self::copy_table_raw(
+                dbtx,
+                &crate::events_pruned_checkpoints::TABLE,
+                &Self::events_pruned_checkpoints_temp(),
+            )?;
+            Self::copy_table_raw(
+                dbtx,
+                &crate::social_posts_pruned::TABLE,
+                &Self::social_posts_pruned_temp(),
+            )?;
+        }
 
         // Receipt timestamps and allocator values are disposable, but acquisition
         // provenance is stable local source metadata. Re-key it by event ID for
@@ -566,6 +668,11 @@ impl Database {
                 &crate::social_post_materializations::TABLE,
             )?;
         }
+        Self::copy_table_raw_if_exists(
+            dbtx,
+            &Self::social_posts_pruned_temp(),
+            &crate::social_posts_pruned::TABLE,
+        )?;
 
         Ok(())
     }
@@ -885,6 +992,27 @@ impl Database {
         drop(replaced_by);
         drop(replaces);
 
+        // Restore pruning state only after the replay, so that retained events
+        // older than the checkpoint are not rejected. Parents that the replay
+        // marked as missing are known pruned ones.
+        Self::copy_table_raw_if_exists(
+            dbtx,
+            &Self::events_pruned_checkpoints_temp(),
+            &crate::events_pruned_checkpoints::TABLE,
+        )?;
+        if Self::copy_table_raw_if_exists(
+            dbtx,
+            &Self::events_pruned_temp(),
+            &crate::events_pruned::TABLE,
+        )? {
+            let events_pruned = dbtx.open_table(&crate::events_pruned::TABLE)?;
+            let mut events_missing = dbtx.open_table(&crate::events_missing::TABLE)?;
+            for entry in events_pruned.range(..)? {
+                let (key, _) = entry?;
+                events_missing.remove(&key.value_try()?)?;
+            }
+        }
+
         // Verify migration results by counting entries in key tables
         let events_count = dbtx
             .as_raw()
@@ -927,6 +1055,12 @@ impl Database {
         dbtx.as_raw().delete_table(db_init_time_temp.as_raw())?;
         dbtx.as_raw().delete_table(replaced_by_temp.as_raw())?;
         dbtx.as_raw().delete_table(materializations_temp.as_raw())?;
+        dbtx.as_raw()
+            .delete_table(Self::events_pruned_temp().as_raw())?;
+        dbtx.as_raw()
+            .delete_table(Self::events_pruned_checkpoints_temp().as_raw())?;
+        dbtx.as_raw()
+            .delete_table(Self::social_posts_pruned_temp().as_raw())?;
         dbtx.as_raw().delete_table(source_ver_temp.as_raw())?;
         dbtx.as_raw().delete_table(event_sources_temp.as_raw())?;
         // Try to delete legacy temp table (may not exist)
@@ -946,6 +1080,40 @@ impl Database {
         Ok(())
     }
 
+    fn backfill_events_by_author_tx(dbtx: &WriteTransactionCtx) -> DbResult<()> {
+        let events_table = dbtx.open_table(&events::TABLE)?;
+        let mut events_by_author_table = dbtx.open_table(&crate::events_by_author::TABLE)?;
+
+        let mut count = 0u64;
+        for entry in events_table.range(..)? {
+            let (event_id, record) = entry?;
+            let record = record.value();
+            events_by_author_table.insert(
+                &(record.author(), record.timestamp(), event_id.value()),
+                &(),
+            )?;
+            count += 1;
+        }
+        info!(target: LOG_TARGET, count, "Backfilled per-author event index");
+        Ok(())
+    }
+
+    fn events_pruned_temp() -> redb_bincode::TableDefinition<'static, (RostraId, ShortEventId), ()>
+    {
+        redb_bincode::TableDefinition::new(MIGRATION_EVENTS_PRUNED_TEMP_TABLE)
+    }
+
+    fn events_pruned_checkpoints_temp()
+    -> redb_bincode::TableDefinition<'static, RostraId, crate::event::EventsPrunedCheckpointRecord>
+    {
+        redb_bincode::TableDefinition::new(MIGRATION_EVENTS_PRUNED_CHECKPOINTS_TEMP_TABLE)
+    }
+
+    fn social_posts_pruned_temp() -> redb_bincode::TableDefinition<'static, ShortEventId, RostraId>
+    {
+        redb_bincode::TableDefinition::new(MIGRATION_SOCIAL_POSTS_PRUNED_TEMP_TABLE)
+    }
+
     /// Copy a table's contents to another table (both must have compatible raw
     /// types).
     fn copy_table_raw<KS, VS, KD, VD>(
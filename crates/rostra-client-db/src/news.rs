@@ -13,8 +13,8 @@ use crate::{
     Database, DbResult, InvalidVoteSingletonProjectionSnafu, LOG_TARGET,
     MissingVoteSingletonProjectionSnafu, SocialNewsRankRecord, SocialVoteScore,
     SocialVoteSumRecord, content_store, events, events_content_state, events_singletons_new,
-    social_news_rank_by_post_id, social_news_rank_by_score, social_news_rank_by_time, social_posts,
-    social_vote_sums,
+    ids_self_blocks, social_news_rank_by_post_id, social_news_rank_by_score,
+    social_news_rank_by_time, social_posts, social_vote_sums,
 };
 
 pub(crate) const NEWS_MAX_AGE_SECS: u64 = 4 * 365 * 24 * 60 * 60;
@@ -350,12 +350,17 @@ impl Database {
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let vote_sums_table = tx.open_table(&social_vote_sums::TABLE)?;
             let rank_by_post_id_table = tx.open_table(&social_news_rank_by_post_id::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let (ret, cursor) = Self::paginate_table_rev(
                 &rank_by_score_table,
                 cursor.map(|c| (c.score, c.post_id)),
                 limit,
                 move |(_score, post_id), _| {
+                    if Self::is_self_blocked_tx(post_id.rostra_id(), &ids_self_blocks_table)? {
+                        return Ok(None);
+                    }
                     Self::news_post_record_for_id_tx(
                         post_id,
                         &events_table,
@@ -365,6 +370,11 @@ impl Database {
                         &vote_sums_table,
                         &rank_by_post_id_table,
                     )
+                    .map(|record| {
+                        record.filter(|record| {
+                            !content_filters.is_muted(record.post.author, &record.post.content)
+                        })
+                    })
                 },
             )?;
 
@@ -394,12 +404,17 @@ impl Database {
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let vote_sums_table = tx.open_table(&social_vote_sums::TABLE)?;
             let rank_by_post_id_table = tx.open_table(&social_news_rank_by_post_id::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let (ret, cursor) = Self::paginate_table_rev(
                 &rank_by_time_table,
                 cursor.map(|c| (c.ts, c.post_id)),
                 limit,
                 move |(_ts, post_id), _| {
+                    if Self::is_self_blocked_tx(post_id.rostra_id(), &ids_self_blocks_table)? {
+                        return Ok(None);
+                    }
                     Self::news_post_record_for_id_tx(
                         post_id,
                         &events_table,
@@ -409,6 +424,11 @@ impl Database {
                         &vote_sums_table,
                         &rank_by_post_id_table,
                     )
+                    .map(|record| {
+                        record.filter(|record| {
+                            !content_filters.is_muted(record.post.author, &record.post.content)
+                        })
+                    })
                 },
             )?;
 
@@ -0,0 +1,188 @@
+//! Polls and their votes.
+//!
+//! A [`content_kind::SocialPollVote`] is a singleton per voter and poll. The
+//! latest vote of every voter is projected into [`crate::social_poll_votes`],
+//! and the number of votes for every option kept in
+//! [`crate::social_poll_vote_sums`], like [`crate::social_vote_sums`] does for
+//! post votes.
+//!
+//! Votes cast once the poll closed, and votes for options the poll doesn't
+//! have, are ignored: when they arrive, if the poll is available locally, and
+//! otherwise when tallying.
+
+use std::collections::BTreeMap;
+
+use rostra_core::event::{EventAuxKey, EventExt as _, EventKind, content_kind};
+use rostra_core::id::RostraId;
+use rostra_core::{ExternalEventId, Timestamp};
+
+use crate::event_order::EventOrder;
+use crate::{
+    Database, DbResult, SocialPollVoteRecord, SocialPollVoteSumRecord, WriteTransactionCtx,
+    content_store, events, events_content_state, social_poll_vote_sums, social_poll_votes,
+};
+
+/// Does a vote for `option` at `ts` count in `poll`
+fn is_vote_valid(poll: &content_kind::SocialPoll, ts: Timestamp, option: Option<u32>) -> bool {
+    !poll.is_closed(ts)
+        && option.is_none_or(|option| usize::try_from(option).is_ok_and(|o| o < poll.options.len()))
+}
+
+impl Database {
+    pub(crate) fn social_poll_vote_aux_key(poll: ExternalEventId) -> EventAuxKey {
+        EventAuxKey::from_bytes(poll.event_id().to_bytes())
+    }
+
+    /// Apply a poll vote of `voter`, unless a later one was already applied
+    pub(crate) fn process_social_poll_vote_tx(
+        voter: RostraId,
+        event_order: EventOrder,
+        content: &content_kind::SocialPollVote,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let poll = content.poll;
+        let poll_content = Self::get_social_poll_tx(
+            poll,
+            &tx.open_table(&events::TABLE)?,
+            &tx.open_table(&events_content_state::TABLE)?,
+            &tx.open_table(&content_store::TABLE)?,
+        )?;
+        if poll_content.is_some_and(|poll_content| {
+            !is_vote_valid(&poll_content, event_order.timestamp(), content.option)
+        }) {
+            return Ok(());
+        }
+        let mut votes_table = tx.open_table(&social_poll_votes::TABLE)?;
+        let previous = votes_table.get(&(poll, voter))?.map(|g| g.value());
+        if previous
+            .is_some_and(|previous| event_order <= EventOrder::new(previous.ts, previous.event_id))
+        {
+            return Ok(());
+        }
+        votes_table.insert(
+            &(poll, voter),
+            &SocialPollVoteRecord {
+                ts: event_order.timestamp(),
+                event_id: event_order.event_id(),
+                option: content.option,
+            },
+        )?;
+
+        let previous_option = previous.and_then(|previous| previous.option);
+        if previous_option == content.option {
+            return Ok(());
+        }
+        let mut sums_table = tx.open_table(&social_poll_vote_sums::TABLE)?;
+        if let Some(option) = previous_option {
+            let previous_record = sums_table.get(&(poll, option))?.map(|g| g.value());
+            if let Some(mut record) = previous_record {
+                record.count = record.count.saturating_sub(1);
+                if record.count == 0 {
+                    sums_table.remove(&(poll, option))?;
+                } else {
+                    sums_table.insert(&(poll, option), &record)?;
+                }
+            }
+        }
+        if let Some(option) = content.option {
+            let mut record = sums_table
+                .get(&(poll, option))?
+                .map(|g| g.value())
+                .unwrap_or(SocialPollVoteSumRecord {
+                    last_vote_time: Timestamp::ZERO,
+                    count: 0,
+                });
+            record.last_vote_time = record.last_vote_time.max(event_order.timestamp());
+            record.count = record.count.saturating_add(1);
+            sums_table.insert(&(poll, option), &record)?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_social_poll_tx(
+        poll_id: ExternalEventId,
+        events_table: &impl events::ReadableTable,
+        events_content_state_table: &impl events_content_state::ReadableTable,
+        content_store_table: &impl content_store::ReadableTable,
+    ) -> DbResult<Option<content_kind::SocialPoll>> {
+        let Some(event) = Database::get_event_tx(poll_id.event_id(), events_table)? else {
+            return Ok(None);
+        };
+        if event.author() != poll_id.rostra_id() || event.kind() != EventKind::SOCIAL_POLL {
+            return Ok(None);
+        }
+        Ok(Database::get_event_content_full_tx(
+            poll_id.event_id(),
+            event.content_hash(),
+            events_content_state_table,
+            content_store_table,
+        )?
+        .and_then(|result| result.content().cloned())
+        .and_then(|content| content.deserialize_cbor().ok()))
+    }
+
+    /// The poll, if available locally
+    pub async fn get_social_poll(
+        &self,
+        poll_id: ExternalEventId,
+    ) -> Option<content_kind::SocialPoll> {
+        self.read_with(|tx| {
+            Self::get_social_poll_tx(
+                poll_id,
+                &tx.open_table(&events::TABLE)?,
+                &tx.open_table(&events_content_state::TABLE)?,
+                &tx.open_table(&content_store::TABLE)?,
+            )
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Number of valid votes for every option of the poll that got any
+    ///
+    /// Empty if the poll is not available locally.
+    pub async fn get_social_poll_tally(&self, poll_id: ExternalEventId) -> BTreeMap<u32, u64> {
+        self.read_with(|tx| {
+            let Some(poll) = Self::get_social_poll_tx(
+                poll_id,
+                &tx.open_table(&events::TABLE)?,
+                &tx.open_table(&events_content_state::TABLE)?,
+                &tx.open_table(&content_store::TABLE)?,
+            )?
+            else {
+                return Ok(BTreeMap::new());
+            };
+            let mut tally = BTreeMap::new();
+            for entry in tx
+                .open_table(&social_poll_votes::TABLE)?
+                .range(&(poll_id, RostraId::ZERO)..=&(poll_id, RostraId::MAX))?
+            {
+                let vote = entry?.1.value();
+                if let Some(option) = vote.option
+                    && is_vote_valid(&poll, vote.ts, vote.option)
+                {
+                    *tally.entry(option).or_default() += 1;
+                }
+            }
+            Ok(tally)
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Option the `voter` currently votes for in the poll
+    pub async fn get_social_poll_vote(
+        &self,
+        voter: RostraId,
+        poll_id: ExternalEventId,
+    ) -> Option<u32> {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&social_poll_votes::TABLE)?
+                .get(&(poll_id, voter))?
+                .and_then(|g| g.value().option))
+        })
+        .await
+        .expect("Storage error")
+    }
+}
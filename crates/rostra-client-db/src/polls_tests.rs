@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use rostra_core::event::content_kind::{self, EventContentKind};
+use rostra_core::event::{Event, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_core::{ExternalEventId, ShortEventId};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::tests::temp_db;
+
+fn event(
+    secret: RostraIdSecretKey,
+    content: &impl EventContentKind,
+    timestamp: i64,
+    parent_marker: u8,
+) -> VerifiedEventContent {
+    let (event, content) = Event::builder(content)
+        .author(secret.id())
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(ShortEventId::from_bytes([parent_marker; 16]))
+        .build()
+        .expect("valid content");
+    let event = VerifiedEvent::verify_signed(secret.id(), event.signed_by(secret))
+        .expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn vote(
+    secret: RostraIdSecretKey,
+    poll_id: ExternalEventId,
+    option: Option<u32>,
+    timestamp: i64,
+) -> VerifiedEventContent {
+    event(
+        secret,
+        &content_kind::SocialPollVote::new(poll_id, option),
+        timestamp,
+        timestamp as u8,
+    )
+}
+
+async fn tally(db: &Database, poll_id: ExternalEventId) -> Vec<(u32, u64)> {
+    db.get_social_poll_tally(poll_id)
+        .await
+        .into_iter()
+        .collect()
+}
+
+/// Test: poll votes are tallied per option, with only the latest vote of
+/// every voter counting, regardless of the order they arrive in.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn poll_votes_are_tallied() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let poll = content_kind::SocialPoll {
+        question: "Lunch?".into(),
+        options: vec!["Pizza".into(), "Sushi".into(), "Salad".into()],
+        closes_at: None,
+    };
+    let poll_event = event(alice, &poll, 10, 1);
+    db.process_event_with_content(&poll_event).await;
+    let poll_id = ExternalEventId::new(alice.id(), poll_event.event_id().to_short());
+    assert_eq!(db.get_social_poll(poll_id).await, Some(poll));
+    assert_eq!(
+        db.get_social_poll(ExternalEventId::new(bob.id(), poll_id.event_id()))
+            .await,
+        None
+    );
+
+    db.process_event_with_content(&vote(alice, poll_id, Some(0), 20))
+        .await;
+    db.process_event_with_content(&vote(bob, poll_id, Some(1), 20))
+        .await;
+    assert_eq!(tally(&db, poll_id).await, vec![(0, 1), (1, 1)]);
+
+    // Bob changes his mind, and then an older vote of his arrives late
+    db.process_event_with_content(&vote(bob, poll_id, Some(0), 40))
+        .await;
+    db.process_event_with_content(&vote(bob, poll_id, Some(2), 30))
+        .await;
+    assert_eq!(tally(&db, poll_id).await, vec![(0, 2)]);
+    assert_eq!(db.get_social_poll_vote(bob.id(), poll_id).await, Some(0));
+
+    // Alice retracts her vote
+    db.process_event_with_content(&vote(alice, poll_id, None, 50))
+        .await;
+    assert_eq!(tally(&db, poll_id).await, vec![(0, 1)]);
+    assert_eq!(db.get_social_poll_vote(alice.id(), poll_id).await, None);
+
+    assert_eq!(
+        db.get_social_poll_tally(ExternalEventId::new(bob.id(), ShortEventId::ZERO))
+            .await,
+        BTreeMap::new()
+    );
+
+    Ok(())
+}
+
+/// Test: votes cast after the poll closed don't count, nor change earlier
+/// votes, whether they arrive after the poll or before it.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn late_poll_votes_are_ignored() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let poll = content_kind::SocialPoll {
+        question: "Lunch?".into(),
+        options: vec!["Pizza".into(), "Sushi".into()],
+        closes_at: Some(100.into()),
+    };
+    let poll_event = event(alice, &poll, 10, 1);
+    let poll_id = ExternalEventId::new(alice.id(), poll_event.event_id().to_short());
+
+    // Bob's late vote arrives before the poll
+    db.process_event_with_content(&vote(bob, poll_id, Some(1), 150))
+        .await;
+    db.process_event_with_content(&poll_event).await;
+    db.process_event_with_content(&vote(alice, poll_id, Some(0), 20))
+        .await;
+    assert_eq!(tally(&db, poll_id).await, vec![(0, 1)]);
+
+    // Alice changes her vote after the poll closed
+    db.process_event_with_content(&vote(alice, poll_id, Some(1), 120))
+        .await;
+    assert_eq!(tally(&db, poll_id).await, vec![(0, 1)]);
+    assert_eq!(db.get_social_poll_vote(alice.id(), poll_id).await, Some(0));
+
+    Ok(())
+}
+
+/// Test: votes for options the poll doesn't have don't count.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn out_of_range_poll_votes_are_ignored() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let poll = content_kind::SocialPoll {
+        question: "Lunch?".into(),
+        options: vec!["Pizza".into(), "Sushi".into()],
+        closes_at: None,
+    };
+    let poll_event = event(alice, &poll, 10, 1);
+    let poll_id = ExternalEventId::new(alice.id(), poll_event.event_id().to_short());
+
+    // Bob's vote arrives before the poll
+    db.process_event_with_content(&vote(bob, poll_id, Some(7), 20))
+        .await;
+    db.process_event_with_content(&poll_event).await;
+    db.process_event_with_content(&vote(alice, poll_id, Some(1), 20))
+        .await;
+    db.process_event_with_content(&vote(alice, poll_id, Some(2), 30))
+        .await;
+    assert_eq!(tally(&db, poll_id).await, vec![(1, 1)]);
+    assert_eq!(db.get_social_poll_vote(alice.id(), poll_id).await, Some(1));
+
+    Ok(())
+}
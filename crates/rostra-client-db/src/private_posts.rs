@@ -0,0 +1,331 @@
+//! Private posts, readable only by the audience of their author.
+//!
+//! A [`content_kind::PrivateSocialPost`] is decrypted as soon as the audience
+//! key it was sealed with is known, and indexed in
+//! [`crate::social_private_posts_by_time`]. Until then it waits in
+//! [`crate::social_private_posts_pending_key`].
+//!
+//! Audience keys granted to the local identity can only be unwrapped with the
+//! identity secret, which the database never keeps. They wait in
+//! [`crate::social_private_audience_grants_pending`] until a client holding
+//! the secret calls [`Database::unwrap_private_audience_grants`].
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use rostra_core::event::{
+    EventContentKind as _, EventExt as _, PrivateAudienceKey, PrivateAudienceKeyId, content_kind,
+};
+use rostra_core::id::{RostraId, RostraIdSecretKey, ToShort as _};
+use rostra_core::{ShortEventId, Timestamp};
+use rostra_util_error::FmtCompact as _;
+use tokio::sync::Notify;
+use tracing::debug;
+
+use crate::event::ContentStoreRecord;
+use crate::social::{EventPaginationCursor, SocialPostRecord};
+use crate::{
+    Database, DbResult, LOG_TARGET, PrivateAudienceRecord, WriteTransactionCtx, content_store,
+    events, events_content_state, social_private_audience_grants_pending,
+    social_private_audience_keys, social_private_audience_self, social_private_posts_by_time,
+    social_private_posts_pending_key,
+};
+
+impl Database {
+    pub(crate) fn insert_private_audience_key_grant_tx(
+        &self,
+        author: RostraId,
+        ts: Timestamp,
+        event_id: ShortEventId,
+        content: &content_kind::PrivateAudienceKeyGrant,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        if author == self.self_id {
+            Database::insert_latest_value_tx(
+                ts,
+                &(),
+                PrivateAudienceRecord {
+                    event_id,
+                    key_id: content.key_id,
+                    members: content.members().collect(),
+                },
+                &mut tx.open_table(&social_private_audience_self::TABLE)?,
+            )?;
+        }
+
+        let Some(grant) = content.grant_for(self.self_id) else {
+            return Ok(());
+        };
+        let key = (author, content.key_id);
+        if tx
+            .open_table(&social_private_audience_keys::TABLE)?
+            .get(&key)?
+            .is_some()
+        {
+            return Ok(());
+        }
+        tx.open_table(&social_private_audience_grants_pending::TABLE)?
+            .insert(&key, grant)?;
+
+        if tx.commit_hooks_enabled() {
+            let notify = self.private_audience_grants_notify.clone();
+            tx.on_commit(move || notify.notify_one());
+        }
+        Ok(())
+    }
+
+    pub(crate) fn insert_private_social_post_tx(
+        author: RostraId,
+        ts: Timestamp,
+        event_id: ShortEventId,
+        content: &content_kind::PrivateSocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let key = tx
+            .open_table(&social_private_audience_keys::TABLE)?
+            .get(&(author, content.key_id))?
+            .map(|g| g.value());
+        match key {
+            Some(key) => {
+                Self::insert_decrypted_private_post_tx(ts, event_id, content, key, tx)?;
+            }
+            None => {
+                tx.open_table(&social_private_posts_pending_key::TABLE)?
+                    .insert(&(author, content.key_id, event_id), &())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_private_social_post_tx(
+        author: RostraId,
+        ts: Timestamp,
+        event_id: ShortEventId,
+        content: &content_kind::PrivateSocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        tx.open_table(&social_private_posts_by_time::TABLE)?
+            .remove(&(ts, event_id))?;
+        tx.open_table(&social_private_posts_pending_key::TABLE)?
+            .remove(&(author, content.key_id, event_id))?;
+        Ok(())
+    }
+
+    fn insert_decrypted_private_post_tx(
+        ts: Timestamp,
+        event_id: ShortEventId,
+        content: &content_kind::PrivateSocialPost,
+        key: PrivateAudienceKey,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let post = match content.open(key) {
+            Ok(post) => post,
+            Err(err) => {
+                debug!(target: LOG_TARGET, %event_id, err = %err.fmt_compact(), "Ignoring undecryptable private post");
+                return Ok(());
+            }
+        };
+        let Ok(post) = post.serialize_cbor() else {
+            debug!(target: LOG_TARGET, %event_id, "Ignoring invalid private post");
+            return Ok(());
+        };
+        tx.open_table(&social_private_posts_by_time::TABLE)?
+            .insert(&(ts, event_id), &ContentStoreRecord(Cow::Owned(post)))?;
+        Ok(())
+    }
+
+    /// Store an audience `key` of `author` and decrypt the posts waiting for it
+    fn insert_private_audience_key_tx(
+        author: RostraId,
+        key: PrivateAudienceKey,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let key_id = key.id();
+        tx.open_table(&social_private_audience_keys::TABLE)?
+            .insert(&(author, key_id), &key)?;
+        tx.open_table(&social_private_audience_grants_pending::TABLE)?
+            .remove(&(author, key_id))?;
+
+        let mut pending_table = tx.open_table(&social_private_posts_pending_key::TABLE)?;
+        let events_table = tx.open_table(&events::TABLE)?;
+        let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+        let content_store_table = tx.open_table(&content_store::TABLE)?;
+
+        let pending = pending_table
+            .range(&(author, key_id, ShortEventId::ZERO)..=&(author, key_id, ShortEventId::MAX))?
+            .map(|entry| Ok(entry?.0.value().2))
+            .collect::<DbResult<Vec<_>>>()?;
+
+        for event_id in pending {
+            pending_table.remove(&(author, key_id, event_id))?;
+
+            let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                continue;
+            };
+            if Database::get_event_content_state_tx(event_id, &events_content_state_table)?
+                .is_some()
+            {
+                continue;
+            }
+            let Some(ContentStoreRecord(content)) = content_store_table
+                .get(&event.content_hash())?
+                .map(|g| g.value())
+            else {
+                continue;
+            };
+            let Ok(content) = content.deserialize_cbor::<content_kind::PrivateSocialPost>() else {
+                continue;
+            };
+            Self::insert_decrypted_private_post_tx(event.timestamp(), event_id, &content, key, tx)?;
+        }
+        Ok(())
+    }
+
+    /// Store a new audience key of the local identity
+    ///
+    /// Called before publishing the grant of a new key, so the local identity
+    /// can seal posts with it right away.
+    pub async fn insert_self_private_audience_key(&self, key: PrivateAudienceKey) -> DbResult<()> {
+        self.write_with(|tx| Self::insert_private_audience_key_tx(self.self_id, key, tx))
+            .await
+    }
+
+    /// Unwrap all the audience keys granted to the local identity
+    ///
+    /// Grants that fail to unwrap are dropped. Returns the number of unwrapped
+    /// keys.
+    pub async fn unwrap_private_audience_grants(
+        &self,
+        id_secret: RostraIdSecretKey,
+    ) -> DbResult<usize> {
+        debug_assert_eq!(id_secret.id(), self.self_id);
+        self.write_with(|tx| {
+            let pending = tx
+                .open_table(&social_private_audience_grants_pending::TABLE)?
+                .range(..)?
+                .map(|entry| {
+                    let (k, v) = entry?;
+                    Ok((k.value(), v.value()))
+                })
+                .collect::<DbResult<Vec<_>>>()?;
+
+            let mut unwrapped = 0;
+            for ((author, key_id), grant) in pending {
+                match grant.unwrap(id_secret, author) {
+                    Ok(key) if key.id() == key_id => {
+                        Self::insert_private_audience_key_tx(author, key, tx)?;
+                        unwrapped += 1;
+                    }
+                    res => {
+                        debug!(
+                            target: LOG_TARGET,
+                            author = %author.to_short(),
+                            %key_id,
+                            err = ?res.err().map(|err| err.fmt_compact().to_string()),
+                            "Dropping invalid audience key grant"
+                        );
+                        tx.open_table(&social_private_audience_grants_pending::TABLE)?
+                            .remove(&(author, key_id))?;
+                    }
+                }
+            }
+            Ok(unwrapped)
+        })
+        .await
+    }
+
+    /// Current audience of the local identity's private posts
+    pub async fn get_private_audience(&self) -> Option<PrivateAudienceRecord> {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&social_private_audience_self::TABLE)?
+                .get(&())?
+                .map(|g| g.value().inner))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// When the current audience of the local identity was set
+    pub async fn get_private_audience_ts(&self) -> Option<Timestamp> {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&social_private_audience_self::TABLE)?
+                .get(&())?
+                .map(|g| g.value().ts))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Audience key `key_id` of `author`, if known
+    pub async fn get_private_audience_key(
+        &self,
+        author: RostraId,
+        key_id: PrivateAudienceKeyId,
+    ) -> Option<PrivateAudienceKey> {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&social_private_audience_keys::TABLE)?
+                .get(&(author, key_id))?
+                .map(|g| g.value()))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Paginate decrypted private posts, newest first
+    ///
+    /// The returned cursor is the first post not returned yet.
+    pub async fn paginate_private_posts_rev(
+        &self,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+    ) -> (
+        Vec<SocialPostRecord<content_kind::SocialPost>>,
+        Option<EventPaginationCursor>,
+    ) {
+        self.read_with(|tx| {
+            let private_posts_table = tx.open_table(&social_private_posts_by_time::TABLE)?;
+            let events_table = tx.open_table(&events::TABLE)?;
+
+            let end = cursor.unwrap_or(EventPaginationCursor::MAX);
+            let mut ret = vec![];
+            for entry in private_posts_table
+                .range(&(Timestamp::ZERO, ShortEventId::ZERO)..=&(end.ts, end.event_id))?
+                .rev()
+            {
+                let (k, v) = entry?;
+                let (ts, event_id) = k.value();
+                if limit <= ret.len() {
+                    return Ok((ret, Some(EventPaginationCursor { ts, event_id })));
+                }
+
+                let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                    continue;
+                };
+                let ContentStoreRecord(content) = v.value();
+                let Ok(post) = content.deserialize_cbor::<content_kind::SocialPost>() else {
+                    continue;
+                };
+                ret.push(SocialPostRecord {
+                    ts,
+                    event_id,
+                    author: event.author(),
+                    reply_to: post.reply_to,
+                    content: post,
+                    reply_count: 0,
+                });
+            }
+            Ok((ret, None))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Get a handle to the notification of new audience key grants waiting to
+    /// be unwrapped with [`Self::unwrap_private_audience_grants`].
+    pub fn private_audience_grants_notify(&self) -> Arc<Notify> {
+        self.private_audience_grants_notify.clone()
+    }
+}
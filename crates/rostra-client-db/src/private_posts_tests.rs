@@ -0,0 +1,182 @@
+use std::collections::BTreeSet;
+
+use rostra_core::event::content_kind::{
+    EventContentKind as _, PrivateAudienceKey, PrivateAudienceKeyGrant, PrivatePostNonce,
+    PrivateSocialPost, SocialPost,
+};
+use rostra_core::event::{Event, EventContentRaw, EventKind, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_core::{EventId, ShortEventId};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::tests::temp_db;
+
+fn event_with_content(
+    secret: RostraIdSecretKey,
+    kind: EventKind,
+    timestamp: i64,
+    parent_prev: Option<EventId>,
+    content: EventContentRaw,
+) -> VerifiedEventContent {
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(kind)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .content(&content)
+        .maybe_parent_prev(parent_prev.map(Into::into))
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn audience_grant(
+    secret: RostraIdSecretKey,
+    key: PrivateAudienceKey,
+    members: &[RostraIdSecretKey],
+    timestamp: i64,
+    parent_prev: Option<EventId>,
+) -> VerifiedEventContent {
+    let content = PrivateAudienceKeyGrant::new(secret, key, members.iter().map(|m| m.id()))
+        .expect("can wrap")
+        .serialize_cbor()
+        .expect("grant must serialize");
+    event_with_content(
+        secret,
+        EventKind::PRIVATE_AUDIENCE_KEY,
+        timestamp,
+        parent_prev,
+        content,
+    )
+}
+
+fn private_post(
+    secret: RostraIdSecretKey,
+    key: PrivateAudienceKey,
+    timestamp: i64,
+    parent_prev: Option<EventId>,
+    text: &str,
+) -> VerifiedEventContent {
+    let content = PrivateSocialPost::seal_with_nonce(
+        key,
+        PrivatePostNonce::from_bytes([timestamp as u8; 24]),
+        &SocialPost::new(text.to_owned(), None, BTreeSet::new()),
+    )
+    .expect("can seal")
+    .serialize_cbor()
+    .expect("private post must serialize");
+    event_with_content(
+        secret,
+        EventKind::PRIVATE_SOCIAL_POST,
+        timestamp,
+        parent_prev,
+        content,
+    )
+}
+
+async fn private_posts(db: &Database) -> Vec<(ShortEventId, String)> {
+    db.paginate_private_posts_rev(None, 100)
+        .await
+        .0
+        .into_iter()
+        .map(|record| {
+            (
+                record.event_id,
+                record.content.djot_content.unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// Test: a private post received before its audience key waits until the
+/// grant is unwrapped, posts for other audiences stay unreadable, and a
+/// deleted post disappears.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn private_posts_are_decrypted_once_key_is_unwrapped() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let key = PrivateAudienceKey::from_bytes([1; 32]);
+    let bob_key = PrivateAudienceKey::from_bytes([2; 32]);
+
+    let post = private_post(alice, key, 100, None, "For friends");
+    let grant = audience_grant(alice, key, &[own, bob], 50, None);
+    let bob_only = private_post(alice, bob_key, 150, Some(post.event_id()), "For Bob");
+    let bob_grant = audience_grant(alice, bob_key, &[bob], 140, Some(grant.event_id()));
+    for event in [&post, &bob_only, &grant, &bob_grant] {
+        db.process_event_with_content(event).await;
+    }
+
+    assert!(private_posts(&db).await.is_empty());
+    assert_eq!(db.unwrap_private_audience_grants(own).await?, 1);
+    assert_eq!(
+        db.get_private_audience_key(alice.id(), key.id()).await,
+        Some(key)
+    );
+    assert_eq!(
+        db.get_private_audience_key(alice.id(), bob_key.id()).await,
+        None
+    );
+    assert_eq!(
+        private_posts(&db).await,
+        vec![(post.event_id().to_short(), "For friends".to_owned())]
+    );
+
+    // Posts arriving after the key are decrypted right away
+    let later = private_post(alice, key, 200, Some(bob_only.event_id()), "Again");
+    db.process_event_with_content(&later).await;
+    assert_eq!(private_posts(&db).await.len(), 2);
+
+    let content = EventContentRaw::new(vec![]);
+    let delete = Event::builder_raw_content()
+        .author(alice.id())
+        .kind(EventKind::PRIVATE_SOCIAL_POST)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(300).expect("valid timestamp"))
+        .parent_prev(later.event_id().into())
+        .delete(later.event_id().into())
+        .content(&content)
+        .build()
+        .signed_by(alice);
+    db.process_event(&VerifiedEvent::verify_signed(alice.id(), delete)?)
+        .await;
+    assert_eq!(
+        private_posts(&db).await,
+        vec![(post.event_id().to_short(), "For friends".to_owned())]
+    );
+
+    Ok(())
+}
+
+/// Test: the latest grant of the local identity is its current audience.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn self_audience_follows_latest_grant() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let first_key = PrivateAudienceKey::from_bytes([1; 32]);
+    let second_key = PrivateAudienceKey::from_bytes([2; 32]);
+    let first = audience_grant(own, first_key, &[alice, bob], 100, None);
+    let second = audience_grant(own, second_key, &[alice], 200, Some(first.event_id()));
+    for event in [&second, &first] {
+        db.process_event_with_content(event).await;
+    }
+
+    let audience = db.get_private_audience().await.expect("audience set");
+    assert_eq!(audience.key_id, second_key.id());
+    let mut expected = vec![own.id(), alice.id()];
+    expected.sort_unstable();
+    assert_eq!(audience.members, expected);
+
+    assert_eq!(db.unwrap_private_audience_grants(own).await?, 2);
+    assert_eq!(
+        db.get_private_audience_key(own.id(), second_key.id()).await,
+        Some(second_key)
+    );
+
+    Ok(())
+}
@@ -14,8 +14,8 @@ use crate::{
     Database, DbError, IdSocialProfileRecord, IrohNodeRecord, LOG_TARGET, OverflowSnafu,
     SocialPostReceiptAlreadyIndexedSnafu, SocialPostReceiptMismatchSnafu,
     SocialPostsReactionsRecord, SocialPostsRepliesRecord, WriteTransactionCtx,
-    events_singletons_new, ids_followees, shoutbox_posts_by_received_at, social_posts,
-    social_posts_by_received_at, social_posts_by_time, social_posts_reactions,
+    events_singletons_new, ids_followees, ids_self_blocks, shoutbox_posts_by_received_at,
+    social_posts, social_posts_by_received_at, social_posts_by_time, social_posts_reactions,
     social_posts_received_at_keys, social_posts_replaced_by, social_posts_replaces,
     social_posts_replies, social_posts_self_mention,
 };
@@ -303,6 +303,10 @@ impl Database {
                         let self_wot = Database::compute_wot_tx(
                             self.self_id,
                             &self_followees,
+                            Database::read_self_blocks_tx(
+                                &tx.open_table(&ids_self_blocks::TABLE)
+                                    .map_err(DbError::from)?,
+                            )?,
                             &ids_followees_t,
                         )?;
                         let self_followees = Arc::new(self_followees);
@@ -323,6 +327,10 @@ impl Database {
                         let self_wot = Database::compute_wot_tx(
                             self.self_id,
                             &self_followees,
+                            Database::read_self_blocks_tx(
+                                &tx.open_table(&ids_self_blocks::TABLE)
+                                    .map_err(DbError::from)?,
+                            )?,
                             &ids_followees_t,
                         )?;
                         let self_wot = Arc::new(self_wot);
@@ -343,6 +351,43 @@ impl Database {
                 }
             }
             _ => match event_content.event.event.kind {
+                EventKind::BLOCK => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::Block>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    if author == self.self_id {
+                        self.insert_self_block_tx(event_order, &content, tx)?;
+                    }
+                }
+                EventKind::DEVICE_DELEGATION => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::DeviceDelegation>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    if event_content.event.is_singleton() {
+                        Self::insert_device_delegation_tx(
+                            &event_content.event,
+                            event_order,
+                            &content,
+                            tx,
+                        )?;
+                    }
+                }
+                EventKind::SUCCESSION => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::Succession>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    if event_content.event.is_singleton() {
+                        Self::insert_succession_tx(
+                            &event_content.event,
+                            event_order,
+                            &content,
+                            tx,
+                        )?;
+                    }
+                }
                 EventKind::NODE_ANNOUNCEMENT => {
                     let content = event_content
                         .deserialize_cbor::<content_kind::NodeAnnouncement>()
@@ -410,6 +455,7 @@ impl Database {
                             }
                         };
                     let event_id = event_content.event_id().to_short();
+                    Self::clear_missing_repost_original_tx(author, event_id, tx)?;
 
                     let mut social_post_by_time_tbl = tx
                         .open_table(&social_posts_by_time::TABLE)
@@ -417,6 +463,25 @@ impl Database {
                     social_post_by_time_tbl
                         .insert(&(event_content.timestamp(), event_id), &())
                         .map_err(DbError::from)?;
+                    Self::index_social_post_search_terms_tx(
+                        event_id,
+                        event_content.timestamp(),
+                        &content,
+                        tx,
+                    )?;
+                    Self::index_social_post_tags_tx(
+                        event_id,
+                        event_content.timestamp(),
+                        &content,
+                        tx,
+                    )?;
+                    Self::index_social_post_mentions_tx(
+                        author,
+                        event_id,
+                        event_content.timestamp(),
+                        &content,
+                        tx,
+                    )?;
 
                     // Also insert into received_at index for notification ordering.
                     // Use effective_received_at to push old synced posts to the
@@ -558,6 +623,35 @@ impl Database {
                         self.process_social_vote_tx(vote, author, event_order, tx)?;
                     }
                 }
+                EventKind::SOCIAL_POLL_VOTE => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::SocialPollVote>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    if event_content.event.is_singleton()
+                        && event_content.aux_key() == Self::social_poll_vote_aux_key(content.poll)
+                    {
+                        Self::process_social_poll_vote_tx(author, event_order, &content, tx)?;
+                    }
+                }
+                EventKind::SOCIAL_REPOST => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::SocialRepost>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    // Deleting a repost publishes a repost replacing it, which
+                    // is not a repost itself
+                    if event_content.event.is_delete_parent_aux_content_set() {
+                        return Ok(());
+                    }
+                    self.insert_social_repost_tx(
+                        author,
+                        event_content.timestamp(),
+                        event_content.event_id().to_short(),
+                        &content,
+                        tx,
+                    )?;
+                }
                 EventKind::SHOUTBOX => {
                     let content = event_content
                         .deserialize_cbor::<content_kind::Shoutbox>()
@@ -600,6 +694,46 @@ impl Database {
                         });
                     }
                 }
+                EventKind::DIRECT_MESSAGE => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::DirectMessage>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    if let Some(counterparty) = content.counterparty(author, self.self_id) {
+                        Self::insert_direct_message_tx(
+                            counterparty,
+                            event_content.timestamp(),
+                            event_content.event_id().to_short(),
+                            tx,
+                        )?;
+                    }
+                }
+                EventKind::PRIVATE_AUDIENCE_KEY => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::PrivateAudienceKeyGrant>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    self.insert_private_audience_key_grant_tx(
+                        author,
+                        event_content.timestamp(),
+                        event_content.event_id().to_short(),
+                        &content,
+                        tx,
+                    )?;
+                }
+                EventKind::PRIVATE_SOCIAL_POST => {
+                    let content = event_content
+                        .deserialize_cbor::<content_kind::PrivateSocialPost>()
+                        .boxed()
+                        .context(InvalidSnafu)?;
+                    Self::insert_private_social_post_tx(
+                        author,
+                        event_content.timestamp(),
+                        event_content.event_id().to_short(),
+                        &content,
+                        tx,
+                    )?;
+                }
                 _ => {}
             },
         };
@@ -627,6 +761,15 @@ impl Database {
         Ok(())
     }
 
+    /// Kinds of events [`Self::process_event_content_reverted_tx`] reverts
+    /// projections of
+    pub(crate) const REVERTED_EVENT_KINDS: [EventKind; 4] = [
+        EventKind::SOCIAL_POST,
+        EventKind::SOCIAL_REPOST,
+        EventKind::DIRECT_MESSAGE,
+        EventKind::PRIVATE_SOCIAL_POST,
+    ];
+
     pub(crate) fn process_event_content_reverted_tx(
         &self,
         event_content: &VerifiedEventContent,
@@ -654,6 +797,25 @@ impl Database {
                     ))
                     .map_err(DbError::from)?;
                 Self::remove_social_post_receipt_tx(tx, event_content.event_id().to_short())?;
+                Self::unindex_social_post_search_terms_tx(
+                    event_content.event_id().to_short(),
+                    event_content.timestamp(),
+                    &content,
+                    tx,
+                )?;
+                Self::unindex_social_post_tags_tx(
+                    event_content.event_id().to_short(),
+                    event_content.timestamp(),
+                    &content,
+                    tx,
+                )?;
+                Self::unindex_social_post_mentions_tx(
+                    event_content.author(),
+                    event_content.event_id().to_short(),
+                    event_content.timestamp(),
+                    &content,
+                    tx,
+                )?;
 
                 if content.news {
                     Self::remove_social_news_rank_tx(
@@ -723,6 +885,47 @@ impl Database {
                         .map_err(DbError::from)?;
                 }
             }
+            EventKind::SOCIAL_REPOST => {
+                let content = event_content
+                    .deserialize_cbor::<content_kind::SocialRepost>()
+                    .boxed()
+                    .context(InvalidSnafu)?;
+                Self::remove_social_repost_tx(
+                    event_content.timestamp(),
+                    event_content.event_id().to_short(),
+                    &content,
+                    tx,
+                )?;
+            }
+            EventKind::DIRECT_MESSAGE => {
+                let content = event_content
+                    .deserialize_cbor::<content_kind::DirectMessage>()
+                    .boxed()
+                    .context(InvalidSnafu)?;
+                if let Some(counterparty) =
+                    content.counterparty(event_content.author(), self.self_id)
+                {
+                    Self::remove_direct_message_tx(
+                        counterparty,
+                        event_content.timestamp(),
+                        event_content.event_id().to_short(),
+                        tx,
+                    )?;
+                }
+            }
+            EventKind::PRIVATE_SOCIAL_POST => {
+                let content = event_content
+                    .deserialize_cbor::<content_kind::PrivateSocialPost>()
+                    .boxed()
+                    .context(InvalidSnafu)?;
+                Self::remove_private_social_post_tx(
+                    event_content.author(),
+                    event_content.timestamp(),
+                    event_content.event_id().to_short(),
+                    &content,
+                    tx,
+                )?;
+            }
             _ => {}
         }
 
@@ -8,9 +8,9 @@ use crate::event::ContentStoreRecord;
 use crate::process_event_content_ops::ProcessEventError;
 use crate::{
     Database, DbResult, EventReceivedRecord, EventReceivedSource, InsertEventOutcome, LOG_TARGET,
-    ProcessEventState, WriteTransactionCtx, content_rc, content_store, events, events_by_time,
-    events_content_missing, events_content_state, events_heads, events_missing, events_received_at,
-    ids_data_usage, ids_full,
+    ProcessEventState, WriteTransactionCtx, content_rc, content_store, events, events_by_author,
+    events_by_time, events_content_missing, events_content_state, events_heads, events_missing,
+    events_pruned, events_pruned_checkpoints, events_received_at, ids_data_usage, ids_full,
 };
 
 impl Database {
@@ -50,7 +50,10 @@ impl Database {
         let mut events_content_missing_tbl = tx.open_table(&events_content_missing::TABLE)?;
         let mut events_missing_tbl = tx.open_table(&events_missing::TABLE)?;
         let mut events_heads_tbl = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_tbl = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_tbl = tx.open_table(&events_pruned_checkpoints::TABLE)?;
         let mut events_by_time_tbl = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_tbl = tx.open_table(&events_by_author::TABLE)?;
         let mut ids_full_tbl = ids_full::Table::open(tx)?;
         let mut ids_data_usage_tbl = tx.open_table(&ids_data_usage::TABLE)?;
 
@@ -60,7 +63,10 @@ impl Database {
             &mut events_tbl,
             &mut events_missing_tbl,
             &mut events_heads_tbl,
+            &mut events_pruned_tbl,
+            &events_pruned_checkpoints_tbl,
             &mut events_by_time_tbl,
+            &mut events_by_author_tbl,
             &mut events_content_state_tbl,
             &mut content_store_tbl,
             &mut content_rc_tbl,
@@ -68,6 +74,16 @@ impl Database {
             Some(&mut ids_data_usage_tbl),
         )?;
 
+        if matches!(insert_event_outcome, InsertEventOutcome::Pruned) {
+            debug!(
+                target: LOG_TARGET,
+                event_id = %event.event_id.to_short(),
+                author = %event.event.author.to_short(),
+                "Ignoring event from locally pruned history"
+            );
+            return Ok((insert_event_outcome, ProcessEventState::Pruned));
+        }
+
         if let InsertEventOutcome::Inserted {
             was_missing,
             is_deleted,
@@ -224,6 +240,7 @@ impl Database {
             } else {
                 match insert_event_outcome {
                     InsertEventOutcome::AlreadyPresent => ProcessEventState::Existing,
+                    InsertEventOutcome::Pruned => ProcessEventState::Pruned,
                     InsertEventOutcome::Inserted { is_deleted, .. } => {
                         if is_deleted {
                             ProcessEventState::Deleted
@@ -0,0 +1,373 @@
+//! Reposts (boosts) of posts.
+//!
+//! Every [`content_kind::SocialRepost`] is indexed by time in
+//! [`crate::social_reposts_by_time`], and by the reposted post in
+//! [`crate::social_reposts_by_original`], with the number of reposts of every
+//! post kept in [`crate::social_repost_counts`].
+//!
+//! Reposts usually reference posts of identities we don't follow, so their
+//! content is often missing. Such posts are tracked in
+//! [`crate::social_reposts_missing_original`] until fetched by the client.
+
+use std::sync::Arc;
+
+use rostra_core::event::{EventExt as _, SocialPost, content_kind};
+use rostra_core::id::RostraId;
+use rostra_core::{ExternalEventId, ShortEventId, Timestamp};
+use tokio::sync::Notify;
+use tracing::debug;
+
+use crate::event::ContentStoreRecord;
+use crate::social::{EventPaginationCursor, SocialPostRecord};
+use crate::{
+    Database, DbResult, LOG_TARGET, WriteTransactionCtx, content_store, events,
+    events_content_state, ids_self_blocks, social_posts, social_posts_replaces,
+    social_repost_counts, social_reposts_by_original, social_reposts_by_time,
+    social_reposts_missing_original,
+};
+
+/// Repost with the reposted post
+#[derive(Clone, Debug)]
+pub struct SocialRepostRecord {
+    pub ts: Timestamp,
+    pub event_id: ShortEventId,
+    /// Reposter
+    pub author: RostraId,
+    /// Quote comment
+    pub comment: Option<String>,
+    pub original: SocialPostRecord<SocialPost>,
+}
+
+/// Entry of a timeline mixing posts and reposts
+#[derive(Clone, Debug)]
+pub enum SocialTimelineRecord {
+    Post(SocialPostRecord<SocialPost>),
+    Repost(SocialRepostRecord),
+}
+
+impl SocialTimelineRecord {
+    /// Position of the entry in the timeline
+    pub fn cursor(&self) -> EventPaginationCursor {
+        match self {
+            SocialTimelineRecord::Post(post) => EventPaginationCursor {
+                ts: post.ts,
+                event_id: post.event_id,
+            },
+            SocialTimelineRecord::Repost(repost) => EventPaginationCursor {
+                ts: repost.ts,
+                event_id: repost.event_id,
+            },
+        }
+    }
+
+    /// The post shown: the post itself, or the reposted post
+    pub fn post(&self) -> &SocialPostRecord<SocialPost> {
+        match self {
+            SocialTimelineRecord::Post(post) => post,
+            SocialTimelineRecord::Repost(repost) => &repost.original,
+        }
+    }
+
+    pub fn repost(&self) -> Option<&SocialRepostRecord> {
+        match self {
+            SocialTimelineRecord::Post(_) => None,
+            SocialTimelineRecord::Repost(repost) => Some(repost),
+        }
+    }
+}
+
+impl Database {
+    pub(crate) fn insert_social_repost_tx(
+        &self,
+        author: RostraId,
+        ts: Timestamp,
+        event_id: ShortEventId,
+        content: &content_kind::SocialRepost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let original = content.original;
+        tx.open_table(&social_reposts_by_time::TABLE)?
+            .insert(&(ts, event_id), &original)?;
+        let is_new = tx
+            .open_table(&social_reposts_by_original::TABLE)?
+            .insert(&(original, event_id), &author)?
+            .is_none();
+        if is_new {
+            let mut counts_table = tx.open_table(&social_repost_counts::TABLE)?;
+            let count = counts_table.get(&original)?.map(|g| g.value()).unwrap_or(0);
+            counts_table.insert(&original, &count.saturating_add(1))?;
+        }
+
+        if !Self::is_post_content_available_tx(original, tx)? {
+            tx.open_table(&social_reposts_missing_original::TABLE)?
+                .insert(&original, &())?;
+            if tx.commit_hooks_enabled() {
+                let notify = self.repost_originals_missing_notify.clone();
+                tx.on_commit(move || notify.notify_one());
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn remove_social_repost_tx(
+        ts: Timestamp,
+        event_id: ShortEventId,
+        content: &content_kind::SocialRepost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let original = content.original;
+        tx.open_table(&social_reposts_by_time::TABLE)?
+            .remove(&(ts, event_id))?;
+        let removed = tx
+            .open_table(&social_reposts_by_original::TABLE)?
+            .remove(&(original, event_id))?
+            .is_some();
+        if !removed {
+            return Ok(());
+        }
+        let mut counts_table = tx.open_table(&social_repost_counts::TABLE)?;
+        let count = counts_table
+            .get(&original)?
+            .map(|g| g.value())
+            .unwrap_or(0)
+            .saturating_sub(1);
+        if count == 0 {
+            counts_table.remove(&original)?;
+            tx.open_table(&social_reposts_missing_original::TABLE)?
+                .remove(&original)?;
+        } else {
+            counts_table.insert(&original, &count)?;
+        }
+        Ok(())
+    }
+
+    /// Stop tracking a post as missing now that it arrived
+    pub(crate) fn clear_missing_repost_original_tx(
+        author: RostraId,
+        event_id: ShortEventId,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        tx.open_table(&social_reposts_missing_original::TABLE)?
+            .remove(&ExternalEventId::new(author, event_id))?;
+        Ok(())
+    }
+
+    fn is_post_content_available_tx(
+        post_id: ExternalEventId,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<bool> {
+        let Some(event) = Self::get_event_tx(post_id.event_id(), &tx.open_table(&events::TABLE)?)?
+        else {
+            return Ok(false);
+        };
+        if Self::get_event_content_state_tx(
+            post_id.event_id(),
+            &tx.open_table(&events_content_state::TABLE)?,
+        )?
+        .is_some()
+        {
+            return Ok(false);
+        }
+        Ok(tx
+            .open_table(&content_store::TABLE)?
+            .get(&event.content_hash())?
+            .is_some())
+    }
+
+    /// Number of reposts of a post
+    pub async fn get_social_repost_count(&self, post_id: ExternalEventId) -> u64 {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&social_repost_counts::TABLE)?
+                .get(&post_id)?
+                .map(|g| g.value())
+                .unwrap_or(0))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Repost of a post by the local identity, if any
+    pub async fn get_self_repost(&self, post_id: ExternalEventId) -> Option<ShortEventId> {
+        self.read_with(|tx| {
+            for entry in tx
+                .open_table(&social_reposts_by_original::TABLE)?
+                .range(&(post_id, ShortEventId::ZERO)..=&(post_id, ShortEventId::MAX))?
+            {
+                let (k, v) = entry?;
+                if v.value() == self.self_id {
+                    return Ok(Some(k.value().1));
+                }
+            }
+            Ok(None)
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Reposted posts whose content still needs to be fetched
+    pub async fn get_missing_repost_originals(&self, limit: usize) -> Vec<ExternalEventId> {
+        self.read_with(|tx| {
+            tx.open_table(&social_reposts_missing_original::TABLE)?
+                .range(..)?
+                .take(limit)
+                .map(|entry| Ok(entry?.0.value()))
+                .collect()
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Stop tracking a reposted post as missing, e.g. after fetching it
+    pub async fn remove_missing_repost_original(&self, post_id: ExternalEventId) -> DbResult<()> {
+        self.write_with(|tx| {
+            tx.open_table(&social_reposts_missing_original::TABLE)?
+                .remove(&post_id)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Get a handle to the notification of reposted posts that need to be
+    /// fetched
+    pub fn repost_originals_missing_notify(&self) -> Arc<Notify> {
+        self.repost_originals_missing_notify.clone()
+    }
+
+    /// Paginate reposts, newest first
+    ///
+    /// Reposts of posts not available locally are skipped. The returned cursor
+    /// is the first repost not returned yet.
+    pub async fn paginate_social_reposts_rev(
+        &self,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+        filter_fn: impl Fn(&SocialRepostRecord) -> bool + Send + 'static,
+    ) -> (Vec<SocialRepostRecord>, Option<EventPaginationCursor>) {
+        self.read_with(|tx| {
+            let events_table = tx.open_table(&events::TABLE)?;
+            let social_posts_table = tx.open_table(&social_posts::TABLE)?;
+            let social_reposts_by_time_table = tx.open_table(&social_reposts_by_time::TABLE)?;
+            let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+            let content_store_table = tx.open_table(&content_store::TABLE)?;
+            let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
+
+            let (ret, cursor) = Self::paginate_table_rev(
+                &social_reposts_by_time_table,
+                cursor.map(|c| (c.ts, c.event_id)),
+                limit,
+                move |(ts, event_id), original| {
+                    let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                        return Ok(None);
+                    };
+                    let author = event.author();
+                    if Self::is_self_blocked_tx(author, &ids_self_blocks_table)?
+                        || Self::is_self_blocked_tx(original.rostra_id(), &ids_self_blocks_table)?
+                    {
+                        return Ok(None);
+                    }
+                    if Database::get_event_content_state_tx(event_id, &events_content_state_table)?
+                        .is_some()
+                    {
+                        return Ok(None);
+                    }
+                    let Some(ContentStoreRecord(content)) = content_store_table
+                        .get(&event.content_hash())?
+                        .map(|g| g.value())
+                    else {
+                        return Ok(None);
+                    };
+                    let Ok(repost) = content.deserialize_cbor::<content_kind::SocialRepost>()
+                    else {
+                        debug!(target: LOG_TARGET, %event_id, "Content invalid");
+                        return Ok(None);
+                    };
+                    if content_filters.is_repost_muted(author, repost.comment.as_deref()) {
+                        return Ok(None);
+                    }
+
+                    let Some(original_event) =
+                        Database::get_event_tx(original.event_id(), &events_table)?
+                    else {
+                        return Ok(None);
+                    };
+                    if original_event.author() != original.rostra_id() {
+                        debug!(target: LOG_TARGET, %event_id, "Repost of a post with a wrong author");
+                        return Ok(None);
+                    }
+                    let Some(original_record) = Self::social_post_record_by_id_tx(
+                        original.event_id(),
+                        original_event.timestamp(),
+                        &events_table,
+                        &social_posts_table,
+                        &events_content_state_table,
+                        &content_store_table,
+                        &social_posts_replaces_table,
+                    )?
+                    else {
+                        return Ok(None);
+                    };
+                    if content_filters.is_muted(original_record.author, &original_record.content) {
+                        return Ok(None);
+                    }
+
+                    let record = SocialRepostRecord {
+                        ts,
+                        event_id,
+                        author,
+                        comment: repost.comment,
+                        original: original_record,
+                    };
+                    if !filter_fn(&record) {
+                        return Ok(None);
+                    }
+                    Ok(Some(record))
+                },
+            )?;
+
+            Ok((
+                ret,
+                cursor.map(|(ts, event_id)| EventPaginationCursor { ts, event_id }),
+            ))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Paginate posts and reposts together, newest first
+    ///
+    /// Both are paginated separately and merged; entries past the point where
+    /// either side stopped are left for the next page, so the returned cursor
+    /// works for both.
+    pub async fn paginate_social_posts_with_reposts_rev(
+        &self,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+        post_filter_fn: impl Fn(&SocialPostRecord<SocialPost>) -> bool + Send + 'static,
+        repost_filter_fn: impl Fn(&SocialRepostRecord) -> bool + Send + 'static,
+    ) -> (Vec<SocialTimelineRecord>, Option<EventPaginationCursor>) {
+        let (posts, posts_cursor) = self
+            .paginate_social_posts_rev(cursor, limit, post_filter_fn)
+            .await;
+        let (reposts, reposts_cursor) = self
+            .paginate_social_reposts_rev(cursor, limit, repost_filter_fn)
+            .await;
+
+        let mut merged: Vec<_> = posts
+            .into_iter()
+            .map(SocialTimelineRecord::Post)
+            .chain(reposts.into_iter().map(SocialTimelineRecord::Repost))
+            .collect();
+        merged.sort_by_key(|record| std::cmp::Reverse(record.cursor()));
+
+        let boundary = posts_cursor
+            .max(reposts_cursor)
+            .max(merged.get(limit).map(SocialTimelineRecord::cursor));
+        if let Some(boundary) = boundary {
+            merged.retain(|record| boundary < record.cursor());
+        }
+        (merged, boundary)
+    }
+}
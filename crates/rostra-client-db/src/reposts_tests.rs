@@ -0,0 +1,206 @@
+use std::collections::BTreeSet;
+
+use rostra_core::event::content_kind::{self, EventContentKind as _};
+use rostra_core::event::{
+    Event, EventContentRaw, EventExt as _, EventKind, VerifiedEvent, VerifiedEventContent,
+};
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_core::{EventId, ExternalEventId, ShortEventId};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::content_filters::ContentFilters;
+use crate::tests::temp_db;
+
+fn event_with_content(
+    secret: RostraIdSecretKey,
+    kind: EventKind,
+    timestamp: i64,
+    parent_marker: u8,
+    content: EventContentRaw,
+) -> VerifiedEventContent {
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(kind)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(ShortEventId::from_bytes([parent_marker; 16]))
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn post(secret: RostraIdSecretKey, timestamp: i64, parent_marker: u8) -> VerifiedEventContent {
+    let content = content_kind::SocialPost::new("Hello".to_owned(), None, BTreeSet::new())
+        .serialize_cbor()
+        .expect("valid post");
+    event_with_content(
+        secret,
+        EventKind::SOCIAL_POST,
+        timestamp,
+        parent_marker,
+        content,
+    )
+}
+
+fn repost(
+    secret: RostraIdSecretKey,
+    original: &VerifiedEventContent,
+    comment: Option<&str>,
+    timestamp: i64,
+    parent_marker: u8,
+) -> VerifiedEventContent {
+    let content = content_kind::SocialRepost::new(
+        ExternalEventId::new(original.author(), original.event_id().to_short()),
+        comment.map(ToOwned::to_owned),
+    )
+    .serialize_cbor()
+    .expect("valid repost");
+    event_with_content(
+        secret,
+        EventKind::SOCIAL_REPOST,
+        timestamp,
+        parent_marker,
+        content,
+    )
+}
+
+fn deletion(secret: RostraIdSecretKey, timestamp: i64, target: EventId) -> VerifiedEvent {
+    let content = EventContentRaw::new(vec![]);
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::SOCIAL_REPOST)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(target.into())
+        .delete(target.into())
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify")
+}
+
+fn post_id(event: &VerifiedEventContent) -> ExternalEventId {
+    ExternalEventId::new(event.author(), event.event_id().to_short())
+}
+
+async fn timeline(db: &Database) -> Vec<ShortEventId> {
+    db.paginate_social_posts_with_reposts_rev(None, 100, |_| true, |_| true)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.cursor().event_id)
+        .collect()
+}
+
+/// Test: reposts are counted per post, merged into the timeline with the
+/// reposted post, and deleting a repost undoes it.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn reposts_are_counted_and_merged_into_timeline() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let original = post(alice, 10, 1);
+    let bob_repost = repost(bob, &original, Some("Must read"), 20, 2);
+    let own_repost = repost(own, &original, None, 30, 3);
+    for event in [&original, &bob_repost, &own_repost] {
+        db.process_event_with_content(event).await;
+    }
+
+    assert_eq!(db.get_social_repost_count(post_id(&original)).await, 2);
+    assert_eq!(
+        db.get_self_repost(post_id(&original)).await,
+        Some(own_repost.event_id().to_short())
+    );
+    assert_eq!(
+        timeline(&db).await,
+        vec![
+            own_repost.event_id().to_short(),
+            bob_repost.event_id().to_short(),
+            original.event_id().to_short(),
+        ]
+    );
+
+    let (reposts, _) = db.paginate_social_reposts_rev(None, 100, |_| true).await;
+    let bob_record = &reposts[1];
+    assert_eq!(bob_record.author, bob.id());
+    assert_eq!(bob_record.comment.as_deref(), Some("Must read"));
+    assert_eq!(bob_record.original.event_id, original.event_id().to_short());
+
+    // Paging through one at a time yields the same entries
+    let mut paged = vec![];
+    let mut cursor = None;
+    loop {
+        let (page, next) = db
+            .paginate_social_posts_with_reposts_rev(cursor, 1, |_| true, |_| true)
+            .await;
+        paged.extend(page.into_iter().map(|record| record.cursor().event_id));
+        if next.is_none() {
+            break;
+        }
+        cursor = next;
+    }
+    assert_eq!(paged, timeline(&db).await);
+
+    db.set_content_filters(ContentFilters {
+        hide_reposts: true,
+        ..Default::default()
+    })
+    .await?;
+    assert_eq!(
+        timeline(&db).await,
+        vec![
+            own_repost.event_id().to_short(),
+            original.event_id().to_short(),
+        ]
+    );
+    db.set_content_filters(ContentFilters::default()).await?;
+
+    db.process_event(&deletion(own, 40, own_repost.event_id()))
+        .await;
+    assert_eq!(db.get_social_repost_count(post_id(&original)).await, 1);
+    assert_eq!(db.get_self_repost(post_id(&original)).await, None);
+    assert_eq!(
+        timeline(&db).await,
+        vec![
+            bob_repost.event_id().to_short(),
+            original.event_id().to_short(),
+        ]
+    );
+
+    Ok(())
+}
+
+/// Test: reposts of posts not available locally are tracked until the post
+/// arrives, and are left out of the timeline meanwhile.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn missing_repost_originals_are_tracked() -> BoxedErrorResult<()> {
+    let own = RostraIdSecretKey::generate();
+    let alice = RostraIdSecretKey::generate();
+    let bob = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db(own.id()).await?;
+
+    let original = post(alice, 10, 1);
+    let bob_repost = repost(bob, &original, None, 20, 2);
+    db.process_event_with_content(&bob_repost).await;
+
+    assert_eq!(
+        db.get_missing_repost_originals(10).await,
+        vec![post_id(&original)]
+    );
+    assert!(timeline(&db).await.is_empty());
+
+    db.process_event_with_content(&original).await;
+    assert!(db.get_missing_repost_originals(10).await.is_empty());
+    assert_eq!(
+        timeline(&db).await,
+        vec![
+            bob_repost.event_id().to_short(),
+            original.event_id().to_short(),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,251 @@
+//! Full-text search over social posts.
+//!
+//! Posts are indexed in [`crate::social_posts_search_terms`] when their
+//! content is processed, by every distinct term of their djot content, title
+//! and URL. A search walks the rows of one of the query terms newest first,
+//! and keeps posts that have rows for all the other terms too.
+
+use std::collections::BTreeSet;
+
+use rostra_core::event::{EventExt as _, content_kind};
+use rostra_core::id::RostraId;
+use rostra_core::{ShortEventId, Timestamp};
+use tracing::info;
+
+use crate::event::ContentStoreRecord;
+use crate::social::{EventPaginationCursor, SocialPostRecord};
+use crate::{
+    Database, DbResult, LOG_TARGET, WriteTransactionCtx, content_store, events,
+    events_content_state, social_posts, social_posts_by_time, social_posts_replaced_by,
+    social_posts_replaces, social_posts_search_terms,
+};
+
+/// Max number of index rows scanned by one search call
+pub const SOCIAL_POSTS_SEARCH_SCAN_MAX: usize = 4_096;
+
+/// Terms shorter than this (in chars) are not indexed
+const MIN_TERM_LEN: usize = 2;
+
+/// Terms longer than this (in chars) are not indexed
+const MAX_TERM_LEN: usize = 64;
+
+/// Max number of distinct terms indexed per post
+const MAX_TERMS_PER_POST: usize = 1024;
+
+/// Split `text` into lowercase alphanumeric search terms
+///
+/// Used both for indexing and for parsing queries, so they always agree.
+pub fn search_terms(text: &str) -> BTreeSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| (MIN_TERM_LEN..=MAX_TERM_LEN).contains(&term.chars().count()))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn social_post_search_terms(content: &content_kind::SocialPost) -> BTreeSet<String> {
+    let mut terms = BTreeSet::new();
+    for text in [
+        content.djot_content.as_deref(),
+        content.title.as_deref(),
+        content.url.as_ref().map(|url| url.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        terms.extend(search_terms(text));
+    }
+    terms.into_iter().take(MAX_TERMS_PER_POST).collect()
+}
+
+impl Database {
+    pub(crate) fn index_social_post_search_terms_tx(
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content: &content_kind::SocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut table = tx.open_table(&social_posts_search_terms::TABLE)?;
+        for term in social_post_search_terms(content) {
+            table.insert(&(term, ts, event_id), &())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unindex_social_post_search_terms_tx(
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content: &content_kind::SocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut table = tx.open_table(&social_posts_search_terms::TABLE)?;
+        for term in social_post_search_terms(content) {
+            table.remove(&(term, ts, event_id))?;
+        }
+        Ok(())
+    }
+
+    /// Call `f` with all stored social posts that still have their content
+    pub(crate) fn for_each_stored_social_post_tx(
+        tx: &WriteTransactionCtx,
+        mut f: impl FnMut(RostraId, ShortEventId, Timestamp, &content_kind::SocialPost) -> DbResult<()>,
+    ) -> DbResult<u64> {
+        let events_table = tx.open_table(&events::TABLE)?;
+        let social_posts_by_time_table = tx.open_table(&social_posts_by_time::TABLE)?;
+        let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+        let content_store_table = tx.open_table(&content_store::TABLE)?;
+
+        let mut count = 0u64;
+        for entry in social_posts_by_time_table.range(..)? {
+            let (ts, event_id) = entry?.0.value();
+            let Some(event) = Database::get_event_tx(event_id, &events_table)? else {
+                continue;
+            };
+            if Database::get_event_content_state_tx(event_id, &events_content_state_table)?
+                .is_some()
+            {
+                continue;
+            }
+            let Some(ContentStoreRecord(content)) = content_store_table
+                .get(&event.content_hash())?
+                .map(|g| g.value())
+            else {
+                continue;
+            };
+            let Ok(social_post) = content.deserialize_cbor::<content_kind::SocialPost>() else {
+                continue;
+            };
+            f(event.author(), event_id, ts, &social_post)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Index all stored social posts that still have their content
+    pub(crate) fn backfill_social_posts_search_tx(tx: &WriteTransactionCtx) -> DbResult<()> {
+        let count = Self::for_each_stored_social_post_tx(tx, |_, event_id, ts, social_post| {
+            Self::index_social_post_search_terms_tx(event_id, ts, social_post, tx)
+        })?;
+        info!(target: LOG_TARGET, count, "Backfilled social post search index");
+        Ok(())
+    }
+
+    /// Remove a post from the search, hashtag and mention indices before its
+    /// content is pruned
+    pub(crate) fn unindex_pruned_social_post_tx(
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content_store_table: &impl content_store::ReadableTable,
+        event: &crate::EventRecord,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let Some(ContentStoreRecord(content)) = content_store_table
+            .get(&event.content_hash())?
+            .map(|g| g.value())
+        else {
+            return Ok(());
+        };
+        let Ok(social_post) = content.deserialize_cbor::<content_kind::SocialPost>() else {
+            return Ok(());
+        };
+        Self::unindex_social_post_search_terms_tx(event_id, ts, &social_post, tx)?;
+        Self::unindex_social_post_tags_tx(event_id, ts, &social_post, tx)?;
+        Self::unindex_social_post_mentions_tx(event.author(), event_id, ts, &social_post, tx)
+    }
+
+    /// Search social posts containing all the terms of `query`, newest first
+    ///
+    /// Replaced posts and posts without content are skipped. A page can have
+    /// fewer than `limit` posts (even none) while the returned cursor is
+    /// `Some`, if [`SOCIAL_POSTS_SEARCH_SCAN_MAX`] index rows were scanned
+    /// first; pass the cursor back to continue.
+    pub async fn search_social_posts(
+        &self,
+        query: &str,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+    ) -> (
+        Vec<SocialPostRecord<content_kind::SocialPost>>,
+        Option<EventPaginationCursor>,
+    ) {
+        let terms = search_terms(query);
+        // Longer terms tend to be rarer, so scanning them is cheaper
+        let Some(scanned_term) = terms
+            .iter()
+            .max_by_key(|term| term.chars().count())
+            .cloned()
+        else {
+            return (vec![], None);
+        };
+        let other_terms: Vec<String> = terms
+            .into_iter()
+            .filter(|term| *term != scanned_term)
+            .collect();
+
+        self.read_with(|tx| {
+            let search_terms_table = tx.open_table(&social_posts_search_terms::TABLE)?;
+            let events_table = tx.open_table(&events::TABLE)?;
+            let social_posts_table = tx.open_table(&social_posts::TABLE)?;
+            let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+            let content_store_table = tx.open_table(&content_store::TABLE)?;
+            let social_posts_replaced_by_table = tx.open_table(&social_posts_replaced_by::TABLE)?;
+            let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
+
+            let start = (scanned_term.clone(), Timestamp::ZERO, ShortEventId::ZERO);
+            let end = match cursor {
+                Some(cursor) => (scanned_term.clone(), cursor.ts, cursor.event_id),
+                None => (scanned_term.clone(), Timestamp::MAX, ShortEventId::MAX),
+            };
+
+            let mut ret = vec![];
+            for (scanned, entry) in search_terms_table.range(&start..=&end)?.rev().enumerate() {
+                let (_, ts, event_id) = entry?.0.value();
+                if limit <= ret.len() || SOCIAL_POSTS_SEARCH_SCAN_MAX <= scanned {
+                    return Ok((ret, Some(EventPaginationCursor { ts, event_id })));
+                }
+
+                let mut matches_all = true;
+                for term in &other_terms {
+                    if search_terms_table
+                        .get(&(term.clone(), ts, event_id))?
+                        .is_none()
+                    {
+                        matches_all = false;
+                        break;
+                    }
+                }
+                if !matches_all {
+                    continue;
+                }
+
+                let Some(record) = Self::social_post_record_by_id_tx(
+                    event_id,
+                    ts,
+                    &events_table,
+                    &social_posts_table,
+                    &events_content_state_table,
+                    &content_store_table,
+                    &social_posts_replaces_table,
+                )?
+                else {
+                    continue;
+                };
+                if Self::is_social_post_replaced_tx(
+                    record.author,
+                    event_id,
+                    &social_posts_replaced_by_table,
+                )? {
+                    continue;
+                }
+                if content_filters.is_muted(record.author, &record.content) {
+                    continue;
+                }
+                ret.push(record);
+            }
+
+            Ok((ret, None))
+        })
+        .await
+        .expect("Storage error")
+    }
+}
@@ -0,0 +1,119 @@
+use rostra_core::ShortEventId;
+use rostra_core::event::content_kind;
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::search::search_terms;
+use crate::tests::fixtures::{deletion, social_post, social_post_with};
+use crate::tests::temp_db_rng;
+
+async fn search(db: &Database, query: &str) -> Vec<ShortEventId> {
+    db.search_social_posts(query, None, 100)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+#[test]
+fn search_terms_are_lowercase_words() {
+    assert_eq!(
+        search_terms("Hello, _World_! [Rust](https://rust-lang.org) a"),
+        ["hello", "https", "lang", "org", "rust", "world"]
+            .map(ToOwned::to_owned)
+            .into()
+    );
+}
+
+/// Test: posts are found by all query terms, newest first, including news
+/// titles, and pagination resumes where the previous page ended.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn search_social_posts_matches_all_terms_newest_first() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let secret = RostraIdSecretKey::generate();
+
+    let old = social_post(secret, 100, None, None, "Rust sync engine");
+    let new = social_post(
+        secret,
+        200,
+        Some(old.event_id()),
+        None,
+        "A new *sync* protocol for rust",
+    );
+    let news = social_post_with(
+        secret,
+        300,
+        Some(new.event_id()),
+        None,
+        content_kind::SocialPost::new_text("details inside".to_owned(), None, Default::default())
+            .with_news_fields(None, Some("Rust release".to_owned())),
+    );
+    for post in [&old, &new, &news] {
+        db.process_event_with_content(post).await;
+    }
+    let [old, new, news] = [&old, &new, &news].map(|post| post.event_id().to_short());
+
+    assert_eq!(search(&db, "RUST").await, vec![news, new, old]);
+    assert_eq!(search(&db, "sync rust").await, vec![new, old]);
+    assert_eq!(search(&db, "release").await, vec![news]);
+    assert!(search(&db, "rust missing").await.is_empty());
+    assert!(search(&db, "!!").await.is_empty());
+
+    let (page, cursor) = db.search_social_posts("rust", None, 2).await;
+    assert_eq!(
+        page.iter()
+            .map(|record| record.event_id)
+            .collect::<Vec<_>>(),
+        vec![news, new]
+    );
+    let (page, cursor) = db.search_social_posts("rust", cursor, 2).await;
+    assert_eq!(
+        page.iter()
+            .map(|record| record.event_id)
+            .collect::<Vec<_>>(),
+        vec![old]
+    );
+    assert_eq!(cursor, None);
+
+    Ok(())
+}
+
+/// Test: deleted posts are removed from the index, and replaced posts are
+/// only found through their latest version.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn search_social_posts_honours_deletions_and_replacements() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let secret = RostraIdSecretKey::generate();
+
+    let deleted = social_post(secret, 100, None, None, "secret plans");
+    let original = social_post(
+        secret,
+        200,
+        Some(deleted.event_id()),
+        None,
+        "tpyo in original",
+    );
+    let edit = social_post(
+        secret,
+        300,
+        Some(original.event_id()),
+        Some(original.event_id()),
+        "typo fixed in original",
+    );
+    for post in [&deleted, &original, &edit] {
+        db.process_event_with_content(post).await;
+    }
+    db.process_event(&deletion(secret, 400, edit.event_id(), deleted.event_id()))
+        .await;
+
+    assert!(search(&db, "secret").await.is_empty());
+    assert!(search(&db, "tpyo").await.is_empty());
+    assert_eq!(
+        search(&db, "original").await,
+        vec![edit.event_id().to_short()]
+    );
+
+    Ok(())
+}
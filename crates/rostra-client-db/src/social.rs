@@ -10,10 +10,10 @@ use tracing::{debug, warn};
 use super::Database;
 use crate::event::ContentStoreRecord;
 use crate::{
-    DbResult, LOG_TARGET, content_store, events, events_content_state,
-    shoutbox_posts_by_received_at, social_posts, social_posts_by_received_at, social_posts_by_time,
-    social_posts_reactions, social_posts_replaced_by, social_posts_replaces, social_posts_replies,
-    tables,
+    DbResult, LOG_TARGET, ReadTransaction, content_store, events, events_content_state,
+    ids_self_blocks, shoutbox_posts_by_received_at, social_posts, social_posts_by_received_at,
+    social_posts_by_time, social_posts_reactions, social_posts_received_at_keys,
+    social_posts_replaced_by, social_posts_replaces, social_posts_replies, tables,
 };
 
 /// Cursor for paginating events by their author timestamp.
@@ -179,7 +179,7 @@ impl Database {
         Ok(reply_count)
     }
 
-    fn social_post_record_by_id_tx(
+    pub(crate) fn social_post_record_by_id_tx(
         event_id: ShortEventId,
         ts: Timestamp,
         events_table: &impl events::ReadableTable,
@@ -228,6 +228,69 @@ impl Database {
         }))
     }
 
+    /// Collect a page of social posts from the `(timestamp, event_id)`
+    /// entries of a secondary post index, in the order given
+    ///
+    /// Posts without content, replaced posts, muted posts and posts of
+    /// blocked identities are skipped, as are posts failing `filter_fn`.
+    /// Stops after `limit` posts or `scan_max` entries, returning the cursor
+    /// of the next entry.
+    pub(crate) fn collect_indexed_social_posts_tx(
+        &self,
+        tx: &ReadTransaction,
+        entries: impl Iterator<Item = DbResult<(Timestamp, ShortEventId)>>,
+        limit: usize,
+        scan_max: usize,
+        filter_fn: impl Fn(&SocialPostRecord<SocialPost>) -> bool,
+    ) -> DbResult<(
+        Vec<SocialPostRecord<content_kind::SocialPost>>,
+        Option<EventPaginationCursor>,
+    )> {
+        let events_table = tx.open_table(&events::TABLE)?;
+        let social_posts_table = tx.open_table(&social_posts::TABLE)?;
+        let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
+        let content_store_table = tx.open_table(&content_store::TABLE)?;
+        let social_posts_replaced_by_table = tx.open_table(&social_posts_replaced_by::TABLE)?;
+        let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+        let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+        let content_filters = self.read_content_filter_matcher_tx(tx)?;
+
+        let mut ret = vec![];
+        for (scanned, entry) in entries.enumerate() {
+            let (ts, event_id) = entry?;
+            if limit <= ret.len() || scan_max <= scanned {
+                return Ok((ret, Some(EventPaginationCursor { ts, event_id })));
+            }
+
+            let Some(record) = Self::social_post_record_by_id_tx(
+                event_id,
+                ts,
+                &events_table,
+                &social_posts_table,
+                &events_content_state_table,
+                &content_store_table,
+                &social_posts_replaces_table,
+            )?
+            else {
+                continue;
+            };
+            if Self::is_self_blocked_tx(record.author, &ids_self_blocks_table)?
+                || Self::is_social_post_replaced_tx(
+                    record.author,
+                    event_id,
+                    &social_posts_replaced_by_table,
+                )?
+                || content_filters.is_muted(record.author, &record.content)
+                || !filter_fn(&record)
+            {
+                continue;
+            }
+            ret.push(record);
+        }
+
+        Ok((ret, None))
+    }
+
     pub async fn paginate_social_posts(
         &self,
         cursor: Option<EventPaginationCursor>,
@@ -245,6 +308,8 @@ impl Database {
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let social_posts_replaced_by_table = tx.open_table(&social_posts_replaced_by::TABLE)?;
             let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let (ret, cursor) = Self::paginate_table(&social_posts_by_time_table,
                 cursor.map(|c| (c.ts, c.event_id)),
@@ -256,6 +321,9 @@ impl Database {
                     return Ok(None);
                 };
                 let author = event.author();
+                if Self::is_self_blocked_tx(author, &ids_self_blocks_table)? {
+                    return Ok(None);
+                }
                 if Self::is_social_post_replaced_tx(author, event_id, &social_posts_replaced_by_table)? {
                     return Ok(None);
                 }
@@ -281,6 +349,10 @@ impl Database {
                     return Ok(None);
                 };
 
+                if content_filters.is_muted(author, &social_post) {
+                    return Ok(None);
+                }
+
                 let reply_count = Self::social_post_reply_count_tx(
                     author,
                     event_id,
@@ -330,6 +402,8 @@ impl Database {
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let social_posts_replaced_by_table = tx.open_table(&social_posts_replaced_by::TABLE)?;
             let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let (ret, cursor) = Self::paginate_table_rev(&social_posts_by_time_table,
                 cursor.map(|c| (c.ts, c.event_id)),
@@ -341,6 +415,9 @@ impl Database {
                     return Ok(None);
                 };
                 let author = event.author();
+                if Self::is_self_blocked_tx(author, &ids_self_blocks_table)? {
+                    return Ok(None);
+                }
                 if Self::is_social_post_replaced_tx(author, event_id, &social_posts_replaced_by_table)? {
                     return Ok(None);
                 }
@@ -366,6 +443,10 @@ impl Database {
                     return Ok(None);
                 };
 
+                if content_filters.is_muted(author, &social_post) {
+                    return Ok(None);
+                }
+
                 let reply_count = Self::social_post_reply_count_tx(
                     author,
                     event_id,
@@ -420,6 +501,28 @@ impl Database {
         .expect("Storage error")
     }
 
+    /// Get the reception-order cursor of a social post.
+    ///
+    /// Used to tell subscribers of new posts where to resume from.
+    pub async fn get_social_post_received_at_cursor(
+        &self,
+        event_id: ShortEventId,
+    ) -> Option<ReceivedAtPaginationCursor> {
+        self.read_with(|tx| {
+            let social_posts_received_at_keys_table =
+                tx.open_table(&social_posts_received_at_keys::TABLE)?;
+
+            Ok(social_posts_received_at_keys_table
+                .get(&event_id)?
+                .map(|g| {
+                    let (ts, seq) = g.value();
+                    ReceivedAtPaginationCursor { ts, seq }
+                }))
+        })
+        .await
+        .expect("Storage error")
+    }
+
     /// Paginate social posts ordered by when we received them (forward).
     ///
     /// Used for notification badge count calculation.
@@ -441,6 +544,8 @@ impl Database {
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let social_posts_replaced_by_table = tx.open_table(&social_posts_replaced_by::TABLE)?;
             let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let (ret, cursor) = Self::paginate_table(
                 &social_posts_by_received_at_table,
@@ -452,6 +557,9 @@ impl Database {
                         return Ok(None);
                     };
                     let author = event.author();
+                    if Self::is_self_blocked_tx(author, &ids_self_blocks_table)? {
+                        return Ok(None);
+                    }
                     if Self::is_social_post_replaced_tx(
                         author,
                         event_id,
@@ -482,6 +590,10 @@ impl Database {
                         return Ok(None);
                     };
 
+                    if content_filters.is_muted(author, &social_post) {
+                        return Ok(None);
+                    }
+
                     let reply_count = Self::social_post_reply_count_tx(
                         author,
                         event_id,
@@ -539,6 +651,8 @@ impl Database {
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let social_posts_replaced_by_table = tx.open_table(&social_posts_replaced_by::TABLE)?;
             let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let (ret, cursor) = Self::paginate_table_rev(
                 &social_posts_by_received_at_table,
@@ -550,6 +664,9 @@ impl Database {
                         return Ok(None);
                     };
                     let author = event.author();
+                    if Self::is_self_blocked_tx(author, &ids_self_blocks_table)? {
+                        return Ok(None);
+                    }
                     if Self::is_social_post_replaced_tx(
                         author,
                         event_id,
@@ -580,6 +697,10 @@ impl Database {
                         return Ok(None);
                     };
 
+                    if content_filters.is_muted(author, &social_post) {
+                        return Ok(None);
+                    }
+
                     let reply_count = Self::social_post_reply_count_tx(
                         author,
                         event_id,
@@ -632,6 +753,8 @@ impl Database {
             let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let versions =
                 if let Some(event) = Database::get_event_tx(post_event_id, &events_table)? {
@@ -668,6 +791,12 @@ impl Database {
                     else {
                         continue;
                     };
+                    if Self::is_self_blocked_tx(record.author, &ids_self_blocks_table)? {
+                        continue;
+                    }
+                    if content_filters.is_muted(record.author, &record.content) {
+                        continue;
+                    }
                     records.push(record);
                 }
             }
@@ -701,6 +830,8 @@ impl Database {
             let events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
             let content_store_table = tx.open_table(&content_store::TABLE)?;
             let social_posts_replaces_table = tx.open_table(&social_posts_replaces::TABLE)?;
+            let ids_self_blocks_table = tx.open_table(&ids_self_blocks::TABLE)?;
+            let content_filters = self.read_content_filter_matcher_tx(tx)?;
 
             let versions =
                 if let Some(event) = Database::get_event_tx(post_event_id, &events_table)? {
@@ -737,6 +868,12 @@ impl Database {
                     else {
                         continue;
                     };
+                    if Self::is_self_blocked_tx(record.author, &ids_self_blocks_table)? {
+                        continue;
+                    }
+                    if content_filters.is_muted(record.author, &record.content) {
+                        continue;
+                    }
                     records.push(record);
                 }
             }
@@ -10,7 +10,8 @@ use snafu::OptionExt as _;
 use crate::event::ContentStoreRecord;
 use crate::{
     Database, DbResult, EventContentState, OverflowSnafu, WriteTransactionCtx, content_store,
-    events, events_content_state, social_post_materializations, social_posts_replaced_by,
+    events, events_content_state, social_post_materializations, social_posts_pruned,
+    social_posts_replaced_by,
 };
 
 /// Maximum number of materialization rows resolved by one scan.
@@ -165,6 +166,7 @@ impl Database {
             let states = tx.open_table(&events_content_state::TABLE)?;
             let content = tx.open_table(&content_store::TABLE)?;
             let replacements = tx.open_table(&social_posts_replaced_by::TABLE)?;
+            let pruned = tx.open_table(&social_posts_pruned::TABLE)?;
             let mut range = feed.range(expected..)?;
             let mut items = Vec::with_capacity(limit.get());
 
@@ -188,6 +190,7 @@ impl Database {
                     &states,
                     &content,
                     &replacements,
+                    &pruned,
                 )?);
                 expected = expected.checked_add(1).context(OverflowSnafu)?;
             }
@@ -232,12 +235,22 @@ impl Database {
         states: &impl events_content_state::ReadableTable,
         content: &impl content_store::ReadableTable,
         replacements: &impl social_posts_replaced_by::ReadableTable,
+        pruned: &impl social_posts_pruned::ReadableTable,
     ) -> DbResult<SocialPostMaterialization> {
-        let event = events_table
+        let Some(event) = events_table
             .get(&event_id)?
             .map(|entry| entry.value_try())
             .transpose()?
-            .context(crate::MissingSocialPostMaterializationEventSnafu { event_id })?;
+        else {
+            let author = pruned
+                .get(&event_id)?
+                .map(|entry| entry.value_try())
+                .transpose()?
+                .context(crate::MissingSocialPostMaterializationEventSnafu { event_id })?;
+            return Ok(SocialPostMaterialization::Removed {
+                post_id: ExternalEventId::new(author, event_id),
+            });
+        };
         if event.kind() != EventKind::SOCIAL_POST {
             return crate::InvalidSocialPostMaterializationKindSnafu {
                 event_id,
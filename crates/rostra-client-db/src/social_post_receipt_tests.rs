@@ -167,6 +167,13 @@ async fn social_receipt_reversion_updates_cursors_without_reusing_order() -> Box
             seq: 6,
         })
     );
+    assert_eq!(
+        db.get_social_post_received_at_cursor(later_id).await,
+        Some(ReceivedAtPaginationCursor {
+            ts: Timestamp::from(900),
+            seq: 6,
+        })
+    );
     let (forward, forward_cursor) = db
         .paginate_social_posts_by_received_at(None, 1, |_| true)
         .await;
@@ -345,7 +352,7 @@ async fn version_24_unmapped_receipt_is_rebuilt_before_open() -> BoxedErrorResul
                     .map(|entry| entry.value()))
             })
             .await?,
-        Some(26)
+        Some(39)
     );
 
     let delete_legacy = deletion(secret, &legacy, 302);
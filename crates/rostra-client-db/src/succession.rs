@@ -0,0 +1,86 @@
+//! Identity successions.
+//!
+//! The latest [`content_kind::Succession`] of every identity, signed by the
+//! identity's own key and with a valid successor signature, is tracked in
+//! [`crate::ids_successors`]. Following the successors of successors gives the
+//! succession chain of an identity.
+
+use rostra_core::event::{EventExt as _, SignedEventExt as _, VerifiedEvent, content_kind};
+use rostra_core::id::RostraId;
+use tracing::debug;
+
+use crate::event_order::EventOrder;
+use crate::ids::IdsSuccessorRecord;
+use crate::{Database, DbResult, LOG_TARGET, WriteTransactionCtx, ids_successors};
+
+/// Max number of successors followed in a succession chain
+pub const MAX_SUCCESSION_CHAIN_LEN: usize = 16;
+
+impl Database {
+    pub(crate) fn insert_succession_tx(
+        event: &VerifiedEvent,
+        event_order: EventOrder,
+        content: &content_kind::Succession,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let author = event.author();
+        // Devices can't move their identity elsewhere
+        if event.event.verify_signature(event.sig()).is_err() {
+            debug!(target: LOG_TARGET, %author, successor = %content.successor, "Ignoring succession not signed by its identity");
+            return Ok(());
+        }
+        if !content.verify(author) {
+            debug!(target: LOG_TARGET, %author, successor = %content.successor, "Ignoring succession without a valid successor signature");
+            return Ok(());
+        }
+        Database::insert_latest_value_tx(
+            event_order.timestamp(),
+            &author,
+            IdsSuccessorRecord {
+                event_id: event_order.event_id(),
+                successor: content.successor,
+            },
+            &mut tx.open_table(&ids_successors::TABLE)?,
+        )?;
+        Ok(())
+    }
+
+    /// Identity announced as the successor of `id`, if any
+    pub async fn get_successor(&self, id: RostraId) -> Option<RostraId> {
+        self.read_with(|tx| {
+            Ok(tx
+                .open_table(&ids_successors::TABLE)?
+                .get(&id)?
+                .map(|record| record.value().inner.successor))
+        })
+        .await
+        .expect("Storage error")
+    }
+
+    /// Successors of `id`, each succeeding the previous one, starting with
+    /// the successor of `id`
+    ///
+    /// Ends before any identity repeats, and after
+    /// [`MAX_SUCCESSION_CHAIN_LEN`] successors.
+    pub async fn get_succession_chain(&self, id: RostraId) -> Vec<RostraId> {
+        self.read_with(|tx| {
+            let ids_successors_table = tx.open_table(&ids_successors::TABLE)?;
+            let mut chain = vec![];
+            let mut current = id;
+            while chain.len() < MAX_SUCCESSION_CHAIN_LEN {
+                let Some(record) = ids_successors_table.get(&current)? else {
+                    break;
+                };
+                let successor = record.value().inner.successor;
+                if successor == id || chain.contains(&successor) {
+                    break;
+                }
+                chain.push(successor);
+                current = successor;
+            }
+            Ok(chain)
+        })
+        .await
+        .expect("Storage error")
+    }
+}
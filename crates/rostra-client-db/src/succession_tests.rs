@@ -0,0 +1,113 @@
+use rostra_core::event::content_kind;
+use rostra_core::event::{Event, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::{RostraId, RostraIdSecretKey};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::tests::temp_db_rng;
+
+fn succession(
+    secret: RostraIdSecretKey,
+    content: &content_kind::Succession,
+    timestamp: i64,
+) -> VerifiedEventContent {
+    let (event, content) = Event::builder(content)
+        .author(secret.id())
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .build()
+        .expect("valid succession");
+    let event = VerifiedEvent::verify_signed(secret.id(), event.signed_by(secret))
+        .expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+fn announce(
+    predecessor: RostraIdSecretKey,
+    successor: RostraIdSecretKey,
+    timestamp: i64,
+) -> VerifiedEventContent {
+    succession(
+        predecessor,
+        &content_kind::Succession::new(predecessor.id(), successor),
+        timestamp,
+    )
+}
+
+/// Test: successions signed by their successor are tracked, newest first, and
+/// followed into chains.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn successions_form_chains() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let old = RostraIdSecretKey::generate();
+    let new = RostraIdSecretKey::generate();
+    let newer = RostraIdSecretKey::generate();
+    let abandoned = RostraIdSecretKey::generate();
+
+    db.process_event_with_content(&announce(old, new, 200))
+        .await;
+    // An older announcement doesn't win over the newer one
+    db.process_event_with_content(&announce(old, abandoned, 100))
+        .await;
+    assert_eq!(db.get_successor(old.id()).await, Some(new.id()));
+
+    db.process_event_with_content(&announce(new, newer, 300))
+        .await;
+    // Chains end before cycling back
+    db.process_event_with_content(&announce(newer, old, 400))
+        .await;
+    assert_eq!(
+        db.get_succession_chain(old.id()).await,
+        vec![new.id(), newer.id()]
+    );
+    assert_eq!(
+        db.get_succession_chain(newer.id()).await,
+        vec![old.id(), new.id()]
+    );
+    assert!(db.get_succession_chain(abandoned.id()).await.is_empty());
+
+    Ok(())
+}
+
+/// Test: successions not signed by the claimed successor are ignored.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn successions_need_successor_consent() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let author = RostraIdSecretKey::generate();
+    let claimed: RostraId = RostraIdSecretKey::generate().id();
+
+    let forged = content_kind::Succession {
+        successor: claimed,
+        ..content_kind::Succession::new(author.id(), RostraIdSecretKey::generate())
+    };
+    db.process_event_with_content(&succession(author, &forged, 100))
+        .await;
+    assert_eq!(db.get_successor(author.id()).await, None);
+
+    Ok(())
+}
+
+/// Test: successions signed by a delegated device instead of the identity's
+/// own key are ignored.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn successions_need_identity_signature() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let author = RostraIdSecretKey::generate();
+    let device = RostraIdSecretKey::generate();
+    let successor = RostraIdSecretKey::generate();
+
+    let (event, content) = Event::builder(&content_kind::Succession::new(author.id(), successor))
+        .author(author.id())
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(100).expect("valid timestamp"))
+        .build()
+        .expect("valid succession");
+    let event = VerifiedEvent::verify_signed_delegated(
+        author.id(),
+        event.signed_by(device),
+        &[device.id()],
+    )
+    .expect("event must verify");
+    db.process_event_with_content(&VerifiedEventContent::assume_verified(event, content))
+        .await;
+    assert_eq!(db.get_successor(author.id()).await, None);
+
+    Ok(())
+}
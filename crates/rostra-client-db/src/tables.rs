@@ -78,14 +78,27 @@
 //!    contributed a reference; later deletion attribution updates do not
 //!    decrement it again
 //!
-//! **Content Pruning** (local decision, e.g., content too large):
+//! **Content Pruning** (local decision, e.g., content too large or not
+//! retained by a [`ContentPruningPolicy`](crate::ContentPruningPolicy)):
 //! 1. Event's content state changes to [`Pruned`](EventContentState::Pruned) in
 //!    [`events_content_state`]
 //! 2. RC is decremented in [`content_rc`]
 //!
 //! **Garbage Collection**:
 //! When RC reaches 0, content is eligible for removal from [`content_store`].
-//! Removal is not currently automatic.
+//! Policy pruning removes the bytes it releases; removal is not otherwise
+//! automatic.
+//!
+//! **Event Header Pruning** (local decision for identities that are neither
+//! self nor directly followed, via
+//! [`Database::prune_event_headers`](crate::Database::prune_event_headers)):
+//! 1. Events authored before a cutoff are removed from [`events`],
+//!    [`events_by_time`] and [`events_by_author`], except heads and current singleton winners. Their
+//!    content is pruned first, and usage moves from `current_metadata_*`
+//!    while `total_metadata_*` is kept
+//! 2. Pruned parents of retained events are recorded in [`events_pruned`] and
+//!    the cutoff in [`events_pruned_checkpoints`], so `insert_event_tx` neither
+//!    marks them as missing nor accepts pruned history again
 //!
 //! ### Interpreting `events_content_state`
 //!
@@ -112,10 +125,16 @@
 
 use bincode::{Decode, Encode};
 pub use event::EventRecord;
-use event::EventsMissingRecord;
+use event::{EventsMissingRecord, EventsPrunedCheckpointRecord};
 use id_self::IdSelfAccountRecord;
-use ids::{IdsFolloweesRecord, IdsFollowersRecord, IdsPersonaRecord, IdsUnfollowedRecord};
-use rostra_core::event::{EventAuxKey, EventKind, IrohNodeId, PersonaId};
+use ids::{
+    IdsDeviceRecord, IdsFolloweesRecord, IdsFollowersRecord, IdsPersonaRecord, IdsSelfBlockRecord,
+    IdsSuccessorRecord, IdsUnfollowedRecord,
+};
+use rostra_core::event::{
+    EventAuxKey, EventKind, IrohNodeId, PersonaId, PrivateAudienceKey, PrivateAudienceKeyId,
+    WrappedAudienceKey,
+};
 use rostra_core::id::RostraId;
 use rostra_core::{ContentHash, ExternalEventId, ShortEventId, Timestamp};
 use serde::Serialize;
@@ -235,6 +254,32 @@ def_table! {
     ids_unfollowed: (RostraId, RostraId) => IdsUnfollowedRecord
 }
 
+def_table! {
+    /// Identities blocked by the local identity.
+    ///
+    /// Key: blocked identity
+    /// Only the latest block event of every target wins; unblocked targets stay
+    /// with `blocked: false`.
+    ids_self_blocks: RostraId => Latest<IdsSelfBlockRecord>
+}
+
+def_table! {
+    /// Successor announced by every identity.
+    ///
+    /// Key: predecessor
+    /// Only the latest succession event with a valid successor signature wins.
+    ids_successors: RostraId => Latest<IdsSuccessorRecord>
+}
+
+def_table! {
+    /// Device keys delegated to sign on behalf of an identity.
+    ///
+    /// Key: (identity, device)
+    /// Only the latest delegation event signed by the identity itself wins;
+    /// revoked devices stay with `revoked: true`.
+    ids_devices: (RostraId, RostraId) => Latest<IdsDeviceRecord>
+}
+
 def_table! {
     /// Custom personas defined by users.
     ///
@@ -259,8 +304,8 @@ def_table! {
 ///
 /// **Invariants:**
 ///
-/// - `current_metadata_{size,num} == total_metadata_{size,num}` (until event
-///   pruning exists)
+/// - `current_metadata_{size,num} <= total_metadata_{size,num}`; the
+///   difference are event headers pruned locally
 /// - `total_content_size == current_content_size + deleted_payload_size +
 ///   pruned_payload_size + missing_payload_size + invalid_payload_size`
 /// - `total_payload_num == current_payload_num + deleted_payload_num +
@@ -273,14 +318,14 @@ pub struct IdsDataUsageRecord {
     pub current_metadata_size: u64,
 
     /// Total metadata size of all events we know about, in bytes.
-    /// Same as `current_metadata_size` until event pruning is implemented.
+    /// Includes event headers that were pruned since.
     pub total_metadata_size: u64,
 
     /// Number of events currently stored.
     pub current_metadata_num: u64,
 
     /// Total number of events we know about.
-    /// Same as `current_metadata_num` until event pruning is implemented.
+    /// Includes event headers that were pruned since.
     pub total_metadata_num: u64,
 
     // -- Content/Payloads --
@@ -363,6 +408,28 @@ def_table! {
     events_heads: (RostraId, ShortEventId) => EventsHeadsTableRecord
 }
 
+def_table! {
+    /// Pruned events on the frontier of an identity's retained DAG.
+    ///
+    /// Key: (author, event_id)
+    /// Event headers that were pruned locally (see
+    /// [`events_pruned_checkpoints`]) but are still referenced as parents by
+    /// retained events of the same author. Such parents are treated as known:
+    /// they are never recorded in `events_missing`, and are not fetched again.
+    /// Pruned events deeper in the history are not recorded individually.
+    events_pruned: (RostraId, ShortEventId) => ()
+}
+
+def_table! {
+    /// Event header pruning checkpoint per identity.
+    ///
+    /// Key: author
+    /// Events of the author older than the checkpoint were pruned, except for
+    /// heads and current singleton winners. Such events are rejected on
+    /// insertion, so pruned history is not downloaded again.
+    events_pruned_checkpoints: RostraId => EventsPrunedCheckpointRecord
+}
+
 def_table! {
     /// Index of every accepted event envelope authored by the local user.
     ///
@@ -418,7 +485,8 @@ def_table! {
     ///   processed yet. Content side effects (reply counts, follow updates,
     ///   etc.) have not been applied.
     /// - **`Deleted`**: Content was deleted by the author via a deletion event.
-    /// - **`Pruned`**: Content was pruned locally (e.g., too large to store).
+    /// - **`Pruned`**: Content was pruned locally (e.g., too large to store, or
+    ///   not retained by a [`crate::ContentPruningPolicy`]).
     /// - **`Invalid`**: Content failed validation (e.g. CBOR deserialization).
     ///
     /// **Idempotency**: The `Missing` state ensures ordinary content processing
@@ -456,6 +524,18 @@ def_table! {
     events_content_missing: (Timestamp, ShortEventId) => ()
 }
 
+def_table! {
+    /// Verified pieces of event content being downloaded in byte ranges.
+    ///
+    /// Key: `(event_id, offset)`
+    /// Value: content bytes starting at `offset`
+    ///
+    /// Large content is fetched a range at a time, so an interrupted download
+    /// resumes after the pieces already stored here. Pieces are removed when
+    /// the content arrives.
+    events_content_partial: (ShortEventId, u32) => Vec<u8>
+}
+
 def_table! {
     /// Time-ordered index of all events.
     ///
@@ -464,6 +544,14 @@ def_table! {
     events_by_time: (Timestamp, ShortEventId) => ()
 }
 
+def_table! {
+    /// Time-ordered index of events of each author.
+    ///
+    /// Key: (author, timestamp, event_id)
+    /// Used to reconcile the event sets of one identity between peers.
+    events_by_author: (RostraId, Timestamp, ShortEventId) => ()
+}
+
 def_table! {
     /// Tracks when and how we received each event.
     ///
@@ -585,11 +673,36 @@ def_table! {
     social_posts_replaces: (RostraId, ShortEventId, ShortEventId) => ()
 }
 
+def_table! {
+    /// Authors of social posts whose event headers were pruned.
+    ///
+    /// Key: post event_id
+    /// Lets `social_post_materializations` entries resolve as removed after the
+    /// referenced event is gone.
+    social_posts_pruned: ShortEventId => RostraId
+}
+
 def_table! {
     /// Vote sums keyed by the external id of the voted post.
     social_vote_sums: ExternalEventId => SocialVoteSumRecord
 }
 
+def_table! {
+    /// Current vote of every voter in every poll.
+    ///
+    /// Key: (poll, voter)
+    ///
+    /// Holds the projection of the latest poll vote singleton, so tallies can
+    /// be adjusted when it's superseded without the source content.
+    social_poll_votes: (ExternalEventId, RostraId) => SocialPollVoteRecord
+}
+
+def_table! {
+    /// Vote counts keyed by the external id of the poll and the option index,
+    /// kept in sync with [`social_poll_votes`].
+    social_poll_vote_sums: (ExternalEventId, u32) => SocialPollVoteSumRecord
+}
+
 def_table! {
     /// News post rank state keyed by post id.
     social_news_rank_by_post_id: ExternalEventId => SocialNewsRankRecord
@@ -619,6 +732,77 @@ def_table! {
     social_posts_self_mention: ShortEventId => ()
 }
 
+def_table! {
+    /// Inverted full-text index of social posts.
+    ///
+    /// Key: (term, post_timestamp, post_event_id)
+    ///
+    /// Terms come from the post's djot content, title and URL (see
+    /// [`crate::search::search_terms`]). Rows are removed when a post is
+    /// deleted, pruned or loses its content. Replaced posts keep their rows
+    /// and are skipped when searching.
+    social_posts_search_terms: (String, Timestamp, ShortEventId) => ()
+}
+
+def_table! {
+    /// Social posts by their hashtags.
+    ///
+    /// Key: (normalized_tag, post_timestamp, post_event_id)
+    ///
+    /// Tags come from the post's djot content (see
+    /// [`rostra_djot::hashtag::extract_hashtags`]). Like
+    /// [`social_posts_search_terms`], rows are removed when a post is deleted,
+    /// pruned or loses its content, while replaced posts keep their rows and
+    /// are skipped when listing.
+    social_posts_by_tag: (String, Timestamp, ShortEventId) => ()
+}
+
+def_table! {
+    /// Social posts by the identities they @mention.
+    ///
+    /// Key: (mentioned_id, post_timestamp, post_event_id)
+    ///
+    /// Mentions come from the post's djot content (see
+    /// [`rostra_djot::mention::extract_mentions`]), with short references
+    /// resolved against known identities. Posts mentioning their own author
+    /// are not recorded. Like [`social_posts_by_tag`], rows are removed when a
+    /// post is deleted, pruned or loses its content, while replaced posts keep
+    /// their rows and are skipped when listing.
+    social_posts_by_mention: (RostraId, Timestamp, ShortEventId) => ()
+}
+
+def_table! {
+    /// Reposts ordered by time.
+    ///
+    /// Key: (repost_timestamp, repost_event_id)
+    /// Value: the reposted post
+    ///
+    /// Rows are removed when a repost is deleted.
+    social_reposts_by_time: (Timestamp, ShortEventId) => ExternalEventId
+}
+
+def_table! {
+    /// Reposts of every post.
+    ///
+    /// Key: (reposted_post, repost_event_id)
+    /// Value: reposter
+    social_reposts_by_original: (ExternalEventId, ShortEventId) => RostraId
+}
+
+def_table! {
+    /// Number of reposts of every post, kept in sync with
+    /// [`social_reposts_by_original`].
+    social_repost_counts: ExternalEventId => u64
+}
+
+def_table! {
+    /// Reposted posts whose content is not available locally yet.
+    ///
+    /// Removed once the post arrives, or when the last repost of it is
+    /// deleted.
+    social_reposts_missing_original: ExternalEventId => ()
+}
+
 // ============================================================================
 // SHOUTBOX TABLES
 // ============================================================================
@@ -637,6 +821,75 @@ def_table! {
     shoutbox_posts_by_received_at: (Timestamp, u64) => ShortEventId
 }
 
+// ============================================================================
+// DIRECT MESSAGE TABLES
+// ============================================================================
+
+def_table! {
+    /// Direct messages sent or received by the local user, per conversation.
+    ///
+    /// Key: (counterparty, message_timestamp, message_event_id)
+    ///
+    /// Messages between other identities are not indexed. Rows are removed
+    /// when a message is deleted.
+    social_direct_messages: (RostraId, Timestamp, ShortEventId) => ()
+}
+
+def_table! {
+    /// Latest message of every direct message conversation of the local user.
+    ///
+    /// Key: counterparty
+    /// Value: (message_timestamp, message_event_id)
+    social_direct_conversations: RostraId => (Timestamp, ShortEventId)
+}
+
+// ============================================================================
+// PRIVATE POST TABLES
+// ============================================================================
+
+def_table! {
+    /// Audience keys of private posts readable by the local user.
+    ///
+    /// Key: (author, key_id)
+    ///
+    /// Unwrapped from [`social_private_audience_grants_pending`] by a client
+    /// holding the identity secret, or inserted directly by the local user
+    /// when it creates a new audience key. Not rebuilt by total migration;
+    /// replayed grants are unwrapped again instead.
+    social_private_audience_keys: (RostraId, PrivateAudienceKeyId) => PrivateAudienceKey
+}
+
+def_table! {
+    /// Audience keys granted to the local user that were not unwrapped yet.
+    ///
+    /// Key: (author, key_id)
+    social_private_audience_grants_pending: (RostraId, PrivateAudienceKeyId) => WrappedAudienceKey
+}
+
+def_table! {
+    /// Current audience of the local user's private posts.
+    ///
+    /// Taken from the latest audience key grant published by the local user.
+    social_private_audience_self: () => Latest<PrivateAudienceRecord>
+}
+
+def_table! {
+    /// Private posts waiting for the audience key they were sealed with.
+    ///
+    /// Key: (author, key_id, post_event_id)
+    social_private_posts_pending_key: (RostraId, PrivateAudienceKeyId, ShortEventId) => ()
+}
+
+def_table! {
+    /// Decrypted private posts, ordered by time.
+    ///
+    /// Key: (post_timestamp, post_event_id)
+    /// Value: the cbor-encoded decrypted `SocialPost`
+    ///
+    /// Rows are removed when a post is deleted or pruned.
+    social_private_posts_by_time: (Timestamp, ShortEventId) => ContentStoreRecordOwned
+}
+
 /// Wrapper for values where only the latest version matters.
 ///
 /// Used for singleton-style data whose value carries the source event ID and
@@ -655,6 +908,23 @@ pub(crate) trait LatestEventValue {
     fn event_id(&self) -> ShortEventId;
 }
 
+/// Audience of the local user's private posts.
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct PrivateAudienceRecord {
+    /// The grant event this audience came from
+    pub event_id: ShortEventId,
+    /// Key sealing new private posts
+    pub key_id: PrivateAudienceKeyId,
+    /// Members the key is granted to, including the local user
+    pub members: Vec<RostraId>,
+}
+
+impl LatestEventValue for PrivateAudienceRecord {
+    fn event_id(&self) -> ShortEventId {
+        self.event_id
+    }
+}
+
 /// Marker record for the `social_posts_replies` index.
 ///
 /// The key `(parent_post_id, timestamp, reply_id)` contains all needed info;
@@ -682,6 +952,24 @@ pub struct IdSocialProfileRecord {
     pub avatar: Option<(String, Vec<u8>)>,
 }
 
+impl LatestEventValue for IdsSelfBlockRecord {
+    fn event_id(&self) -> ShortEventId {
+        self.event_id
+    }
+}
+
+impl LatestEventValue for IdsDeviceRecord {
+    fn event_id(&self) -> ShortEventId {
+        self.event_id
+    }
+}
+
+impl LatestEventValue for IdsSuccessorRecord {
+    fn event_id(&self) -> ShortEventId {
+        self.event_id
+    }
+}
+
 impl LatestEventValue for IdSocialProfileRecord {
     fn event_id(&self) -> ShortEventId {
         self.event_id
@@ -718,6 +1006,20 @@ pub struct SocialVoteSumRecord {
     pub current_sum: i64,
 }
 
+#[derive(Debug, Encode, Decode, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct SocialPollVoteRecord {
+    pub ts: Timestamp,
+    pub event_id: ShortEventId,
+    /// `None` for a retracted vote
+    pub option: Option<u32>,
+}
+
+#[derive(Debug, Encode, Decode, Serialize, Clone, Copy)]
+pub struct SocialPollVoteSumRecord {
+    pub last_vote_time: Timestamp,
+    pub count: u64,
+}
+
 #[derive(Debug, Encode, Decode, Serialize, Clone, Copy)]
 pub struct SocialNewsRankRecord {
     pub creation_ts: Timestamp,
@@ -54,6 +54,16 @@ pub struct EventsMissingRecord {
 #[derive(Decode, Encode, Debug)]
 pub struct EventsHeadsTableRecord;
 
+/// Record for the `events_pruned_checkpoints` table.
+///
+/// Event headers of an identity authored before `before` were pruned locally.
+/// Checkpoints only move forward.
+#[derive(Decode, Encode, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventsPrunedCheckpointRecord {
+    /// Events authored before this timestamp are not retained.
+    pub before: Timestamp,
+}
+
 /// Authoritative value retained for a social-vote singleton winner.
 #[derive(Debug, Encode, Decode, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum SocialVoteValue {
@@ -5,6 +5,7 @@
 
 use bincode::{Decode, Encode};
 use rostra_core::event::{PersonaSelector, PersonaTag, PersonasTagsSelector};
+use rostra_core::id::RostraId;
 use rostra_core::{ShortEventId, Timestamp};
 
 /// Record for the `ids_followees` table.
@@ -110,6 +111,47 @@ pub struct IdsUnfollowedRecord {
     pub event_id: ShortEventId,
 }
 
+/// Record for the `ids_self_blocks` table.
+///
+/// Kept after an unblock too, so an older block event can't win over it.
+#[derive(Debug, Encode, Decode, Clone)]
+pub struct IdsSelfBlockRecord {
+    /// Event ID of the winning block event.
+    pub event_id: ShortEventId,
+    /// `false` if the latest event lifted the block.
+    pub blocked: bool,
+}
+
+/// Record for the `ids_successors` table.
+#[derive(Debug, Encode, Decode, Clone, Copy)]
+pub struct IdsSuccessorRecord {
+    /// Event ID of the winning succession event.
+    pub event_id: ShortEventId,
+    /// Identity announced as the successor.
+    pub successor: RostraId,
+}
+
+/// Record for the `ids_devices` table.
+///
+/// Kept after a revocation too, so an older delegation event can't win over
+/// it.
+#[derive(Debug, Encode, Decode, Clone, Copy)]
+pub struct IdsDeviceRecord {
+    /// Event ID of the winning delegation event.
+    pub event_id: ShortEventId,
+    /// `true` if the latest event revoked the delegation.
+    pub revoked: bool,
+    /// When the delegation expires, if ever.
+    pub expires_at: Option<Timestamp>,
+}
+
+impl IdsDeviceRecord {
+    /// Can the device sign on behalf of the identity at `now`
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        !self.revoked && self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
 /// Record for the `ids_personas` table.
 ///
 /// Users can define custom personas to categorize their posts (beyond the
@@ -0,0 +1,118 @@
+//! Hashtag index of social posts.
+//!
+//! Posts are indexed in [`crate::social_posts_by_tag`] when their content is
+//! processed, by every distinct hashtag of their djot content, and unindexed
+//! along with the full-text index (see [`crate::search`]).
+
+use std::collections::BTreeSet;
+
+use rostra_core::event::content_kind;
+use rostra_core::{ShortEventId, Timestamp};
+use tracing::info;
+
+use crate::social::{EventPaginationCursor, SocialPostRecord};
+use crate::{Database, DbResult, LOG_TARGET, WriteTransactionCtx, social_posts_by_tag};
+
+/// Max number of index rows scanned by one tag listing call
+pub const SOCIAL_POSTS_BY_TAG_SCAN_MAX: usize = 4_096;
+
+/// Max number of distinct hashtags indexed per post
+const MAX_TAGS_PER_POST: usize = 32;
+
+fn social_post_tags(content: &content_kind::SocialPost) -> BTreeSet<String> {
+    content
+        .djot_content
+        .as_deref()
+        .map(rostra_djot::hashtag::extract_hashtags)
+        .unwrap_or_default()
+        .into_iter()
+        .take(MAX_TAGS_PER_POST)
+        .collect()
+}
+
+impl Database {
+    pub(crate) fn index_social_post_tags_tx(
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content: &content_kind::SocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut table = tx.open_table(&social_posts_by_tag::TABLE)?;
+        for tag in social_post_tags(content) {
+            table.insert(&(tag, ts, event_id), &())?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unindex_social_post_tags_tx(
+        event_id: ShortEventId,
+        ts: Timestamp,
+        content: &content_kind::SocialPost,
+        tx: &WriteTransactionCtx,
+    ) -> DbResult<()> {
+        let mut table = tx.open_table(&social_posts_by_tag::TABLE)?;
+        for tag in social_post_tags(content) {
+            table.remove(&(tag, ts, event_id))?;
+        }
+        Ok(())
+    }
+
+    /// Index the hashtags of all stored social posts that still have their
+    /// content
+    pub(crate) fn backfill_social_posts_by_tag_tx(tx: &WriteTransactionCtx) -> DbResult<()> {
+        let count = Self::for_each_stored_social_post_tx(tx, |_, event_id, ts, social_post| {
+            Self::index_social_post_tags_tx(event_id, ts, social_post, tx)
+        })?;
+        info!(target: LOG_TARGET, count, "Backfilled social post hashtag index");
+        Ok(())
+    }
+
+    /// List social posts with the hashtag `tag` passing `filter_fn`, newest
+    /// first
+    ///
+    /// `tag` is normalized first, with or without its `#`. Replaced posts,
+    /// posts without content and posts of blocked identities are skipped. A
+    /// page can have fewer than `limit` posts (even none) while the returned
+    /// cursor is `Some`, if [`SOCIAL_POSTS_BY_TAG_SCAN_MAX`] index rows were
+    /// scanned first; pass the cursor back to continue.
+    pub async fn paginate_social_posts_by_tag_rev(
+        &self,
+        tag: &str,
+        cursor: Option<EventPaginationCursor>,
+        limit: usize,
+        filter_fn: impl Fn(&SocialPostRecord<content_kind::SocialPost>) -> bool + Send + 'static,
+    ) -> (
+        Vec<SocialPostRecord<content_kind::SocialPost>>,
+        Option<EventPaginationCursor>,
+    ) {
+        let Some(tag) = rostra_djot::hashtag::normalize_hashtag(tag.trim_start_matches('#')) else {
+            return (vec![], None);
+        };
+
+        self.read_with(|tx| {
+            let start = (tag.clone(), Timestamp::ZERO, ShortEventId::ZERO);
+            let end = match cursor {
+                Some(cursor) => (tag.clone(), cursor.ts, cursor.event_id),
+                None => (tag.clone(), Timestamp::MAX, ShortEventId::MAX),
+            };
+
+            let social_posts_by_tag_table = tx.open_table(&social_posts_by_tag::TABLE)?;
+            let entries = social_posts_by_tag_table
+                .range(&start..=&end)?
+                .rev()
+                .map(|entry| {
+                    let (_, ts, event_id) = entry?.0.value();
+                    Ok((ts, event_id))
+                });
+            self.collect_indexed_social_posts_tx(
+                tx,
+                entries,
+                limit,
+                SOCIAL_POSTS_BY_TAG_SCAN_MAX,
+                filter_fn,
+            )
+        })
+        .await
+        .expect("Storage error")
+    }
+}
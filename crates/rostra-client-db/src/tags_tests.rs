@@ -0,0 +1,109 @@
+use rostra_core::ShortEventId;
+use rostra_core::id::{RostraIdSecretKey, ToShort as _};
+use rostra_util_error::BoxedErrorResult;
+
+use crate::Database;
+use crate::tests::fixtures::{deletion, social_post};
+use crate::tests::temp_db_rng;
+
+async fn tagged(db: &Database, tag: &str) -> Vec<ShortEventId> {
+    db.paginate_social_posts_by_tag_rev(tag, None, 100, |_| true)
+        .await
+        .0
+        .into_iter()
+        .map(|record| record.event_id)
+        .collect()
+}
+
+/// Test: posts are listed by their hashtags newest first, whatever the case
+/// and `#` of the tag, and pagination resumes where the previous page ended.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn posts_are_listed_by_tag_newest_first() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let secret = RostraIdSecretKey::generate();
+
+    let old = social_post(secret, 100, None, None, "Learning #Rust");
+    let new = social_post(
+        secret,
+        200,
+        Some(old.event_id()),
+        None,
+        "#rust and #p2p, not `#code`",
+    );
+    for post in [&old, &new] {
+        db.process_event_with_content(post).await;
+    }
+    let [old, new] = [&old, &new].map(|post| post.event_id().to_short());
+
+    assert_eq!(tagged(&db, "rust").await, vec![new, old]);
+    assert_eq!(tagged(&db, "#RUST").await, vec![new, old]);
+    assert_eq!(tagged(&db, "p2p").await, vec![new]);
+    assert!(tagged(&db, "code").await.is_empty());
+    assert!(tagged(&db, "not a tag").await.is_empty());
+
+    let (page, cursor) = db
+        .paginate_social_posts_by_tag_rev("rust", None, 1, |_| true)
+        .await;
+    assert_eq!(
+        page.iter()
+            .map(|record| record.event_id)
+            .collect::<Vec<_>>(),
+        vec![new]
+    );
+    let (page, cursor) = db
+        .paginate_social_posts_by_tag_rev("rust", cursor, 1, |_| true)
+        .await;
+    assert_eq!(
+        page.iter()
+            .map(|record| record.event_id)
+            .collect::<Vec<_>>(),
+        vec![old]
+    );
+    assert_eq!(cursor, None);
+
+    let (page, _) = db
+        .paginate_social_posts_by_tag_rev("rust", None, 100, move |record| record.event_id == old)
+        .await;
+    assert_eq!(page.len(), 1);
+
+    Ok(())
+}
+
+/// Test: deleted posts are removed from the tag index, and replaced posts are
+/// only listed through their latest version.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn posts_by_tag_honour_deletions_and_replacements() -> BoxedErrorResult<()> {
+    let (_dir, db) = temp_db_rng().await?;
+    let secret = RostraIdSecretKey::generate();
+
+    let deleted = social_post(secret, 100, None, None, "#plans");
+    let original = social_post(
+        secret,
+        200,
+        Some(deleted.event_id()),
+        None,
+        "#tpyo in #original",
+    );
+    let edit = social_post(
+        secret,
+        300,
+        Some(original.event_id()),
+        Some(original.event_id()),
+        "#typo fixed in #original",
+    );
+    for post in [&deleted, &original, &edit] {
+        db.process_event_with_content(post).await;
+    }
+    db.process_event(&deletion(secret, 400, edit.event_id(), deleted.event_id()))
+        .await;
+
+    assert!(tagged(&db, "plans").await.is_empty());
+    assert!(tagged(&db, "tpyo").await.is_empty());
+    assert_eq!(
+        tagged(&db, "original").await,
+        vec![edit.event_id().to_short()]
+    );
+    assert_eq!(tagged(&db, "typo").await, vec![edit.event_id().to_short()]);
+
+    Ok(())
+}
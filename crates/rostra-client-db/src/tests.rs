@@ -1,4 +1,5 @@
 mod event_order;
+pub(crate) mod fixtures;
 mod property;
 
 use rostra_core::event::content_kind::{self, EventContentKind as _};
@@ -7,7 +8,7 @@ use rostra_core::event::{
     VerifiedEventContent,
 };
 use rostra_core::id::{RostraId, RostraIdSecretKey, ToShort as _};
-use rostra_core::{EventId, Timestamp};
+use rostra_core::{EventId, ShortEventId, Timestamp};
 use rostra_util_error::BoxedErrorResult;
 use snafu::ResultExt as _;
 use tempfile::{TempDir, tempdir};
@@ -16,8 +17,9 @@ use tracing::info;
 use crate::event::EventContentState;
 use crate::event_order::EventOrder;
 use crate::{
-    Database, content_rc, content_store, events, events_by_time, events_content_missing,
-    events_content_state, events_heads, events_missing, ids_full,
+    Database, content_rc, content_store, events, events_by_author, events_by_time,
+    events_content_missing, events_content_state, events_heads, events_missing, events_pruned,
+    events_pruned_checkpoints, ids_full,
 };
 
 pub(crate) async fn temp_db_rng() -> BoxedErrorResult<(TempDir, super::Database)> {
@@ -126,12 +128,16 @@ async fn test_store_event() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE).boxed()?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE).boxed()?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE).boxed()?;
         let mut content_store_table = tx.open_table(&content_store::TABLE).boxed()?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE).boxed()?;
         let mut events_content_missing_table =
             tx.open_table(&events_content_missing::TABLE).boxed()?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE).boxed()?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE).boxed()?;
+        let events_pruned_checkpoints_table =
+            tx.open_table(&events_pruned_checkpoints::TABLE).boxed()?;
 
         for (event, missing_expect, heads_expect) in [
             (event_a, vec![], vec![event_a_id]),
@@ -155,7 +161,10 @@ async fn test_store_event() -> BoxedErrorResult<()> {
                     &mut events_table,
                     &mut events_missing_table,
                     &mut events_heads_table,
+                    &mut events_pruned_table,
+                    &events_pruned_checkpoints_table,
                     &mut events_by_time_table,
+                    &mut events_by_author_table,
                     &mut events_content_state_table,
                     &mut content_store_table,
                     &mut content_rc_table,
@@ -233,6 +242,7 @@ async fn test_store_deleted_event() -> BoxedErrorResult<()> {
         let mut ids_full_tbl = ids_full::Table::open(tx).boxed()?;
         let mut events_table = tx.open_table(&events::TABLE).boxed()?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE).boxed()?;
         let mut content_store_table = tx.open_table(&content_store::TABLE).boxed()?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE).boxed()?;
@@ -240,6 +250,9 @@ async fn test_store_deleted_event() -> BoxedErrorResult<()> {
             tx.open_table(&events_content_missing::TABLE).boxed()?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE).boxed()?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE).boxed()?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE).boxed()?;
+        let events_pruned_checkpoints_table =
+            tx.open_table(&events_pruned_checkpoints::TABLE).boxed()?;
 
         // All events have content_len=0. With the new behavior, deletion
         // of content_len=0 parents DOES set EventContentState::Deleted
@@ -285,7 +298,10 @@ async fn test_store_deleted_event() -> BoxedErrorResult<()> {
                     &mut events_table,
                     &mut events_missing_table,
                     &mut events_heads_table,
+                    &mut events_pruned_table,
+                    &events_pruned_checkpoints_table,
                     &mut events_by_time_table,
+                    &mut events_by_author_table,
                     &mut events_content_state_table,
                     &mut content_store_table,
                     &mut content_rc_table,
@@ -547,11 +563,14 @@ async fn test_event_arrives_before_content() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
         let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
 
         // Step 1: Insert event - content not in store yet
         Database::insert_event_tx(
@@ -560,7 +579,10 @@ async fn test_event_arrives_before_content() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -646,11 +668,14 @@ async fn test_content_exists_when_event_arrives() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
         let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
 
         // Step 1: Pre-store content in content_store
         let test_content = EventContentRaw::new(vec![]);
@@ -663,7 +688,10 @@ async fn test_content_exists_when_event_arrives() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -747,11 +775,14 @@ async fn test_multiple_events_share_content() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
         let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
 
         // Step 1: Event A arrives - no content in store
         Database::insert_event_tx(
@@ -760,7 +791,10 @@ async fn test_multiple_events_share_content() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -803,7 +837,10 @@ async fn test_multiple_events_share_content() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -868,11 +905,14 @@ async fn test_multiple_events_waiting_for_content() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
         let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
 
         // Step 1: Both events arrive - no content in store
         Database::insert_event_tx(
@@ -881,7 +921,10 @@ async fn test_multiple_events_waiting_for_content() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -894,7 +937,10 @@ async fn test_multiple_events_waiting_for_content() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -1050,11 +1096,14 @@ async fn test_delete_event_arrives_before_target() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
         let mut events_content_missing_table = tx.open_table(&events_content_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
 
         // Insert delete event B (targeting missing A)
         Database::insert_event_tx(
@@ -1063,7 +1112,10 @@ async fn test_delete_event_arrives_before_target() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -1092,7 +1144,10 @@ async fn test_delete_event_arrives_before_target() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -1305,6 +1360,108 @@ async fn test_follow_unfollow_timestamp_ordering() -> BoxedErrorResult<()> {
     Ok(())
 }
 
+/// Test: get_ancestors walks back from heads, newest first, until known
+/// events or the limit.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_get_ancestors() -> BoxedErrorResult<()> {
+    let id_secret = RostraIdSecretKey::generate();
+    let other_secret = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db_rng().await?;
+
+    // a <- b <- c <- d, and an event of another author on top of d
+    let event_a = build_test_event(id_secret, None);
+    let event_b = build_test_event(id_secret, event_a.event_id);
+    let event_c = build_test_event(id_secret, event_b.event_id);
+    let event_d = build_test_event(id_secret, event_c.event_id);
+    let other = build_test_event(other_secret, event_d.event_id);
+    for event in [&event_a, &event_b, &event_c, &event_d, &other] {
+        db.process_event(event).await;
+    }
+    let ids = |events: Vec<crate::event::EventRecord>| {
+        events
+            .into_iter()
+            .map(|event| event.signed.compute_short_id())
+            .collect::<Vec<_>>()
+    };
+    let author = id_secret.id();
+    let [a, b, c, d] = [&event_a, &event_b, &event_c, &event_d].map(|e| e.event_id.to_short());
+
+    assert_eq!(
+        ids(db.get_ancestors(author, &[d], &[], 16).await),
+        vec![d, c, b, a]
+    );
+    assert_eq!(
+        ids(db.get_ancestors(author, &[d], &[b], 16).await),
+        vec![d, c]
+    );
+    assert_eq!(
+        ids(db.get_ancestors(author, &[d], &[], 2).await),
+        vec![d, c]
+    );
+    assert!(
+        db.get_ancestors(author, &[other.event_id.to_short()], &[], 16)
+            .await
+            .is_empty()
+    );
+
+    Ok(())
+}
+
+/// Test: get_events_by_author_range returns only events of the author, in
+/// `(timestamp, event id)` order, within the range.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_get_events_by_author_range() -> BoxedErrorResult<()> {
+    let id_secret = RostraIdSecretKey::generate();
+    let other_secret = RostraIdSecretKey::generate();
+    let (_dir, db) = temp_db_rng().await?;
+
+    let event_a = build_test_event(id_secret, None);
+    let event_b = build_test_event(id_secret, event_a.event_id);
+    let event_c = build_test_event(id_secret, event_b.event_id);
+    let other = build_test_event(other_secret, None);
+    for event in [&event_a, &event_b, &event_c, &other] {
+        db.process_event(event).await;
+    }
+    let author = id_secret.id();
+    let mut expected: Vec<_> = [&event_a, &event_b, &event_c]
+        .map(|e| (e.event.timestamp.into(), e.event_id.to_short()))
+        .into_iter()
+        .collect();
+    expected.sort();
+
+    let all = db
+        .get_events_by_author_range(
+            author,
+            (Timestamp::ZERO, ShortEventId::ZERO),
+            None,
+            usize::MAX,
+        )
+        .await;
+    assert_eq!(all, expected);
+
+    assert_eq!(
+        db.get_events_by_author_range(author, all[1], None, usize::MAX)
+            .await,
+        expected[1..]
+    );
+    assert_eq!(
+        db.get_events_by_author_range(author, all[0], None, 2).await,
+        expected[..2]
+    );
+    assert_eq!(
+        db.get_events_by_author_range(author, all[0], Some(all[2]), usize::MAX)
+            .await,
+        expected[..2]
+    );
+    assert!(
+        db.get_events_by_author_range(author, all[2], Some(all[0]), usize::MAX)
+            .await
+            .is_empty()
+    );
+
+    Ok(())
+}
+
 /// Test: get_random_self_event returns events correctly.
 #[test_log::test(tokio::test(flavor = "multi_thread"))]
 async fn test_get_random_self_event() -> BoxedErrorResult<()> {
@@ -2029,7 +2186,10 @@ async fn test_data_usage_new_event_metadata() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
@@ -2048,7 +2208,10 @@ async fn test_data_usage_new_event_metadata() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -2069,7 +2232,10 @@ async fn test_data_usage_new_event_metadata() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -2727,7 +2893,10 @@ async fn test_follow_unfollow_refollow_flow() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
@@ -2740,7 +2909,10 @@ async fn test_follow_unfollow_refollow_flow() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -2794,7 +2966,10 @@ async fn test_follow_unfollow_refollow_flow() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
@@ -2806,7 +2981,10 @@ async fn test_follow_unfollow_refollow_flow() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -2867,7 +3045,10 @@ async fn test_follow_unfollow_refollow_flow() -> BoxedErrorResult<()> {
         let mut events_table = tx.open_table(&events::TABLE)?;
         let mut events_missing_table = tx.open_table(&events_missing::TABLE)?;
         let mut events_heads_table = tx.open_table(&events_heads::TABLE)?;
+        let mut events_pruned_table = tx.open_table(&events_pruned::TABLE)?;
+        let events_pruned_checkpoints_table = tx.open_table(&events_pruned_checkpoints::TABLE)?;
         let mut events_by_time_table = tx.open_table(&events_by_time::TABLE)?;
+        let mut events_by_author_table = tx.open_table(&events_by_author::TABLE)?;
         let mut events_content_state_table = tx.open_table(&events_content_state::TABLE)?;
         let mut content_store_table = tx.open_table(&content_store::TABLE)?;
         let mut content_rc_table = tx.open_table(&content_rc::TABLE)?;
@@ -2879,7 +3060,10 @@ async fn test_follow_unfollow_refollow_flow() -> BoxedErrorResult<()> {
             &mut events_table,
             &mut events_missing_table,
             &mut events_heads_table,
+            &mut events_pruned_table,
+            &events_pruned_checkpoints_table,
             &mut events_by_time_table,
+            &mut events_by_author_table,
             &mut events_content_state_table,
             &mut content_store_table,
             &mut content_rc_table,
@@ -3388,7 +3572,7 @@ async fn test_total_migration() -> BoxedErrorResult<()> {
         let db_ver_table = tx.open_table(&db_version::TABLE)?;
         let current_ver = db_ver_table.first()?.map(|g| g.1.value());
         info!("DB version after migration: {:?}", current_ver);
-        assert_eq!(current_ver, Some(26), "DB version should be updated");
+        assert_eq!(current_ver, Some(39), "DB version should be updated");
         assert_eq!(
             tx.open_table(&EXTENSION_TABLE)?
                 .get(&1)?
@@ -3941,7 +4125,7 @@ async fn too_new_version_precedes_identity_decode() -> BoxedErrorResult<()> {
     {
         let db = redb_bincode::Database::from(redb::Database::open(&db_path)?);
         let tx = db.begin_write()?;
-        tx.open_table(&db_version::TABLE)?.insert(&(), &27)?;
+        tx.open_table(&db_version::TABLE)?.insert(&(), &40)?;
         let mut ids_self_raw = tx.as_raw().open_table(ids_self::TABLE.as_raw())?;
         ids_self_raw.insert(&[][..], &[0xff][..])?;
         drop(ids_self_raw);
@@ -3951,8 +4135,8 @@ async fn too_new_version_precedes_identity_decode() -> BoxedErrorResult<()> {
     assert!(matches!(
         Database::open(&db_path, self_id).await,
         Err(DbError::DbVersionTooHigh {
-            db_ver: 27,
-            code_ver: 26,
+            db_ver: 40,
+            code_ver: 39,
             ..
         })
     ));
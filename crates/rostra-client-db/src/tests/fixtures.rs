@@ -0,0 +1,67 @@
+//! Signed events shared by the tests of the individual database features.
+
+use rostra_core::EventId;
+use rostra_core::event::content_kind::{self, EventContentKind as _};
+use rostra_core::event::{Event, EventContentRaw, EventKind, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::RostraIdSecretKey;
+
+/// A text post by `secret` at `timestamp`, replacing `replaced` if set
+pub(crate) fn social_post(
+    secret: RostraIdSecretKey,
+    timestamp: i64,
+    parent_prev: Option<EventId>,
+    replaced: Option<EventId>,
+    body: &str,
+) -> VerifiedEventContent {
+    social_post_with(
+        secret,
+        timestamp,
+        parent_prev,
+        replaced,
+        content_kind::SocialPost::new_text(body.to_owned(), None, Default::default()),
+    )
+}
+
+/// Like [`social_post`], with any `content`
+pub(crate) fn social_post_with(
+    secret: RostraIdSecretKey,
+    timestamp: i64,
+    parent_prev: Option<EventId>,
+    replaced: Option<EventId>,
+    content: content_kind::SocialPost,
+) -> VerifiedEventContent {
+    let content = content
+        .serialize_cbor()
+        .expect("social post must serialize");
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::SOCIAL_POST)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .content(&content)
+        .maybe_parent_prev(parent_prev.map(Into::into))
+        .maybe_delete(replaced.map(Into::into))
+        .build()
+        .signed_by(secret);
+    let event = VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify");
+    VerifiedEventContent::assume_verified(event, content)
+}
+
+/// An event by `secret` deleting its `target` post
+pub(crate) fn deletion(
+    secret: RostraIdSecretKey,
+    timestamp: i64,
+    parent: EventId,
+    target: EventId,
+) -> VerifiedEvent {
+    let content = EventContentRaw::new(vec![]);
+    let event = Event::builder_raw_content()
+        .author(secret.id())
+        .kind(EventKind::SOCIAL_POST)
+        .timestamp(time::OffsetDateTime::from_unix_timestamp(timestamp).expect("valid timestamp"))
+        .parent_prev(parent.into())
+        .delete(target.into())
+        .content(&content)
+        .build()
+        .signed_by(secret);
+    VerifiedEvent::verify_signed(secret.id(), event).expect("event must verify")
+}
@@ -22,9 +22,9 @@ use super::event_order::EventOrder;
 use super::id_self::IdSelfAccountRecord;
 use super::{
     Database, DbError, DbResult, EventsHeadsTableRecord, InsertEventOutcome, content_rc,
-    content_store, events, events_by_time, events_content_state, events_heads, events_missing,
-    events_self, get_first_in_range, get_last_in_range, ids, ids_follow_events, ids_followees,
-    ids_followers, ids_self, tables,
+    content_store, events, events_by_author, events_by_time, events_content_state, events_heads,
+    events_missing, events_pruned, events_pruned_checkpoints, events_self, get_first_in_range,
+    get_last_in_range, ids, ids_follow_events, ids_followees, ids_followers, ids_self, tables,
 };
 use crate::{
     IdSocialProfileRecord, IdsDataUsageRecord, LOG_TARGET, Latest, LatestEventValue,
@@ -141,27 +141,40 @@ impl Database {
     /// - Direct followees (passed in)
     /// - Extended followees: followees of direct followees, excluding those
     ///   already in direct followees
+    ///
+    /// `blocked` identities are left out of both, and their followees don't
+    /// extend the WoT.
     pub(crate) fn compute_wot_tx(
         self_id: RostraId,
         direct_followees: &HashMap<RostraId, IdsFolloweesRecord>,
+        blocked: HashSet<RostraId>,
         ids_followees_table: &impl ids_followees::ReadableTable,
     ) -> DbResult<WotData> {
+        let followees: HashMap<_, _> = direct_followees
+            .iter()
+            .filter(|(id, _)| !blocked.contains(id))
+            .map(|(id, record)| (*id, record.clone()))
+            .collect();
         let mut extended = HashSet::new();
 
-        for followee_id in direct_followees.keys() {
+        for followee_id in followees.keys() {
             // Get the followees of this followee
             for result in Self::read_followees_tx_iter(*followee_id, ids_followees_table)? {
                 let (ext_id, _record) = result?;
-                // Don't include self or direct followees in extended
-                if ext_id != self_id && !direct_followees.contains_key(&ext_id) {
+                // Don't include self, direct followees or blocked ids in extended
+                if ext_id != self_id
+                    && !direct_followees.contains_key(&ext_id)
+                    && !blocked.contains(&ext_id)
+                {
                     extended.insert(ext_id);
                 }
             }
         }
 
         Ok(WotData {
-            followees: direct_followees.clone(),
+            followees,
             extended,
+            blocked,
         })
     }
 
@@ -205,7 +218,10 @@ impl Database {
         events_table: &mut events::Table,
         events_missing_table: &mut events_missing::Table,
         events_heads_table: &mut events_heads::Table,
+        events_pruned_table: &mut events_pruned::Table,
+        events_pruned_checkpoints_table: &impl events_pruned_checkpoints::ReadableTable,
         events_by_time_table: &mut events_by_time::Table,
+        events_by_author_table: &mut events_by_author::Table,
         events_content_state_table: &mut events_content_state::Table,
         content_store_table: &mut content_store::Table,
         content_rc_table: &mut content_rc::Table,
@@ -221,6 +237,21 @@ impl Database {
             return Ok(InsertEventOutcome::AlreadyPresent);
         }
 
+        if Self::is_event_pruned_tx(
+            author,
+            event_id,
+            event.timestamp(),
+            events_pruned_table,
+            events_pruned_checkpoints_table,
+        )? {
+            // If a retained child was waiting for it, it is now a known pruned
+            // parent instead of a missing one.
+            if events_missing_table.remove(&(author, event_id))?.is_some() {
+                events_pruned_table.insert(&(author, event_id), &())?;
+            }
+            return Ok(InsertEventOutcome::Pruned);
+        }
+
         let (was_missing, is_deleted) = match events_missing_table
             .remove(&(author, event_id))?
             .map(|g| g.value())
@@ -337,6 +368,9 @@ impl Database {
                         }
                     }
                 }
+            } else if events_pruned_table.get(&(author, parent_id))?.is_some() {
+                // The parent was pruned locally, so it is known, just not
+                // retained.
             } else {
                 // We do not have this parent yet, so we mark it as missing
                 let old_deleted_by = events_missing_table
@@ -363,6 +397,7 @@ impl Database {
             },
         )?;
         events_by_time_table.insert(&(event.timestamp(), event_id), &())?;
+        events_by_author_table.insert(&(author, event.timestamp(), event_id), &())?;
 
         // Track metadata for this event
         if let Some(ref mut usage_table) = ids_data_usage_table {
@@ -653,6 +688,26 @@ impl Database {
         Ok(events_table.get(&event.into())?.is_some())
     }
 
+    /// Check if an event of `author` authored at `timestamp` belongs to
+    /// locally pruned history.
+    ///
+    /// This is the case for a pruned parent on the retained frontier, and for
+    /// anything authored before the author's pruning checkpoint.
+    pub(crate) fn is_event_pruned_tx(
+        author: RostraId,
+        event_id: ShortEventId,
+        timestamp: Timestamp,
+        events_pruned_table: &impl events_pruned::ReadableTable,
+        events_pruned_checkpoints_table: &impl events_pruned_checkpoints::ReadableTable,
+    ) -> DbResult<bool> {
+        if events_pruned_table.get(&(author, event_id))?.is_some() {
+            return Ok(true);
+        }
+        Ok(events_pruned_checkpoints_table
+            .get(&author)?
+            .is_some_and(|checkpoint| timestamp < checkpoint.value().before))
+    }
+
     /// Get the per-event content state (not the content itself).
     ///
     /// To get the actual content, use `get_event_content_full_tx` which also
@@ -1128,7 +1183,6 @@ impl Database {
     }
 
     /// Get the reference count for content by its hash.
-    #[cfg(test)]
     pub(crate) fn get_content_rc_tx(
         content_hash: ContentHash,
         content_rc_table: &impl content_rc::ReadableTable,
@@ -1175,6 +1229,24 @@ impl Database {
         Ok(())
     }
 
+    /// Track a locally pruned event header (metadata only).
+    ///
+    /// The event stays accounted in `total_metadata_*`.
+    pub(crate) fn track_pruned_event_tx(
+        author: RostraId,
+        ids_data_usage_table: &mut ids_data_usage::Table,
+    ) -> DbResult<()> {
+        let mut usage = Self::get_usage_mut(author, ids_data_usage_table)?;
+
+        usage.current_metadata_size = usage
+            .current_metadata_size
+            .saturating_sub(Self::EVENT_METADATA_SIZE);
+        usage.current_metadata_num = usage.current_metadata_num.saturating_sub(1);
+
+        ids_data_usage_table.insert(&author, &usage)?;
+        Ok(())
+    }
+
     /// Track a newly inserted payload (starts as missing).
     ///
     /// Called in `insert_event_tx` when an event with `content_len > 0` is
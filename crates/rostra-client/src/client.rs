@@ -14,8 +14,10 @@ use std::time::Duration;
 use backon::Retryable as _;
 use iroh_base::EndpointAddr;
 use n0_future::task::AbortOnDropHandle;
+use rostra_client_db::drafts::PostDraftId;
 use rostra_client_db::{
-    CurrentState, Database, DbError, DbResult, IdsFolloweesRecord, IdsFollowersRecord, WotData,
+    ContentPruningPolicy, CurrentState, Database, DbError, DbResult, IdsFolloweesRecord,
+    IdsFollowersRecord, WotData,
 };
 use rostra_core::event::{
     Event, EventContentRaw, EventExt as _, IrohNodeId, PersonaTag, PersonasTagsSelector,
@@ -34,17 +36,21 @@ use tracing::{debug, info, trace, warn};
 
 use crate::LOG_TARGET;
 use crate::error::{
-    ActivateResult, ActivateSnafu, ConnectResult, IdResolveError, IdResolveResult,
-    IdSecretReadResult, InitIrohClientSnafu, InitPkarrClientSnafu, InitResult, IoSnafu,
-    LocalAnnouncementStorageSnafu, ParsingSnafu, PostResult, SecretMismatchSnafu, StorageSnafu,
-    StoreEventError, StoreEventResult,
+    ActivateResult, ActivateSnafu, ConnectResult, DirectMessageSnafu, DraftNotFoundSnafu,
+    IdResolveError, IdResolveResult, IdSecretReadResult, InitIrohClientSnafu, InitPkarrClientSnafu,
+    InitResult, InvalidPollOptionSnafu, IoSnafu, LocalAnnouncementStorageSnafu,
+    NoPrivateAudienceSnafu, NoSuccessorSnafu, NotDelegatedSnafu, ParsingSnafu, PollClosedSnafu,
+    PollUnavailableSnafu, PostResult, PrivatePostSnafu, RootSecretRequiredSnafu,
+    SecretMismatchSnafu, StorageSnafu, StoreEventError, StoreEventResult, UnsupportedImageSnafu,
 };
 use crate::id::{CompactTicket, IdResolvedData};
 use crate::task::head_merger::HeadMerger;
 use crate::task::missing_event_content_fetcher::MissingEventContentFetcher;
 use crate::task::missing_event_fetcher::MissingEventFetcher;
 use crate::task::pkarr_id_publisher::PkarrIdPublisher;
+use crate::task::private_audience_keeper::PrivateAudienceKeeper;
 use crate::task::request_handler::RequestHandler;
+use crate::task::scheduled_post_publisher::ScheduledPostPublisher;
 
 /// Per-identity P2P connection state for debugging.
 ///
@@ -371,6 +377,10 @@ impl Client {
         /// a single instance can be shared across all Rostra clients.
         /// Use [`Client::make_pkarr_client`] to create one.
         pkarr_client: Option<Arc<pkarr::Client>>,
+        /// Local content retention policy, enforced periodically by a
+        /// background task, also on the temporary in-memory database.
+        /// Nothing is pruned when unset.
+        content_pruning_policy: Option<ContentPruningPolicy>,
     ) -> InitResult<Arc<Self>> {
         debug!(target: LOG_TARGET, id = %id, "Starting Rostra client");
         let client_start = Instant::now();
@@ -432,6 +442,12 @@ impl Client {
             client.start_poll_followee_head_updates();
             client.start_wot_head_sync();
             client.start_news_score_updater();
+            client.start_repost_original_fetcher();
+        }
+        if start_background_tasks
+            && let Some(policy) = content_pruning_policy.filter(|policy| !policy.is_noop())
+        {
+            client.start_content_pruner(policy);
         }
 
         if let Some(secret) = secret {
@@ -494,6 +510,8 @@ impl Client {
         self.active.store(true, SeqCst);
         self.start_pkarr_id_publisher(id_secret);
         self.start_head_merger(id_secret);
+        self.start_private_audience_keeper(id_secret);
+        self.start_scheduled_post_publisher(id_secret);
         Ok(())
     }
 
@@ -574,6 +592,14 @@ impl Client {
         self.spawn_task(HeadMerger::new(self, secret_id).run());
     }
 
+    pub(crate) fn start_private_audience_keeper(&self, secret_id: RostraIdSecretKey) {
+        self.spawn_task(PrivateAudienceKeeper::new(self, secret_id).run());
+    }
+
+    pub(crate) fn start_scheduled_post_publisher(&self, secret_id: RostraIdSecretKey) {
+        self.spawn_task(ScheduledPostPublisher::new(self, secret_id).run());
+    }
+
     pub(crate) fn start_request_handler(&self) {
         self.spawn_task(RequestHandler::new(self, self.networking.endpoint.clone()).run());
     }
@@ -614,6 +640,16 @@ impl Client {
         self.spawn_task(crate::task::news_score_updater::NewsScoreUpdater::new(self).run());
     }
 
+    pub(crate) fn start_repost_original_fetcher(&self) {
+        self.spawn_task(
+            crate::task::repost_original_fetcher::RepostOriginalFetcher::new(self).run(),
+        );
+    }
+
+    pub(crate) fn start_content_pruner(&self, policy: ContentPruningPolicy) {
+        self.spawn_task(crate::task::content_pruner::ContentPruner::new(self, policy).run());
+    }
+
     pub(crate) async fn iroh_address(&self) -> WhateverResult<EndpointAddr> {
         pub(crate) fn sanitize_endpoint_addr(endpoint_addr: EndpointAddr) -> EndpointAddr {
             use iroh_base::TransportAddr;
@@ -869,6 +905,8 @@ impl Client {
         #[builder(start_fn)] id_secret: RostraIdSecretKey,
         #[builder(start_fn)] content: C,
         replace: Option<ShortEventId>,
+        /// Defaults to now
+        timestamp: Option<Timestamp>,
     ) -> PostResult<VerifiedEvent>
     where
         C: content_kind::EventContentKind,
@@ -885,12 +923,16 @@ impl Client {
             .maybe_parent_prev(current_head)
             .maybe_parent_aux(aux_event)
             .maybe_delete(replace)
+            .maybe_timestamp(timestamp.and_then(Timestamp::to_offset_date_time))
             .build()?;
 
         let signed_event = event.signed_by(id_secret);
 
-        let verified_event = VerifiedEvent::verify_signed(self.id, signed_event)
-            .expect("Can't fail to verify self-created event");
+        let delegates = self.db.get_device_delegates(self.id).await;
+        let verified_event =
+            VerifiedEvent::verify_signed_delegated(self.id, signed_event, &delegates)
+                .ok()
+                .context(NotDelegatedSnafu)?;
         let verified_event_content =
             rostra_core::event::VerifiedEventContent::verify(verified_event, content)
                 .expect("Can't fail to verify self-created content");
@@ -912,6 +954,23 @@ impl Client {
             .await
     }
 
+    /// Publish an end-to-end encrypted direct message to `recipient`
+    ///
+    /// Besides the followers, the event is delivered to the recipient's node.
+    pub async fn send_direct_message(
+        &self,
+        id_secret: RostraIdSecretKey,
+        recipient: RostraId,
+        body: String,
+    ) -> PostResult<VerifiedEvent> {
+        ensure!(id_secret.id() == self.id, RootSecretRequiredSnafu);
+        let body = content_kind::DirectMessageBody { djot_content: body };
+        body.validate()?;
+        let content = content_kind::DirectMessage::seal(id_secret, recipient, &body)
+            .context(DirectMessageSnafu)?;
+        self.publish_event(id_secret, content).call().await
+    }
+
     pub async fn social_post(
         &self,
         id_secret: RostraIdSecretKey,
@@ -927,6 +986,217 @@ impl Client {
         .await
     }
 
+    /// Publish a draft as a post, and remove it
+    pub async fn publish_post_draft(
+        &self,
+        id_secret: RostraIdSecretKey,
+        id: PostDraftId,
+    ) -> PostResult<VerifiedEvent> {
+        let draft = self
+            .db
+            .get_post_draft(id)
+            .await
+            .context(DraftNotFoundSnafu)?;
+        let event = self
+            .social_post(id_secret, draft.content, draft.reply_to, draft.persona_tags)
+            .await?;
+        self.db.remove_post_draft(id).await.context(StorageSnafu)?;
+        Ok(event)
+    }
+
+    /// Repost (boost) `original` to our followers, optionally quoting it
+    pub async fn social_repost(
+        &self,
+        id_secret: RostraIdSecretKey,
+        original: ExternalEventId,
+        comment: Option<String>,
+    ) -> PostResult<VerifiedEvent> {
+        self.publish_event(
+            id_secret,
+            content_kind::SocialRepost::new(original, comment),
+        )
+        .call()
+        .await
+    }
+
+    /// Undo the repost `repost` of `original`
+    pub async fn social_unrepost(
+        &self,
+        id_secret: RostraIdSecretKey,
+        original: ExternalEventId,
+        repost: ShortEventId,
+    ) -> PostResult<VerifiedEvent> {
+        self.publish_event(id_secret, content_kind::SocialRepost::new(original, None))
+            .replace(repost)
+            .call()
+            .await
+    }
+
+    /// Publish a media file
+    ///
+    /// Location metadata of images is removed first, and images it can't be
+    /// removed from are refused. Files larger than
+    /// [`content_kind::SocialMediaChunk::MAX_LEN`] are published as chunks,
+    /// followed by a [`content_kind::SocialMediaManifest`] referencing them,
+    /// which is the returned event.
+    pub async fn publish_media(
+        &self,
+        id_secret: RostraIdSecretKey,
+        mime: String,
+        data: Vec<u8>,
+    ) -> PostResult<VerifiedEvent> {
+        let data = crate::util::exif::strip_location_metadata(&mime, data)
+            .context(UnsupportedImageSnafu { mime: mime.clone() })?;
+        if data.len() <= content_kind::SocialMediaChunk::MAX_LEN {
+            return self
+                .publish_event(id_secret, content_kind::SocialMedia { mime, data })
+                .call()
+                .await;
+        }
+
+        let mut chunks = vec![];
+        for chunk in data.chunks(content_kind::SocialMediaChunk::MAX_LEN) {
+            let event = self
+                .publish_event(
+                    id_secret,
+                    content_kind::SocialMediaChunk {
+                        data: chunk.to_vec(),
+                    },
+                )
+                .call()
+                .await?;
+            chunks.push(ShortEventId::from(event.event_id));
+        }
+
+        self.publish_event(
+            id_secret,
+            content_kind::SocialMediaManifest {
+                mime,
+                len: data.len() as u64,
+                chunk_size: content_kind::SocialMediaChunk::MAX_LEN as u32,
+                chunks,
+            },
+        )
+        .call()
+        .await
+    }
+
+    /// Publish a social post readable only by the private audience
+    ///
+    /// See [`Self::set_private_audience`].
+    pub async fn social_private_post(
+        &self,
+        id_secret: RostraIdSecretKey,
+        body: String,
+        reply_to: Option<ExternalEventId>,
+        persona_tags: BTreeSet<PersonaTag>,
+    ) -> PostResult<VerifiedEvent> {
+        let audience = self
+            .db
+            .get_private_audience()
+            .await
+            .context(NoPrivateAudienceSnafu)?;
+        let key = self
+            .db
+            .get_private_audience_key(self.id, audience.key_id)
+            .await
+            .context(NoPrivateAudienceSnafu)?;
+        let content = content_kind::PrivateSocialPost::seal(
+            key,
+            &content_kind::SocialPost::new(body, reply_to, persona_tags),
+        )
+        .context(PrivatePostSnafu)?;
+        self.publish_event(id_secret, content).call().await
+    }
+
+    /// Replace the audience of private posts with `members`, under a new key
+    ///
+    /// Members removed from the audience can't read private posts published
+    /// afterwards.
+    pub async fn set_private_audience(
+        &self,
+        id_secret: RostraIdSecretKey,
+        members: impl IntoIterator<Item = RostraId>,
+    ) -> PostResult<VerifiedEvent> {
+        ensure!(id_secret.id() == self.id, RootSecretRequiredSnafu);
+        let key = content_kind::PrivateAudienceKey::generate();
+        let grant = content_kind::PrivateAudienceKeyGrant::new(id_secret, key, members)
+            .context(PrivatePostSnafu)?;
+        self.db
+            .insert_self_private_audience_key(key)
+            .await
+            .context(StorageSnafu)?;
+        self.publish_private_audience_grant(id_secret, grant).await
+    }
+
+    /// Grant the current private audience key to one more `member`
+    ///
+    /// Starts a new audience if there is none yet.
+    pub async fn add_private_audience_member(
+        &self,
+        id_secret: RostraIdSecretKey,
+        member: RostraId,
+    ) -> PostResult<VerifiedEvent> {
+        ensure!(id_secret.id() == self.id, RootSecretRequiredSnafu);
+        let Some(audience) = self.db.get_private_audience().await else {
+            return self.set_private_audience(id_secret, [member]).await;
+        };
+        let Some(key) = self
+            .db
+            .get_private_audience_key(self.id, audience.key_id)
+            .await
+        else {
+            return self
+                .set_private_audience(id_secret, audience.members.into_iter().chain([member]))
+                .await;
+        };
+        let grant = content_kind::PrivateAudienceKeyGrant::new(
+            id_secret,
+            key,
+            audience.members.into_iter().chain([member]),
+        )
+        .context(PrivatePostSnafu)?;
+        self.publish_private_audience_grant(id_secret, grant).await
+    }
+
+    /// Publish `grant`, replacing the current audience
+    ///
+    /// Only a newer grant replaces the audience, so one replacing a grant
+    /// from the same second is published a second later.
+    async fn publish_private_audience_grant(
+        &self,
+        id_secret: RostraIdSecretKey,
+        grant: content_kind::PrivateAudienceKeyGrant,
+    ) -> PostResult<VerifiedEvent> {
+        let timestamp = self
+            .db
+            .get_private_audience_ts()
+            .await
+            .map(|ts| Timestamp::now().max(ts.saturating_add_secs(1)));
+        self.publish_event(id_secret, grant)
+            .maybe_timestamp(timestamp)
+            .call()
+            .await
+    }
+
+    /// Remove `member` from the private audience, rotating its key
+    pub async fn remove_private_audience_member(
+        &self,
+        id_secret: RostraIdSecretKey,
+        member: RostraId,
+    ) -> PostResult<VerifiedEvent> {
+        let audience = self
+            .db
+            .get_private_audience()
+            .await
+            .context(NoPrivateAudienceSnafu)?;
+        self.set_private_audience(
+            id_secret,
+            audience.members.into_iter().filter(|id| *id != member),
+        )
+        .await
+    }
+
     pub async fn social_news_post(
         &self,
         id_secret: RostraIdSecretKey,
@@ -961,6 +1231,58 @@ impl Client {
     pub async fn get_self_social_vote(&self, post_id: ExternalEventId) -> Option<Option<bool>> {
         self.db.get_social_vote(self.rostra_id(), post_id).await
     }
+
+    /// Publish a poll, presented by a post with the question
+    ///
+    /// Returns the post event.
+    pub async fn social_poll(
+        &self,
+        id_secret: RostraIdSecretKey,
+        poll: content_kind::SocialPoll,
+        persona_tags: BTreeSet<PersonaTag>,
+    ) -> PostResult<VerifiedEvent> {
+        let question = poll.question.clone();
+        let poll_event = self.publish_event(id_secret, poll).call().await?;
+        self.publish_event(
+            id_secret,
+            content_kind::SocialPost::new_text(question, None, persona_tags)
+                .with_poll(ShortEventId::from(poll_event.event_id)),
+        )
+        .call()
+        .await
+    }
+
+    /// Vote for `option` in a poll, or retract the vote with `None`
+    pub async fn set_social_poll_vote(
+        &self,
+        id_secret: RostraIdSecretKey,
+        poll_id: ExternalEventId,
+        option: Option<u32>,
+    ) -> PostResult<VerifiedEvent> {
+        let poll = self
+            .db
+            .get_social_poll(poll_id)
+            .await
+            .context(PollUnavailableSnafu)?;
+        ensure!(!poll.is_closed(Timestamp::now()), PollClosedSnafu);
+        ensure!(
+            option.is_none_or(|option| (option as usize) < poll.options.len()),
+            InvalidPollOptionSnafu
+        );
+        self.publish_event(
+            id_secret,
+            content_kind::SocialPollVote::new(poll_id, option),
+        )
+        .call()
+        .await
+    }
+
+    pub async fn get_self_social_poll_vote(&self, poll_id: ExternalEventId) -> Option<u32> {
+        self.db
+            .get_social_poll_vote(self.rostra_id(), poll_id)
+            .await
+    }
+
     pub async fn post_social_profile_update(
         &self,
         id_secret: RostraIdSecretKey,
@@ -973,6 +1295,14 @@ impl Client {
             .get_social_profile(self.rostra_id())
             .await
             .map(|r| r.event_id);
+        let avatar = match avatar {
+            Some((mime, data)) => {
+                let data = crate::util::exif::strip_location_metadata(&mime, data)
+                    .context(UnsupportedImageSnafu { mime: mime.clone() })?;
+                Some((mime, data))
+            }
+            None => None,
+        };
         self.publish_event(
             id_secret,
             content_kind::SocialProfileUpdate {
@@ -1022,6 +1352,141 @@ impl Client {
         .call()
         .await
     }
+
+    /// Announce the owner of `successor_secret` as the successor of the local
+    /// identity
+    pub async fn announce_successor(
+        &self,
+        id_secret: RostraIdSecretKey,
+        successor_secret: RostraIdSecretKey,
+    ) -> PostResult<VerifiedEvent> {
+        ensure!(id_secret.id() == self.id, RootSecretRequiredSnafu);
+        self.publish_event(
+            id_secret,
+            content_kind::Succession::new(self.id, successor_secret),
+        )
+        .call()
+        .await
+    }
+
+    /// Delegate signing events to the `device` key, or revoke a delegation
+    ///
+    /// Only the identity's own secret key can manage devices. Devices can't
+    /// encrypt or decrypt direct messages and private posts, which need that
+    /// key too.
+    pub async fn delegate_device(
+        &self,
+        id_secret: RostraIdSecretKey,
+        device: RostraId,
+        revoked: bool,
+    ) -> PostResult<VerifiedEvent> {
+        ensure!(id_secret.id() == self.id, RootSecretRequiredSnafu);
+        self.publish_event(
+            id_secret,
+            content_kind::DeviceDelegation {
+                device,
+                revoked,
+                expires_at: None,
+            },
+        )
+        .call()
+        .await
+    }
+
+    /// Delegate signing events to the `device` key until `expires_at`
+    ///
+    /// After that the delegation counts as revoked, without the identity's
+    /// secret key having to be around to revoke it.
+    pub async fn delegate_device_until(
+        &self,
+        id_secret: RostraIdSecretKey,
+        device: RostraId,
+        expires_at: Timestamp,
+    ) -> PostResult<VerifiedEvent> {
+        ensure!(id_secret.id() == self.id, RootSecretRequiredSnafu);
+        self.publish_event(
+            id_secret,
+            content_kind::DeviceDelegation {
+                device,
+                revoked: false,
+                expires_at: Some(expires_at),
+            },
+        )
+        .call()
+        .await
+    }
+
+    /// Accept the succession of `predecessor`, moving the follow of it to its
+    /// latest successor
+    ///
+    /// The successor is followed with the same persona tags selector as the
+    /// predecessor was. Returns the follow event.
+    pub async fn accept_succession(
+        &self,
+        id_secret: RostraIdSecretKey,
+        predecessor: RostraId,
+    ) -> PostResult<VerifiedEvent> {
+        let successor = self
+            .db
+            .get_succession_chain(predecessor)
+            .await
+            .last()
+            .copied()
+            .context(NoSuccessorSnafu)?;
+        let predecessor_selector = self
+            .db
+            .get_followees(self.id)
+            .await
+            .into_iter()
+            .find_map(|(id, selector)| (id == predecessor).then_some(selector));
+
+        let follow_event = self
+            .follow(
+                id_secret,
+                successor,
+                predecessor_selector.clone().unwrap_or_default(),
+            )
+            .await?;
+        if predecessor_selector.is_some() {
+            self.unfollow(id_secret, predecessor).await?;
+        }
+        Ok(follow_event)
+    }
+
+    /// Block `target`, hiding its content and leaving it out of the web of
+    /// trust
+    pub async fn block(
+        &self,
+        id_secret: RostraIdSecretKey,
+        target: RostraId,
+    ) -> PostResult<VerifiedEvent> {
+        self.publish_event(
+            id_secret,
+            content_kind::Block {
+                target,
+                blocked: true,
+            },
+        )
+        .call()
+        .await
+    }
+
+    pub async fn unblock(
+        &self,
+        id_secret: RostraIdSecretKey,
+        target: RostraId,
+    ) -> PostResult<VerifiedEvent> {
+        self.publish_event(
+            id_secret,
+            content_kind::Block {
+                target,
+                blocked: false,
+            },
+        )
+        .call()
+        .await
+    }
+
     pub async fn publish_omni_tbd(
         &self,
         id_secret: RostraIdSecretKey,
@@ -1123,11 +1588,14 @@ impl Client {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeSet;
     use std::sync::atomic::Ordering::SeqCst;
     use std::time::Duration;
 
     use iroh::endpoint::presets;
+    use rostra_client_db::drafts::PostDraft;
     use rostra_client_db::{Database, DbError, EventContentState};
+    use rostra_core::Timestamp;
     use rostra_core::event::{Event, EventContentRaw, EventKind};
     use rostra_core::id::{RostraIdSecretKey, ToShort as _};
     use rostra_p2p::connection::FeedEventResponse;
@@ -1135,7 +1603,7 @@ mod tests {
     use rostra_p2p_api::ROSTRA_P2P_V0_ALPN;
 
     use super::Client;
-    use crate::error::PostError;
+    use crate::error::{ActivateError, PostError};
 
     #[test_log::test(tokio::test(flavor = "multi_thread"))]
     async fn failed_announcement_does_not_commit_activation_and_retry_can_start_tasks() {
@@ -1171,11 +1639,228 @@ mod tests {
         assert!(client.active.load(SeqCst));
         assert_eq!(
             client.task_handles.lock().expect("task handles").len(),
-            2,
+            4,
             "the retry starts each signing task exactly once"
         );
     }
 
+    #[test_log::test(tokio::test(flavor = "multi_thread"))]
+    async fn delegated_device_publishes_until_revoked() {
+        let secret = RostraIdSecretKey::from_bytes([54; 32]);
+        let device = RostraIdSecretKey::from_bytes([55; 32]);
+        let client = Client::builder(secret.id())
+            .db(Database::new_in_memory(secret.id())
+                .await
+                .expect("in-memory database"))
+            .start_request_handler(false)
+            .start_background_tasks(false)
+            .build()
+            .await
+            .expect("test client");
+
+        assert!(matches!(
+            client
+                .social_post(device, "Too early".to_owned(), None, BTreeSet::new())
+                .await,
+            Err(PostError::NotDelegated)
+        ));
+
+        client
+            .delegate_device(secret, device.id(), false)
+            .await
+            .expect("delegation published");
+        let event = client
+            .social_post(device, "From a device".to_owned(), None, BTreeSet::new())
+            .await
+            .expect("delegated device can post");
+        assert_eq!(event.event.author, secret.id());
+        assert!(matches!(
+            client.delegate_device(device, device.id(), true).await,
+            Err(PostError::RootSecretRequired)
+        ));
+
+        client
+            .delegate_device(secret, device.id(), true)
+            .await
+            .expect("revocation published");
+        assert!(matches!(
+            client
+                .social_post(device, "Revoked".to_owned(), None, BTreeSet::new())
+                .await,
+            Err(PostError::NotDelegated)
+        ));
+    }
+
+    #[test_log::test(tokio::test(flavor = "multi_thread"))]
+    async fn private_audience_changes_within_a_second_take_effect() {
+        let secret = RostraIdSecretKey::from_bytes([58; 32]);
+        let member = RostraIdSecretKey::from_bytes([59; 32]).id();
+        let client = Client::builder(secret.id())
+            .db(Database::new_in_memory(secret.id())
+                .await
+                .expect("in-memory database"))
+            .start_request_handler(false)
+            .start_background_tasks(false)
+            .build()
+            .await
+            .expect("test client");
+
+        let members = async || {
+            client
+                .db()
+                .get_private_audience()
+                .await
+                .expect("audience set")
+                .members
+        };
+        for _ in 0..4 {
+            client
+                .add_private_audience_member(secret, member)
+                .await
+                .expect("member added");
+            assert!(members().await.contains(&member));
+            client
+                .remove_private_audience_member(secret, member)
+                .await
+                .expect("member removed");
+            assert_eq!(members().await, vec![secret.id()]);
+        }
+    }
+
+    #[test_log::test(tokio::test(flavor = "multi_thread"))]
+    async fn device_key_does_not_activate_client() {
+        let secret = RostraIdSecretKey::from_bytes([58; 32]);
+        let device = RostraIdSecretKey::from_bytes([59; 32]);
+        let endpoint = iroh::Endpoint::builder(presets::Minimal)
+            .relay_mode(iroh::RelayMode::Disabled)
+            .alpns(vec![ROSTRA_P2P_V0_ALPN.to_vec()])
+            .bind()
+            .await
+            .expect("test endpoint");
+        let client = Client::builder(secret.id())
+            .db(Database::new_in_memory(secret.id())
+                .await
+                .expect("in-memory database"))
+            .iroh_endpoint(endpoint)
+            .start_request_handler(false)
+            .start_background_tasks(false)
+            .build()
+            .await
+            .expect("test client");
+        client
+            .delegate_device(secret, device.id(), false)
+            .await
+            .expect("delegation published");
+
+        assert!(matches!(
+            client.unlock_active(device).await,
+            Err(ActivateError::SecretMismatch)
+        ));
+        assert!(!client.active.load(SeqCst));
+        assert!(client.task_handles.lock().expect("task handles").is_empty());
+        client
+            .social_post(device, "Signed".to_owned(), None, BTreeSet::new())
+            .await
+            .expect("device signs without unlocking");
+
+        client
+            .unlock_active(secret)
+            .await
+            .expect("own secret unlocks");
+        assert!(client.active.load(SeqCst));
+        assert_eq!(client.task_handles.lock().expect("task handles").len(), 4);
+    }
+
+    #[test_log::test(tokio::test(flavor = "multi_thread"))]
+    async fn expired_device_delegation_can_not_publish() {
+        let secret = RostraIdSecretKey::from_bytes([56; 32]);
+        let device = RostraIdSecretKey::from_bytes([57; 32]);
+        let client = Client::builder(secret.id())
+            .db(Database::new_in_memory(secret.id())
+                .await
+                .expect("in-memory database"))
+            .start_request_handler(false)
+            .start_background_tasks(false)
+            .build()
+            .await
+            .expect("test client");
+
+        client
+            .delegate_device_until(secret, device.id(), Timestamp::ZERO)
+            .await
+            .expect("delegation published");
+        assert!(matches!(
+            client
+                .social_post(device, "Expired".to_owned(), None, BTreeSet::new())
+                .await,
+            Err(PostError::NotDelegated)
+        ));
+    }
+
+    #[test_log::test(tokio::test(flavor = "multi_thread"))]
+    async fn due_scheduled_drafts_are_published_once_unlocked() {
+        let secret = RostraIdSecretKey::from_bytes([53; 32]);
+        let endpoint = iroh::Endpoint::builder(presets::Minimal)
+            .relay_mode(iroh::RelayMode::Disabled)
+            .alpns(vec![ROSTRA_P2P_V0_ALPN.to_vec()])
+            .bind()
+            .await
+            .expect("test endpoint");
+        let client = Client::builder(secret.id())
+            .db(Database::new_in_memory(secret.id())
+                .await
+                .expect("in-memory database"))
+            .iroh_endpoint(endpoint)
+            .start_request_handler(false)
+            .start_background_tasks(false)
+            .build()
+            .await
+            .expect("test client");
+
+        let draft = |content: &str, scheduled_at: Timestamp| PostDraft {
+            content: content.to_owned(),
+            reply_to: None,
+            persona_tags: BTreeSet::new(),
+            scheduled_at: Some(scheduled_at),
+            updated_at: Timestamp::now(),
+            last_error: None,
+        };
+        let due = client
+            .db()
+            .insert_post_draft(draft("Due", Timestamp::from(1)))
+            .await
+            .expect("draft stored");
+        let future = client
+            .db()
+            .insert_post_draft(draft("Future", Timestamp::MAX))
+            .await
+            .expect("draft stored");
+
+        client
+            .finish_activation(secret, Ok(()))
+            .expect("activation");
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while client.db().get_post_draft(due).await.is_some() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("due draft published");
+        let (posts, _) = client
+            .db()
+            .paginate_social_posts_rev(None, 10, |_| true)
+            .await;
+        assert_eq!(
+            posts
+                .into_iter()
+                .map(|post| post.content.djot_content)
+                .collect::<Vec<_>>(),
+            vec![Some("Due".to_owned())]
+        );
+        assert!(client.db().get_post_draft(future).await.is_some());
+    }
+
     #[test_log::test(tokio::test(flavor = "multi_thread"))]
     async fn oversized_feed_event_retains_pruned_envelope() {
         let secret = RostraIdSecretKey::from_bytes([52; 32]);
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
 
@@ -103,12 +104,14 @@ impl ConnectionCache {
     /// Try to fetch an event from multiple peers with some parallelism.
     ///
     /// Returns `Some(event)` from the first peer that has it, or `None`.
+    /// Events signed by one of the author's device `delegates` are accepted.
     pub async fn get_event_from_peers(
         &self,
         networking: &ClientNetworking,
         peers: &[RostraId],
         author_id: RostraId,
         event_id: ShortEventId,
+        delegates: &[RostraId],
     ) -> Option<VerifiedEvent> {
         let result = futures_lite::StreamExt::find_map(
             &mut stream::iter(peers.iter().copied())
@@ -116,7 +119,7 @@ impl ConnectionCache {
                     let cache = self.clone();
                     async move {
                         let conn = cache.get_or_connect(networking, peer_id).await.ok()?;
-                        match conn.get_event(author_id, event_id).await {
+                        match conn.get_event(author_id, event_id, delegates).await {
                             Ok(Some(event)) => Some(event),
                             Ok(None) => {
                                 debug!(
@@ -155,6 +158,118 @@ impl ConnectionCache {
         result
     }
 
+    /// Try to fetch a batch of events walking back from `head` from multiple
+    /// peers with some parallelism.
+    ///
+    /// Returns the batch from the first peer that has `head`, or `None` if no
+    /// peer has it or supports batch fetching.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_ancestors_from_peers(
+        &self,
+        networking: &ClientNetworking,
+        peers: &[RostraId],
+        author_id: RostraId,
+        head: ShortEventId,
+        known: &[ShortEventId],
+        limit: u32,
+        delegates: &[RostraId],
+    ) -> Option<Vec<VerifiedEvent>> {
+        let result = futures_lite::StreamExt::find_map(
+            &mut stream::iter(peers.iter().copied())
+                .map(|peer_id| {
+                    let cache = self.clone();
+                    let known = known.to_vec();
+                    async move {
+                        let conn = cache.get_or_connect(networking, peer_id).await.ok()?;
+                        match conn
+                            .get_ancestors(author_id, vec![head], known, limit, delegates)
+                            .await
+                        {
+                            Ok(events) if !events.is_empty() => Some(events),
+                            Ok(_) => {
+                                debug!(
+                                    target: LOG_TARGET,
+                                    peer_id = %peer_id.to_short(),
+                                    event_id = %head.to_short(),
+                                    "Event not found on peer"
+                                );
+                                None
+                            }
+                            Err(_err) => {
+                                debug!(
+                                    target: LOG_TARGET,
+                                    peer_id = %peer_id.to_short(),
+                                    event_id = %head.to_short(),
+                                    "Failed to fetch event batch from peer"
+                                );
+                                None
+                            }
+                        }
+                    }
+                })
+                .buffer_unordered(4),
+            |result| result,
+        )
+        .await;
+
+        if result.is_none() {
+            debug!(
+                target: LOG_TARGET,
+                event_id = %head.to_short(),
+                "Event batch not available from any peer"
+            );
+        }
+
+        result
+    }
+
+    /// Try to fetch the bytes `range` of event content from peers, one at a
+    /// time.
+    ///
+    /// Returns the bytes and the peer that had them from the first peer that
+    /// does, or `None`.
+    pub async fn get_event_content_range_from_peers(
+        &self,
+        networking: &ClientNetworking,
+        peers: &[RostraId],
+        event: VerifiedEvent,
+        range: Range<u32>,
+    ) -> Option<(RostraId, Vec<u8>)> {
+        for &peer_id in peers {
+            let Ok(conn) = self.get_or_connect(networking, peer_id).await else {
+                continue;
+            };
+            match conn.get_event_content_range(event, range.clone()).await {
+                Ok(Some(data)) => return Some((peer_id, data)),
+                Ok(None) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        peer_id = %peer_id.to_short(),
+                        event_id = %event.event_id.to_short(),
+                        "Peer does not have content range"
+                    );
+                }
+                Err(_err) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        peer_id = %peer_id.to_short(),
+                        event_id = %event.event_id.to_short(),
+                        "Failed to fetch content range from peer"
+                    );
+                }
+            }
+        }
+
+        debug!(
+            target: LOG_TARGET,
+            event_id = %event.event_id.to_short(),
+            start = range.start,
+            end = range.end,
+            "Event content range not found from any peer"
+        );
+        None
+    }
+
     /// Try to fetch event content from multiple peers with some parallelism.
     ///
     /// Returns `Some(content)` from the first peer that has it, or `None`.
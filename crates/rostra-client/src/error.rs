@@ -3,7 +3,7 @@ use std::io;
 use pkarr::dns::SimpleDnsError;
 use rostra_client_db::DbError;
 use rostra_core::ShortEventId;
-use rostra_core::event::ContentValidationError;
+use rostra_core::event::{ContentValidationError, DirectMessageError, PrivatePostError};
 use rostra_core::id::{RostraId, RostraIdSecretKeyError};
 use rostra_util_error::BoxedError;
 use snafu::Snafu;
@@ -115,6 +115,28 @@ pub enum PostError {
     Encode { source: BoxedError },
     #[snafu(transparent)]
     Validation { source: ContentValidationError },
+    #[snafu(display("Failed to encrypt direct message: {source}"))]
+    DirectMessage { source: DirectMessageError },
+    #[snafu(display("Failed to encrypt private post: {source}"))]
+    PrivatePost { source: PrivatePostError },
+    #[snafu(display("No audience for private posts"))]
+    NoPrivateAudience,
+    #[snafu(display("Poll is not available"))]
+    PollUnavailable,
+    #[snafu(display("Poll is closed"))]
+    PollClosed,
+    #[snafu(display("Invalid poll option"))]
+    InvalidPollOption,
+    #[snafu(display("Can't remove location metadata from {mime} images"))]
+    UnsupportedImage { mime: String },
+    #[snafu(display("Draft not found"))]
+    DraftNotFound,
+    #[snafu(display("Identity has no successor"))]
+    NoSuccessor,
+    #[snafu(display("Secret key is not the identity's or of a delegated device"))]
+    NotDelegated,
+    #[snafu(display("Requires the identity's own secret key, not a device's"))]
+    RootSecretRequired,
     #[snafu(display("Failed to store the published event: {source}"))]
     Storage { source: DbError },
 }
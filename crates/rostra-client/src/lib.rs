@@ -35,6 +35,8 @@ pub mod multiclient;
 
 pub mod id;
 
+pub mod timeline;
+
 mod util;
 
 use std::str::FromStr;
@@ -55,7 +57,7 @@ const LOG_TARGET: &str = "rostra";
 mod client;
 mod net;
 pub use rostra_client_db::{
-    Database, DbError, SOCIAL_POST_MATERIALIZATION_SCAN_MAX, SelfFollowee,
+    ContentPruningPolicy, Database, DbError, SOCIAL_POST_MATERIALIZATION_SCAN_MAX, SelfFollowee,
     SocialPostMaterialization, SocialPostMaterializationCursor, SocialPostMaterializationPage,
 };
 pub use rostra_core::id::{RostraId, RostraIdSecretKey};
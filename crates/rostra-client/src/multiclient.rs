@@ -4,7 +4,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Instant;
 
-use rostra_client_db::{Database, DbError};
+use rostra_client_db::{ContentPruningPolicy, Database, DbError};
 use rostra_core::id::RostraId;
 use rostra_util_error::FmtCompact as _;
 use snafu::{ResultExt as _, Snafu};
@@ -41,6 +41,8 @@ pub struct MultiClient {
     public_mode: bool,
     /// Shared pkarr client reused across all Rostra client instances.
     pkarr_client: Arc<pkarr::Client>,
+    /// Content retention policy applied to every loaded client.
+    content_pruning_policy: Option<ContentPruningPolicy>,
 }
 
 impl MultiClient {
@@ -57,8 +59,15 @@ impl MultiClient {
             usage_queue: Arc::new(RwLock::new(VecDeque::new())),
             public_mode,
             pkarr_client,
+            content_pruning_policy: None,
         }
     }
+
+    /// Enforce `policy` on the databases of all clients loaded afterwards.
+    pub fn with_content_pruning_policy(mut self, policy: Option<ContentPruningPolicy>) -> Self {
+        self.content_pruning_policy = policy;
+        self
+    }
 }
 
 impl MultiClient {
@@ -102,6 +111,7 @@ impl MultiClient {
             .db(db)
             .public_mode(self.public_mode)
             .pkarr_client(self.pkarr_client.clone())
+            .maybe_content_pruning_policy(self.content_pruning_policy.clone())
             .build()
             .await
             .context(ClientInitSnafu)?;
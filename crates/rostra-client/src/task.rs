@@ -1,3 +1,4 @@
+pub(crate) mod content_pruner;
 pub(crate) mod head_merger;
 pub(crate) mod head_selection;
 pub(crate) mod head_update_broadcaster;
@@ -9,5 +10,8 @@ pub(crate) mod outbound_deadline;
 pub(crate) mod pkarr_id_publisher;
 pub(crate) mod poll_followee_head_updates;
 pub(crate) mod poll_follower_head_updates;
+pub(crate) mod private_audience_keeper;
+pub(crate) mod repost_original_fetcher;
 pub(crate) mod request_handler;
+pub(crate) mod scheduled_post_publisher;
 pub(crate) mod wot_head_sync;
@@ -0,0 +1,150 @@
+use std::time::Duration;
+
+use rostra_client_db::ContentPruningPolicy;
+use rostra_core::Timestamp;
+use rostra_util_error::FmtCompact as _;
+use tracing::{debug, info, instrument, warn};
+
+use crate::LOG_TARGET;
+use crate::client::Client;
+
+pub fn content_pruning_interval() -> Duration {
+    Duration::from_secs(60 * 60)
+}
+
+/// Number of events examined per pruning write transaction.
+const CONTENT_PRUNING_BATCH_SIZE: usize = 256;
+
+/// Periodically enforces a [`ContentPruningPolicy`] on the client database.
+///
+/// Each pass walks all events in bounded batches so that regular writes are
+/// not blocked by a single long transaction. Event headers are pruned one
+/// identity per transaction.
+#[derive(Clone)]
+pub struct ContentPruner {
+    client: crate::client::ClientHandle,
+    self_id: rostra_core::id::RostraId,
+    policy: ContentPruningPolicy,
+}
+
+impl ContentPruner {
+    pub fn new(client: &Client, policy: ContentPruningPolicy) -> Self {
+        debug!(target: LOG_TARGET, ?policy, "Starting content pruner");
+        Self {
+            client: client.handle(),
+            self_id: client.rostra_id(),
+            policy,
+        }
+    }
+
+    #[instrument(name = "content-pruner", skip(self), fields(self_id = %self.self_id.fmt_short()), ret)]
+    pub async fn run(self) {
+        let mut interval = tokio::time::interval(content_pruning_interval());
+        loop {
+            interval.tick().await;
+            if !self.prune_pass().await {
+                break;
+            }
+        }
+    }
+
+    /// Run one full pruning pass. Returns `false` if the client is gone.
+    async fn prune_pass(&self) -> bool {
+        let now = Timestamp::now();
+        let mut after = None;
+        let mut pruned_num = 0u64;
+        let mut pruned_size = 0u64;
+        let mut reclaimed_size = 0u64;
+
+        loop {
+            let Ok(db) = self.client.db() else {
+                return false;
+            };
+            let batch = match db
+                .prune_content_batch(&self.policy, now, after, CONTENT_PRUNING_BATCH_SIZE)
+                .await
+            {
+                Ok(batch) => batch,
+                Err(err) => {
+                    warn!(target: LOG_TARGET, err = %err.fmt_compact(), "Content pruning pass failed");
+                    return true;
+                }
+            };
+            drop(db);
+
+            pruned_num += batch.pruned_num;
+            pruned_size += batch.pruned_size;
+            reclaimed_size += batch.reclaimed_size;
+
+            let Some(next) = batch.next else {
+                break;
+            };
+            after = Some(next);
+            tokio::task::yield_now().await;
+        }
+
+        if 0 < pruned_num {
+            info!(
+                target: LOG_TARGET,
+                pruned_num,
+                pruned_size,
+                reclaimed_size,
+                "Pruned event content"
+            );
+        } else {
+            debug!(target: LOG_TARGET, "Content pruning pass found nothing to prune");
+        }
+
+        if let Some(max_header_age) = self.policy.max_header_age {
+            let before = Timestamp::from(now.as_u64().saturating_sub(max_header_age.as_secs()));
+            return self.prune_headers_pass(before).await;
+        }
+        true
+    }
+
+    /// Prune event headers of all candidate identities. Returns `false` if
+    /// the client is gone.
+    async fn prune_headers_pass(&self, before: Timestamp) -> bool {
+        let Ok(db) = self.client.db() else {
+            return false;
+        };
+        let candidates = match db.get_event_header_pruning_candidates().await {
+            Ok(candidates) => candidates,
+            Err(err) => {
+                warn!(target: LOG_TARGET, err = %err.fmt_compact(), "Event header pruning pass failed");
+                return true;
+            }
+        };
+        drop(db);
+
+        let mut pruned_num = 0u64;
+        let mut reclaimed_size = 0u64;
+        for id in candidates {
+            let Ok(db) = self.client.db() else {
+                return false;
+            };
+            match db.prune_event_headers(id, before).await {
+                Ok(outcome) => {
+                    pruned_num += outcome.pruned_num;
+                    reclaimed_size += outcome.reclaimed_size;
+                }
+                Err(err) => {
+                    // Most likely followed since the candidates were listed.
+                    debug!(target: LOG_TARGET, %id, err = %err.fmt_compact(), "Skipping event header pruning");
+                }
+            }
+            drop(db);
+            tokio::task::yield_now().await;
+        }
+
+        if 0 < pruned_num {
+            info!(
+                target: LOG_TARGET,
+                pruned_num,
+                reclaimed_size,
+                "Pruned event headers"
+            );
+        }
+        true
+    }
+}
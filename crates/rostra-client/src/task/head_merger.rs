@@ -5,7 +5,7 @@ use rostra_client_db::{CurrentState, DbResult};
 use rostra_core::event::{EventContentRaw, EventKind, VerifiedEvent};
 use rostra_core::id::{RostraId, RostraIdSecretKey};
 use rostra_core::{Event, ShortEventId};
-use tracing::{debug, error, instrument, trace};
+use tracing::{debug, error, instrument, trace, warn};
 
 use crate::client::Client;
 use crate::task::head_selection::sorted_heads;
@@ -23,6 +23,8 @@ pub struct HeadMerger {
 
 enum MergeOutcome {
     ClientDropped,
+    /// The device key signing merges is not (or no longer) delegated
+    NotDelegated,
     NoFork,
     Merged,
 }
@@ -53,7 +55,7 @@ impl HeadMerger {
                     );
                     break;
                 }
-                Ok(MergeOutcome::ClientDropped) => break,
+                Ok(MergeOutcome::ClientDropped | MergeOutcome::NotDelegated) => break,
                 Ok(MergeOutcome::Merged) => continue,
                 Ok(MergeOutcome::NoFork) => {}
             }
@@ -102,8 +104,13 @@ impl HeadMerger {
             .build()
             .signed_by(self.id_secret);
 
-        let verified_event = VerifiedEvent::verify_signed(self.id, signed_event)
-            .expect("Can't fail to verify self-created event");
+        let delegates = db.get_device_delegates(self.id).await;
+        let Ok(verified_event) =
+            VerifiedEvent::verify_signed_delegated(self.id, signed_event, &delegates)
+        else {
+            warn!(target: LOG_TARGET, "Device key is not delegated, stopping head merging");
+            return Ok(MergeOutcome::NotDelegated);
+        };
         let verified_event_content =
             rostra_core::event::VerifiedEventContent::verify(verified_event, empty_content)
                 .expect("Can't fail to verify self-created content");
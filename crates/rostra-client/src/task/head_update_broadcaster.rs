@@ -6,7 +6,9 @@ use rostra_client_db::{
     CurrentState, Database, EventContentState, EventRecord, IdsFollowersRecord,
 };
 use rostra_core::ShortEventId;
-use rostra_core::event::{EventContentRaw, EventExt as _, SignedEvent, VerifiedEventContent};
+use rostra_core::event::{
+    EventContentRaw, EventExt as _, EventKind, SignedEvent, VerifiedEventContent, content_kind,
+};
 use rostra_core::id::{RostraId, ToShort as _};
 use rostra_util_error::{FmtCompact, WhateverResult};
 use snafu::ResultExt as _;
@@ -206,8 +208,15 @@ impl HeadUpdateBroadcaster {
 
         let mut retry = false;
 
+        let direct_message_recipient = direct_message_recipient(event, event_content)
+            .filter(|id| *id != self.self_id && !followers.contains_key(id));
+
         // Send to ourselves first, in case we have redundant nodes.
-        for id in [self.self_id].into_iter().chain(followers.keys().copied()) {
+        for id in [self.self_id]
+            .into_iter()
+            .chain(followers.keys().copied())
+            .chain(direct_message_recipient)
+        {
             if self.client.app_ref_opt().is_none() {
                 debug!(target: LOG_TARGET, "Client gone, quitting");
                 return BroadcastHeadOutcome::Stop;
@@ -296,6 +305,20 @@ fn broadcast_retry_delay(retry_count: u32, policy: BroadcastPolicy) -> Duration
         .min(policy.retry_max_delay)
 }
 
+/// Recipient of a direct message, who needs it even without following us
+fn direct_message_recipient(
+    event: &EventRecord,
+    event_content: &EventContentRaw,
+) -> Option<RostraId> {
+    if event.kind() != EventKind::DIRECT_MESSAGE {
+        return None;
+    }
+    event_content
+        .deserialize_cbor::<content_kind::DirectMessage>()
+        .ok()
+        .map(|content| content.recipient)
+}
+
 fn content_completes_pending(
     content: &VerifiedEventContent,
     self_id: RostraId,
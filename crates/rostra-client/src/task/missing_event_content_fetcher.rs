@@ -54,6 +54,9 @@ impl MissingEventContentFetcher {
     /// - If due: attempts to fetch, on failure records backoff
     /// - If not yet due: sleeps until the scheduled time or a notification
     /// - If empty: waits for a notification that new missing content arrived
+    ///
+    /// Large content is downloaded in ranges, and the downloaded ranges are
+    /// kept, so a retry after a failed attempt resumes where it stopped.
     #[instrument(name = "missing-event-content-fetcher", skip(self), fields(self_id = %self.self_id.fmt_short()), ret)]
     pub async fn run(self) {
         let Ok(db) = self.client.db() else {
@@ -210,8 +210,13 @@ impl MissingEventFetcher {
         db: &rostra_client_db::Database,
         author_id: RostraId,
     ) -> rostra_client_db::DbResult<()> {
+        if db.is_self_blocked(author_id).await {
+            debug!(target: LOG_TARGET, id=%author_id.to_short(), "Not fetching missing events of a blocked id");
+            return Ok(());
+        }
         let followers = db.get_followers(author_id).await;
         let missing_events = db.get_missing_events_for_id(author_id).await;
+        let delegates = db.get_device_delegates(author_id).await;
 
         debug!(target: LOG_TARGET, len=missing_events.len(), id=%author_id.to_short(), "Missing events for id");
         if missing_events.is_empty() {
@@ -243,7 +248,10 @@ impl MissingEventFetcher {
                 if db.has_event(*missing_event).await {
                     continue;
                 }
-                let event = match self.get_event(author_id, *missing_event, &conn).await {
+                let event = match self
+                    .get_event(author_id, *missing_event, &delegates, &conn)
+                    .await
+                {
                     Ok(event) => event,
                     Err(err) => {
                         debug!(
@@ -281,19 +289,25 @@ impl MissingEventFetcher {
         &self,
         author_id: RostraId,
         event_id: ShortEventId,
+        delegates: &[RostraId],
         conn: &Connection,
     ) -> WhateverResult<Option<VerifiedEvent>> {
         let event = conn
-            .get_event(author_id, event_id)
+            .get_event(author_id, event_id, delegates)
             .await
             .whatever_context("Failed to query peer")?;
 
         let Some(event) = event else {
             return Ok(None);
         };
-        let event =
-            VerifiedEvent::verify_response(author_id, event_id, *event.event(), event.sig())
-                .whatever_context("Invalid event received")?;
+        let event = VerifiedEvent::verify_response_delegated(
+            author_id,
+            event_id,
+            *event.event(),
+            event.sig(),
+            delegates,
+        )
+        .whatever_context("Invalid event received")?;
 
         Ok(Some(event))
     }
@@ -370,12 +370,14 @@ impl PollFolloweeHeadUpdates {
             };
 
             let local_heads = db.get_heads(followee_id).await;
+            let delegates = db.get_device_delegates(followee_id).await;
             let local_head =
                 representative_head(&local_heads).unwrap_or(rostra_core::ShortEventId::ZERO);
             let err = match Self::poll_connection_slot(
                 &conn,
                 followee_id,
                 local_head,
+                &delegates,
                 &followee_state,
                 |event| {
                     let db = db.clone();
@@ -422,6 +424,7 @@ impl PollFolloweeHeadUpdates {
         conn: &rostra_p2p::Connection,
         followee_id: RostraId,
         local_head: rostra_core::ShortEventId,
+        delegates: &[RostraId],
         followee_state: &RwLock<FolloweePollState>,
         mut persist_event: F,
     ) -> Result<(), FolloweePollError>
@@ -433,7 +436,8 @@ impl PollFolloweeHeadUpdates {
             let pending_event = followee_state.read().await.pending_event();
             if let Some(event_id) = pending_event {
                 tokio::time::sleep(MISSING_EVENT_RETRY_DELAY).await;
-                let Some(event) = Self::fetch_pending_event(conn, followee_id, event_id).await?
+                let Some(event) =
+                    Self::fetch_pending_event(conn, followee_id, event_id, delegates).await?
                 else {
                     continue;
                 };
@@ -478,7 +482,8 @@ impl PollFolloweeHeadUpdates {
             );
 
             followee_state.write().await.record_remote_head(new_head_id);
-            let Some(event) = Self::fetch_pending_event(conn, followee_id, new_head_id).await?
+            let Some(event) =
+                Self::fetch_pending_event(conn, followee_id, new_head_id, delegates).await?
             else {
                 warn!(
                     target: LOG_TARGET,
@@ -502,8 +507,9 @@ impl PollFolloweeHeadUpdates {
         conn: &rostra_p2p::Connection,
         followee_id: RostraId,
         event_id: rostra_core::ShortEventId,
+        delegates: &[RostraId],
     ) -> Result<Option<VerifiedEvent>, FolloweePollError> {
-        conn.get_event(followee_id, event_id)
+        conn.get_event(followee_id, event_id, delegates)
             .await
             .map_err(FolloweePollError::Peer)
     }
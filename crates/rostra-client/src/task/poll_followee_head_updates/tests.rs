@@ -141,6 +141,7 @@ async fn poll_slots_retain_cursor_and_retry_pending_event_after_cancellation() {
                 &slot_connection,
                 remote_id,
                 local_descendant_id,
+                &[],
                 &slot_state,
                 move |_| {
                     slot_attempts.fetch_add(1, Ordering::SeqCst);
@@ -181,6 +182,7 @@ async fn poll_slots_retain_cursor_and_retry_pending_event_after_cancellation() {
                 &slot_connection,
                 remote_id,
                 local_descendant_id,
+                &[],
                 &slot_state,
                 |_| async { Ok(()) },
             ),
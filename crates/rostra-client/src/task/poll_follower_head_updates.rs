@@ -366,7 +366,7 @@ impl PollFollowerHeadUpdates {
         backoff_state: &SharedBackoffState,
     ) -> Result<(), FollowerPollError> {
         loop {
-            match Self::poll_once(conn, self_id, wot).await {
+            match Self::poll_once(conn, db, self_id, wot).await {
                 Ok(event) => {
                     match Self::finish_successful_poll(db, peer_id, event.as_ref(), backoff_state)
                         .await
@@ -426,7 +426,8 @@ impl PollFollowerHeadUpdates {
                 rostra_client_db::InsertEventOutcome::Inserted { .. } => {
                     PollProgress::Inserted(insert_outcome)
                 }
-                rostra_client_db::InsertEventOutcome::AlreadyPresent => PollProgress::NoProgress,
+                rostra_client_db::InsertEventOutcome::AlreadyPresent
+                | rostra_client_db::InsertEventOutcome::Pruned => PollProgress::NoProgress,
             }
         } else {
             PollProgress::NoProgress
@@ -441,6 +442,7 @@ impl PollFollowerHeadUpdates {
 
     async fn poll_once(
         conn: &Connection,
+        db: &Database,
         self_id: RostraId,
         wot: &CurrentState<Arc<WotData>>,
     ) -> Result<Option<VerifiedEvent>, FollowerPeerError> {
@@ -463,9 +465,11 @@ impl PollFollowerHeadUpdates {
 
         // Bind the routing/admission claim to the signed envelope. The response
         // author is peer-controlled and must not independently grant WoT
-        // admission to an event signed by another identity.
-        let verified_event =
-            VerifiedEvent::verify_signed(author, event).map_err(FollowerPeerError::Verification)?;
+        // admission to an event signed by another identity, other than by a
+        // device key it delegated to.
+        let delegates = db.get_device_delegates(author).await;
+        let verified_event = VerifiedEvent::verify_signed_delegated(author, event, &delegates)
+            .map_err(FollowerPeerError::Verification)?;
         let authenticated_author = verified_event.author();
 
         // Check the cryptographically authenticated author against our Web of
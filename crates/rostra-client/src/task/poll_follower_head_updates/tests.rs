@@ -119,7 +119,7 @@ async fn rpc_ingests_event_when_claimed_and_signed_authors_match() {
         })
         .await;
 
-    let event = PollFollowerHeadUpdates::poll_once(&connection, self_id, &wot)
+    let event = PollFollowerHeadUpdates::poll_once(&connection, &db, self_id, &wot)
         .await
         .expect("matching self-authored response is admitted")
         .expect("self-authored response is in the web of trust");
@@ -152,7 +152,7 @@ async fn rpc_rejects_trusted_claim_with_event_signed_by_another_author() {
         })
         .await;
 
-    let error = PollFollowerHeadUpdates::poll_once(&connection, self_id, &wot)
+    let error = PollFollowerHeadUpdates::poll_once(&connection, &db, self_id, &wot)
         .await
         .expect_err("claimed author must match the signed event author");
     release_server.send(()).expect("release server");
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use rostra_client_db::{CurrentState, IdsFollowersRecord, WotData};
+use rostra_core::id::{RostraId, RostraIdSecretKey};
+use rostra_util_error::FmtCompact as _;
+use tokio::sync::Notify;
+use tracing::{debug, info, instrument, warn};
+
+use crate::client::Client;
+
+const LOG_TARGET: &str = "rostra::private_audience";
+
+/// Keeps the private post audience keys in order
+///
+/// Unwraps audience keys granted to us by others, and rotates our own audience
+/// key when one of its members stops following us, or gets blocked.
+pub struct PrivateAudienceKeeper {
+    client: crate::client::ClientHandle,
+    id: RostraId,
+    id_secret: RostraIdSecretKey,
+    grants_notify: Arc<Notify>,
+    self_followers: CurrentState<Arc<HashMap<RostraId, IdsFollowersRecord>>>,
+    self_wot: CurrentState<Arc<WotData>>,
+}
+
+impl PrivateAudienceKeeper {
+    pub fn new(client: &Client, id_secret: RostraIdSecretKey) -> Self {
+        debug!(target: LOG_TARGET, "Starting private audience keeper task");
+        Self {
+            client: client.handle(),
+            id: client.rostra_id(),
+            id_secret,
+            grants_notify: client.db().private_audience_grants_notify(),
+            self_followers: client.self_followers_subscribe(),
+            self_wot: client.self_wot_subscribe(),
+        }
+    }
+
+    /// Run the thread
+    #[instrument(name = "private-audience-keeper", skip(self), fields(self_id = %self.id.fmt_short()), ret)]
+    pub async fn run(self) {
+        let mut self_followers = self.self_followers.clone();
+        let mut self_wot = self.self_wot.clone();
+        loop {
+            let Ok(client) = self.client.client_ref() else {
+                break;
+            };
+
+            match client
+                .db()
+                .unwrap_private_audience_grants(self.id_secret)
+                .await
+            {
+                Ok(0) => {}
+                Ok(count) => {
+                    debug!(target: LOG_TARGET, count, "Unwrapped audience keys");
+                }
+                Err(err) => {
+                    warn!(target: LOG_TARGET, err = %err.fmt_compact(), "Failed to unwrap audience keys");
+                }
+            }
+
+            if let Some(audience) = client.db().get_private_audience().await {
+                let followers = self_followers.snapshot();
+                let wot = self_wot.snapshot();
+                let (members, removed): (Vec<_>, Vec<_>) = audience
+                    .members
+                    .into_iter()
+                    .filter(|member| *member != self.id)
+                    .partition(|member| followers.contains_key(member) && !wot.is_blocked(*member));
+                if !removed.is_empty() {
+                    info!(target: LOG_TARGET, removed = removed.len(), "Rotating private audience key after unfollow or block");
+                    if let Err(err) = client.set_private_audience(self.id_secret, members).await {
+                        warn!(target: LOG_TARGET, err = %err.fmt_compact(), "Failed to rotate private audience key");
+                    }
+                }
+            }
+            drop(client);
+
+            tokio::select! {
+                _ = self.grants_notify.notified() => {}
+                res = self_followers.changed() => {
+                    if res.is_err() {
+                        break;
+                    }
+                }
+                res = self_wot.changed() => {
+                    if res.is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
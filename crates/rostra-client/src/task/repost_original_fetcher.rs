@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use rostra_core::id::{RostraId, ToShort as _};
+use rostra_util_error::FmtCompact as _;
+use tracing::{debug, instrument, trace};
+
+use crate::LOG_TARGET;
+use crate::client::Client;
+
+/// How long to wait before retrying posts that could not be fetched
+const RETRY_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Maximum number of posts to fetch in one pass
+const BATCH_SIZE: usize = 32;
+
+/// Fetches reposted posts that are not available locally
+///
+/// Reposts mostly reference posts of identities we don't follow, so their
+/// events are fetched from the author or their followers, like
+/// `fetch_missing_post` in the web UI does on demand.
+pub struct RepostOriginalFetcher {
+    client: crate::client::ClientHandle,
+    self_id: RostraId,
+}
+
+impl RepostOriginalFetcher {
+    pub fn new(client: &Client) -> Self {
+        debug!(target: LOG_TARGET, "Starting repost original fetcher");
+        Self {
+            client: client.handle(),
+            self_id: client.rostra_id(),
+        }
+    }
+
+    #[instrument(name = "repost-original-fetcher", skip(self), fields(self_id = %self.self_id.fmt_short()), ret)]
+    pub async fn run(self) {
+        let Ok(db) = self.client.db() else {
+            return;
+        };
+        let notify = db.repost_originals_missing_notify();
+        drop(db);
+
+        loop {
+            let Ok(client) = self.client.client_ref() else {
+                break;
+            };
+
+            let missing = client.db().get_missing_repost_originals(BATCH_SIZE).await;
+            let mut followers_cache = BTreeMap::new();
+            for post_id in missing {
+                let author_id = post_id.rostra_id();
+                let event_id = post_id.event_id();
+                match client
+                    .fetch_event_content(author_id, event_id, &mut followers_cache)
+                    .await
+                {
+                    Ok(true) => {
+                        trace!(target: LOG_TARGET, author_id = %author_id.to_short(), %event_id, "Fetched reposted post");
+                        if let Err(err) = client.db().remove_missing_repost_original(post_id).await
+                        {
+                            debug!(target: LOG_TARGET, err = %err.fmt_compact(), "Failed to clear missing reposted post");
+                        }
+                    }
+                    Ok(false) => {
+                        debug!(target: LOG_TARGET, author_id = %author_id.to_short(), %event_id, "Reposted post unavailable from peers");
+                    }
+                    Err(err) => {
+                        debug!(target: LOG_TARGET, author_id = %author_id.to_short(), %event_id, err = %err.fmt_compact(), "Failed to fetch reposted post");
+                    }
+                }
+            }
+            drop(client);
+
+            tokio::select! {
+                () = notify.notified() => {}
+                () = tokio::time::sleep(RETRY_INTERVAL) => {}
+            }
+        }
+    }
+}
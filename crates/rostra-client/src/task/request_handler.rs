@@ -2,22 +2,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use convi::CastFrom as _;
 use futures::StreamExt as _;
 use futures::stream::FuturesUnordered;
 use iroh::Endpoint;
 use iroh::endpoint::Incoming;
 use n0_future::task::AbortOnDropHandle;
-use rostra_client_db::{CurrentState, DbError, IdsFolloweesRecord, IdsFollowersRecord};
-use rostra_core::event::{EventContentRaw, EventExt as _, VerifiedEvent, VerifiedEventContent};
+use rostra_client_db::{CurrentState, DbError, IdsFolloweesRecord, IdsFollowersRecord, WotData};
+use rostra_core::event::{
+    EventContentRaw, EventExt as _, EventKind, VerifiedEvent, VerifiedEventContent, content_kind,
+};
 use rostra_core::id::RostraId;
 use rostra_p2p::RpcError;
 use rostra_p2p::connection::{
-    Connection, FeedEventRequest, FeedEventResponse, GetEventContentRequest,
+    Connection, FeedEventRequest, FeedEventResponse, GetAncestorsRequest, GetAncestorsResponse,
+    GetEventContentRangeRequest, GetEventContentRangeResponse, GetEventContentRequest,
     GetEventContentResponse, GetEventRequest, GetEventResponse, GetHeadRequest, GetHeadResponse,
-    MAX_REQUEST_SIZE, PingRequest, PingResponse, RpcId, RpcMessage as _,
-    WaitFollowersNewHeadsRequest, WaitFollowersNewHeadsResponse, WaitHeadUpdateRequest,
-    WaitHeadUpdateResponse,
+    MAX_REQUEST_SIZE, PingRequest, PingResponse, ReconcileEventsRequest, ReconcileEventsResponse,
+    RpcId, RpcMessage as _, WaitFollowersNewHeadsRequest, WaitFollowersNewHeadsResponse,
+    WaitHeadUpdateRequest, WaitHeadUpdateResponse,
 };
+use rostra_p2p::reconcile::{self, EventsBound, EventsRangeReconciliation};
 use rostra_p2p::util::ToShort as _;
 use rostra_util_error::{BoxedError, FmtCompact as _};
 use snafu::{Location, OptionExt as _, ResultExt as _, Snafu};
@@ -169,6 +174,7 @@ pub struct RequestHandler {
     our_id: RostraId,
     self_followees: CurrentState<Arc<HashMap<RostraId, IdsFolloweesRecord>>>,
     self_followers: CurrentState<Arc<HashMap<RostraId, IdsFollowersRecord>>>,
+    self_wot: CurrentState<Arc<WotData>>,
     inbound_admission: InboundAdmission,
 }
 
@@ -181,6 +187,7 @@ impl RequestHandler {
             our_id: client.rostra_id(),
             self_followees: client.self_followees_subscribe(),
             self_followers: client.self_followers_subscribe(),
+            self_wot: client.self_wot_subscribe(),
             inbound_admission: InboundAdmission::new(),
         }
         .into()
@@ -316,9 +323,20 @@ impl RequestHandler {
                                 RpcId::GET_EVENT => {
                                     handler.handle_get_event(req_msg, send, recv).await
                                 }
+                                RpcId::GET_ANCESTORS => {
+                                    handler.handle_get_ancestors(req_msg, send, recv).await
+                                }
+                                RpcId::RECONCILE_EVENTS => {
+                                    handler.handle_reconcile_events(req_msg, send, recv).await
+                                }
                                 RpcId::GET_EVENT_CONTENT => {
                                     handler.handle_get_event_content(req_msg, send, recv).await
                                 }
+                                RpcId::GET_EVENT_CONTENT_RANGE => {
+                                    handler
+                                        .handle_get_event_content_range(req_msg, send, recv)
+                                        .await
+                                }
                                 RpcId::WAIT_HEAD_UPDATE => {
                                     handler.handle_wait_head_update(req_msg, send, recv).await
                                 }
@@ -379,9 +397,14 @@ impl RequestHandler {
         let FeedEventRequest(event) =
             FeedEventRequest::decode_whole::<MAX_REQUEST_SIZE>(&req_msg).context(DecodingSnafu)?;
         let our_id = self.our_id;
-
-        if event.author() == our_id || self.self_followees.snapshot().contains_key(&event.author())
-        {
+        let author_blocked = self.self_wot.snapshot().is_blocked(event.author());
+        let author_needed = !author_blocked
+            && (event.author() == our_id
+                || self.self_followees.snapshot().contains_key(&event.author()));
+
+        // Direct messages are accepted from anyone not blocked, if addressed to
+        // us, which can only be checked once the content arrives.
+        if author_needed || (!author_blocked && event.kind() == EventKind::DIRECT_MESSAGE) {
             // accept
         } else {
             Connection::write_return_code(&mut send, FeedEventResponse::RETURN_CODE_DOES_NOT_NEED)
@@ -390,11 +413,12 @@ impl RequestHandler {
             return Err("Author not needed".into()).context(InvalidRequestSnafu);
         }
 
-        let event = VerifiedEvent::verify_received_as_is(event)
-            .boxed()
-            .context(InvalidRequestSnafu)?;
-        {
+        let event = {
             let client = self.client.app_ref_opt().context(ExitingSnafu)?;
+            let delegates = client.db().get_device_delegates(event.author()).await;
+            let event = VerifiedEvent::verify_received_delegated(event, &delegates)
+                .boxed()
+                .context(InvalidRequestSnafu)?;
 
             if client.event_size_limit() < event.content_len() {
                 client.store_event_too_large(&event).await?;
@@ -416,7 +440,8 @@ impl RequestHandler {
                 .context(RpcSnafu)?;
                 return Ok(());
             }
-        }
+            event
+        };
         Connection::write_success_return_code(&mut send)
             .await
             .context(RpcSnafu)?;
@@ -436,6 +461,15 @@ impl RequestHandler {
                 .boxed()
                 .context(InvalidRequestSnafu)?;
 
+            if !author_needed
+                && !verified_content
+                    .deserialize_cbor::<content_kind::DirectMessage>()
+                    .is_ok_and(|content| content.recipient == our_id)
+            {
+                return Err("Direct message not addressed to us".into())
+                    .context(InvalidRequestSnafu);
+            }
+
             if let Err(err) = client
                 .store_event_with_content(event.event_id, &verified_content)
                 .await
@@ -483,6 +517,94 @@ impl RequestHandler {
         Ok(())
     }
 
+    async fn handle_get_ancestors(
+        &self,
+        req_msg: Vec<u8>,
+        mut send: iroh::endpoint::SendStream,
+        _read: iroh::endpoint::RecvStream,
+    ) -> Result<(), IncomingConnectionError> {
+        let GetAncestorsRequest {
+            author,
+            heads,
+            known,
+            limit,
+        } = GetAncestorsRequest::decode_whole::<MAX_REQUEST_SIZE>(&req_msg)
+            .context(DecodingSnafu)?;
+
+        if GetAncestorsRequest::MAX_HEADS < heads.len()
+            || GetAncestorsRequest::MAX_KNOWN < known.len()
+        {
+            return Err("Too many event ids".into()).context(InvalidRequestSnafu);
+        }
+        let limit = limit.min(GetAncestorsRequest::MAX_LIMIT);
+
+        let events = self
+            .client
+            .db()?
+            .get_ancestors(author, &heads, &known, usize::cast_from(limit))
+            .await;
+
+        Connection::write_success_return_code(&mut send)
+            .await
+            .context(RpcSnafu)?;
+
+        Connection::write_message(
+            &mut send,
+            &GetAncestorsResponse(events.into_iter().map(|e| e.signed).collect()),
+        )
+        .await
+        .context(RpcSnafu)?;
+
+        Ok(())
+    }
+
+    async fn handle_reconcile_events(
+        &self,
+        req_msg: Vec<u8>,
+        mut send: iroh::endpoint::SendStream,
+        _read: iroh::endpoint::RecvStream,
+    ) -> Result<(), IncomingConnectionError> {
+        let ReconcileEventsRequest { author, ranges } =
+            ReconcileEventsRequest::decode_whole::<MAX_REQUEST_SIZE>(&req_msg)
+                .context(DecodingSnafu)?;
+
+        if reconcile::MAX_RANGES < ranges.len() {
+            return Err("Too many ranges".into()).context(InvalidRequestSnafu);
+        }
+
+        let db = self.client.db()?;
+        let mut results = Vec::with_capacity(ranges.len());
+        for (range, fingerprint) in ranges {
+            let events: Vec<EventsBound> = db
+                .get_events_by_author_range(
+                    author,
+                    (range.start.timestamp, range.start.event_id),
+                    range.end.map(|end| (end.timestamp, end.event_id)),
+                    reconcile::MAX_SCANNED_EVENTS + 1,
+                )
+                .await
+                .into_iter()
+                .map(EventsBound::from)
+                .collect();
+            results.push(EventsRangeReconciliation::respond(
+                range,
+                fingerprint,
+                &events,
+            ));
+        }
+        drop(db);
+
+        Connection::write_success_return_code(&mut send)
+            .await
+            .context(RpcSnafu)?;
+
+        Connection::write_message(&mut send, &ReconcileEventsResponse(results))
+            .await
+            .context(RpcSnafu)?;
+
+        Ok(())
+    }
+
     async fn handle_get_event_content(
         &self,
         req_msg: Vec<u8>,
@@ -519,6 +641,44 @@ impl RequestHandler {
         Ok(())
     }
 
+    async fn handle_get_event_content_range(
+        &self,
+        req_msg: Vec<u8>,
+        mut send: iroh::endpoint::SendStream,
+        _read: iroh::endpoint::RecvStream,
+    ) -> Result<(), IncomingConnectionError> {
+        let GetEventContentRangeRequest {
+            event_id,
+            start,
+            end,
+        } = GetEventContentRangeRequest::decode_whole::<MAX_REQUEST_SIZE>(&req_msg)
+            .context(DecodingSnafu)?;
+
+        let client = self.client.client_ref()?;
+        let db = client.db();
+
+        // Ranges not within the content are answered like missing content
+        let content = db.get_event_content(event_id).await.filter(|content| {
+            start < end && usize::try_from(end).is_ok_and(|end| end <= content.len())
+        });
+
+        Connection::write_success_return_code(&mut send)
+            .await
+            .context(RpcSnafu)?;
+
+        Connection::write_message(&mut send, &GetEventContentRangeResponse(content.is_some()))
+            .await
+            .context(RpcSnafu)?;
+
+        if let Some(content) = content {
+            Connection::write_bao_content_range(&mut send, content.as_ref(), start..end)
+                .await
+                .context(RpcSnafu)?;
+        }
+
+        Ok(())
+    }
+
     async fn handle_wait_head_update(
         &self,
         req_msg: Vec<u8>,
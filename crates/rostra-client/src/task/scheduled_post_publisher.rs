@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+use rostra_core::Timestamp;
+use rostra_core::id::{RostraId, RostraIdSecretKey};
+use rostra_util_error::FmtCompact as _;
+use tracing::{debug, info, instrument, warn};
+
+use crate::LOG_TARGET;
+use crate::client::Client;
+
+/// How long to sleep when nothing is scheduled
+///
+/// Drafts scheduled meanwhile wake the task up anyway, this only bounds the
+/// effect of clock jumps.
+const IDLE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Publishes scheduled drafts when their time comes
+///
+/// Needs the identity secret, so it runs only once the client is unlocked:
+/// drafts scheduled for while no one had it unlocked are published as soon
+/// as it is again.
+pub struct ScheduledPostPublisher {
+    client: crate::client::ClientHandle,
+    self_id: RostraId,
+    id_secret: RostraIdSecretKey,
+}
+
+impl ScheduledPostPublisher {
+    pub fn new(client: &Client, id_secret: RostraIdSecretKey) -> Self {
+        debug!(target: LOG_TARGET, "Starting scheduled post publisher");
+        Self {
+            client: client.handle(),
+            self_id: client.rostra_id(),
+            id_secret,
+        }
+    }
+
+    #[instrument(name = "scheduled-post-publisher", skip(self), fields(self_id = %self.self_id.fmt_short()), ret)]
+    pub async fn run(self) {
+        let Ok(db) = self.client.db() else {
+            return;
+        };
+        let notify = db.post_drafts_scheduled_notify();
+        drop(db);
+
+        loop {
+            let Ok(client) = self.client.client_ref() else {
+                break;
+            };
+
+            let now = Timestamp::now();
+            for (id, mut draft) in client.db().get_due_post_drafts(now).await {
+                match client.publish_post_draft(self.id_secret, id).await {
+                    Ok(event) => {
+                        info!(target: LOG_TARGET, id, event_id = %event.event_id, "Published scheduled post");
+                    }
+                    Err(err) => {
+                        warn!(target: LOG_TARGET, id, err = %err.fmt_compact(), "Failed to publish scheduled post");
+                        // Keep it as a draft, so it's not retried forever
+                        draft.scheduled_at = None;
+                        draft.last_error = Some(err.to_string());
+                        if let Err(err) = client.db().update_post_draft(id, draft).await {
+                            warn!(target: LOG_TARGET, id, err = %err.fmt_compact(), "Failed to unschedule post draft");
+                        }
+                    }
+                }
+            }
+
+            let sleep = client
+                .db()
+                .get_next_post_draft_schedule()
+                .await
+                .map(|next| Duration::from_secs(next.secs_since(Timestamp::now())))
+                .unwrap_or(IDLE_INTERVAL)
+                .min(IDLE_INTERVAL);
+            drop(client);
+
+            tokio::select! {
+                () = notify.notified() => {}
+                () = tokio::time::sleep(sleep) => {}
+            }
+        }
+    }
+}
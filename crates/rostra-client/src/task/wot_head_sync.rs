@@ -25,18 +25,21 @@
 //!
 //! On startup and then every hour, it iterates over every ID in the
 //! current Web of Trust (self + direct followees + extended followees).
-//! For each ID it asks that ID's known followers (plus the ID itself and
-//! ourselves) for an independently sampled head via the lightweight `GET_HEAD`
-//! RPC.
-//! If any peer reports a head event we don't have locally, we call
-//! `download_events_from_child` to fetch the full DAG — the same
-//! function used by `NewHeadFetcher`. Repeated cycles can discover durable
-//! sibling heads because each `GET_HEAD` response samples the peer's complete
-//! current set; no individual response has complete-set semantics.
+//! For each ID it reconciles the ID's events with that ID's known followers
+//! (plus the ID itself and ourselves) via `RECONCILE_EVENTS`, which finds
+//! every event the peer has and we don't — including sibling heads and gaps
+//! in the middle of the history — in a few round trips. For each such event
+//! we call `download_events_from_child` to fetch it with its ancestors — the
+//! same function used by `NewHeadFetcher`.
+//!
+//! Peers that don't support `RECONCILE_EVENTS` are asked for an independently
+//! sampled head via the lightweight `GET_HEAD` RPC instead. Repeated cycles
+//! can discover durable sibling heads that way too, because each `GET_HEAD`
+//! response samples the peer's complete current set.
 //!
 //! Because this is a background maintenance sweep (not latency-critical),
-//! it processes IDs sequentially and moves on after finding one new head
-//! per ID, keeping resource usage low.
+//! it processes IDs sequentially and moves on after catching up with one
+//! peer per ID, keeping resource usage low.
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -166,7 +169,12 @@ impl WotHeadSync {
 
     async fn sync_id_with_deadline(&self, id: RostraId, deadline: Duration) -> DbResult<()> {
         let followers = self.db.get_followers(id).await;
-        let peers: Vec<RostraId> = followers.into_iter().chain([id, self.self_id]).collect();
+        let wot = self.wot.snapshot();
+        let peers: Vec<RostraId> = followers
+            .into_iter()
+            .chain([id, self.self_id])
+            .filter(|peer_id| !wot.is_blocked(*peer_id))
+            .collect();
 
         for &peer_id in &peers {
             let conn = match within(
@@ -197,18 +205,45 @@ impl WotHeadSync {
                 }
             };
 
-            let remote_head = match within(deadline, conn.get_head(id)).await {
-                Ok(Ok(Some(head))) => head,
-                Ok(Ok(None)) => continue,
+            let remote_heads = match within(
+                deadline,
+                crate::util::rpc::reconcile_events_with_peer(&conn, id, &self.db),
+            )
+            .await
+            {
+                Ok(Ok(local_missing)) => local_missing,
                 Ok(Err(err)) => {
                     trace!(
                         target: LOG_TARGET,
                         id = %id.to_short(),
                         peer = %peer_id.to_short(),
                         err = %err.fmt_compact(),
-                        "GET_HEAD failed, skipping peer"
+                        "RECONCILE_EVENTS failed, falling back to GET_HEAD"
                     );
-                    continue;
+                    match within(deadline, conn.get_head(id)).await {
+                        Ok(Ok(Some(head))) => vec![head],
+                        Ok(Ok(None)) => continue,
+                        Ok(Err(err)) => {
+                            trace!(
+                                target: LOG_TARGET,
+                                id = %id.to_short(),
+                                peer = %peer_id.to_short(),
+                                err = %err.fmt_compact(),
+                                "GET_HEAD failed, skipping peer"
+                            );
+                            continue;
+                        }
+                        Err(_) => {
+                            trace!(
+                                target: LOG_TARGET,
+                                id = %id.to_short(),
+                                peer = %peer_id.to_short(),
+                                timeout_secs = deadline.as_secs(),
+                                "GET_HEAD timed out, skipping peer"
+                            );
+                            continue;
+                        }
+                    }
                 }
                 Err(_) => {
                     trace!(
@@ -216,89 +251,96 @@ impl WotHeadSync {
                         id = %id.to_short(),
                         peer = %peer_id.to_short(),
                         timeout_secs = deadline.as_secs(),
-                        "GET_HEAD timed out, skipping peer"
+                        "RECONCILE_EVENTS timed out, skipping peer"
                     );
                     continue;
                 }
             };
 
-            if self.db.has_event(remote_head).await {
-                trace!(
-                    target: LOG_TARGET,
-                    id = %id.to_short(),
-                    peer = %peer_id.to_short(),
-                    head = %remote_head.to_short(),
-                    "Head already known"
-                );
-                continue;
-            }
-
-            debug!(
-                target: LOG_TARGET,
-                id = %id.to_short(),
-                peer = %peer_id.to_short(),
-                head = %remote_head.to_short(),
-                "Found unknown head, fetching events"
-            );
-
-            let downloaded = match within(
-                deadline,
-                crate::util::rpc::download_events_from_child(
-                    id,
-                    remote_head,
-                    &self.networking,
-                    &self.connections,
-                    &peers,
-                    &self.db,
-                ),
-            )
-            .await
-            {
-                Ok(Ok(downloaded)) => downloaded,
-                Ok(Err(err)) => {
-                    error!(
-                        target: LOG_TARGET,
-                        id = %id.to_short(),
-                        peer_id = %peer_id.to_short(),
-                        head = %remote_head.to_short(),
-                        err = %err,
-                        "Database ingestion failed while syncing a WoT head"
-                    );
-                    return Err(err);
-                }
-                Err(_) => {
+            let mut found_unknown = false;
+            for remote_head in remote_heads {
+                // Downloading a newer event often brings its missing ancestors too
+                if self.db.has_event(remote_head).await {
                     trace!(
                         target: LOG_TARGET,
                         id = %id.to_short(),
                         peer = %peer_id.to_short(),
                         head = %remote_head.to_short(),
-                        timeout_secs = deadline.as_secs(),
-                        "Event download timed out, skipping peer"
+                        "Event already known"
                     );
                     continue;
                 }
-            };
-            match downloaded {
-                true => {
-                    debug!(
-                        target: LOG_TARGET,
-                        id = %id.to_short(),
-                        head = %remote_head.to_short(),
-                        "Successfully fetched events for unknown head"
-                    );
-                }
-                false => {
-                    debug!(
-                        target: LOG_TARGET,
-                        id = %id.to_short(),
-                        head = %remote_head.to_short(),
-                        "No new events found from peers"
-                    );
+                found_unknown = true;
+
+                debug!(
+                    target: LOG_TARGET,
+                    id = %id.to_short(),
+                    peer = %peer_id.to_short(),
+                    head = %remote_head.to_short(),
+                    "Found unknown event, fetching events"
+                );
+
+                let downloaded = match within(
+                    deadline,
+                    crate::util::rpc::download_events_from_child(
+                        id,
+                        remote_head,
+                        &self.networking,
+                        &self.connections,
+                        &peers,
+                        &self.db,
+                    ),
+                )
+                .await
+                {
+                    Ok(Ok(downloaded)) => downloaded,
+                    Ok(Err(err)) => {
+                        error!(
+                            target: LOG_TARGET,
+                            id = %id.to_short(),
+                            peer_id = %peer_id.to_short(),
+                            head = %remote_head.to_short(),
+                            err = %err,
+                            "Database ingestion failed while syncing a WoT head"
+                        );
+                        return Err(err);
+                    }
+                    Err(_) => {
+                        trace!(
+                            target: LOG_TARGET,
+                            id = %id.to_short(),
+                            peer = %peer_id.to_short(),
+                            head = %remote_head.to_short(),
+                            timeout_secs = deadline.as_secs(),
+                            "Event download timed out, skipping peer"
+                        );
+                        break;
+                    }
+                };
+                match downloaded {
+                    true => {
+                        debug!(
+                            target: LOG_TARGET,
+                            id = %id.to_short(),
+                            head = %remote_head.to_short(),
+                            "Successfully fetched events for unknown event"
+                        );
+                    }
+                    false => {
+                        debug!(
+                            target: LOG_TARGET,
+                            id = %id.to_short(),
+                            head = %remote_head.to_short(),
+                            "No new events found from peers"
+                        );
+                    }
                 }
             }
 
-            // Found and processed an unknown head for this ID — move on
-            break;
+            if found_unknown {
+                // Caught up with a peer that was ahead for this ID — move on
+                break;
+            }
         }
 
         Ok(())
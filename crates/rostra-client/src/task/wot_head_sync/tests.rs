@@ -8,7 +8,8 @@ use rostra_core::event::{
 };
 use rostra_core::id::{RostraIdSecretKey, ToShort as _};
 use rostra_p2p::connection::{
-    Connection, GetHeadRequest, MAX_REQUEST_SIZE, PingRequest, PingResponse, RpcId, RpcMessage as _,
+    Connection, MAX_REQUEST_SIZE, PingRequest, PingResponse, ReconcileEventsRequest, RpcId,
+    RpcMessage as _,
 };
 use rostra_p2p_api::ROSTRA_P2P_V0_ALPN;
 use tokio::sync::Notify;
@@ -50,7 +51,7 @@ fn follow_content(
     VerifiedEventContent::verify(event, content).expect("matching follow content")
 }
 
-async fn hanging_head_server(endpoint: iroh::Endpoint, get_head_received: Arc<Notify>) {
+async fn hanging_reconcile_server(endpoint: iroh::Endpoint, reconcile_received: Arc<Notify>) {
     let incoming = endpoint.accept().await.expect("incoming connection");
     let connection = incoming
         .accept()
@@ -72,13 +73,14 @@ async fn hanging_head_server(endpoint: iroh::Endpoint, get_head_received: Arc<No
         .expect("ping response");
     send.finish().expect("finish ping response");
 
-    let (_send, mut recv) = connection.accept_bi().await.expect("head stream");
+    let (_send, mut recv) = connection.accept_bi().await.expect("reconcile stream");
     let (rpc_id, request) = Connection::read_request_raw(&mut recv)
         .await
-        .expect("head request");
-    assert_eq!(rpc_id, RpcId::GET_HEAD);
-    GetHeadRequest::decode_whole::<MAX_REQUEST_SIZE>(&request).expect("decode GET_HEAD");
-    get_head_received.notify_one();
+        .expect("reconcile request");
+    assert_eq!(rpc_id, RpcId::RECONCILE_EVENTS);
+    ReconcileEventsRequest::decode_whole::<MAX_REQUEST_SIZE>(&request)
+        .expect("decode RECONCILE_EVENTS");
+    reconcile_received.notify_one();
     std::future::pending::<()>().await;
 }
 
@@ -101,10 +103,10 @@ async fn hanging_peer_does_not_block_later_wot_head_sync() {
         .expect("hanging endpoint");
     let hanging_node_id = hanging_endpoint.id();
     lookup.add_endpoint_info(hanging_endpoint.addr());
-    let get_head_received = Arc::new(Notify::new());
-    let hanging_server = tokio::spawn(hanging_head_server(
+    let reconcile_received = Arc::new(Notify::new());
+    let hanging_server = tokio::spawn(hanging_reconcile_server(
         hanging_endpoint,
-        get_head_received.clone(),
+        reconcile_received.clone(),
     ));
 
     let target_endpoint = iroh::Endpoint::builder(iroh::endpoint::presets::Minimal)
@@ -173,9 +175,9 @@ async fn hanging_peer_does_not_block_later_wot_head_sync() {
     .expect("cycle continues after a hanging peer")
     .expect("database ingestion succeeds");
     assert_eq!(outcome, SyncCycleOutcome::Complete);
-    tokio::time::timeout(Duration::from_secs(1), get_head_received.notified())
+    tokio::time::timeout(Duration::from_secs(1), reconcile_received.notified())
         .await
-        .expect("first peer received GET_HEAD");
+        .expect("first peer received RECONCILE_EVENTS");
     assert!(
         db.has_event(target_head).await,
         "later responsive peer supplied the missing head"
@@ -0,0 +1,88 @@
+//! Timelines of social posts, for clients other than the web UI
+
+use std::collections::HashMap;
+
+use rostra_client_db::Database;
+use rostra_client_db::social::SocialPostRecord;
+use rostra_core::event::{PersonasTagsSelector, SocialPost};
+use rostra_core::id::RostraId;
+
+/// A timeline of social posts, from the point of view of an identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timeline {
+    /// Posts of followed identities matching their persona tags selectors
+    Following,
+    /// Posts of everyone else
+    Network,
+    /// News posts, by rank
+    News,
+    /// Replies to and mentions of the identity, by time received
+    Notifications,
+}
+
+impl Timeline {
+    pub const ALL: [Timeline; 4] = [
+        Timeline::Following,
+        Timeline::Network,
+        Timeline::News,
+        Timeline::Notifications,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Timeline::Following => "Following",
+            Timeline::Network => "Network",
+            Timeline::News => "News",
+            Timeline::Notifications => "Notifications",
+        }
+    }
+
+    /// Load up to `limit` newest posts of the timeline of `self_id`
+    ///
+    /// Posts are filtered the same way as in the web UI timelines, without
+    /// reposts.
+    pub async fn load(
+        self,
+        db: &Database,
+        self_id: RostraId,
+        limit: usize,
+    ) -> Vec<SocialPostRecord<SocialPost>> {
+        match self {
+            Timeline::Following => {
+                let followees: HashMap<RostraId, PersonasTagsSelector> =
+                    db.get_followees(self_id).await.into_iter().collect();
+                db.paginate_social_posts_rev(None, limit, move |post| {
+                    post.author != self_id
+                        && followees.get(&post.author).is_some_and(|selector| {
+                            let tags = post.content.persona_tags();
+                            tags.is_empty() || selector.matches_tags(&tags)
+                        })
+                })
+                .await
+                .0
+            }
+            Timeline::Network => {
+                db.paginate_social_posts_rev(None, limit, move |post| post.author != self_id)
+                    .await
+                    .0
+            }
+            Timeline::News => db
+                .paginate_news_posts_by_rank_rev(None, limit)
+                .await
+                .0
+                .into_iter()
+                .map(|record| record.post)
+                .collect(),
+            Timeline::Notifications => {
+                let self_mentions = db.get_self_mentions().await;
+                db.paginate_social_posts_by_received_at_rev(None, limit, move |post| {
+                    post.author != self_id
+                        && (post.reply_to.map(|ext_id| ext_id.rostra_id()) == Some(self_id)
+                            || self_mentions.contains(&post.event_id))
+                })
+                .await
+                .0
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Removal of location metadata from images before they are published.
+//!
+//! Cameras and phones embed the GPS position a photo was taken at in its EXIF
+//! (and sometimes XMP) metadata, which would be published along with the
+//! image. JPEG files keep all the other EXIF metadata (like the orientation)
+//! and only get their GPS data zeroed in place. Their XMP segments, and the
+//! EXIF and XMP chunks of PNG and WebP files, are dropped altogether. GIF,
+//! SVG, BMP and icon images, and files other than images, are published as
+//! they are. Images in other formats (like HEIC, AVIF or TIFF) could carry
+//! location metadata that isn't removed here, so they are refused.
+
+/// Return `data` of `mime` type without the location metadata
+///
+/// Returns `None` for images of a format that can't be cleaned.
+pub(crate) fn strip_location_metadata(mime: &str, data: Vec<u8>) -> Option<Vec<u8>> {
+    match mime {
+        "image/jpeg" | "image/jpg" => Some(strip_jpeg_location(data)),
+        "image/png" => Some(strip_png_metadata(data)),
+        "image/webp" => Some(strip_webp_metadata(data)),
+        "image/gif" | "image/svg+xml" | "image/bmp" | "image/x-icon" => Some(data),
+        _ if mime.starts_with("image/") => None,
+        _ => Some(data),
+    }
+}
+
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+/// Headers of the APP1 segments of XMP metadata, and of its extension
+const XMP_HEADERS: [&[u8]; 2] = [
+    b"http://ns.adobe.com/xap/1.0/\0",
+    b"http://ns.adobe.com/xmp/extension/\0",
+];
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// Keyword of the `iTXt` chunk holding XMP metadata
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp\0";
+/// IFD0 tag pointing at the GPS IFD
+const TAG_GPS_IFD: u16 = 0x8825;
+/// Flags of the `VP8X` chunk telling there are `EXIF` and `XMP ` chunks
+const WEBP_EXIF_XMP_FLAGS: u8 = 0x08 | 0x04;
+
+fn strip_jpeg_location(mut data: Vec<u8>) -> Vec<u8> {
+    if !data.starts_with(&[0xff, 0xd8]) {
+        return data;
+    }
+    let mut xmp_segments = vec![];
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xff {
+            break;
+        }
+        let marker = data[pos + 1];
+        match marker {
+            // Fill byte
+            0xff => {
+                pos += 1;
+                continue;
+            }
+            // Markers without a payload
+            0x01 | 0xd0..=0xd7 => {
+                pos += 2;
+                continue;
+            }
+            // Start of scan and end of image, metadata comes before them
+            0xda | 0xd9 => break,
+            _ => {}
+        }
+        let len = usize::from(u16::from_be_bytes([data[pos + 2], data[pos + 3]]));
+        let end = pos + 2 + len;
+        if len < 2 || data.len() < end {
+            break;
+        }
+        let payload = &mut data[pos + 4..end];
+        if marker == 0xe1 && payload.starts_with(EXIF_HEADER) {
+            strip_tiff_gps(&mut payload[EXIF_HEADER.len()..]);
+        }
+        if marker == 0xe1 && XMP_HEADERS.iter().any(|header| payload.starts_with(header)) {
+            xmp_segments.push(pos..end);
+        }
+        pos = end;
+    }
+
+    for segment in xmp_segments.into_iter().rev() {
+        data.drain(segment);
+    }
+    data
+}
+
+/// Zero the GPS IFD of the TIFF structure of EXIF metadata, with all its
+/// values
+fn strip_tiff_gps(tiff: &mut [u8]) {
+    let big_endian = match tiff.get(..2) {
+        Some(b"MM") => true,
+        Some(b"II") => false,
+        _ => return,
+    };
+    let read_u16 = |tiff: &[u8], offset: usize| {
+        let bytes = [*tiff.get(offset)?, *tiff.get(offset + 1)?];
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let read_u32 = |tiff: &[u8], offset: usize| {
+        let bytes: [u8; 4] = tiff.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    let Some(ifd0) = read_u32(tiff, 4).map(|offset| offset as usize) else {
+        return;
+    };
+    let Some(ifd0_len) = read_u16(tiff, ifd0) else {
+        return;
+    };
+    let gps_ifd = (0..usize::from(ifd0_len))
+        .map(|i| ifd0 + 2 + i * 12)
+        .find(|entry| read_u16(tiff, *entry) == Some(TAG_GPS_IFD))
+        .and_then(|entry| read_u32(tiff, entry + 8))
+        .map(|offset| offset as usize);
+    let Some(gps_ifd) = gps_ifd else {
+        return;
+    };
+    let Some(gps_len) = read_u16(tiff, gps_ifd).map(usize::from) else {
+        return;
+    };
+
+    // Values longer than 4 bytes are stored outside of the IFD entries
+    for i in 0..gps_len {
+        let entry = gps_ifd + 2 + i * 12;
+        let (Some(value_type), Some(count), Some(value_offset)) = (
+            read_u16(tiff, entry + 2),
+            read_u32(tiff, entry + 4),
+            read_u32(tiff, entry + 8),
+        ) else {
+            return;
+        };
+        let value_len = tiff_type_len(value_type).saturating_mul(count as usize);
+        if 4 < value_len {
+            zero(tiff, value_offset as usize, value_len);
+        }
+    }
+
+    // Entry count, entries, and the offset of the next IFD
+    zero(tiff, gps_ifd, 2 + gps_len * 12 + 4);
+}
+
+fn tiff_type_len(value_type: u16) -> usize {
+    match value_type {
+        1 | 2 | 6 | 7 => 1,
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => 0,
+    }
+}
+
+fn zero(data: &mut [u8], offset: usize, len: usize) {
+    let end = offset.saturating_add(len).min(data.len());
+    if offset < end {
+        data[offset..end].fill(0);
+    }
+}
+
+fn strip_png_metadata(data: Vec<u8>) -> Vec<u8> {
+    if !data.starts_with(PNG_SIGNATURE) {
+        return data;
+    }
+    let mut stripped = Vec::with_capacity(data.len());
+    stripped.extend_from_slice(PNG_SIGNATURE);
+    let mut pos = PNG_SIGNATURE.len();
+    while pos < data.len() {
+        // Length, type, data and crc
+        let Some(len) = data
+            .get(pos..pos + 4)
+            .map(|len| u32::from_be_bytes(len.try_into().expect("4 bytes")) as usize)
+        else {
+            return data;
+        };
+        let end = pos.saturating_add(12).saturating_add(len);
+        if data.len() < end {
+            return data;
+        }
+        let kind = &data[pos + 4..pos + 8];
+        let is_xmp = kind == b"iTXt" && data[pos + 8..end].starts_with(PNG_XMP_KEYWORD);
+        if kind != b"eXIf" && !is_xmp {
+            stripped.extend_from_slice(&data[pos..end]);
+        }
+        pos = end;
+    }
+    stripped
+}
+
+fn strip_webp_metadata(data: Vec<u8>) -> Vec<u8> {
+    if data.len() < 12 || &data[..4] != b"RIFF" || &data[8..12] != b"WEBP" {
+        return data;
+    }
+    let mut stripped = Vec::with_capacity(data.len());
+    stripped.extend_from_slice(&data[..12]);
+    let mut pos = 12;
+    while pos < data.len() {
+        // Fourcc, size, and data padded to an even length
+        let Some(len) = data
+            .get(pos + 4..pos + 8)
+            .map(|len| u32::from_le_bytes(len.try_into().expect("4 bytes")) as usize)
+        else {
+            return data;
+        };
+        let end = pos
+            .saturating_add(8)
+            .saturating_add(len)
+            .saturating_add(len % 2);
+        if data.len() < end {
+            return data;
+        }
+        let kind = &data[pos..pos + 4];
+        if kind != b"EXIF" && kind != b"XMP " {
+            let start = stripped.len();
+            stripped.extend_from_slice(&data[pos..end]);
+            if kind == b"VP8X" && 8 < end - pos {
+                stripped[start + 8] &= !WEBP_EXIF_XMP_FLAGS;
+            }
+        }
+        pos = end;
+    }
+    let riff_len = u32::try_from(stripped.len() - 8).expect("smaller than the original");
+    stripped[4..8].copy_from_slice(&riff_len.to_le_bytes());
+    stripped
+}
+
+#[cfg(test)]
+mod tests;
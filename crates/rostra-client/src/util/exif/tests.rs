@@ -0,0 +1,176 @@
+use super::*;
+
+/// A JPEG with EXIF metadata holding an orientation, and a GPS latitude
+fn jpeg_with_gps() -> Vec<u8> {
+    let mut tiff = vec![];
+    tiff.extend_from_slice(b"II");
+    tiff.extend_from_slice(&42u16.to_le_bytes());
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    // IFD0 at 8: orientation and GPS IFD pointer
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    tiff.extend_from_slice(&0x0112u16.to_le_bytes());
+    tiff.extend_from_slice(&3u16.to_le_bytes());
+    tiff.extend_from_slice(&1u32.to_le_bytes());
+    tiff.extend_from_slice(&6u32.to_le_bytes());
+    tiff.extend_from_slice(&TAG_GPS_IFD.to_le_bytes());
+    tiff.extend_from_slice(&4u16.to_le_bytes());
+    tiff.extend_from_slice(&1u32.to_le_bytes());
+    tiff.extend_from_slice(&38u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    // GPS IFD at 38: latitude, 3 rationals stored at 56
+    tiff.extend_from_slice(&1u16.to_le_bytes());
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    tiff.extend_from_slice(&5u16.to_le_bytes());
+    tiff.extend_from_slice(&3u32.to_le_bytes());
+    tiff.extend_from_slice(&56u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    for value in [52u32, 1, 13, 1, 2, 1] {
+        tiff.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let mut jpeg = vec![0xff, 0xd8, 0xff, 0xe1];
+    let len = u16::try_from(2 + EXIF_HEADER.len() + tiff.len()).expect("small");
+    jpeg.extend_from_slice(&len.to_be_bytes());
+    jpeg.extend_from_slice(EXIF_HEADER);
+    jpeg.extend_from_slice(&tiff);
+    jpeg.extend_from_slice(&[0xff, 0xda, 0x00, 0x02, 0x12, 0x34, 0xff, 0xd9]);
+    jpeg
+}
+
+#[test]
+fn jpeg_gps_is_zeroed_and_the_rest_kept() {
+    let original = jpeg_with_gps();
+    let stripped = strip_location_metadata("image/jpeg", original.clone()).expect("supported");
+
+    assert_eq!(stripped.len(), original.len());
+    let tiff_start = 4 + 2 + EXIF_HEADER.len();
+    let tiff = &stripped[tiff_start..];
+    // Orientation is kept
+    assert_eq!(&tiff[10..22], &original[tiff_start + 10..tiff_start + 22]);
+    // GPS IFD and the latitude are gone
+    assert!(tiff[38..80].iter().all(|b| *b == 0));
+    // Image data is untouched
+    assert_eq!(
+        &stripped[stripped.len() - 8..],
+        &original[original.len() - 8..]
+    );
+}
+
+#[test]
+fn jpeg_xmp_segments_are_dropped() {
+    let segment = |payload: &[u8]| {
+        let mut segment = vec![0xff, 0xe1];
+        let len = u16::try_from(2 + payload.len()).expect("small");
+        segment.extend_from_slice(&len.to_be_bytes());
+        segment.extend_from_slice(payload);
+        segment
+    };
+    let app0 = [0xff, 0xe0, 0x00, 0x04, 0x4a, 0x46].to_vec();
+    let scan = [0xff, 0xda, 0x00, 0x02, 0x12, 0x34, 0xff, 0xd9].to_vec();
+    let jpeg = [
+        vec![0xff, 0xd8],
+        app0.clone(),
+        segment(
+            &[
+                XMP_HEADERS[0],
+                b"<exif:GPSLatitude>52,13</exif:GPSLatitude>",
+            ]
+            .concat(),
+        ),
+        segment(&[XMP_HEADERS[1], b"more location"].concat()),
+        scan.clone(),
+    ]
+    .concat();
+
+    assert_eq!(
+        strip_location_metadata("image/jpeg", jpeg).expect("supported"),
+        [vec![0xff, 0xd8], app0, scan].concat()
+    );
+}
+
+#[test]
+fn png_exif_chunk_is_dropped() {
+    let chunk = |kind: &[u8], data: &[u8]| {
+        let mut chunk = (data.len() as u32).to_be_bytes().to_vec();
+        chunk.extend_from_slice(kind);
+        chunk.extend_from_slice(data);
+        chunk.extend_from_slice(&[0; 4]);
+        chunk
+    };
+    let ihdr = chunk(b"IHDR", &[1; 13]);
+    let iend = chunk(b"IEND", &[]);
+    let png = [
+        PNG_SIGNATURE.to_vec(),
+        ihdr.clone(),
+        chunk(b"eXIf", b"MM\0*location"),
+        chunk(b"iTXt", &[PNG_XMP_KEYWORD, b"\0\0\0\0<xmp/>"].concat()),
+        iend.clone(),
+    ]
+    .concat();
+
+    assert_eq!(
+        strip_location_metadata("image/png", png).expect("supported"),
+        [PNG_SIGNATURE.to_vec(), ihdr, iend].concat()
+    );
+}
+
+#[test]
+fn webp_exif_and_xmp_chunks_are_dropped() {
+    let chunk = |kind: &[u8], data: &[u8]| {
+        let mut chunk = kind.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    };
+    let riff = |chunks: &[Vec<u8>]| {
+        let chunks = chunks.concat();
+        let mut riff = b"RIFF".to_vec();
+        riff.extend_from_slice(&(4 + chunks.len() as u32).to_le_bytes());
+        riff.extend_from_slice(b"WEBP");
+        riff.extend_from_slice(&chunks);
+        riff
+    };
+    // Alpha, EXIF and XMP flags set
+    let vp8x = |flags: u8| chunk(b"VP8X", &[flags, 0, 0, 0, 1, 0, 0, 1, 0, 0]);
+    let image = chunk(b"VP8L", &[0x2f; 5]);
+    let webp = riff(&[
+        vp8x(0x10 | 0x08 | 0x04),
+        image.clone(),
+        chunk(b"EXIF", b"MM\0*location"),
+        chunk(b"XMP ", b"<exif:GPSLatitude>52,13</exif:GPSLatitude>"),
+    ]);
+
+    assert_eq!(
+        strip_location_metadata("image/webp", webp).expect("supported"),
+        riff(&[vp8x(0x10), image])
+    );
+}
+
+#[test]
+fn malformed_and_other_data_is_kept() {
+    for data in [vec![], vec![0xff, 0xd8, 0xff], b"not an image".to_vec()] {
+        for mime in ["image/jpeg", "image/png", "image/webp"] {
+            assert_eq!(
+                strip_location_metadata(mime, data.clone()),
+                Some(data.clone())
+            );
+        }
+    }
+    let gif = b"GIF89a".to_vec();
+    assert_eq!(strip_location_metadata("image/gif", gif.clone()), Some(gif));
+    let video = b"not an image".to_vec();
+    assert_eq!(
+        strip_location_metadata("video/mp4", video.clone()),
+        Some(video)
+    );
+}
+
+#[test]
+fn images_that_can_not_be_cleaned_are_refused() {
+    for mime in ["image/heic", "image/avif", "image/tiff"] {
+        assert_eq!(strip_location_metadata(mime, b"location".to_vec()), None);
+    }
+}
@@ -1 +1,2 @@
+pub(crate) mod exif;
 pub mod rpc;
@@ -1,10 +1,17 @@
 use std::cmp::Reverse;
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
 
+use convi::CastFrom as _;
 use rostra_client_db::{DbResult, InsertEventOutcome, ProcessEventState};
-use rostra_core::ShortEventId;
-use rostra_core::event::VerifiedEvent;
+use rostra_core::event::{EventContentRaw, EventExt as _, VerifiedEvent, VerifiedEventContent};
 use rostra_core::id::{RostraId, ToShort as _};
+use rostra_core::{ShortEventId, Timestamp};
+use rostra_p2p::connection::GetAncestorsRequest;
+use rostra_p2p::reconcile::{
+    self, EventsBound, EventsFingerprint, EventsRange, EventsRangeReconciliation,
+};
+use rostra_p2p::{Connection, RpcError};
+use rostra_util_error::FmtCompact as _;
 use rostra_util_fmt::AsFmtOption as _;
 use tracing::debug;
 
@@ -48,8 +55,9 @@ pub(crate) async fn get_event_content_from_followers(
             %event_id,
             "Event not in DB, fetching from peers first"
         );
+        let delegates = db.get_device_delegates(author_id).await;
         let Some(event) = connections_cache
-            .get_event_from_peers(networking, &peers, author_id, event_id)
+            .get_event_from_peers(networking, &peers, author_id, event_id, &delegates)
             .await
         else {
             return Ok(false);
@@ -58,9 +66,9 @@ pub(crate) async fn get_event_content_from_followers(
         event
     };
 
-    let Some(content) = connections_cache
-        .get_event_content_from_peers(networking, &peers, event)
-        .await
+    let Some(content) =
+        get_event_content_from_peers_resumable(networking, connections_cache, &peers, event, db)
+            .await?
     else {
         return Ok(false);
     };
@@ -70,6 +78,82 @@ pub(crate) async fn get_event_content_from_followers(
     Ok(true)
 }
 
+/// Content larger than this is downloaded a range of this size at a time
+const CONTENT_RANGE_LEN: u32 = 1024 * 1024;
+
+/// Fetch event content from peers, resuming an interrupted download.
+///
+/// Large content is downloaded a range at a time (`GET_EVENT_CONTENT_RANGE`),
+/// and every verified range is stored in the database, so a later attempt
+/// (e.g. a retry of the missing content fetcher) continues where the last one
+/// stopped. If the ranges are not available, the whole content is requested
+/// like small content is, for peers that don't serve ranges.
+pub(crate) async fn get_event_content_from_peers_resumable(
+    networking: &ClientNetworking,
+    connections: &ConnectionCache,
+    peers: &[RostraId],
+    event: VerifiedEvent,
+    db: &rostra_client_db::Database,
+) -> DbResult<Option<VerifiedEventContent>> {
+    let len = event.content_len();
+    if len <= CONTENT_RANGE_LEN {
+        return Ok(connections
+            .get_event_content_from_peers(networking, peers, event)
+            .await);
+    }
+
+    let event_id = event.event_id.to_short();
+    let mut data = db.get_event_content_partial(event_id).await;
+    data.truncate(usize::cast_from(len));
+    if !data.is_empty() {
+        debug!(
+            target: LOG_TARGET,
+            %event_id,
+            len,
+            resumed_at = data.len(),
+            "Resuming event content download"
+        );
+    }
+
+    // Keep asking the peer that served the last range first
+    let mut peers = peers.to_vec();
+    while data.len() < usize::cast_from(len) {
+        let start = u32::try_from(data.len()).expect("Less than len");
+        let end = start.saturating_add(CONTENT_RANGE_LEN).min(len);
+        let Some((peer_id, piece)) = connections
+            .get_event_content_range_from_peers(networking, &peers, event, start..end)
+            .await
+        else {
+            break;
+        };
+        if let Some(pos) = peers.iter().position(|peer| *peer == peer_id) {
+            peers[..=pos].rotate_right(1);
+        }
+        db.insert_event_content_partial(event_id, start, piece.clone())
+            .await?;
+        data.extend_from_slice(&piece);
+    }
+
+    if data.len() == usize::cast_from(len) {
+        match VerifiedEventContent::verify(event, EventContentRaw::new(data)) {
+            Ok(content) => return Ok(Some(content)),
+            Err(err) => {
+                debug!(
+                    target: LOG_TARGET,
+                    %event_id,
+                    err = %err.fmt_compact(),
+                    "Downloaded content ranges do not match, starting over"
+                );
+                db.remove_event_content_partial(event_id).await?;
+            }
+        }
+    }
+
+    Ok(connections
+        .get_event_content_from_peers(networking, &peers, event)
+        .await)
+}
+
 /// Downloads events from a child event, traversing backward toward older
 /// history.
 ///
@@ -87,6 +171,12 @@ pub(crate) async fn get_event_content_from_followers(
 /// Depth starts at 0 for the head and increments for each parent traversal.
 /// Higher depth (deeper into the DAG) is processed first via `Reverse`.
 ///
+/// Missing events are fetched in batches walking back from the missing event
+/// until the local heads (`GET_ANCESTORS`), so long histories take one round
+/// trip per batch instead of one per event. Batched events are still processed
+/// in the traversal order. Peers that don't support batches are asked for one
+/// event at a time (`GET_EVENT`).
+///
 /// Tries multiple peers (via the connection cache) when fetching events and
 /// content.
 ///
@@ -99,8 +189,6 @@ pub(crate) async fn download_events_from_child(
     peers: &[RostraId],
     storage: &rostra_client_db::Database,
 ) -> DbResult<bool> {
-    use rostra_core::event::EventExt as _;
-
     struct QueueItemData {
         process_state: Option<ProcessEventState>,
         child_timestamp: u64,
@@ -127,10 +215,15 @@ pub(crate) async fn download_events_from_child(
 
     let mut downloaded_anything = false;
 
+    // Events fetched in batches ahead of the traversal
+    let mut prefetched: BTreeMap<ShortEventId, VerifiedEvent> = BTreeMap::new();
+    let mut batch_fetch_enabled = true;
+
     // Stats
     let mut max_queue_len: usize = 0;
     let mut events_traversed: usize = 0;
     let mut event_fetch_attempts: usize = 0;
+    let mut batch_fetches: usize = 0;
     let mut content_fetch_attempts: usize = 0;
     let mut new_events: usize = 0;
     let mut new_contents: usize = 0;
@@ -175,9 +268,14 @@ pub(crate) async fn download_events_from_child(
                 );
 
                 content_fetch_attempts += 1;
-                if let Some(content) = connections
-                    .get_event_content_from_peers(networking, peers, event)
-                    .await
+                if let Some(content) = get_event_content_from_peers_resumable(
+                    networking,
+                    connections,
+                    peers,
+                    event,
+                    storage,
+                )
+                .await?
                 {
                     storage.try_process_event_content(&content).await?;
                     new_contents += 1;
@@ -205,19 +303,71 @@ pub(crate) async fn download_events_from_child(
                     ProcessEventState::Existing,
                     InsertEventOutcome::AlreadyPresent,
                 )
-            } else {
+            } else if storage.is_event_pruned(rostra_id, q_item_event_id).await {
                 debug!(
                     target: LOG_TARGET,
                     depth = %q_item_depth,
                     event_id = %q_item_event_id,
-                    "Querying peers for event"
+                    "Event was pruned locally, skipping"
                 );
+                continue;
+            } else {
+                let new_event = if let Some(event) = prefetched.remove(&q_item_event_id) {
+                    Some(event)
+                } else {
+                    debug!(
+                        target: LOG_TARGET,
+                        depth = %q_item_depth,
+                        event_id = %q_item_event_id,
+                        "Querying peers for event"
+                    );
 
-                event_fetch_attempts += 1;
-                let Some(new_event) = connections
-                    .get_event_from_peers(networking, peers, rostra_id, q_item_event_id)
-                    .await
-                else {
+                    event_fetch_attempts += 1;
+                    let delegates = storage.get_device_delegates(rostra_id).await;
+                    let batch = if batch_fetch_enabled {
+                        let mut known = storage.get_heads_events_for_id(rostra_id).await;
+                        known.truncate(GetAncestorsRequest::MAX_KNOWN);
+                        connections
+                            .get_ancestors_from_peers(
+                                networking,
+                                peers,
+                                rostra_id,
+                                q_item_event_id,
+                                &known,
+                                GetAncestorsRequest::MAX_LIMIT,
+                                &delegates,
+                            )
+                            .await
+                    } else {
+                        None
+                    };
+                    if let Some(batch) = batch {
+                        batch_fetches += 1;
+                        prefetched.extend(
+                            batch
+                                .into_iter()
+                                .map(|event| (event.event_id.to_short(), event)),
+                        );
+                        prefetched.remove(&q_item_event_id)
+                    } else {
+                        let event = connections
+                            .get_event_from_peers(
+                                networking,
+                                peers,
+                                rostra_id,
+                                q_item_event_id,
+                                &delegates,
+                            )
+                            .await;
+                        if event.is_some() {
+                            // Some peer has the event, but does not serve batches, so
+                            // don't keep asking for them.
+                            batch_fetch_enabled = false;
+                        }
+                        event
+                    }
+                };
+                let Some(new_event) = new_event else {
                     debug!(
                         target: LOG_TARGET,
                         depth = %q_item_depth,
@@ -229,6 +379,10 @@ pub(crate) async fn download_events_from_child(
                 downloaded_anything = true;
                 new_events += 1;
                 let (insert_outcome, process_state) = storage.try_process_event(&new_event).await?;
+                if matches!(insert_outcome, InsertEventOutcome::Pruned) {
+                    // Locally pruned history, which is not followed any further.
+                    continue;
+                }
                 (new_event, process_state, insert_outcome)
             };
 
@@ -302,6 +456,7 @@ pub(crate) async fn download_events_from_child(
         max_queue_len,
         events_traversed,
         event_fetch_attempts,
+        batch_fetches,
         content_fetch_attempts,
         new_events,
         new_contents,
@@ -310,3 +465,106 @@ pub(crate) async fn download_events_from_child(
 
     Ok(downloaded_anything)
 }
+
+/// Max number of round trips of a single reconciliation
+const MAX_RECONCILE_ROUNDS: usize = 64;
+
+/// Find events of `author` that the peer has and we don't, newest first
+/// (`RECONCILE_EVENTS`).
+///
+/// Only events since the local pruning checkpoint of `author` are compared,
+/// as older ones are not wanted anyway. If the difference is too large to be
+/// found within [`MAX_RECONCILE_ROUNDS`] round trips, a part of it is
+/// returned.
+pub(crate) async fn reconcile_events_with_peer(
+    conn: &Connection,
+    author: RostraId,
+    storage: &rostra_client_db::Database,
+) -> Result<Vec<ShortEventId>, RpcError> {
+    let start = EventsBound {
+        timestamp: storage
+            .get_events_pruned_checkpoint(author)
+            .await
+            .unwrap_or(Timestamp::ZERO),
+        event_id: ShortEventId::ZERO,
+    };
+    let mut pending = VecDeque::from([EventsRange { start, end: None }]);
+    let mut local_missing = vec![];
+    let mut remote_missing = 0;
+    let mut rounds = 0;
+
+    while !pending.is_empty() {
+        if MAX_RECONCILE_ROUNDS <= rounds {
+            debug!(
+                target: LOG_TARGET,
+                author = %author.to_short(),
+                pending = pending.len(),
+                "Reconciliation round limit reached, returning partial difference"
+            );
+            break;
+        }
+        rounds += 1;
+
+        let mut batch = vec![];
+        let mut request = vec![];
+        while request.len() < reconcile::MAX_RANGES {
+            let Some(range) = pending.pop_front() else {
+                break;
+            };
+            let local: Vec<EventsBound> = storage
+                .get_events_by_author_range(
+                    author,
+                    (range.start.timestamp, range.start.event_id),
+                    range.end.map(|end| (end.timestamp, end.event_id)),
+                    usize::MAX,
+                )
+                .await
+                .into_iter()
+                .map(EventsBound::from)
+                .collect();
+            request.push((range, local.iter().map(|event| event.event_id).collect()));
+            batch.push(local);
+        }
+
+        let results = conn.reconcile_events(author, request).await?;
+
+        for (local, result) in batch.into_iter().zip(results) {
+            match result {
+                EventsRangeReconciliation::Equal => {}
+                EventsRangeReconciliation::Events(remote) => {
+                    let remote_set: BTreeSet<_> = remote.iter().copied().collect();
+                    let local_set: BTreeSet<_> = local.iter().copied().collect();
+                    local_missing.extend(remote_set.difference(&local_set).copied());
+                    remote_missing += local_set.difference(&remote_set).count();
+                }
+                EventsRangeReconciliation::Split(subranges) => {
+                    for (subrange, remote_fingerprint) in subranges {
+                        let local_fingerprint: EventsFingerprint = local
+                            .iter()
+                            .filter(|event| subrange.contains(**event))
+                            .map(|event| event.event_id)
+                            .collect();
+                        if local_fingerprint != remote_fingerprint {
+                            pending.push_back(subrange);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    debug!(
+        target: LOG_TARGET,
+        author = %author.to_short(),
+        rounds,
+        local_missing = local_missing.len(),
+        remote_missing,
+        "Reconciled events with peer"
+    );
+
+    local_missing.sort_unstable_by(|a, b| b.cmp(a));
+    Ok(local_missing
+        .into_iter()
+        .map(|event| event.event_id)
+        .collect())
+}
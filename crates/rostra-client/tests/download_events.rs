@@ -269,3 +269,96 @@ async fn test_follow_while_running_syncs_events() -> BoxedErrorResult<()> {
 
     Ok(())
 }
+
+/// Test that large content is downloaded in verified ranges, continuing
+/// after the ranges stored by an earlier, interrupted download.
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn test_large_content_download_resumes() -> BoxedErrorResult<()> {
+    use rostra_core::event::content_kind::{self, EventContentKind as _};
+
+    let secret_a = RostraIdSecretKey::generate();
+    let id_a = secret_a.id();
+    let id_b = RostraIdSecretKey::generate().id();
+
+    let mem_lookup = iroh::address_lookup::memory::MemoryLookup::new();
+
+    let ep_a = iroh::Endpoint::builder(iroh::endpoint::presets::Minimal)
+        .relay_mode(iroh::RelayMode::Disabled)
+        .alpns(vec![ROSTRA_P2P_V0_ALPN.to_vec()])
+        .address_lookup(mem_lookup.clone())
+        .bind()
+        .await
+        .boxed()?;
+    let ep_a_pub_id = ep_a.id();
+    mem_lookup.add_endpoint_info(ep_a.addr());
+
+    let ep_b = iroh::Endpoint::builder(iroh::endpoint::presets::Minimal)
+        .relay_mode(iroh::RelayMode::Disabled)
+        .alpns(vec![ROSTRA_P2P_V0_ALPN.to_vec()])
+        .address_lookup(mem_lookup.clone())
+        .bind()
+        .await
+        .boxed()?;
+
+    let client_a = Client::builder(id_a)
+        .db(Database::new_in_memory(id_a).await?)
+        .iroh_endpoint(ep_a)
+        .start_background_tasks(false)
+        .build()
+        .await?;
+    let client_b = Client::builder(id_b)
+        .db(Database::new_in_memory(id_b).await?)
+        .iroh_endpoint(ep_b)
+        .start_request_handler(false)
+        .start_background_tasks(false)
+        .build()
+        .await?;
+    let db_a = client_a.db();
+    let db_b = client_b.db();
+    db_b.insert_id_node(
+        id_a,
+        IrohNodeId::from_bytes(*ep_a_pub_id.as_bytes()),
+        Timestamp::now(),
+    )
+    .await;
+
+    // A chunk spanning a few download ranges, with a shorter last one
+    let data: Vec<u8> = (0..(3 * 1024 * 1024 + 12345))
+        .map(|i: u32| (i % 251) as u8)
+        .collect();
+    let content = content_kind::SocialMediaChunk { data }
+        .serialize_cbor()
+        .expect("valid chunk");
+    let event = Event::builder_raw_content()
+        .author(id_a)
+        .kind(EventKind::SOCIAL_MEDIA_CHUNK)
+        .content(&content)
+        .build()
+        .signed_by(secret_a);
+    let event = VerifiedEvent::verify_signed(id_a, event).expect("Valid event");
+    let event_id = event.event_id.to_short();
+    db_a.process_event_with_content(
+        &VerifiedEventContent::verify(event, content.clone()).expect("Valid content"),
+    )
+    .await;
+
+    // B has the event, and the first range from an earlier download
+    db_b.process_event(&event).await;
+    db_b.insert_event_content_partial(event_id, 0, content.as_slice()[..1024 * 1024].to_vec())
+        .await?;
+
+    let fetched = client_b
+        .fetch_event_content(id_a, event_id, &mut Default::default())
+        .await?;
+    assert!(fetched, "Content should have been downloaded");
+
+    assert_eq!(
+        db_b.get_event_content(event_id)
+            .await
+            .map(|content| content.as_slice().to_vec()),
+        Some(content.as_slice().to_vec())
+    );
+    assert!(db_b.get_event_content_partial(event_id).await.is_empty());
+
+    Ok(())
+}
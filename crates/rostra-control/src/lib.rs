@@ -0,0 +1,156 @@
+//! Local control API of a running Rostra node
+//!
+//! An HTTP+JSON API served on a Unix socket, letting local programs publish,
+//! follow, query timelines and subscribe to new events of the node's identity.
+//!
+//! There is no authentication on the requests themselves: the socket is only
+//! accessible to the user running the node (mode `0600`), and connections
+//! from processes of other users are dropped. Programs with access to the
+//! socket act with the secret the node was started with, without ever
+//! handling it. Without a secret the API is read-only.
+//!
+//! See `docs/control-api.md` for the endpoints.
+
+mod routes;
+
+use std::os::unix::fs::{FileTypeExt as _, MetadataExt as _, PermissionsExt as _};
+use std::path::Path;
+use std::sync::Arc;
+use std::{fs, io};
+
+use axum::Router;
+use rostra_client::Client;
+use rostra_core::id::RostraIdSecretKey;
+use rostra_util_error::FmtCompact as _;
+use snafu::{ResultExt as _, Snafu};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::{info, warn};
+
+pub const LOG_TARGET: &str = "rostra::control";
+
+/// Header every request must set to the API version it expects
+pub const API_VERSION_HEADER: &str = "x-rostra-api-version";
+
+/// Version of the API served
+pub const API_CURRENT_VERSION: u32 = 0;
+
+#[derive(Debug, Snafu)]
+pub enum ControlError {
+    #[snafu(display("Failed to bind control socket: {source}"))]
+    Bind { source: io::Error },
+    #[snafu(display("Control server error: {source}"))]
+    Serve { source: io::Error },
+}
+
+pub type ControlResult<T> = std::result::Result<T, ControlError>;
+
+/// State shared by the control API handlers
+pub struct ControlState {
+    pub client: Arc<Client>,
+    /// Secret used to publish, if the node has one
+    pub id_secret: Option<RostraIdSecretKey>,
+}
+
+pub fn router(state: Arc<ControlState>) -> Router {
+    routes::router().with_state(state)
+}
+
+/// Serve the control API of `client` on a Unix socket at `path`
+///
+/// An existing socket at `path` is replaced, any other file is left alone and
+/// fails the binding. Runs until the server fails.
+pub async fn run_control(
+    client: Arc<Client>,
+    id_secret: Option<RostraIdSecretKey>,
+    path: &Path,
+) -> ControlResult<()> {
+    let listener = OwnerOnlyListener::bind(path).context(BindSnafu)?;
+    info!(target: LOG_TARGET, path = %path.display(), "Starting control API");
+
+    axum::serve(
+        listener,
+        router(Arc::new(ControlState { client, id_secret })).into_make_service(),
+    )
+    .await
+    .context(ServeSnafu)
+}
+
+/// A Unix socket listener accepting only connections from its owner
+pub struct OwnerOnlyListener {
+    listener: UnixListener,
+    owner_uid: u32,
+}
+
+impl OwnerOnlyListener {
+    pub fn bind(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        match fs::symlink_metadata(path) {
+            // Only take over the socket of a node that is not running anymore
+            Ok(metadata) if metadata.file_type().is_socket() => {
+                match std::os::unix::net::UnixStream::connect(path) {
+                    Ok(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AddrInUse,
+                            format!("{} is in use by a running node", path.display()),
+                        ));
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => {
+                        fs::remove_file(path)?
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} exists and is not a socket", path.display()),
+                ));
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        let listener = UnixListener::bind(path)?;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+        let owner_uid = fs::metadata(path)?.uid();
+        Ok(Self {
+            listener,
+            owner_uid,
+        })
+    }
+}
+
+impl axum::serve::Listener for OwnerOnlyListener {
+    type Io = UnixStream;
+    type Addr = tokio::net::unix::SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            let (stream, addr) = match self.listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    warn!(target: LOG_TARGET, err = %err.fmt_compact(), "Failed to accept connection");
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    continue;
+                }
+            };
+            match stream.peer_cred() {
+                Ok(cred) if cred.uid() == self.owner_uid => return (stream, addr),
+                Ok(cred) => {
+                    warn!(target: LOG_TARGET, uid = cred.uid(), "Rejecting connection of another user");
+                }
+                Err(err) => {
+                    warn!(target: LOG_TARGET, err = %err.fmt_compact(), "Failed to get peer credentials");
+                }
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+#[cfg(test)]
+mod tests;
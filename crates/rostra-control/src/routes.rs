@@ -0,0 +1,432 @@
+//! Endpoints of the control API
+
+use std::collections::BTreeSet;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use axum::body::Body;
+use axum::extract::{FromRequestParts, Path, Query, State};
+use axum::http::request::Parts;
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::stream;
+use rostra_client::error::PostError;
+use rostra_client::timeline::Timeline;
+use rostra_client_db::Database;
+use rostra_client_db::social::SocialPostRecord;
+use rostra_core::event::{
+    EventKind, PersonaTag, PersonasTagsSelector, SocialPost, VerifiedEvent, VerifiedEventContent,
+};
+use rostra_core::id::{ExternalEventId, RostraId, RostraIdSecretKey, ToShort as _};
+use rostra_core::{ShortEventId, Timestamp};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use crate::{API_CURRENT_VERSION, API_VERSION_HEADER, ControlState};
+
+type SharedState = Arc<ControlState>;
+
+/// Number of posts returned when the request doesn't set a limit
+const DEFAULT_LIMIT: usize = 20;
+
+/// Maximum number of posts returned per request
+const MAX_LIMIT: usize = 1000;
+
+#[derive(Debug)]
+pub(crate) struct ApiError {
+    status: StatusCode,
+    error: String,
+}
+
+#[derive(Serialize)]
+struct ApiErrorResponse {
+    error: String,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(ApiErrorResponse { error: self.error })).into_response()
+    }
+}
+
+fn api_error(status: StatusCode, msg: impl Into<String>) -> ApiError {
+    ApiError {
+        status,
+        error: msg.into(),
+    }
+}
+
+impl From<PostError> for ApiError {
+    fn from(err: PostError) -> Self {
+        let status = match err {
+            PostError::Validation { .. }
+            | PostError::PollUnavailable
+            | PostError::PollClosed
+            | PostError::InvalidPollOption
+            | PostError::NoSuccessor
+            | PostError::NotDelegated
+            | PostError::RootSecretRequired => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        api_error(status, err.to_string())
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// Extracts and validates the `X-Rostra-Api-Version` header
+struct ApiVersion;
+
+impl FromRequestParts<SharedState> for ApiVersion {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &SharedState,
+    ) -> Result<Self, Self::Rejection> {
+        let value = parts.headers.get(API_VERSION_HEADER).ok_or_else(|| {
+            api_error(
+                StatusCode::BAD_REQUEST,
+                format!("Missing required header: {API_VERSION_HEADER}"),
+            )
+        })?;
+        let version: u32 = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| {
+                api_error(
+                    StatusCode::BAD_REQUEST,
+                    "Version must be a non-negative integer",
+                )
+            })?;
+        if API_CURRENT_VERSION < version {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Unsupported API version: {version}. Maximum supported: {API_CURRENT_VERSION}"
+                ),
+            ));
+        }
+        Ok(ApiVersion)
+    }
+}
+
+fn id_secret(state: &ControlState) -> ApiResult<RostraIdSecretKey> {
+    state.id_secret.ok_or_else(|| {
+        api_error(
+            StatusCode::FORBIDDEN,
+            "The node runs without a secret key, so the API is read-only",
+        )
+    })
+}
+
+pub(crate) fn router() -> Router<SharedState> {
+    Router::new()
+        .route("/info", get(get_info))
+        .route("/posts", post(publish_post))
+        .route("/posts/{event_id}", get(get_thread))
+        .route("/follow", post(follow))
+        .route("/unfollow", post(unfollow))
+        .route("/timelines/{timeline}", get(get_timeline))
+        .route("/events", get(subscribe_events))
+}
+
+#[derive(Serialize)]
+struct InfoResponse {
+    api_version: u32,
+    rostra_id: RostraId,
+    read_only: bool,
+}
+
+async fn get_info(State(state): State<SharedState>, _version: ApiVersion) -> Json<InfoResponse> {
+    Json(InfoResponse {
+        api_version: API_CURRENT_VERSION,
+        rostra_id: state.client.rostra_id(),
+        read_only: state.id_secret.is_none(),
+    })
+}
+
+#[derive(Serialize)]
+struct PublishedResponse {
+    event_id: ShortEventId,
+}
+
+impl From<VerifiedEvent> for PublishedResponse {
+    fn from(event: VerifiedEvent) -> Self {
+        Self {
+            event_id: event.event_id.to_short(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PublishPostRequest {
+    body: String,
+    reply_to: Option<ExternalEventId>,
+    #[serde(default)]
+    persona_tags: BTreeSet<PersonaTag>,
+}
+
+async fn publish_post(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Json(req): Json<PublishPostRequest>,
+) -> ApiResult<Json<PublishedResponse>> {
+    let id_secret = id_secret(&state)?;
+    if req.body.trim().is_empty() {
+        return Err(api_error(StatusCode::BAD_REQUEST, "Post body is empty"));
+    }
+    let event = state
+        .client
+        .social_post(id_secret, req.body, req.reply_to, req.persona_tags)
+        .await?;
+    Ok(Json(event.into()))
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+enum FilterMode {
+    Only,
+    #[default]
+    Except,
+}
+
+#[derive(Deserialize)]
+struct FollowRequest {
+    followee: RostraId,
+    /// Persona tags to show or hide, per `filter_mode`
+    #[serde(default)]
+    persona_tags: BTreeSet<PersonaTag>,
+    #[serde(default)]
+    filter_mode: FilterMode,
+}
+
+async fn follow(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Json(req): Json<FollowRequest>,
+) -> ApiResult<Json<PublishedResponse>> {
+    let id_secret = id_secret(&state)?;
+    let ids = req.persona_tags;
+    let selector = match req.filter_mode {
+        FilterMode::Only => PersonasTagsSelector::Only { ids },
+        FilterMode::Except => PersonasTagsSelector::Except { ids },
+    };
+    let event = state
+        .client
+        .follow(id_secret, req.followee, selector)
+        .await?;
+    Ok(Json(event.into()))
+}
+
+#[derive(Deserialize)]
+struct UnfollowRequest {
+    followee: RostraId,
+}
+
+async fn unfollow(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Json(req): Json<UnfollowRequest>,
+) -> ApiResult<Json<PublishedResponse>> {
+    let id_secret = id_secret(&state)?;
+    let event = state.client.unfollow(id_secret, req.followee).await?;
+    Ok(Json(event.into()))
+}
+
+#[derive(Serialize)]
+struct PostItem {
+    event_id: ShortEventId,
+    author: RostraId,
+    ts: Timestamp,
+    reply_to: Option<ExternalEventId>,
+    /// Djot content, `null` if deleted or a reaction
+    content: Option<String>,
+    reaction: Option<String>,
+    persona_tags: BTreeSet<PersonaTag>,
+    reply_count: u64,
+}
+
+impl From<SocialPostRecord<SocialPost>> for PostItem {
+    fn from(record: SocialPostRecord<SocialPost>) -> Self {
+        let reaction = record.content.get_reaction().map(ToOwned::to_owned);
+        Self {
+            event_id: record.event_id,
+            author: record.author,
+            ts: record.ts,
+            reply_to: record.reply_to,
+            persona_tags: record.content.persona_tags(),
+            content: if reaction.is_some() {
+                None
+            } else {
+                record.content.djot_content
+            },
+            reaction,
+            reply_count: record.reply_count,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LimitQuery {
+    limit: Option<usize>,
+}
+
+impl LimitQuery {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT)
+    }
+}
+
+#[derive(Serialize)]
+struct TimelineResponse {
+    posts: Vec<PostItem>,
+}
+
+async fn get_timeline(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path(timeline): Path<String>,
+    Query(query): Query<LimitQuery>,
+) -> ApiResult<Json<TimelineResponse>> {
+    let timeline = Timeline::ALL
+        .into_iter()
+        .find(|t| t.name().eq_ignore_ascii_case(&timeline))
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Unknown timeline"))?;
+    let posts = timeline
+        .load(state.client.db(), state.client.rostra_id(), query.limit())
+        .await;
+    Ok(Json(TimelineResponse {
+        posts: posts.into_iter().map(PostItem::from).collect(),
+    }))
+}
+
+#[derive(Serialize)]
+struct ThreadResponse {
+    post: PostItem,
+    /// Replies, newest first
+    replies: Vec<PostItem>,
+}
+
+async fn get_thread(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path(event_id): Path<ShortEventId>,
+    Query(query): Query<LimitQuery>,
+) -> ApiResult<Json<ThreadResponse>> {
+    let db = state.client.db();
+    let post = db
+        .get_social_post(event_id)
+        .await
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Post not found"))?;
+    let (replies, _) = db
+        .paginate_social_post_comments_rev(event_id, None, query.limit())
+        .await;
+    Ok(Json(ThreadResponse {
+        post: post.into(),
+        replies: replies.into_iter().map(PostItem::from).collect(),
+    }))
+}
+
+/// A line of the `/events` stream
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamItem {
+    Event {
+        event_id: ShortEventId,
+        author: RostraId,
+        kind: String,
+        ts: Timestamp,
+        /// Content of social posts
+        post: Option<StreamPost>,
+    },
+    /// The subscriber fell behind and missed some events
+    Lagged { missed: u64 },
+}
+
+#[derive(Serialize)]
+struct StreamPost {
+    reply_to: Option<ExternalEventId>,
+    content: Option<String>,
+    reaction: Option<String>,
+    persona_tags: BTreeSet<PersonaTag>,
+}
+
+impl From<&VerifiedEventContent> for StreamItem {
+    fn from(event_content: &VerifiedEventContent) -> Self {
+        let event = &event_content.event.event;
+        let post = (event.kind == EventKind::SOCIAL_POST)
+            .then(|| event_content.deserialize_cbor::<SocialPost>().ok())
+            .flatten()
+            .map(|post| {
+                let reaction = post.get_reaction().map(ToOwned::to_owned);
+                StreamPost {
+                    reply_to: post.reply_to,
+                    persona_tags: post.persona_tags(),
+                    content: if reaction.is_some() {
+                        None
+                    } else {
+                        post.djot_content
+                    },
+                    reaction,
+                }
+            });
+        StreamItem::Event {
+            event_id: event_content.event.event_id.to_short(),
+            author: event.author,
+            kind: event.kind.to_string(),
+            ts: event.timestamp.into(),
+            post,
+        }
+    }
+}
+
+/// Is `event_content` hidden, like in the timelines: by a block of its
+/// author, or for posts, by the content filters
+async fn is_event_hidden(db: &Database, event_content: &VerifiedEventContent) -> bool {
+    let event = &event_content.event.event;
+    if event.kind == EventKind::SOCIAL_POST {
+        if let Ok(post) = event_content.deserialize_cbor::<SocialPost>() {
+            return db.is_social_post_hidden(event.author, &post).await;
+        }
+    }
+    db.is_self_blocked(event.author).await
+}
+
+/// Stream new events as newline-delimited JSON, until the client disconnects
+///
+/// Events of blocked identities and posts hidden by the content filters are
+/// left out.
+async fn subscribe_events(State(state): State<SharedState>, _version: ApiVersion) -> Response {
+    let new_content = state.client.new_content_subscribe();
+    let db = state.client.db().clone();
+    let lines = stream::unfold(new_content, move |mut new_content| {
+        let db = db.clone();
+        async move {
+            let item = loop {
+                match new_content.recv().await {
+                    Ok(event_content) => {
+                        if !is_event_hidden(&db, &event_content).await {
+                            break StreamItem::from(&event_content);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(missed)) => {
+                        break StreamItem::Lagged { missed };
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            };
+            let mut line = serde_json::to_string(&item).expect("Can't fail");
+            line.push('\n');
+            Some((Ok::<_, Infallible>(line), new_content))
+        }
+    });
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(lines),
+    )
+        .into_response()
+}
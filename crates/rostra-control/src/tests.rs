@@ -0,0 +1,258 @@
+use std::os::unix::fs::PermissionsExt as _;
+use std::sync::Arc;
+
+use axum::body::{Body, to_bytes};
+use axum::http::{Request, StatusCode};
+use futures::StreamExt as _;
+use rostra_client::Client;
+use rostra_client_db::Database;
+use rostra_core::event::{Event, SocialPost, VerifiedEvent, VerifiedEventContent};
+use rostra_core::id::RostraIdSecretKey;
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+use tower::ServiceExt as _;
+
+use crate::{API_VERSION_HEADER, ControlState, OwnerOnlyListener, router};
+
+async fn state(secret: RostraIdSecretKey, read_only: bool) -> Arc<ControlState> {
+    let client = Client::builder(secret.id())
+        .db(Database::new_in_memory(secret.id())
+            .await
+            .expect("in-memory database"))
+        .start_request_handler(false)
+        .start_background_tasks(false)
+        .build()
+        .await
+        .expect("test client");
+    Arc::new(ControlState {
+        client,
+        id_secret: (!read_only).then_some(secret),
+    })
+}
+
+fn request(method: &str, uri: &str, body: Option<Value>) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(API_VERSION_HEADER, "0");
+    match body {
+        Some(body) => builder
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => builder.body(Body::empty()),
+    }
+    .expect("valid request")
+}
+
+async fn call(state: &Arc<ControlState>, request: Request<Body>) -> (StatusCode, Value) {
+    let response = router(state.clone())
+        .oneshot(request)
+        .await
+        .expect("infallible");
+    let status = response.status();
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("body");
+    (status, serde_json::from_slice(&body).expect("json body"))
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn publish_and_query_posts() {
+    let secret = RostraIdSecretKey::from_bytes([60; 32]);
+    let state = state(secret, false).await;
+
+    let (status, info) = call(&state, request("GET", "/info", None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(info["read_only"], json!(false));
+    assert_eq!(info["rostra_id"], json!(secret.id().to_string()));
+
+    let (status, published) = call(
+        &state,
+        request("POST", "/posts", Some(json!({ "body": "Hello *local*" }))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let event_id = published["event_id"].as_str().expect("event id").to_owned();
+
+    let reply_to = format!("{}-{event_id}", secret.id());
+    let (status, _) = call(
+        &state,
+        request(
+            "POST",
+            "/posts",
+            Some(json!({ "body": "A reply", "reply_to": reply_to })),
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, thread) = call(&state, request("GET", &format!("/posts/{event_id}"), None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(thread["post"]["content"], json!("Hello *local*"));
+    assert_eq!(thread["replies"][0]["content"], json!("A reply"));
+
+    let followee = RostraIdSecretKey::from_bytes([61; 32]).id();
+    let (status, _) = call(
+        &state,
+        request("POST", "/follow", Some(json!({ "followee": followee }))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(state.client.db().get_followees(secret.id()).await.len(), 1);
+
+    let (status, timeline) = call(&state, request("GET", "/timelines/network?limit=5", None)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(timeline["posts"], json!([]));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn rejects_bad_requests() {
+    let secret = RostraIdSecretKey::from_bytes([62; 32]);
+    let state = state(secret, true).await;
+
+    let unversioned = Request::get("/info").body(Body::empty()).expect("valid");
+    let (status, body) = call(&state, unversioned).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string());
+
+    let (status, _) = call(
+        &state,
+        request("POST", "/posts", Some(json!({ "body": "Not allowed" }))),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, _) = call(&state, request("GET", "/timelines/elsewhere", None)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn streams_new_events() {
+    let secret = RostraIdSecretKey::from_bytes([63; 32]);
+    let state = state(secret, false).await;
+
+    let response = router(state.clone())
+        .oneshot(request("GET", "/events", None))
+        .await
+        .expect("infallible");
+    assert_eq!(response.status(), StatusCode::OK);
+    let mut lines = response.into_body().into_data_stream();
+
+    state
+        .client
+        .social_post(secret, "Streamed".to_owned(), None, Default::default())
+        .await
+        .expect("posted");
+
+    let line = lines.next().await.expect("a line").expect("data");
+    let item: Value = serde_json::from_slice(&line).expect("json line");
+    assert_eq!(item["type"], json!("event"));
+    assert_eq!(item["kind"], json!("social-post"));
+    assert_eq!(item["post"]["content"], json!("Streamed"));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn serves_on_owner_only_socket() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let path = dir.path().join("control.sock");
+    let state = state(RostraIdSecretKey::from_bytes([64; 32]), true).await;
+
+    let listener = OwnerOnlyListener::bind(&path).expect("bound");
+    assert_eq!(
+        std::fs::metadata(&path)
+            .expect("socket file")
+            .permissions()
+            .mode()
+            & 0o777,
+        0o600
+    );
+    tokio::spawn(axum::serve(listener, router(state).into_make_service()).into_future());
+
+    let mut stream = tokio::net::UnixStream::connect(&path)
+        .await
+        .expect("connected");
+    stream
+        .write_all(
+            format!(
+                "GET /info HTTP/1.1\r\nhost: localhost\r\n{API_VERSION_HEADER}: 0\r\nconnection: close\r\n\r\n"
+            )
+            .as_bytes(),
+        )
+        .await
+        .expect("written");
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .await
+        .expect("response");
+    assert!(response.starts_with("HTTP/1.1 200"));
+    assert!(response.contains("\"read_only\":true"));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn replaces_only_stale_sockets() {
+    let dir = tempfile::tempdir().expect("temp dir");
+
+    let path = dir.path().join("control.sock");
+    drop(OwnerOnlyListener::bind(&path).expect("bound"));
+    let live = OwnerOnlyListener::bind(&path).expect("stale socket replaced");
+    assert_eq!(
+        OwnerOnlyListener::bind(&path)
+            .err()
+            .expect("live socket kept")
+            .kind(),
+        std::io::ErrorKind::AddrInUse
+    );
+    std::os::unix::net::UnixStream::connect(&path).expect("live socket still reachable");
+    drop(live);
+
+    let file = dir.path().join("precious.txt");
+    std::fs::write(&file, "keep me").expect("written");
+    assert!(OwnerOnlyListener::bind(&file).is_err());
+    assert_eq!(std::fs::read_to_string(&file).expect("kept"), "keep me");
+
+    let link = dir.path().join("link.sock");
+    std::os::unix::fs::symlink(&file, &link).expect("linked");
+    assert!(OwnerOnlyListener::bind(&link).is_err());
+    assert_eq!(std::fs::read_to_string(&file).expect("kept"), "keep me");
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn events_of_blocked_identities_are_not_streamed() {
+    let secret = RostraIdSecretKey::from_bytes([65; 32]);
+    let state = state(secret, false).await;
+    let blocked = RostraIdSecretKey::from_bytes([66; 32]);
+    let other = RostraIdSecretKey::from_bytes([67; 32]);
+    state
+        .client
+        .block(secret, blocked.id())
+        .await
+        .expect("blocked");
+
+    let response = router(state.clone())
+        .oneshot(request("GET", "/events", None))
+        .await
+        .expect("infallible");
+    let mut lines = response.into_body().into_data_stream();
+
+    for (author, text) in [(blocked, "Blocked"), (other, "Visible")] {
+        let (event, content) =
+            Event::builder(&SocialPost::new(text.to_owned(), None, Default::default()))
+                .author(author.id())
+                .build()
+                .expect("valid post");
+        let event =
+            VerifiedEvent::verify_received_as_is(event.signed_by(author)).expect("event verifies");
+        state
+            .client
+            .db()
+            .process_event_with_content(
+                &VerifiedEventContent::verify(event, content).expect("content verifies"),
+            )
+            .await;
+    }
+
+    let line = lines.next().await.expect("a line").expect("data");
+    let item: Value = serde_json::from_slice(&line).expect("json line");
+    assert_eq!(item["post"]["content"], json!("Visible"));
+}
@@ -425,14 +425,37 @@ impl EventKind {
     // PERSONA_UPDATE (0x12) - not implemented yet
     /// Control: Node Announcement
     pub const NODE_ANNOUNCEMENT: Self = EventKind::from_u16(0x13);
+    /// Control: Block or unblock identity
+    pub const BLOCK: Self = EventKind::from_u16(0x14);
+    /// Control: Announce the identity succeeding the author
+    pub const SUCCESSION: Self = EventKind::from_u16(0x15);
+    /// Control: Authorize (or revoke) a device key to sign on behalf of the
+    /// author
+    pub const DEVICE_DELEGATION: Self = EventKind::from_u16(0x16);
 
     /// Social Post, backbone of the social network
     pub const SOCIAL_POST: Self = EventKind::from_u16(0x20);
     pub const SOCIAL_VOTE: Self = EventKind::from_u16(0x21);
+    /// Repost (boost) of a social post
+    pub const SOCIAL_REPOST: Self = EventKind::from_u16(0x22);
     pub const SOCIAL_PROFILE_UPDATE: Self = EventKind::from_u16(0x24);
     pub const SOCIAL_MEDIA: Self = EventKind::from_u16(0x25);
+    /// Chunk of a media file too large for a single event
+    pub const SOCIAL_MEDIA_CHUNK: Self = EventKind::from_u16(0x26);
+    /// Media file assembled from [`Self::SOCIAL_MEDIA_CHUNK`] events
+    pub const SOCIAL_MEDIA_MANIFEST: Self = EventKind::from_u16(0x27);
+    /// Poll, attached to a social post
+    pub const SOCIAL_POLL: Self = EventKind::from_u16(0x28);
+    /// Vote in a [`Self::SOCIAL_POLL`]
+    pub const SOCIAL_POLL_VOTE: Self = EventKind::from_u16(0x29);
     /// Shoutbox post - simple broadcast message
     pub const SHOUTBOX: Self = EventKind::from_u16(0x30);
+    /// End-to-end encrypted direct message to a single recipient
+    pub const DIRECT_MESSAGE: Self = EventKind::from_u16(0x40);
+    /// Social post encrypted to the audience of its author
+    pub const PRIVATE_SOCIAL_POST: Self = EventKind::from_u16(0x41);
+    /// Audience key of private posts, wrapped for every member
+    pub const PRIVATE_AUDIENCE_KEY: Self = EventKind::from_u16(0x42);
 
     pub const fn from_u16(value: u16) -> Self {
         Self(value.to_be_bytes())
@@ -451,11 +474,22 @@ impl fmt::Display for EventKind {
             Self::FOLLOW => "follow",
             Self::UNFOLLOW => "unfollow",
             Self::NODE_ANNOUNCEMENT => "node-announcement",
+            Self::BLOCK => "block",
+            Self::SUCCESSION => "succession",
+            Self::DEVICE_DELEGATION => "device-delegation",
             Self::SOCIAL_POST => "social-post",
             Self::SOCIAL_VOTE => "social-vote",
+            Self::SOCIAL_REPOST => "social-repost",
             Self::SOCIAL_PROFILE_UPDATE => "social-profile-update",
             Self::SOCIAL_MEDIA => "social-media",
+            Self::SOCIAL_MEDIA_CHUNK => "social-media-chunk",
+            Self::SOCIAL_MEDIA_MANIFEST => "social-media-manifest",
+            Self::SOCIAL_POLL => "social-poll",
+            Self::SOCIAL_POLL_VOTE => "social-poll-vote",
             Self::SHOUTBOX => "shoutbox",
+            Self::DIRECT_MESSAGE => "direct-message",
+            Self::PRIVATE_SOCIAL_POST => "private-social-post",
+            Self::PRIVATE_AUDIENCE_KEY => "private-audience-key",
             v => {
                 f.write_fmt(format_args!("{}", v.as_u16()))?;
                 return Ok(());
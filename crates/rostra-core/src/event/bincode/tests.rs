@@ -28,3 +28,29 @@ fn event_size() {
 
     assert_eq!(signed_encoded.len(), 192);
 }
+
+#[test_log::test]
+fn delegated_signatures_verify_with_their_delegate() {
+    use crate::event::VerifiedEvent;
+
+    let root = RostraIdSecretKey::generate();
+    let device = RostraIdSecretKey::generate();
+
+    let signed = Event::builder_raw_content()
+        .author(root.id())
+        .kind(EventKind::RAW)
+        .content(&EventContentRaw::new(vec![1, 2, 3]))
+        .build()
+        .signed_by(device);
+
+    assert!(VerifiedEvent::verify_received_as_is(signed).is_err());
+    assert!(
+        VerifiedEvent::verify_received_delegated(signed, &[RostraIdSecretKey::generate().id()])
+            .is_err()
+    );
+    let verified = VerifiedEvent::verify_signed_delegated(root.id(), signed, &[device.id()])
+        .expect("delegated signature must verify");
+    assert_eq!(verified.event.author, root.id());
+    // The device can't sign as itself with a delegated author
+    assert!(VerifiedEvent::verify_signed_delegated(device.id(), signed, &[device.id()]).is_err());
+}
@@ -13,9 +13,24 @@ use crate::id::RostraId;
 #[cfg(feature = "serde")]
 use crate::id::ToShort as _;
 use crate::{
-    ExternalEventId, array_type_define, array_type_impl_base32_str, array_type_impl_serde,
+    ExternalEventId, array_type_define, array_type_impl_base32_str, array_type_impl_base64_str,
+    array_type_impl_serde,
 };
 
+#[cfg(all(feature = "ed25519-dalek", feature = "serde"))]
+mod direct_message;
+#[cfg(all(feature = "ed25519-dalek", feature = "serde"))]
+mod private_post;
+#[cfg(feature = "ed25519-dalek")]
+mod sealing;
+#[cfg(all(feature = "ed25519-dalek", feature = "serde"))]
+mod succession;
+
+#[cfg(all(feature = "ed25519-dalek", feature = "serde"))]
+pub use direct_message::*;
+#[cfg(all(feature = "ed25519-dalek", feature = "serde"))]
+pub use private_post::*;
+
 #[derive(Debug, Snafu)]
 #[snafu(display("Content validation error: {public_message}"))]
 pub struct ContentValidationError {
@@ -95,6 +110,103 @@ impl EventContentKind for Follow {
     }
 }
 
+/// Block (or unblock) an identity
+///
+/// Content of blocked identities is neither fetched nor displayed, and they
+/// are excluded from the web of trust.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Block {
+    #[cfg_attr(feature = "serde", serde(rename = "i"))]
+    pub target: RostraId,
+    /// `false` lifts a previous block
+    #[cfg_attr(feature = "serde", serde(rename = "b"))]
+    pub blocked: bool,
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for Block {
+    const KIND: EventKind = EventKind::BLOCK;
+
+    fn singleton_key_aux(&self) -> Option<EventAuxKey> {
+        Some(EventAuxKey::from_bytes(self.target.to_short().to_bytes()))
+    }
+}
+
+/// Authorize a device key to sign events on behalf of the author, or revoke it
+///
+/// Only counts when signed by the author's own (root) key, so a device can't
+/// authorize further devices. Events signed by an authorized device are
+/// accepted as events of the author (see [`super::VerifiedEvent`]), so the
+/// root secret can stay offline. Peers accept them only once they know the
+/// delegation, so it should be published and synced before the device
+/// publishes. Within the same second, a revocation wins over a delegation.
+///
+/// Revoking rejects all events signed by the device that a peer hasn't
+/// accepted yet, whatever their timestamp: timestamps are chosen by the
+/// signer, so a leaked device key could otherwise backdate events to before
+/// the revocation. Peers syncing after the revocation don't get the device's
+/// earlier events, so anything worth keeping should be published again with
+/// another key. A delegation can also expire by itself, which works the same
+/// as a revocation from then on.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DeviceDelegation {
+    #[cfg_attr(feature = "serde", serde(rename = "d"))]
+    pub device: RostraId,
+    /// `true` revokes a previous delegation
+    #[cfg_attr(feature = "serde", serde(rename = "r"))]
+    pub revoked: bool,
+    /// The delegation counts as revoked from this time on, if set
+    ///
+    /// Checked against the clock of the verifying node, as the timestamps of
+    /// the device's events are chosen by the device.
+    #[cfg_attr(feature = "serde", serde(rename = "e", default))]
+    pub expires_at: Option<crate::Timestamp>,
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for DeviceDelegation {
+    const KIND: EventKind = EventKind::DEVICE_DELEGATION;
+
+    fn singleton_key_aux(&self) -> Option<EventAuxKey> {
+        Some(EventAuxKey::from_bytes(self.device.to_short().to_bytes()))
+    }
+}
+
+array_type_define!(
+    /// Signature of the successor identity over a [`Succession`]
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct SuccessionSignature, 64
+);
+array_type_impl_serde!(struct SuccessionSignature, 64);
+array_type_impl_base64_str!(SuccessionSignature);
+
+/// Announcement of the identity succeeding the author, e.g. when moving away
+/// from a key that leaked
+///
+/// Singleton, so only the latest announcement counts. The successor signs the
+/// announcement too (see [`Succession::verify`]), so no identity can be
+/// claimed as a successor without its consent. Followers decide whether to
+/// accept it and move their follow to the successor.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Succession {
+    #[cfg_attr(feature = "serde", serde(rename = "s"))]
+    pub successor: RostraId,
+    #[cfg_attr(feature = "serde", serde(rename = "g"))]
+    pub successor_sig: SuccessionSignature,
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for Succession {
+    const KIND: EventKind = EventKind::SUCCESSION;
+
+    fn singleton_key_aux(&self) -> Option<EventAuxKey> {
+        Some(EventAuxKey::ZERO)
+    }
+}
+
 array_type_define!(
     /// To avoid importing whole iroh to `rostra-core` we define our own type
     /// for `iroh::NodeAddr`
@@ -196,6 +308,9 @@ pub struct SocialPost {
     pub title: Option<String>,
     #[cfg_attr(feature = "serde", serde(rename = "n", default))]
     pub news: bool,
+    /// [`SocialPoll`] event of the same author attached to the post
+    #[cfg_attr(feature = "serde", serde(rename = "o", default))]
+    pub poll: Option<crate::ShortEventId>,
     /// Persona tags for this post
     #[cfg_attr(feature = "serde", serde(rename = "t", default))]
     persona_tags: BTreeSet<PersonaTag>,
@@ -242,6 +357,7 @@ impl SocialPost {
             url: None,
             title: None,
             news: false,
+            poll: None,
             persona_tags,
         }
     }
@@ -313,6 +429,11 @@ impl SocialPost {
         self.news = true;
         self
     }
+
+    pub fn with_poll(mut self, poll: crate::ShortEventId) -> Self {
+        self.poll = Some(poll);
+        self
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -356,6 +477,150 @@ impl EventContentKind for SocialVote {
     }
 }
 
+/// Poll, with a fixed set of options to vote for
+///
+/// Published before the [`SocialPost`] presenting it, which references it in
+/// [`SocialPost::poll`]. Votes are [`SocialPollVote`] events.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SocialPoll {
+    #[cfg_attr(feature = "serde", serde(rename = "q"))]
+    pub question: String,
+    #[cfg_attr(feature = "serde", serde(rename = "o"))]
+    pub options: Vec<String>,
+    /// No votes are accepted after this time, if set
+    ///
+    /// Vote timestamps are claimed by the voters, so clients enforce this
+    /// only when voting.
+    #[cfg_attr(feature = "serde", serde(rename = "c", default))]
+    pub closes_at: Option<crate::Timestamp>,
+}
+
+impl SocialPoll {
+    pub const MAX_OPTIONS: usize = 16;
+    pub const MAX_QUESTION_LEN: usize = 1000;
+    pub const MAX_OPTION_LEN: usize = 200;
+
+    pub fn is_closed(&self, now: crate::Timestamp) -> bool {
+        self.closes_at.is_some_and(|closes_at| closes_at <= now)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for SocialPoll {
+    const KIND: EventKind = EventKind::SOCIAL_POLL;
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if self.question.trim().is_empty() {
+            return Err(ContentValidationError {
+                public_message: "Poll question is empty".into(),
+            });
+        }
+        if Self::MAX_QUESTION_LEN < self.question.len() {
+            return Err(ContentValidationError {
+                public_message: "Poll question too long".into(),
+            });
+        }
+        if self.options.len() < 2 || Self::MAX_OPTIONS < self.options.len() {
+            return Err(ContentValidationError {
+                public_message: format!("Poll needs 2 to {} options", Self::MAX_OPTIONS),
+            });
+        }
+        if self
+            .options
+            .iter()
+            .any(|option| option.trim().is_empty() || Self::MAX_OPTION_LEN < option.len())
+        {
+            return Err(ContentValidationError {
+                public_message: "Poll options must be non-empty and short".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Vote in a [`SocialPoll`]
+///
+/// Singleton per voter and poll, like [`SocialVote`]. A vote without an
+/// `option` retracts the previous one.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SocialPollVote {
+    #[cfg_attr(feature = "serde", serde(rename = "p"))]
+    pub poll: ExternalEventId,
+    /// Index into [`SocialPoll::options`]
+    #[cfg_attr(feature = "serde", serde(rename = "o"))]
+    pub option: Option<u32>,
+}
+
+impl SocialPollVote {
+    pub fn new(poll: ExternalEventId, option: Option<u32>) -> Self {
+        Self { poll, option }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for SocialPollVote {
+    const KIND: EventKind = EventKind::SOCIAL_POLL_VOTE;
+
+    fn singleton_key_aux(&self) -> Option<EventAuxKey> {
+        Some(EventAuxKey::from_bytes(self.poll.event_id().to_bytes()))
+    }
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if self
+            .option
+            .is_some_and(|option| SocialPoll::MAX_OPTIONS <= option as usize)
+        {
+            return Err(ContentValidationError {
+                public_message: "Invalid poll option".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Repost (boost) of a post of anyone, shared with the followers of the
+/// reposter
+///
+/// An optional `comment` turns it into a quote. Undone by deleting the repost
+/// event.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SocialRepost {
+    #[cfg_attr(feature = "serde", serde(rename = "o"))]
+    pub original: ExternalEventId,
+    #[cfg_attr(feature = "serde", serde(rename = "c", default))]
+    pub comment: Option<String>,
+}
+
+impl SocialRepost {
+    pub fn new(original: ExternalEventId, comment: Option<String>) -> Self {
+        Self {
+            original,
+            comment: comment.filter(|comment| !comment.trim().is_empty()),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for SocialRepost {
+    const KIND: EventKind = EventKind::SOCIAL_REPOST;
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if self
+            .comment
+            .as_ref()
+            .is_some_and(|comment| comment.trim().is_empty())
+        {
+            return Err(ContentValidationError {
+                public_message: "Repost comment cannot be empty".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Shoutbox post - simple broadcast message without persona, replies, or
 /// reactions
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
@@ -385,6 +650,259 @@ impl EventContentKind for Shoutbox {
     }
 }
 
+array_type_define!(
+    /// Random nonce making the sealing of every [`DirectMessage`] unique
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct DirectMessageNonce, 24
+);
+array_type_impl_serde!(struct DirectMessageNonce, 24);
+array_type_impl_base64_str!(DirectMessageNonce);
+
+array_type_define!(
+    /// Authentication tag of a [`DirectMessage`] ciphertext
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct DirectMessageMac, 16
+);
+array_type_impl_serde!(struct DirectMessageMac, 16);
+array_type_impl_base64_str!(DirectMessageMac);
+
+/// End-to-end encrypted direct message
+///
+/// Only the recipient is public. The body is a cbor-encoded
+/// [`DirectMessageBody`], encrypted with a key that only the author and the
+/// recipient can derive from their identity keys (X25519 on the ed25519
+/// keys), so both of them can read it back.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirectMessage {
+    #[cfg_attr(feature = "serde", serde(rename = "i"))]
+    pub recipient: RostraId,
+    #[cfg_attr(feature = "serde", serde(rename = "n"))]
+    pub nonce: DirectMessageNonce,
+    #[cfg_attr(feature = "serde", serde(rename = "c", with = "serde_bytes"))]
+    pub ciphertext: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(rename = "m"))]
+    pub mac: DirectMessageMac,
+}
+
+impl DirectMessage {
+    /// Max length of the encrypted body
+    pub const MAX_CIPHERTEXT_LEN: usize = 64 * 1024;
+
+    /// The other party of the conversation, from the point of view of `own_id`
+    ///
+    /// Returns `None` if `own_id` is neither the `author` nor the recipient.
+    pub fn counterparty(&self, author: RostraId, own_id: RostraId) -> Option<RostraId> {
+        if author == own_id {
+            Some(self.recipient)
+        } else if self.recipient == own_id {
+            Some(author)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for DirectMessage {
+    const KIND: EventKind = EventKind::DIRECT_MESSAGE;
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if Self::MAX_CIPHERTEXT_LEN < self.ciphertext.len() {
+            return Err(ContentValidationError {
+                public_message: "Direct message too long".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Decrypted content of a [`DirectMessage`]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DirectMessageBody {
+    #[cfg_attr(feature = "serde", serde(rename = "c"))]
+    pub djot_content: String,
+}
+
+impl DirectMessageBody {
+    pub fn validate(&self) -> ContentValidationResult<()> {
+        // Limit to 10000 characters
+        if 10_000 < self.djot_content.len() {
+            return Err(ContentValidationError {
+                public_message: "Direct message too long (max 10000 characters)".into(),
+            });
+        }
+        if self.djot_content.trim().is_empty() {
+            return Err(ContentValidationError {
+                public_message: "Direct message cannot be empty".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+array_type_define!(
+    /// Identifier of a [`PrivateAudienceKey`], derived from the key itself
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct PrivateAudienceKeyId, 16
+);
+array_type_impl_serde!(struct PrivateAudienceKeyId, 16);
+array_type_impl_base32_str!(PrivateAudienceKeyId);
+
+array_type_define!(
+    /// Random nonce making the sealing of every private payload unique
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct PrivatePostNonce, 24
+);
+array_type_impl_serde!(struct PrivatePostNonce, 24);
+array_type_impl_base64_str!(PrivatePostNonce);
+
+array_type_define!(
+    /// Authentication tag of a sealed private payload
+    #[derive(PartialEq, Eq, PartialOrd, Ord)]
+    struct PrivatePostMac, 16
+);
+array_type_impl_serde!(struct PrivatePostMac, 16);
+array_type_impl_base64_str!(PrivatePostMac);
+
+/// Symmetric key sealing the [`PrivateSocialPost`]s of an audience
+///
+/// Never published in the clear, only wrapped for every member of the
+/// audience in a [`PrivateAudienceKeyGrant`].
+#[cfg_attr(feature = "bincode", derive(::bincode::Encode, ::bincode::Decode))]
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct PrivateAudienceKey([u8; 32]);
+
+impl PrivateAudienceKey {
+    const ID_CONTEXT: &'static str = "rostra private-post 2025-01 audience key id";
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    pub fn id(&self) -> PrivateAudienceKeyId {
+        let hash = blake3::derive_key(Self::ID_CONTEXT, &self.0);
+        let mut id = [0u8; 16];
+        id.copy_from_slice(&hash[..16]);
+        PrivateAudienceKeyId::from_bytes(id)
+    }
+}
+
+impl std::fmt::Debug for PrivateAudienceKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("PrivateAudienceKey")
+            .field(&self.id())
+            .finish()
+    }
+}
+
+/// A [`PrivateAudienceKey`] wrapped for a single member of the audience
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[cfg_attr(feature = "bincode", derive(::bincode::Encode, ::bincode::Decode))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct WrappedAudienceKey {
+    #[cfg_attr(feature = "serde", serde(rename = "i"))]
+    pub recipient: RostraId,
+    #[cfg_attr(feature = "serde", serde(rename = "n"))]
+    pub nonce: PrivatePostNonce,
+    #[cfg_attr(feature = "serde", serde(rename = "c", with = "serde_bytes"))]
+    pub ciphertext: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(rename = "m"))]
+    pub mac: PrivatePostMac,
+}
+
+/// Distribution of a [`PrivateAudienceKey`] to the whole audience
+///
+/// Every grant lists all the members the key is wrapped for, the author
+/// included, so the latest grant of an author describes its current audience.
+/// Members are public; only the key is not.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PrivateAudienceKeyGrant {
+    #[cfg_attr(feature = "serde", serde(rename = "k"))]
+    pub key_id: PrivateAudienceKeyId,
+    #[cfg_attr(feature = "serde", serde(rename = "g"))]
+    pub grants: Vec<WrappedAudienceKey>,
+}
+
+impl PrivateAudienceKeyGrant {
+    /// Max number of members of an audience
+    pub const MAX_MEMBERS: usize = 5_000;
+
+    pub fn members(&self) -> impl Iterator<Item = RostraId> + '_ {
+        self.grants.iter().map(|grant| grant.recipient)
+    }
+
+    pub fn grant_for(&self, id: RostraId) -> Option<&WrappedAudienceKey> {
+        self.grants.iter().find(|grant| grant.recipient == id)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for PrivateAudienceKeyGrant {
+    const KIND: EventKind = EventKind::PRIVATE_AUDIENCE_KEY;
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if self.grants.is_empty() {
+            return Err(ContentValidationError {
+                public_message: "Audience cannot be empty".into(),
+            });
+        }
+        if Self::MAX_MEMBERS < self.grants.len() {
+            return Err(ContentValidationError {
+                public_message: "Audience too large".into(),
+            });
+        }
+        if self.grants.iter().any(|grant| grant.ciphertext.len() != 32) {
+            return Err(ContentValidationError {
+                public_message: "Invalid wrapped audience key".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A [`SocialPost`] readable only by the audience of its author
+///
+/// The body is a cbor-encoded [`SocialPost`], sealed with the
+/// [`PrivateAudienceKey`] identified by `key_id`.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct PrivateSocialPost {
+    #[cfg_attr(feature = "serde", serde(rename = "k"))]
+    pub key_id: PrivateAudienceKeyId,
+    #[cfg_attr(feature = "serde", serde(rename = "n"))]
+    pub nonce: PrivatePostNonce,
+    #[cfg_attr(feature = "serde", serde(rename = "c", with = "serde_bytes"))]
+    pub ciphertext: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(rename = "m"))]
+    pub mac: PrivatePostMac,
+}
+
+impl PrivateSocialPost {
+    /// Max length of the encrypted body
+    pub const MAX_CIPHERTEXT_LEN: usize = 64 * 1024;
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for PrivateSocialPost {
+    const KIND: EventKind = EventKind::PRIVATE_SOCIAL_POST;
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if Self::MAX_CIPHERTEXT_LEN < self.ciphertext.len() {
+            return Err(ContentValidationError {
+                public_message: "Private post too long".into(),
+            });
+        }
+        Ok(())
+    }
+}
+
 /// A piece of media (like an image, or a video)
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
@@ -436,6 +954,142 @@ impl EventContentKind for SocialMedia {
     }
 }
 
+/// A piece of a media file published as a [`SocialMediaManifest`]
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SocialMediaChunk {
+    /// Binary content of the chunk
+    #[cfg_attr(feature = "serde", serde(rename = "d", with = "serde_bytes"))]
+    pub data: Vec<u8>,
+}
+
+impl SocialMediaChunk {
+    /// Max size of a single chunk
+    ///
+    /// Well below the content size limits of the database and the network,
+    /// so a chunk is stored and transferred like any other content.
+    pub const MAX_LEN: usize = 4 * 1024 * 1024;
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for SocialMediaChunk {
+    const KIND: EventKind = EventKind::SOCIAL_MEDIA_CHUNK;
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if self.data.is_empty() {
+            return Err(ContentValidationError {
+                public_message: "Chunk is empty".into(),
+            });
+        }
+
+        if Self::MAX_LEN < self.data.len() {
+            return Err(ContentValidationError {
+                public_message: "Chunk too large".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// A media file too large for a single [`SocialMedia`] event
+///
+/// The data is split into [`SocialMediaChunk`] events of `chunk_size` bytes
+/// (the last one possibly shorter), published before the manifest, so they
+/// replicate along with the rest of the author's events.
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct SocialMediaManifest {
+    /// Mime type of the whole file
+    #[cfg_attr(feature = "serde", serde(rename = "m"))]
+    pub mime: String,
+    /// Length of the whole file
+    #[cfg_attr(feature = "serde", serde(rename = "l"))]
+    pub len: u64,
+    /// Length of every chunk but the last one
+    #[cfg_attr(feature = "serde", serde(rename = "s"))]
+    pub chunk_size: u32,
+    /// Chunk events, in order
+    #[cfg_attr(feature = "serde", serde(rename = "c"))]
+    pub chunks: Vec<crate::ShortEventId>,
+}
+
+impl SocialMediaManifest {
+    /// Max size of the whole file
+    pub const MAX_LEN: u64 = 2 * 1024 * 1024 * 1024;
+
+    /// Byte range of the file covered by the chunk at `index`
+    pub fn chunk_range(&self, index: usize) -> std::ops::Range<u64> {
+        let chunk_size = u64::from(self.chunk_size);
+        let start = index as u64 * chunk_size;
+        start..(start + chunk_size).min(self.len)
+    }
+
+    /// Indices of the chunks overlapping the byte `range`
+    pub fn chunks_in(&self, range: std::ops::Range<u64>) -> std::ops::Range<usize> {
+        let chunk_size = u64::from(self.chunk_size);
+        if range.is_empty() || chunk_size == 0 {
+            return 0..0;
+        }
+        let end = range.end.min(self.len);
+        if end <= range.start {
+            return 0..0;
+        }
+        (range.start / chunk_size) as usize..end.div_ceil(chunk_size) as usize
+    }
+}
+
+#[cfg(feature = "serde")]
+impl EventContentKind for SocialMediaManifest {
+    const KIND: EventKind = EventKind::SOCIAL_MEDIA_MANIFEST;
+
+    fn singleton_key_aux(&self) -> Option<EventAuxKey> {
+        // Like `SocialMedia`, keyed by the content, here identified by the chunks
+        let mut hasher = blake3::Hasher::new();
+        for chunk in &self.chunks {
+            hasher.update(&chunk.to_bytes());
+        }
+        let hash = hasher.finalize();
+        let mut key_bytes = [0u8; 16];
+        key_bytes.copy_from_slice(&hash.as_bytes()[..16]);
+        Some(EventAuxKey::from_bytes(key_bytes))
+    }
+
+    fn validate(&self) -> ContentValidationResult<()> {
+        if 100 < self.mime.len() {
+            return Err(ContentValidationError {
+                public_message: "MIME type too long".into(),
+            });
+        }
+
+        if self.len == 0 {
+            return Err(ContentValidationError {
+                public_message: "File is empty".into(),
+            });
+        }
+
+        if Self::MAX_LEN < self.len {
+            return Err(ContentValidationError {
+                public_message: "File too large (max 2GB)".into(),
+            });
+        }
+
+        if self.chunk_size == 0 || SocialMediaChunk::MAX_LEN < self.chunk_size as usize {
+            return Err(ContentValidationError {
+                public_message: "Invalid chunk size".into(),
+            });
+        }
+
+        if self.len.div_ceil(u64::from(self.chunk_size)) != self.chunks.len() as u64 {
+            return Err(ContentValidationError {
+                public_message: "Chunk count does not match file length".into(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 pub struct SocialProfileUpdate {
@@ -0,0 +1,116 @@
+//! Encryption of [`DirectMessage`]s
+//!
+//! Both parties derive the same key with X25519 between their own ed25519
+//! identity key and the other party's public key, so a message can be read by
+//! its recipient and by its author, from any of their nodes. See
+//! [`super::sealing`] for how the body is sealed with it.
+
+use std::convert::Infallible;
+
+use snafu::{OptionExt as _, ResultExt as _, Snafu, ensure};
+
+use super::sealing;
+use super::{
+    ContentValidationError, DirectMessage, DirectMessageBody, DirectMessageMac, DirectMessageNonce,
+};
+use crate::id::{RostraId, RostraIdSecretKey};
+
+const SHARED_KEY_CONTEXT: &str = "rostra direct-message 2025-01 shared key";
+const SEAL_CONTEXT: &str = "rostra direct-message 2025-01 seal key";
+
+#[derive(Debug, Snafu)]
+pub enum DirectMessageError {
+    /// The counterparty key does not allow a key exchange
+    InvalidKey,
+    /// Not sealed between these two identities, or tampered with
+    Authentication,
+    Decoding {
+        source: cbor4ii::serde::DecodeError<Infallible>,
+    },
+    #[snafu(transparent)]
+    Validation { source: ContentValidationError },
+}
+
+pub type DirectMessageResult<T> = std::result::Result<T, DirectMessageError>;
+
+impl DirectMessage {
+    /// Encrypt `body` from the owner of `secret` to `recipient`
+    #[cfg(feature = "rand")]
+    pub fn seal(
+        secret: RostraIdSecretKey,
+        recipient: RostraId,
+        body: &DirectMessageBody,
+    ) -> DirectMessageResult<Self> {
+        use rand::Rng as _;
+
+        let mut nonce = [0u8; 24];
+        rand::rng().fill(&mut nonce);
+        Self::seal_with_nonce(
+            secret,
+            recipient,
+            DirectMessageNonce::from_bytes(nonce),
+            body,
+        )
+    }
+
+    /// Like [`Self::seal`], with a given `nonce`
+    ///
+    /// The nonce must never be reused between the same two identities.
+    pub fn seal_with_nonce(
+        secret: RostraIdSecretKey,
+        recipient: RostraId,
+        nonce: DirectMessageNonce,
+        body: &DirectMessageBody,
+    ) -> DirectMessageResult<Self> {
+        body.validate()?;
+        let mut ciphertext = Vec::with_capacity(128);
+        cbor4ii::serde::to_writer(&mut ciphertext, body).expect("Can't fail");
+
+        let key = sealing::pairwise_key(secret, recipient, SHARED_KEY_CONTEXT)
+            .context(InvalidKeySnafu)?;
+        let mac = DirectMessageMac::from_bytes(sealing::seal(
+            &key,
+            &nonce.to_bytes(),
+            SEAL_CONTEXT,
+            &mut ciphertext,
+        ));
+
+        Ok(Self {
+            recipient,
+            nonce,
+            ciphertext,
+            mac,
+        })
+    }
+
+    /// Decrypt the message with the `secret` of one of the parties
+    ///
+    /// `counterparty` is the other party: the author of the event when opening
+    /// a received message, or [`Self::recipient`] when opening a sent one.
+    pub fn open(
+        &self,
+        secret: RostraIdSecretKey,
+        counterparty: RostraId,
+    ) -> DirectMessageResult<DirectMessageBody> {
+        let key = sealing::pairwise_key(secret, counterparty, SHARED_KEY_CONTEXT)
+            .context(InvalidKeySnafu)?;
+        let mut plaintext = self.ciphertext.clone();
+        ensure!(
+            sealing::open(
+                &key,
+                &self.nonce.to_bytes(),
+                SEAL_CONTEXT,
+                &mut plaintext,
+                self.mac.to_bytes()
+            ),
+            AuthenticationSnafu
+        );
+        let body: DirectMessageBody =
+            cbor4ii::serde::from_slice(&plaintext).context(DecodingSnafu)?;
+        body.validate()?;
+        Ok(body)
+    }
+}
+
+#[cfg(test)]
+mod tests;
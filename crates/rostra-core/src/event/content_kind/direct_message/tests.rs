@@ -0,0 +1,102 @@
+use super::{DirectMessage, DirectMessageBody, DirectMessageError, DirectMessageNonce};
+use crate::id::{RostraId, RostraIdSecretKey};
+
+fn body(text: &str) -> DirectMessageBody {
+    DirectMessageBody {
+        djot_content: text.to_owned(),
+    }
+}
+
+#[test]
+fn direct_message_opens_for_both_parties_only() {
+    let alice = RostraIdSecretKey::from_bytes([1; 32]);
+    let bob = RostraIdSecretKey::from_bytes([2; 32]);
+    let eve = RostraIdSecretKey::from_bytes([3; 32]);
+
+    let msg = DirectMessage::seal_with_nonce(
+        alice,
+        bob.id(),
+        DirectMessageNonce::from_bytes([7; 24]),
+        &body("Hi *Bob*"),
+    )
+    .expect("can seal");
+
+    assert_eq!(msg.recipient, bob.id());
+    assert_eq!(
+        msg.open(bob, alice.id()).expect("bob can open"),
+        body("Hi *Bob*")
+    );
+    assert_eq!(
+        msg.open(alice, bob.id()).expect("alice can open"),
+        body("Hi *Bob*")
+    );
+    assert!(matches!(
+        msg.open(eve, alice.id()),
+        Err(DirectMessageError::Authentication)
+    ));
+    assert_eq!(msg.counterparty(alice.id(), bob.id()), Some(alice.id()));
+    assert_eq!(msg.counterparty(alice.id(), alice.id()), Some(bob.id()));
+    assert_eq!(msg.counterparty(alice.id(), eve.id()), None);
+}
+
+#[test]
+fn direct_message_rejects_tampering() {
+    let alice = RostraIdSecretKey::from_bytes([1; 32]);
+    let bob = RostraIdSecretKey::from_bytes([2; 32]);
+    let msg = DirectMessage::seal_with_nonce(
+        alice,
+        bob.id(),
+        DirectMessageNonce::from_bytes([7; 24]),
+        &body("Meet at noon"),
+    )
+    .expect("can seal");
+
+    let mut flipped = msg.clone();
+    flipped.ciphertext[0] ^= 1;
+    assert!(matches!(
+        flipped.open(bob, alice.id()),
+        Err(DirectMessageError::Authentication)
+    ));
+
+    let mut renonced = msg.clone();
+    renonced.nonce = DirectMessageNonce::from_bytes([8; 24]);
+    assert!(matches!(
+        renonced.open(bob, alice.id()),
+        Err(DirectMessageError::Authentication)
+    ));
+
+    let other = DirectMessage::seal_with_nonce(
+        alice,
+        bob.id(),
+        DirectMessageNonce::from_bytes([8; 24]),
+        &body("Meet at noon"),
+    )
+    .expect("can seal");
+    assert_ne!(msg.ciphertext, other.ciphertext);
+
+    assert!(matches!(
+        DirectMessage::seal_with_nonce(alice, bob.id(), DirectMessageNonce::ZERO, &body("  ")),
+        Err(DirectMessageError::Validation { .. })
+    ));
+}
+
+#[test]
+fn direct_message_rejects_invalid_point_ids() {
+    let alice = RostraIdSecretKey::from_bytes([1; 32]);
+    // Not the encoding of a curve point
+    let invalid = RostraId::from_bytes([2; 32]);
+
+    assert!(matches!(
+        DirectMessage::seal_with_nonce(alice, invalid, DirectMessageNonce::ZERO, &body("Hello?")),
+        Err(DirectMessageError::InvalidKey)
+    ));
+
+    let bob = RostraIdSecretKey::from_bytes([3; 32]);
+    let msg =
+        DirectMessage::seal_with_nonce(alice, bob.id(), DirectMessageNonce::ZERO, &body("Hello"))
+            .expect("can seal");
+    assert!(matches!(
+        msg.open(bob, invalid),
+        Err(DirectMessageError::InvalidKey)
+    ));
+}
@@ -0,0 +1,211 @@
+//! Encryption of [`PrivateSocialPost`]s
+//!
+//! Private posts are sealed with a [`PrivateAudienceKey`] of their author. The
+//! key reaches the audience in a [`PrivateAudienceKeyGrant`], wrapped for every
+//! member with the key the author shares with that member (like a
+//! [`super::DirectMessage`]). Removing a member requires a new key, so it can't
+//! read the posts published afterwards.
+
+use std::convert::Infallible;
+
+use snafu::{OptionExt as _, ResultExt as _, Snafu, ensure};
+
+use super::sealing;
+use super::{
+    ContentValidationError, EventContentKind as _, PrivateAudienceKey, PrivateAudienceKeyGrant,
+    PrivatePostMac, PrivatePostNonce, PrivateSocialPost, SocialPost, WrappedAudienceKey,
+};
+use crate::id::{RostraId, RostraIdSecretKey};
+
+const WRAPPING_KEY_CONTEXT: &str = "rostra private-post 2025-01 wrapping key";
+const WRAPPING_SEAL_CONTEXT: &str = "rostra private-post 2025-01 wrapping seal key";
+const POST_SEAL_CONTEXT: &str = "rostra private-post 2025-01 seal key";
+
+#[derive(Debug, Snafu)]
+pub enum PrivatePostError {
+    /// The member key does not allow a key exchange
+    InvalidKey,
+    /// Not sealed with this key, or tampered with
+    Authentication,
+    Decoding {
+        source: cbor4ii::serde::DecodeError<Infallible>,
+    },
+    #[snafu(transparent)]
+    Validation { source: ContentValidationError },
+}
+
+pub type PrivatePostResult<T> = std::result::Result<T, PrivatePostError>;
+
+#[cfg(feature = "rand")]
+fn random_nonce() -> PrivatePostNonce {
+    use rand::Rng as _;
+
+    let mut nonce = [0u8; 24];
+    rand::rng().fill(&mut nonce);
+    PrivatePostNonce::from_bytes(nonce)
+}
+
+impl PrivateAudienceKey {
+    #[cfg(feature = "rand")]
+    pub fn generate() -> Self {
+        use rand::Rng as _;
+
+        let mut key = [0u8; 32];
+        rand::rng().fill(&mut key);
+        Self::from_bytes(key)
+    }
+}
+
+impl WrappedAudienceKey {
+    /// Wrap `key` from the owner of `secret` for `recipient`
+    ///
+    /// The nonce must never be reused between the same two identities.
+    pub fn wrap(
+        secret: RostraIdSecretKey,
+        recipient: RostraId,
+        nonce: PrivatePostNonce,
+        key: PrivateAudienceKey,
+    ) -> PrivatePostResult<Self> {
+        let wrapping_key = sealing::pairwise_key(secret, recipient, WRAPPING_KEY_CONTEXT)
+            .context(InvalidKeySnafu)?;
+        let mut ciphertext = key.to_bytes().to_vec();
+        let mac = PrivatePostMac::from_bytes(sealing::seal(
+            &wrapping_key,
+            &nonce.to_bytes(),
+            WRAPPING_SEAL_CONTEXT,
+            &mut ciphertext,
+        ));
+        Ok(Self {
+            recipient,
+            nonce,
+            ciphertext,
+            mac,
+        })
+    }
+
+    /// Unwrap the key with the `secret` of [`Self::recipient`], wrapped by
+    /// `author`
+    pub fn unwrap(
+        &self,
+        secret: RostraIdSecretKey,
+        author: RostraId,
+    ) -> PrivatePostResult<PrivateAudienceKey> {
+        let wrapping_key =
+            sealing::pairwise_key(secret, author, WRAPPING_KEY_CONTEXT).context(InvalidKeySnafu)?;
+        let mut plaintext = self.ciphertext.clone();
+        ensure!(
+            sealing::open(
+                &wrapping_key,
+                &self.nonce.to_bytes(),
+                WRAPPING_SEAL_CONTEXT,
+                &mut plaintext,
+                self.mac.to_bytes()
+            ),
+            AuthenticationSnafu
+        );
+        let key = <[u8; 32]>::try_from(plaintext.as_slice())
+            .ok()
+            .context(AuthenticationSnafu)?;
+        Ok(PrivateAudienceKey::from_bytes(key))
+    }
+}
+
+impl PrivateAudienceKeyGrant {
+    /// Wrap `key` from the owner of `secret` for every one of `members`
+    ///
+    /// The author itself is always added to the members, so all its nodes can
+    /// read its own private posts.
+    #[cfg(feature = "rand")]
+    pub fn new(
+        secret: RostraIdSecretKey,
+        key: PrivateAudienceKey,
+        members: impl IntoIterator<Item = RostraId>,
+    ) -> PrivatePostResult<Self> {
+        let mut members: Vec<_> = members.into_iter().collect();
+        members.push(secret.id());
+        members.sort_unstable();
+        members.dedup();
+
+        let grant = Self {
+            key_id: key.id(),
+            grants: members
+                .into_iter()
+                .map(|member| WrappedAudienceKey::wrap(secret, member, random_nonce(), key))
+                .collect::<PrivatePostResult<_>>()?,
+        };
+        grant.validate()?;
+        Ok(grant)
+    }
+
+    /// Unwrap the key granted to the owner of `secret` by `author`, if any
+    pub fn unwrap_for(
+        &self,
+        secret: RostraIdSecretKey,
+        author: RostraId,
+    ) -> PrivatePostResult<Option<PrivateAudienceKey>> {
+        let Some(grant) = self.grant_for(secret.id()) else {
+            return Ok(None);
+        };
+        let key = grant.unwrap(secret, author)?;
+        ensure!(key.id() == self.key_id, AuthenticationSnafu);
+        Ok(Some(key))
+    }
+}
+
+impl PrivateSocialPost {
+    /// Encrypt `post` with the audience `key`
+    #[cfg(feature = "rand")]
+    pub fn seal(key: PrivateAudienceKey, post: &SocialPost) -> PrivatePostResult<Self> {
+        Self::seal_with_nonce(key, random_nonce(), post)
+    }
+
+    /// Like [`Self::seal`], with a given `nonce`
+    ///
+    /// The nonce must never be reused with the same key.
+    pub fn seal_with_nonce(
+        key: PrivateAudienceKey,
+        nonce: PrivatePostNonce,
+        post: &SocialPost,
+    ) -> PrivatePostResult<Self> {
+        post.validate()?;
+        let mut ciphertext = Vec::with_capacity(128);
+        cbor4ii::serde::to_writer(&mut ciphertext, post).expect("Can't fail");
+
+        let mac = PrivatePostMac::from_bytes(sealing::seal(
+            &key.to_bytes(),
+            &nonce.to_bytes(),
+            POST_SEAL_CONTEXT,
+            &mut ciphertext,
+        ));
+        let sealed = Self {
+            key_id: key.id(),
+            nonce,
+            ciphertext,
+            mac,
+        };
+        sealed.validate()?;
+        Ok(sealed)
+    }
+
+    /// Decrypt the post with the audience `key` it was sealed with
+    pub fn open(&self, key: PrivateAudienceKey) -> PrivatePostResult<SocialPost> {
+        ensure!(key.id() == self.key_id, AuthenticationSnafu);
+        let mut plaintext = self.ciphertext.clone();
+        ensure!(
+            sealing::open(
+                &key.to_bytes(),
+                &self.nonce.to_bytes(),
+                POST_SEAL_CONTEXT,
+                &mut plaintext,
+                self.mac.to_bytes()
+            ),
+            AuthenticationSnafu
+        );
+        let post: SocialPost = cbor4ii::serde::from_slice(&plaintext).context(DecodingSnafu)?;
+        post.validate()?;
+        Ok(post)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,93 @@
+use std::collections::BTreeSet;
+
+use super::{
+    PrivateAudienceKey, PrivateAudienceKeyGrant, PrivatePostError, PrivatePostNonce,
+    PrivateSocialPost, SocialPost, WrappedAudienceKey,
+};
+use crate::id::RostraIdSecretKey;
+
+fn post(text: &str) -> SocialPost {
+    SocialPost::new(text.to_owned(), None, BTreeSet::new())
+}
+
+#[test]
+fn audience_key_unwraps_for_members_only() {
+    let alice = RostraIdSecretKey::from_bytes([1; 32]);
+    let bob = RostraIdSecretKey::from_bytes([2; 32]);
+    let eve = RostraIdSecretKey::from_bytes([3; 32]);
+    let key = PrivateAudienceKey::from_bytes([9; 32]);
+
+    let grant = PrivateAudienceKeyGrant {
+        key_id: key.id(),
+        grants: vec![
+            WrappedAudienceKey::wrap(
+                alice,
+                alice.id(),
+                PrivatePostNonce::from_bytes([1; 24]),
+                key,
+            )
+            .expect("can wrap"),
+            WrappedAudienceKey::wrap(alice, bob.id(), PrivatePostNonce::from_bytes([2; 24]), key)
+                .expect("can wrap"),
+        ],
+    };
+
+    assert_eq!(
+        grant.unwrap_for(bob, alice.id()).expect("bob can unwrap"),
+        Some(key)
+    );
+    assert_eq!(
+        grant
+            .unwrap_for(alice, alice.id())
+            .expect("alice can unwrap"),
+        Some(key)
+    );
+    assert_eq!(
+        grant.unwrap_for(eve, alice.id()).expect("not a member"),
+        None
+    );
+
+    // A grant attributed to the wrong author does not open
+    assert!(matches!(
+        grant.unwrap_for(bob, eve.id()),
+        Err(PrivatePostError::Authentication)
+    ));
+
+    // A grant can't smuggle a key with a different id
+    let mut mislabeled = grant.clone();
+    mislabeled.key_id = PrivateAudienceKey::from_bytes([10; 32]).id();
+    assert!(matches!(
+        mislabeled.unwrap_for(bob, alice.id()),
+        Err(PrivatePostError::Authentication)
+    ));
+}
+
+#[test]
+fn private_post_opens_with_its_key_only() {
+    let key = PrivateAudienceKey::from_bytes([9; 32]);
+    let other_key = PrivateAudienceKey::from_bytes([10; 32]);
+
+    let sealed = PrivateSocialPost::seal_with_nonce(
+        key,
+        PrivatePostNonce::from_bytes([7; 24]),
+        &post("Only for *friends*"),
+    )
+    .expect("can seal");
+
+    assert_eq!(sealed.key_id, key.id());
+    assert_eq!(
+        sealed.open(key).expect("can open"),
+        post("Only for *friends*")
+    );
+    assert!(matches!(
+        sealed.open(other_key),
+        Err(PrivatePostError::Authentication)
+    ));
+
+    let mut flipped = sealed.clone();
+    flipped.ciphertext[0] ^= 1;
+    assert!(matches!(
+        flipped.open(key),
+        Err(PrivatePostError::Authentication)
+    ));
+}
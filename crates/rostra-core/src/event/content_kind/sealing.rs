@@ -0,0 +1,81 @@
+//! Symmetric sealing shared by the encrypted content kinds
+//!
+//! Payloads are sealed with XChaCha20-Poly1305 and a per-payload random
+//! nonce, under a key derived from a 32 byte key for every kind of payload.
+
+use chacha20poly1305::aead::AeadInPlace as _;
+use chacha20poly1305::{KeyInit as _, Tag, XChaCha20Poly1305, XNonce};
+use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use crate::id::{RostraId, RostraIdSecretKey};
+
+/// Length of the authentication tag of sealed payloads
+pub(super) const TAG_LEN: usize = 16;
+
+/// Key shared by the owner of `secret` and `counterparty`
+///
+/// Both parties derive the same key with X25519 between their own ed25519
+/// identity key and the other party's public key. Returns `None` if
+/// `counterparty` does not allow a key exchange.
+pub(super) fn pairwise_key(
+    secret: RostraIdSecretKey,
+    counterparty: RostraId,
+    context: &'static str,
+) -> Option<[u8; 32]> {
+    let own_id = secret.id();
+    let shared_point = VerifyingKey::from_bytes(&counterparty.to_bytes())
+        .ok()?
+        .to_montgomery()
+        .mul_clamped(SigningKey::from(secret).to_scalar_bytes());
+    // Low order points would make the shared secret predictable
+    if shared_point.to_bytes() == [0u8; 32] {
+        return None;
+    }
+
+    let (first, second) = if own_id < counterparty {
+        (own_id, counterparty)
+    } else {
+        (counterparty, own_id)
+    };
+    Some(
+        *blake3::Hasher::new_derive_key(context)
+            .update(shared_point.as_bytes())
+            .update(first.as_slice())
+            .update(second.as_slice())
+            .finalize()
+            .as_bytes(),
+    )
+}
+
+/// Encrypt `data` in place, returning its authentication tag
+pub(super) fn seal(
+    key: &[u8; 32],
+    nonce: &[u8; 24],
+    context: &'static str,
+    data: &mut [u8],
+) -> [u8; TAG_LEN] {
+    cipher(key, context)
+        .encrypt_in_place_detached(XNonce::from_slice(nonce), &[], data)
+        .expect("Payloads are far below the XChaCha20 limit")
+        .into()
+}
+
+/// Decrypt `data` in place, if `tag` authenticates it
+///
+/// Returns `false`, leaving `data` untouched, if authentication failed.
+pub(super) fn open(
+    key: &[u8; 32],
+    nonce: &[u8; 24],
+    context: &'static str,
+    data: &mut [u8],
+    tag: [u8; TAG_LEN],
+) -> bool {
+    cipher(key, context)
+        .decrypt_in_place_detached(XNonce::from_slice(nonce), &[], data, Tag::from_slice(&tag))
+        .is_ok()
+}
+
+/// Cipher of one kind of payload sealed with `key`
+fn cipher(key: &[u8; 32], context: &'static str) -> XChaCha20Poly1305 {
+    XChaCha20Poly1305::new(&blake3::derive_key(context, key).into())
+}
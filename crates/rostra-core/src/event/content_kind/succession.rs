@@ -0,0 +1,50 @@
+//! Signing of [`Succession`]s by the successor
+//!
+//! The successor signs the predecessor and successor ids under a dedicated
+//! context, so the signature can't be mistaken for an event signature and only
+//! ever vouches for one predecessor.
+
+use ed25519_dalek::{Signer as _, SigningKey, VerifyingKey};
+
+use super::{Succession, SuccessionSignature};
+use crate::id::{RostraId, RostraIdSecretKey};
+
+const SIGNATURE_CONTEXT: &[u8] = b"rostra succession 2026-10";
+
+fn signed_message(predecessor: RostraId, successor: RostraId) -> Vec<u8> {
+    [
+        SIGNATURE_CONTEXT,
+        predecessor.as_slice(),
+        successor.as_slice(),
+    ]
+    .concat()
+}
+
+impl Succession {
+    /// Announce the owner of `successor` as the successor of `predecessor`
+    pub fn new(predecessor: RostraId, successor: RostraIdSecretKey) -> Self {
+        let successor_id = successor.id();
+        let sig = SigningKey::from(successor).sign(&signed_message(predecessor, successor_id));
+        Self {
+            successor: successor_id,
+            successor_sig: SuccessionSignature::from_bytes(sig.to_bytes()),
+        }
+    }
+
+    /// Whether the successor consented to succeed `predecessor`
+    ///
+    /// Identities can't succeed themselves.
+    pub fn verify(&self, predecessor: RostraId) -> bool {
+        if predecessor == self.successor {
+            return false;
+        }
+        let Ok(key) = VerifyingKey::from_bytes(&self.successor.to_bytes()) else {
+            return false;
+        };
+        key.verify_strict(
+            &signed_message(predecessor, self.successor),
+            &ed25519_dalek::Signature::from_bytes(&self.successor_sig.to_bytes()),
+        )
+        .is_ok()
+    }
+}
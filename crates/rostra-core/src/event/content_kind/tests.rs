@@ -32,3 +32,90 @@ fn sanity_check_node_annocment() {
     let ann = NodeAnnouncement::Iroh { addr: node_id };
     round_trip(ann);
 }
+
+#[test]
+fn media_manifest_chunk_ranges() {
+    let manifest = super::SocialMediaManifest {
+        mime: "video/mp4".into(),
+        len: 10,
+        chunk_size: 4,
+        chunks: vec![crate::ShortEventId::ZERO; 3],
+    };
+
+    assert_eq!(manifest.chunk_range(0), 0..4);
+    assert_eq!(manifest.chunk_range(2), 8..10);
+    assert_eq!(manifest.chunks_in(0..10), 0..3);
+    assert_eq!(manifest.chunks_in(3..5), 0..2);
+    assert_eq!(manifest.chunks_in(4..8), 1..2);
+    assert_eq!(manifest.chunks_in(9..100), 2..3);
+    assert!(manifest.chunks_in(10..12).is_empty());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn social_poll_validation() {
+    use super::{EventContentKind as _, SocialPoll, SocialPollVote};
+
+    let poll = SocialPoll {
+        question: "Lunch?".into(),
+        options: vec!["Pizza".into(), "Sushi".into()],
+        closes_at: Some(crate::Timestamp::from(100)),
+    };
+    assert!(poll.validate().is_ok());
+    assert!(!poll.is_closed(crate::Timestamp::from(99)));
+    assert!(poll.is_closed(crate::Timestamp::from(100)));
+    round_trip(poll.clone());
+
+    for options in [
+        vec!["Pizza".into()],
+        vec!["Pizza".into(), " ".into()],
+        vec!["Pizza".into(); SocialPoll::MAX_OPTIONS + 1],
+    ] {
+        assert!(
+            SocialPoll {
+                options,
+                ..poll.clone()
+            }
+            .validate()
+            .is_err()
+        );
+    }
+
+    let poll_id = crate::ExternalEventId::new(
+        crate::id::RostraId::from_bytes([1; 32]),
+        crate::ShortEventId::ZERO,
+    );
+    assert!(SocialPollVote::new(poll_id, Some(1)).validate().is_ok());
+    assert!(SocialPollVote::new(poll_id, None).validate().is_ok());
+    assert!(
+        SocialPollVote::new(poll_id, Some(SocialPoll::MAX_OPTIONS as u32))
+            .validate()
+            .is_err()
+    );
+}
+
+#[cfg(all(feature = "ed25519-dalek", feature = "serde", feature = "rand"))]
+#[test]
+fn succession_is_signed_by_successor() {
+    use super::Succession;
+    use crate::id::RostraIdSecretKey;
+
+    let predecessor = RostraIdSecretKey::generate().id();
+    let successor = RostraIdSecretKey::generate();
+
+    let succession = Succession::new(predecessor, successor);
+    assert_eq!(succession.successor, successor.id());
+    assert!(succession.verify(predecessor));
+    assert!(!succession.verify(RostraIdSecretKey::generate().id()));
+    round_trip(succession.clone());
+
+    // Claiming someone else as successor
+    let claimed = Succession {
+        successor: RostraIdSecretKey::generate().id(),
+        ..succession
+    };
+    assert!(!claimed.verify(predecessor));
+
+    let own = Succession::new(successor.id(), successor);
+    assert!(!own.verify(successor.id()));
+}
@@ -22,10 +22,29 @@ impl Event {
     }
 
     pub fn verify_signature(&self, sig: EventSignature) -> Result<(), SignatureError> {
+        self.verify_signature_delegated(sig, &[])
+    }
+
+    /// Verify `sig` was made by the author or by one of its `delegates`
+    pub fn verify_signature_delegated(
+        &self,
+        sig: EventSignature,
+        delegates: &[RostraId],
+    ) -> Result<(), SignatureError> {
         let encoded = bincode::encode_to_vec(self, crate::bincode::STD_BINCODE_CONFIG)
             .expect("Can't fail to encode");
 
-        Self::verify_signature_raw(&encoded, sig, self.author)
+        let res = Self::verify_signature_raw(&encoded, sig, self.author);
+        if res.is_ok()
+            || delegates.iter().any(|delegate| {
+                // Delegates come from event content, so don't assume they are valid keys
+                VerifyingKey::from_bytes(&delegate.to_bytes())
+                    .is_ok_and(|key| key.verify_strict(&encoded, &sig.into()).is_ok())
+            })
+        {
+            return Ok(());
+        }
+        res
     }
 }
 
@@ -11,7 +11,8 @@ use crate::{EventId, ShortEventId};
 /// Invariants:
 ///
 /// * `event_id` matches
-/// * `sig` valid for `event.author`
+/// * `sig` valid for `event.author`, or for a device key it delegated to (see
+///   [`super::content_kind::DeviceDelegation`])
 /// * if `content` is `Some`, matches `event.content_hash` and
 ///   `event.content_len`
 #[derive(Copy, Clone, Debug)]
@@ -68,6 +69,18 @@ impl VerifiedEvent {
         event_id: impl Into<ShortEventId>,
         event: Event,
         sig: EventSignature,
+    ) -> VerifiedEventResult<Self> {
+        Self::verify_response_delegated(author, event_id, event, sig, &[])
+    }
+
+    /// Like [`Self::verify_response`], also accepting signatures of the
+    /// author's device `delegates`
+    pub fn verify_response_delegated(
+        author: RostraId,
+        event_id: impl Into<ShortEventId>,
+        event: Event,
+        sig: EventSignature,
+        delegates: &[RostraId],
     ) -> VerifiedEventResult<Self> {
         if author != event.author {
             return AuthorMismatchSnafu.fail();
@@ -78,7 +91,9 @@ impl VerifiedEvent {
             return EventIdMismatchSnafu.fail();
         }
 
-        event.verify_signature(sig).context(SignatureInvalidSnafu)?;
+        event
+            .verify_signature_delegated(sig, delegates)
+            .context(SignatureInvalidSnafu)?;
 
         Ok(Self {
             event_id,
@@ -88,10 +103,19 @@ impl VerifiedEvent {
     }
 
     /// Verify event received event
-    pub fn verify_received_as_is(
+    pub fn verify_received_as_is(signed_event: SignedEvent) -> VerifiedEventResult<Self> {
+        Self::verify_received_delegated(signed_event, &[])
+    }
+
+    /// Like [`Self::verify_received_as_is`], also accepting signatures of the
+    /// author's device `delegates`
+    pub fn verify_received_delegated(
         SignedEvent { event, sig }: SignedEvent,
+        delegates: &[RostraId],
     ) -> VerifiedEventResult<Self> {
-        event.verify_signature(sig).context(SignatureInvalidSnafu)?;
+        event
+            .verify_signature_delegated(sig, delegates)
+            .context(SignatureInvalidSnafu)?;
 
         Ok(Self {
             event_id: event.compute_id(),
@@ -100,10 +124,11 @@ impl VerifiedEvent {
         })
     }
 
+    /// Use an event verified before, e.g. when it was stored
+    ///
+    /// Can't be checked, as the event might be signed by a device key whose
+    /// delegation was revoked since.
     pub fn assume_verified_from_signed(SignedEvent { event, sig }: SignedEvent) -> Self {
-        debug_assert!(
-            VerifiedEvent::verify_received_as_is(SignedEvent::unverified(event, sig)).is_ok()
-        );
         Self {
             event_id: event.compute_id(),
             event,
@@ -111,11 +136,18 @@ impl VerifiedEvent {
         }
     }
 
-    pub fn verify_signed(
+    pub fn verify_signed(author: RostraId, signed_event: SignedEvent) -> VerifiedEventResult<Self> {
+        Self::verify_signed_delegated(author, signed_event, &[])
+    }
+
+    /// Like [`Self::verify_signed`], also accepting signatures of the author's
+    /// device `delegates`
+    pub fn verify_signed_delegated(
         author: RostraId,
         SignedEvent { event, sig }: SignedEvent,
+        delegates: &[RostraId],
     ) -> VerifiedEventResult<Self> {
-        Self::verify_response(author, event.compute_id(), event, sig)
+        Self::verify_response_delegated(author, event.compute_id(), event, sig, delegates)
     }
 }
 
@@ -0,0 +1,113 @@
+//! Hashtag detection in djot content.
+//!
+//! A hashtag is a `#` followed by letters, digits and underscores, with at
+//! least one character that is not a digit (so `#1` is not a hashtag). The `#`
+//! must not follow a word character or a `/` (so `issue#12` and URL fragments
+//! are not hashtags). Hashtags are only looked for in plain text, not in code,
+//! math, raw content, links and images.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use jotup::{Container, Event};
+
+/// Hashtags longer than this (in chars, without the `#`) are ignored
+pub const MAX_HASHTAG_LEN: usize = 64;
+
+fn is_hashtag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Normalize a hashtag name (without the `#`)
+///
+/// Returns `None` if `name` is not a valid hashtag.
+pub fn normalize_hashtag(name: &str) -> Option<String> {
+    let len = name.chars().count();
+    if len == 0
+        || MAX_HASHTAG_LEN < len
+        || !name.chars().all(is_hashtag_char)
+        || name.chars().all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+    Some(name.to_lowercase())
+}
+
+/// Find the hashtags in plain `text`
+///
+/// Returns the byte ranges of the hashtags, including their `#`.
+pub fn find_hashtags(text: &str) -> Vec<Range<usize>> {
+    let mut ret = vec![];
+    let mut prev = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let can_start = !prev.is_some_and(|prev: char| is_hashtag_char(prev) || prev == '/');
+        prev = Some(c);
+        if c != '#' || !can_start {
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some((i, c)) = chars.next_if(|(_, c)| is_hashtag_char(*c)) {
+            end = i + c.len_utf8();
+            prev = Some(c);
+        }
+        if normalize_hashtag(&text[start + 1..end]).is_some() {
+            ret.push(start..end);
+        }
+    }
+    ret
+}
+
+/// Whether the text of `container` can have hashtags
+pub fn container_can_have_hashtags(container: &Container<'_>) -> bool {
+    !matches!(
+        container,
+        Container::Link(..)
+            | Container::Image(..)
+            | Container::Verbatim
+            | Container::CodeBlock { .. }
+            | Container::RawBlock { .. }
+            | Container::RawInline { .. }
+            | Container::Math { .. }
+            | Container::LinkDefinition { .. }
+    )
+}
+
+/// Extract the distinct normalized hashtags of djot content
+pub fn extract_hashtags(djot_content: &str) -> BTreeSet<String> {
+    let mut tags = BTreeSet::new();
+    // Depth inside containers that can't have hashtags
+    let mut skip_depth = 0usize;
+    // Consecutive text, which the parser might split
+    let mut text = String::new();
+
+    let mut flush = |text: &mut String| {
+        for range in find_hashtags(text) {
+            tags.extend(normalize_hashtag(&text[range.start + 1..range.end]));
+        }
+        text.clear();
+    };
+
+    for event in jotup::Parser::new(djot_content) {
+        match event {
+            Event::Str(s) => {
+                if skip_depth == 0 {
+                    text.push_str(&s);
+                }
+                continue;
+            }
+            Event::Start(container, _)
+                if 0 < skip_depth || !container_can_have_hashtags(&container) =>
+            {
+                skip_depth += 1;
+            }
+            Event::End => {
+                skip_depth = skip_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        flush(&mut text);
+    }
+    flush(&mut text);
+    tags
+}
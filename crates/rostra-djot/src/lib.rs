@@ -1,9 +1,10 @@
 //! Shared djot utilities for Rostra.
 //!
 //! This crate provides common djot parsing utilities used across multiple
-//! Rostra crates, including link extraction and mention detection.
+//! Rostra crates, including link extraction, mention and hashtag detection.
 
 pub mod extract;
+pub mod hashtag;
 pub mod links;
 pub mod mention;
 
@@ -9,7 +9,7 @@ use rostra_core::id::{RostraId, ShortRostraId};
 ///
 /// Short references identify a collision-protected prefix and require a
 /// caller-owned identity index before they can become full identities.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RostraIdLink {
     /// A complete Rostra identity.
     Full(RostraId),
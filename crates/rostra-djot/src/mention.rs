@@ -1,26 +1,44 @@
 //! Mention detection in djot content.
 
+use std::collections::BTreeSet;
+
 use jotup::{Container, Event};
 use rostra_core::id::{RostraId, ToShort as _};
 
 use crate::links::{RostraIdLink, extract_rostra_id_link_reference};
 
+impl RostraIdLink {
+    /// Whether this reference points at `id`
+    ///
+    /// A short reference matches any identity with the same canonical prefix.
+    pub fn matches(self, id: RostraId) -> bool {
+        match self {
+            RostraIdLink::Full(mentioned_id) => mentioned_id == id,
+            RostraIdLink::Short(mentioned_id) => mentioned_id == id.to_short(),
+        }
+    }
+}
+
+/// Extract the distinct identities mentioned in djot content.
+///
+/// A mention is any `rostra:<id>` link, with either the full identifier or
+/// its canonical short prefix. Short references are returned as-is, for the
+/// caller to resolve against its identity index.
+pub fn extract_mentions(djot_content: &str) -> BTreeSet<RostraIdLink> {
+    jotup::Parser::new(djot_content)
+        .filter_map(|event| match event {
+            Event::Start(Container::Link(url, _), _) => extract_rostra_id_link_reference(&url),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Check if djot content contains a mention of the target RostraId.
 ///
 /// This function parses the djot content and looks for `rostra:<id>` links
 /// where the full identifier or canonical short prefix matches the target.
 pub fn contains_mention(djot_content: &str, target_id: RostraId) -> bool {
-    for event in jotup::Parser::new(djot_content) {
-        if let Event::Start(Container::Link(url, _), _) = event {
-            let is_target = match extract_rostra_id_link_reference(&url) {
-                Some(RostraIdLink::Full(mentioned_id)) => mentioned_id == target_id,
-                Some(RostraIdLink::Short(mentioned_id)) => mentioned_id == target_id.to_short(),
-                None => false,
-            };
-            if is_target {
-                return true;
-            }
-        }
-    }
-    false
+    extract_mentions(djot_content)
+        .into_iter()
+        .any(|mention| mention.matches(target_id))
 }
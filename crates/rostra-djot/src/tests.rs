@@ -2,11 +2,12 @@ use std::str::FromStr;
 
 use rostra_core::id::{RostraId, ToShort as _};
 
+use crate::hashtag::{extract_hashtags, find_hashtags, normalize_hashtag};
 use crate::links::{
     RostraIdLink, extract_rostra_id_link, extract_rostra_id_link_reference,
     extract_rostra_media_link,
 };
-use crate::mention::contains_mention;
+use crate::mention::{contains_mention, extract_mentions};
 
 #[test]
 fn extracts_full_rostra_id_link_encodings() {
@@ -89,3 +90,46 @@ fn contains_mention_matches_full_and_short_rostra_ids() {
         assert!(!contains_mention(&content, other_id));
     }
 }
+
+#[test]
+fn extracts_distinct_mentions_from_links_only() {
+    let full_id = RostraId::from_bytes([42; 32]);
+    let short_id = RostraId::from_bytes([43; 32]).to_short();
+    let content = format!(
+        "Hi <rostra:{full_id}> and [you](rostra:{short_id}), again <rostra:{full_id}>.\n\n\
+        Not `rostra:{full_id}` nor [a link](https://example.com)."
+    );
+
+    assert_eq!(
+        extract_mentions(&content).into_iter().collect::<Vec<_>>(),
+        vec![RostraIdLink::Full(full_id), RostraIdLink::Short(short_id)]
+    );
+    assert!(RostraIdLink::Short(full_id.to_short()).matches(full_id));
+    assert!(!RostraIdLink::Short(short_id).matches(full_id));
+}
+
+#[test]
+fn finds_hashtags_in_text() {
+    let text = "#Rust and #p2p_net, not issue#12 or #1 or example.com/#frag. ##ok #ünï";
+    let tags: Vec<&str> = find_hashtags(text)
+        .into_iter()
+        .map(|range| &text[range])
+        .collect();
+    assert_eq!(tags, vec!["#Rust", "#p2p_net", "#ok", "#ünï"]);
+
+    assert_eq!(normalize_hashtag("Rust").as_deref(), Some("rust"));
+    assert_eq!(normalize_hashtag("2024"), None);
+    assert_eq!(normalize_hashtag("with space"), None);
+    assert_eq!(normalize_hashtag(&"a".repeat(65)), None);
+}
+
+#[test]
+fn extracts_hashtags_from_djot_text_only() {
+    let content = "# Heading #news\n\n\
+        Text #Rust #rust _#emph_ `#code` [#link](https://example.com) ![#img](x.png)\n\n\
+        ```\n#block\n```\n";
+    assert_eq!(
+        extract_hashtags(content).into_iter().collect::<Vec<_>>(),
+        vec!["emph", "news", "rust"]
+    );
+}
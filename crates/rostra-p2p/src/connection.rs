@@ -1,14 +1,17 @@
+use std::collections::HashSet;
 use std::fmt;
 use std::future::Future;
+use std::io;
+use std::ops::Range;
 use std::pin::Pin;
 
 use bao_tree::io::outboard::{EmptyOutboard, PreOrderMemOutboard};
 use bao_tree::io::round_up_to_chunks;
-use bao_tree::{BlockSize, ByteRanges, blake3};
+use bao_tree::{BlockSize, ByteRanges, ChunkRanges, blake3};
 use bincode::{Decode, Encode};
-use convi::{CastInto, ExpectFrom};
+use convi::{CastFrom as _, CastInto, ExpectFrom};
 use iroh::endpoint::{RecvStream, SendStream};
-use iroh_io::{TokioStreamReader, TokioStreamWriter};
+use iroh_io::{AsyncSliceWriter, TokioStreamReader, TokioStreamWriter};
 use rostra_core::bincode::STD_BINCODE_CONFIG;
 use rostra_core::event::{
     EventContentRaw, EventExt as _, SignedEvent, VerifiedEvent, VerifiedEventContent,
@@ -19,10 +22,11 @@ use rostra_util_error::BoxedErrorResult;
 use snafu::{OptionExt as _, ResultExt as _};
 use tracing::trace;
 
+use crate::reconcile::{EventsFingerprint, EventsRange, EventsRangeReconciliation};
 use crate::{
     DecodingBaoSnafu, DecodingSnafu, EncodingBaoSnafu, EventVerificationSnafu, FailedSnafu,
     LOG_TARGET, MessageTooLargeSnafu, ReadSnafu, RpcResult, StreamConnectionSnafu, TrailerSnafu,
-    WriteSnafu,
+    UnexpectedResponseSnafu, WriteSnafu,
 };
 
 #[derive(Debug, Clone)]
@@ -82,6 +86,9 @@ impl fmt::Display for RpcId {
             Self::WAIT_HEAD_UPDATE => f.write_str("WAIT_HEAD_UPDATE"),
             Self::GET_HEAD => f.write_str("GET_HEAD"),
             Self::WAIT_FOLLOWERS_NEW_HEADS => f.write_str("WAIT_FOLLOWERS_NEW_HEADS"),
+            Self::GET_ANCESTORS => f.write_str("GET_ANCESTORS"),
+            Self::RECONCILE_EVENTS => f.write_str("RECONCILE_EVENTS"),
+            Self::GET_EVENT_CONTENT_RANGE => f.write_str("GET_EVENT_CONTENT_RANGE"),
             _ => write!(f, "UNKNOWN({})", self.0),
         }
     }
@@ -95,6 +102,9 @@ impl RpcId {
     pub const WAIT_HEAD_UPDATE: Self = Self(4);
     pub const GET_HEAD: Self = Self(5);
     pub const WAIT_FOLLOWERS_NEW_HEADS: Self = Self(6);
+    pub const GET_ANCESTORS: Self = Self(7);
+    pub const RECONCILE_EVENTS: Self = Self(8);
+    pub const GET_EVENT_CONTENT_RANGE: Self = Self(9);
     pub const fn const_from(value: u16) -> Self {
         Self(value)
     }
@@ -226,6 +236,24 @@ define_rpc!(
     pub struct GetEventContentResponse(pub bool);
 );
 
+define_rpc!(
+    RpcId::GET_EVENT_CONTENT_RANGE,
+    GetEventContentRangeRequest,
+    /// Request the bytes `start..end` of the content of `event_id`.
+    ///
+    /// Like with [`GetEventContentRequest`], the bytes follow the response
+    /// bao-encoded, so they are verified against the content hash of the
+    /// event without having the rest of the content.
+    pub struct GetEventContentRangeRequest {
+        pub event_id: ShortEventId,
+        pub start: u32,
+        pub end: u32,
+    },
+    GetEventContentRangeResponse,
+    /// Whether the content is available, and the range is within it.
+    pub struct GetEventContentRangeResponse(pub bool);
+);
+
 define_rpc!(
     RpcId::WAIT_FOLLOWERS_NEW_HEADS,
     WaitFollowersNewHeadsRequest,
@@ -245,6 +273,53 @@ define_rpc!(
     }
 );
 
+define_rpc!(
+    RpcId::GET_ANCESTORS,
+    GetAncestorsRequest,
+    /// Request a batch of events of `author`, walking back from `heads`.
+    ///
+    /// The walk does not return or traverse past any of the `known` events,
+    /// which are usually the caller's current heads of `author`.
+    pub struct GetAncestorsRequest {
+        pub author: RostraId,
+        pub heads: Vec<ShortEventId>,
+        pub known: Vec<ShortEventId>,
+        pub limit: u32,
+    },
+    GetAncestorsResponse,
+    /// At most `limit` events, each of them either one of the requested
+    /// heads, or a parent of an event earlier in the batch.
+    ///
+    /// A batch shorter than `limit` does not imply that the walk reached
+    /// `known` events, as the server might be missing some history itself.
+    pub struct GetAncestorsResponse(pub Vec<SignedEvent>);
+);
+
+impl GetAncestorsRequest {
+    /// Max number of heads to start the walk from
+    pub const MAX_HEADS: usize = 16;
+    /// Max number of known events to stop the walk at
+    pub const MAX_KNOWN: usize = 128;
+    /// Max number of events in a single response
+    pub const MAX_LIMIT: u32 = 1024;
+}
+
+define_rpc!(
+    RpcId::RECONCILE_EVENTS,
+    ReconcileEventsRequest,
+    /// Request to compare events of `author` in each of the ranges with the
+    /// caller's fingerprints of them.
+    ///
+    /// See [`crate::reconcile`].
+    pub struct ReconcileEventsRequest {
+        pub author: RostraId,
+        pub ranges: Vec<(EventsRange, EventsFingerprint)>,
+    },
+    ReconcileEventsResponse,
+    /// Result of comparing each requested range, in the request order.
+    pub struct ReconcileEventsResponse(pub Vec<EventsRangeReconciliation>);
+);
+
 impl FeedEventResponse {
     pub const RETURN_CODE_ALREADY_HAVE: u8 = 1;
     pub const RETURN_CODE_DOES_NOT_NEED: u8 = 2;
@@ -467,12 +542,18 @@ impl Connection {
                 len: u32::MAX,
                 limit: u32::MAX,
             })?;
-        /// Use a block size of 16 KiB, a good default
-        /// for most cases
-        const BLOCK_SIZE: BlockSize = BlockSize::from_chunk_log(4);
-        let ranges = ByteRanges::from(0u64..bytes_len.into());
-        let ranges = round_up_to_chunks(&ranges);
-        let mut ob = PreOrderMemOutboard::create(bytes, BLOCK_SIZE);
+        Self::write_bao_content_range(send, bytes, 0..bytes_len).await
+    }
+
+    /// Write the bytes `range` of the content `bytes`, with the bao proofs of
+    /// them
+    pub async fn write_bao_content_range(
+        send: &mut SendStream,
+        bytes: &[u8],
+        range: Range<u32>,
+    ) -> RpcResult<()> {
+        let ranges = bao_chunk_ranges(&range);
+        let mut ob = PreOrderMemOutboard::create(bytes, BAO_BLOCK_SIZE);
 
         bao_tree::io::fsm::encode_ranges_validated(
             bytes,
@@ -491,20 +572,85 @@ impl Connection {
         len: u32,
         hash: ContentHash,
     ) -> RpcResult<Vec<u8>> {
-        const BLOCK_SIZE: BlockSize = BlockSize::from_chunk_log(4);
-        let ranges = ByteRanges::from(0u64..len.into());
-        let ranges = round_up_to_chunks(&ranges);
+        Self::read_bao_content_range(read, len, hash, 0..len).await
+    }
+
+    /// Read and verify the bytes `range` of a content of `len` bytes, written
+    /// with [`Self::write_bao_content_range`]
+    pub async fn read_bao_content_range(
+        read: &mut RecvStream,
+        len: u32,
+        hash: ContentHash,
+        range: Range<u32>,
+    ) -> RpcResult<Vec<u8>> {
+        let ranges = bao_chunk_ranges(&range);
         let mut ob = EmptyOutboard {
-            tree: bao_tree::BaoTree::new(len.into(), BLOCK_SIZE),
+            tree: bao_tree::BaoTree::new(len.into(), BAO_BLOCK_SIZE),
             root: blake3::Hash::from_bytes(hash.into()),
         };
 
-        let mut decoded = Vec::with_capacity(len.cast_into());
+        // Whole blocks are transferred, so the decoded data can start before,
+        // and end after the requested range
+        let block_len = u32::try_from(BAO_BLOCK_SIZE.bytes()).expect("Can't fail");
+        let mut decoded = OffsetWriter {
+            offset: u64::from(range.start - range.start % block_len),
+            buf: Vec::with_capacity(usize::cast_from(range.end - range.start)),
+        };
         bao_tree::io::fsm::decode_ranges(TokioStreamReader(read), ranges, &mut decoded, &mut ob)
             .await
             .context(DecodingBaoSnafu)?;
 
-        Ok(decoded)
+        let start = usize::cast_from(range.start % block_len);
+        let end = start + usize::cast_from(range.end - range.start);
+        if decoded.buf.len() < end {
+            return UnexpectedResponseSnafu.fail();
+        }
+        decoded.buf.truncate(end);
+        decoded.buf.drain(..start);
+        Ok(decoded.buf)
+    }
+}
+
+/// Use a block size of 16 KiB, a good default for most cases
+const BAO_BLOCK_SIZE: BlockSize = BlockSize::from_chunk_log(4);
+
+fn bao_chunk_ranges(range: &Range<u32>) -> ChunkRanges {
+    round_up_to_chunks(&ByteRanges::from(
+        u64::from(range.start)..u64::from(range.end),
+    ))
+}
+
+/// Collects data written at or after `offset`
+struct OffsetWriter {
+    offset: u64,
+    buf: Vec<u8>,
+}
+
+impl OffsetWriter {
+    fn buf_offset(&self, offset: u64) -> io::Result<u64> {
+        offset
+            .checked_sub(self.offset)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "write before offset"))
+    }
+}
+
+impl AsyncSliceWriter for OffsetWriter {
+    async fn write_at(&mut self, offset: u64, data: &[u8]) -> io::Result<()> {
+        let offset = self.buf_offset(offset)?;
+        self.buf.write_at(offset, data).await
+    }
+
+    async fn write_bytes_at(&mut self, offset: u64, data: bytes::Bytes) -> io::Result<()> {
+        let offset = self.buf_offset(offset)?;
+        self.buf.write_bytes_at(offset, data).await
+    }
+
+    async fn set_len(&mut self, len: u64) -> io::Result<()> {
+        AsyncSliceWriter::set_len(&mut self.buf, len.saturating_sub(self.offset)).await
+    }
+
+    async fn sync(&mut self) -> io::Result<()> {
+        Ok(())
     }
 }
 
@@ -518,10 +664,13 @@ impl Connection {
         Ok(event.0)
     }
 
+    /// Fetch and verify an event of `rostra_id`, possibly signed by one of its
+    /// device `delegates`
     pub async fn get_event(
         &self,
         rostra_id: RostraId,
         event_id: impl Into<ShortEventId>,
+        delegates: &[RostraId],
     ) -> RpcResult<Option<VerifiedEvent>> {
         let event_id = event_id.into();
         let signed_event = self.get_event_unverified(event_id).await?;
@@ -529,13 +678,98 @@ impl Connection {
         let Some(event) = signed_event else {
             return Ok(None);
         };
-        let event =
-            VerifiedEvent::verify_response(rostra_id, event_id, *event.event(), event.sig())
-                .context(EventVerificationSnafu)?;
+        let event = VerifiedEvent::verify_response_delegated(
+            rostra_id,
+            event_id,
+            *event.event(),
+            event.sig(),
+            delegates,
+        )
+        .context(EventVerificationSnafu)?;
 
         Ok(Some(event))
     }
 
+    /// Fetch a batch of events of `author`, walking back from `heads` until
+    /// `known` events.
+    ///
+    /// Events are verified and returned in the order they were walked, so
+    /// every event is either one of the `heads`, or a parent of an event
+    /// returned before it. Peers that do not support batch fetching fail this call, and
+    /// [`Self::get_event`] should be used instead.
+    pub async fn get_ancestors(
+        &self,
+        author: RostraId,
+        heads: Vec<ShortEventId>,
+        known: Vec<ShortEventId>,
+        limit: u32,
+        delegates: &[RostraId],
+    ) -> RpcResult<Vec<VerifiedEvent>> {
+        let limit = limit.min(GetAncestorsRequest::MAX_LIMIT);
+        let mut expected: HashSet<ShortEventId> = heads.iter().copied().collect();
+        let GetAncestorsResponse(events) = self
+            .make_rpc(&GetAncestorsRequest {
+                author,
+                heads,
+                known,
+                limit,
+            })
+            .await?;
+
+        if usize::cast_from(limit) < events.len() {
+            return UnexpectedResponseSnafu.fail();
+        }
+
+        let mut verified = Vec::with_capacity(events.len());
+        for event in events {
+            // Only accept events reachable from the requested heads, which
+            // bounds the response to what was actually asked for.
+            let event_id = event.compute_short_id();
+            if !expected.remove(&event_id) {
+                return UnexpectedResponseSnafu.fail();
+            }
+            let event = VerifiedEvent::verify_response_delegated(
+                author,
+                event_id,
+                *event.event(),
+                event.sig(),
+                delegates,
+            )
+            .context(EventVerificationSnafu)?;
+            expected.extend(event.all_parents());
+            verified.push(event);
+        }
+
+        Ok(verified)
+    }
+
+    /// Compare events of `author` in `ranges` with the caller's fingerprints
+    /// of them.
+    ///
+    /// Responses are checked to stay within the requested ranges. Peers that
+    /// do not support reconciliation fail this call.
+    pub async fn reconcile_events(
+        &self,
+        author: RostraId,
+        ranges: Vec<(EventsRange, EventsFingerprint)>,
+    ) -> RpcResult<Vec<EventsRangeReconciliation>> {
+        let requested: Vec<EventsRange> = ranges.iter().map(|(range, _)| *range).collect();
+        let ReconcileEventsResponse(results) = self
+            .make_rpc(&ReconcileEventsRequest { author, ranges })
+            .await?;
+
+        if results.len() != requested.len()
+            || !results
+                .iter()
+                .zip(requested)
+                .all(|(result, range)| result.is_valid_for(range))
+        {
+            return UnexpectedResponseSnafu.fail();
+        }
+
+        Ok(results)
+    }
+
     pub async fn get_event_content(
         &self,
         event: VerifiedEvent,
@@ -570,6 +804,53 @@ impl Connection {
         Ok(verified_content)
     }
 
+    /// Fetch the bytes `range` of the content of `event`
+    ///
+    /// The bytes are verified against the content hash of the event. Peers
+    /// that do not support range requests fail this call, and
+    /// [`Self::get_event_content`] should be used instead.
+    ///
+    /// # Panics
+    ///
+    /// If `range` is empty, or not within the content of `event`.
+    pub async fn get_event_content_range(
+        &self,
+        event: VerifiedEvent,
+        range: Range<u32>,
+    ) -> RpcResult<Option<Vec<u8>>> {
+        assert!(range.start < range.end && range.end <= event.content_len());
+        let (_resp, data) = self
+            .make_rpc_with_extra_data_recv(
+                &GetEventContentRangeRequest {
+                    event_id: event.event_id.to_short(),
+                    start: range.start,
+                    end: range.end,
+                },
+                |recv, resp| {
+                    let resp = resp.to_owned();
+                    let range = range.clone();
+                    Box::pin(async move {
+                        if resp.0 {
+                            Ok(Some(
+                                Connection::read_bao_content_range(
+                                    recv,
+                                    event.content_len(),
+                                    event.content_hash(),
+                                    range,
+                                )
+                                .await?,
+                            ))
+                        } else {
+                            Ok(None)
+                        }
+                    })
+                },
+            )
+            .await?;
+
+        Ok(data)
+    }
+
     pub async fn feed_event(
         &self,
         event: SignedEvent,
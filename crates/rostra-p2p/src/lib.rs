@@ -1,5 +1,6 @@
 pub mod connection;
 pub mod error;
+pub mod reconcile;
 pub mod util;
 
 pub use connection::Connection;
@@ -44,6 +45,8 @@ pub enum RpcError {
     EventVerification {
         source: VerifiedEventError,
     },
+    /// Other side responded with data that was not asked for
+    UnexpectedResponse,
     /// Other side responded with rpc failure
     Failed {
         return_code: u8,
@@ -0,0 +1,220 @@
+//! Range-based set reconciliation of events of one identity
+//!
+//! Events of an identity are ordered by `(timestamp, event id)`. To find the
+//! difference between two event sets, the caller sends fingerprints of ranges
+//! of its own events. The responder compares them with fingerprints of its
+//! events in the same ranges and for each range either confirms it is equal,
+//! lists all its events in it (if there are few), or splits it into smaller
+//! subranges with their fingerprints, which the caller compares again.
+//!
+//! The protocol is stateless on the responding side, and every round trip
+//! narrows down the differing ranges, so the whole difference is found in a
+//! logarithmic number of round trips.
+
+use bincode::{Decode, Encode};
+use rostra_core::{ShortEventId, Timestamp};
+
+/// Max number of ranges in a single request
+pub const MAX_RANGES: usize = 32;
+
+/// Max number of events a range can have to be listed instead of split
+pub const MAX_LISTED_EVENTS: usize = 64;
+
+/// Number of subranges a differing range is split into
+pub const SPLIT_FACTOR: usize = 16;
+
+/// Max number of events of a single range the responder looks at
+///
+/// Larger ranges are split into subranges of the events looked at, and one
+/// more covering the rest of the range, with an
+/// [`EventsFingerprint::UNKNOWN`] fingerprint.
+pub const MAX_SCANNED_EVENTS: usize = 4096;
+
+/// Position of an event in the `(timestamp, event id)` order
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EventsBound {
+    pub timestamp: Timestamp,
+    pub event_id: ShortEventId,
+}
+
+impl EventsBound {
+    pub const MIN: Self = Self {
+        timestamp: Timestamp::ZERO,
+        event_id: ShortEventId::ZERO,
+    };
+}
+
+impl From<(Timestamp, ShortEventId)> for EventsBound {
+    fn from((timestamp, event_id): (Timestamp, ShortEventId)) -> Self {
+        Self {
+            timestamp,
+            event_id,
+        }
+    }
+}
+
+/// Range of events from `start` (inclusive) to `end` (exclusive, or unbounded
+/// if `None`)
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EventsRange {
+    pub start: EventsBound,
+    pub end: Option<EventsBound>,
+}
+
+impl EventsRange {
+    /// Range of all events
+    pub const FULL: Self = Self {
+        start: EventsBound::MIN,
+        end: None,
+    };
+
+    pub fn contains(&self, bound: EventsBound) -> bool {
+        self.start <= bound && self.end.is_none_or(|end| bound < end)
+    }
+
+    /// Check if the range can contain any events at all
+    pub fn is_empty(&self) -> bool {
+        self.end.is_some_and(|end| end <= self.start)
+    }
+}
+
+/// Fingerprint of a set of events
+///
+/// Event ids are hashes, so xor-ing them together with the number of events is
+/// enough to detect differences between honest peers.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EventsFingerprint {
+    pub count: u64,
+    pub xor: [u8; 16],
+}
+
+impl EventsFingerprint {
+    /// Fingerprint of events the responder didn't look at, never matching a
+    /// fingerprint of actual events
+    pub const UNKNOWN: Self = Self {
+        count: u64::MAX,
+        xor: [0; 16],
+    };
+
+    pub fn add(&mut self, event_id: ShortEventId) {
+        self.count += 1;
+        for (acc, byte) in self.xor.iter_mut().zip(event_id.to_bytes()) {
+            *acc ^= byte;
+        }
+    }
+}
+
+impl FromIterator<ShortEventId> for EventsFingerprint {
+    fn from_iter<T: IntoIterator<Item = ShortEventId>>(iter: T) -> Self {
+        let mut fingerprint = Self::default();
+        for event_id in iter {
+            fingerprint.add(event_id);
+        }
+        fingerprint
+    }
+}
+
+/// Result of comparing one range
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encode, Decode, Clone, Debug, PartialEq, Eq)]
+pub enum EventsRangeReconciliation {
+    /// Both sides have the same events in the range
+    Equal,
+    /// All events the responder has in the range
+    Events(Vec<EventsBound>),
+    /// Consecutive subranges covering the range, with fingerprints of the
+    /// responder's events in them
+    Split(Vec<(EventsRange, EventsFingerprint)>),
+}
+
+impl EventsRangeReconciliation {
+    /// Compare the caller's `fingerprint` of `range` with the responder's
+    /// `events` in it, ordered
+    ///
+    /// Only the first [`MAX_SCANNED_EVENTS`] events are looked at, and one
+    /// more tells if there are any beyond them, so the responder never needs
+    /// to read more than that.
+    pub fn respond(
+        range: EventsRange,
+        fingerprint: EventsFingerprint,
+        events: &[EventsBound],
+    ) -> Self {
+        let (events, rest_start) = match events.get(MAX_SCANNED_EVENTS) {
+            Some(&rest_start) => (&events[..MAX_SCANNED_EVENTS], Some(rest_start)),
+            None => (events, None),
+        };
+        if rest_start.is_none() {
+            if events
+                .iter()
+                .map(|event| event.event_id)
+                .collect::<EventsFingerprint>()
+                == fingerprint
+            {
+                return Self::Equal;
+            }
+            if events.len() <= MAX_LISTED_EVENTS {
+                return Self::Events(events.to_vec());
+            }
+        }
+
+        let chunk_len = events
+            .len()
+            .div_ceil(SPLIT_FACTOR - usize::from(rest_start.is_some()));
+        let chunks: Vec<_> = events.chunks(chunk_len).collect();
+        let mut subranges = Vec::with_capacity(chunks.len() + 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let start = if i == 0 { range.start } else { chunk[0] };
+            let end = match chunks.get(i + 1) {
+                Some(next) => Some(next[0]),
+                None => rest_start.or(range.end),
+            };
+            subranges.push((
+                EventsRange { start, end },
+                chunk.iter().map(|event| event.event_id).collect(),
+            ));
+        }
+        if let Some(start) = rest_start {
+            subranges.push((
+                EventsRange {
+                    start,
+                    end: range.end,
+                },
+                EventsFingerprint::UNKNOWN,
+            ));
+        }
+        Self::Split(subranges)
+    }
+
+    /// Check that a response to a request for `range` stays within it
+    ///
+    /// Splits must make progress, so a malicious responder can't make the
+    /// caller compare the same range forever.
+    pub fn is_valid_for(&self, range: EventsRange) -> bool {
+        match self {
+            Self::Equal => true,
+            Self::Events(events) => {
+                events.iter().all(|event| range.contains(*event))
+                    && events.is_sorted_by(|a, b| a < b)
+            }
+            Self::Split(subranges) => {
+                let (Some(first), Some(last)) = (subranges.first(), subranges.last()) else {
+                    return false;
+                };
+                2 <= subranges.len()
+                    && subranges.len() <= SPLIT_FACTOR
+                    && first.0.start == range.start
+                    && last.0.end == range.end
+                    && subranges.iter().all(|(subrange, _)| !subrange.is_empty())
+                    && subranges
+                        .windows(2)
+                        .all(|pair| pair[0].0.end == Some(pair[1].0.start))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
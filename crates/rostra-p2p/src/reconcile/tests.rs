@@ -0,0 +1,90 @@
+use super::*;
+
+#[test]
+fn respond_splits_large_ranges_into_valid_subranges() {
+    let events: Vec<EventsBound> = (0u8..200)
+        .map(|i| EventsBound {
+            timestamp: Timestamp::from(u64::from(i / 2)),
+            event_id: ShortEventId::from_bytes([i; 16]),
+        })
+        .collect();
+    let all: EventsFingerprint = events.iter().map(|event| event.event_id).collect();
+
+    assert_eq!(
+        EventsRangeReconciliation::respond(EventsRange::FULL, all, &events),
+        EventsRangeReconciliation::Equal
+    );
+
+    let response = EventsRangeReconciliation::respond(
+        EventsRange::FULL,
+        EventsFingerprint::default(),
+        &events,
+    );
+    assert!(response.is_valid_for(EventsRange::FULL));
+    let EventsRangeReconciliation::Split(subranges) = response else {
+        panic!("Expected a split");
+    };
+    assert_eq!(subranges.len(), SPLIT_FACTOR);
+    assert_eq!(
+        subranges
+            .iter()
+            .map(|(_, fingerprint)| fingerprint.count)
+            .sum::<u64>(),
+        200
+    );
+    for (subrange, fingerprint) in &subranges {
+        let in_range: EventsFingerprint = events
+            .iter()
+            .filter(|event| subrange.contains(**event))
+            .map(|event| event.event_id)
+            .collect();
+        assert_eq!(in_range, *fingerprint);
+    }
+
+    let small = &events[..10];
+    let response =
+        EventsRangeReconciliation::respond(EventsRange::FULL, EventsFingerprint::default(), small);
+    assert_eq!(response, EventsRangeReconciliation::Events(small.to_vec()));
+    assert!(response.is_valid_for(EventsRange::FULL));
+    assert!(!response.is_valid_for(EventsRange {
+        start: events[5],
+        end: None,
+    }));
+}
+
+#[test]
+fn respond_looks_at_a_bounded_number_of_events() {
+    let events: Vec<EventsBound> = (0..=MAX_SCANNED_EVENTS as u64)
+        .map(|i| EventsBound {
+            timestamp: Timestamp::from(i),
+            event_id: ShortEventId::from_bytes([(i % 256) as u8; 16]),
+        })
+        .collect();
+    let all: EventsFingerprint = events.iter().map(|event| event.event_id).collect();
+
+    // Even a matching fingerprint can't be confirmed without looking at all
+    // events
+    let response = EventsRangeReconciliation::respond(EventsRange::FULL, all, &events);
+    assert!(response.is_valid_for(EventsRange::FULL));
+    let EventsRangeReconciliation::Split(subranges) = response else {
+        panic!("Expected a split");
+    };
+    assert_eq!(subranges.len(), SPLIT_FACTOR);
+    assert_eq!(
+        subranges.last(),
+        Some(&(
+            EventsRange {
+                start: events[MAX_SCANNED_EVENTS],
+                end: None,
+            },
+            EventsFingerprint::UNKNOWN
+        ))
+    );
+    assert_eq!(
+        subranges[..SPLIT_FACTOR - 1]
+            .iter()
+            .map(|(_, fingerprint)| fingerprint.count)
+            .sum::<u64>(),
+        MAX_SCANNED_EVENTS as u64
+    );
+}
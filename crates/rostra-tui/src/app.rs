@@ -0,0 +1,317 @@
+//! State of the terminal UI and its handling of input
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::widgets::ListState;
+use rostra_client::Client;
+use rostra_client::timeline::Timeline;
+use rostra_client_db::social::SocialPostRecord;
+use rostra_core::ShortEventId;
+use rostra_core::event::SocialPost;
+use rostra_core::id::{ExternalEventId, RostraId, RostraIdSecretKey, ToShort as _};
+use tracing::debug;
+
+use crate::LOG_TARGET;
+
+/// Number of posts loaded per timeline
+const TIMELINE_LIMIT: usize = 100;
+
+/// Number of replies loaded per thread
+const THREAD_LIMIT: usize = 100;
+
+pub(crate) type PostRecord = SocialPostRecord<SocialPost>;
+
+/// A post opened along with its replies
+pub(crate) struct Thread {
+    pub(crate) post: PostRecord,
+    pub(crate) replies: Vec<PostRecord>,
+    /// Selection, with the post itself at index 0
+    pub(crate) list_state: ListState,
+}
+
+/// A post being written
+pub(crate) struct Composer {
+    pub(crate) text: String,
+    pub(crate) reply_to: Option<ExternalEventId>,
+    /// Whether the rendered djot is shown instead of the source
+    pub(crate) preview: bool,
+}
+
+pub(crate) struct App {
+    client: Arc<Client>,
+    id_secret: Option<RostraIdSecretKey>,
+    pub(crate) timeline: Timeline,
+    pub(crate) posts: Vec<PostRecord>,
+    pub(crate) list_state: ListState,
+    pub(crate) thread: Option<Thread>,
+    pub(crate) composer: Option<Composer>,
+    /// Message shown in the status line until the next key press
+    pub(crate) status: Option<String>,
+    display_names: HashMap<RostraId, String>,
+    quit: bool,
+}
+
+impl App {
+    pub(crate) fn new(client: Arc<Client>, id_secret: Option<RostraIdSecretKey>) -> Self {
+        Self {
+            client,
+            id_secret,
+            timeline: Timeline::Following,
+            posts: vec![],
+            list_state: ListState::default(),
+            thread: None,
+            composer: None,
+            status: None,
+            display_names: HashMap::new(),
+            quit: false,
+        }
+    }
+
+    pub(crate) fn should_quit(&self) -> bool {
+        self.quit
+    }
+
+    pub(crate) fn is_read_only(&self) -> bool {
+        self.id_secret.is_none()
+    }
+
+    /// Display name of `id`, or its short id if it has no profile
+    pub(crate) fn display_name(&self, id: RostraId) -> String {
+        self.display_names
+            .get(&id)
+            .cloned()
+            .unwrap_or_else(|| id.to_short().to_string())
+    }
+
+    async fn load_display_names(&mut self, ids: impl IntoIterator<Item = RostraId>) {
+        for id in ids {
+            if self.display_names.contains_key(&id) {
+                continue;
+            }
+            if let Some(profile) = self.client.db().get_social_profile(id).await {
+                let display_name = profile.display_name.trim();
+                if !display_name.is_empty() {
+                    self.display_names.insert(id, display_name.to_owned());
+                }
+            }
+        }
+    }
+
+    /// Reload the current timeline, keeping the selected post if still there
+    pub(crate) async fn reload_timeline(&mut self) {
+        let selected = self
+            .list_state
+            .selected()
+            .and_then(|i| self.posts.get(i))
+            .map(|post| post.event_id);
+
+        self.posts = self
+            .timeline
+            .load(self.client.db(), self.client.rostra_id(), TIMELINE_LIMIT)
+            .await;
+        let authors: Vec<_> = self.posts.iter().map(|post| post.author).collect();
+        self.load_display_names(authors).await;
+
+        let selected = selected
+            .and_then(|event_id| self.posts.iter().position(|post| post.event_id == event_id))
+            .or((!self.posts.is_empty()).then_some(0));
+        self.list_state.select(selected);
+    }
+
+    /// Load the thread of `event_id`, keeping the selection if it is open
+    async fn load_thread(&mut self, event_id: ShortEventId) {
+        let db = self.client.db();
+        let Some(post) = db.get_social_post(event_id).await else {
+            self.status = Some("Post not found locally".to_owned());
+            return;
+        };
+        let (replies, _) = db
+            .paginate_social_post_comments_rev(event_id, None, THREAD_LIMIT)
+            .await;
+        let authors: Vec<_> = replies
+            .iter()
+            .map(|reply| reply.author)
+            .chain([post.author])
+            .collect();
+        self.load_display_names(authors).await;
+
+        let selected = self
+            .thread
+            .as_ref()
+            .filter(|thread| thread.post.event_id == event_id)
+            .and_then(|thread| thread.list_state.selected())
+            .map_or(0, |selected| selected.min(replies.len()));
+        let mut list_state = ListState::default();
+        list_state.select(Some(selected));
+        self.thread = Some(Thread {
+            post,
+            replies,
+            list_state,
+        });
+    }
+
+    /// Reload the timeline and the open thread
+    pub(crate) async fn refresh(&mut self) {
+        self.reload_timeline().await;
+        if let Some(event_id) = self.thread.as_ref().map(|thread| thread.post.event_id) {
+            self.load_thread(event_id).await;
+        }
+    }
+
+    pub(crate) async fn handle_new_post(&mut self, post: &SocialPost) {
+        debug!(target: LOG_TARGET, "New post received");
+        self.reload_timeline().await;
+        let thread_event_id = self.thread.as_ref().map(|thread| thread.post.event_id);
+        if let Some(event_id) = thread_event_id {
+            if post.reply_to.map(ExternalEventId::event_id) == Some(event_id) {
+                self.load_thread(event_id).await;
+            }
+        }
+    }
+
+    /// The post currently selected, in the open thread or the timeline
+    fn selected_post(&self) -> Option<&PostRecord> {
+        match &self.thread {
+            Some(thread) => match thread.list_state.selected() {
+                Some(0) | None => Some(&thread.post),
+                Some(i) => thread.replies.get(i - 1),
+            },
+            None => self.list_state.selected().and_then(|i| self.posts.get(i)),
+        }
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let (list_state, len) = match &mut self.thread {
+            Some(thread) => (&mut thread.list_state, thread.replies.len() + 1),
+            None => (&mut self.list_state, self.posts.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        let selected = list_state.selected().unwrap_or(0);
+        let selected = if down {
+            (selected + 1).min(len - 1)
+        } else {
+            selected.saturating_sub(1)
+        };
+        list_state.select(Some(selected));
+    }
+
+    async fn switch_timeline(&mut self, timeline: Timeline) {
+        if self.timeline != timeline {
+            self.timeline = timeline;
+            self.list_state = ListState::default();
+        }
+        self.thread = None;
+        self.reload_timeline().await;
+    }
+
+    fn open_composer(&mut self, reply_to: Option<ExternalEventId>) {
+        if self.is_read_only() {
+            self.status = Some("Read-only: no secret key given".to_owned());
+            return;
+        }
+        self.composer = Some(Composer {
+            text: String::new(),
+            reply_to,
+            preview: false,
+        });
+    }
+
+    pub(crate) async fn handle_key(&mut self, key: KeyEvent) {
+        self.status = None;
+        if self.composer.is_some() {
+            self.handle_composer_key(key).await;
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Esc => self.thread = None,
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(true),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(false),
+            KeyCode::Tab | KeyCode::BackTab => {
+                let i = Timeline::ALL
+                    .iter()
+                    .position(|timeline| *timeline == self.timeline)
+                    .unwrap_or(0);
+                let len = Timeline::ALL.len();
+                let i = if key.code == KeyCode::Tab {
+                    (i + 1) % len
+                } else {
+                    (i + len - 1) % len
+                };
+                self.switch_timeline(Timeline::ALL[i]).await;
+            }
+            KeyCode::Char(c @ '1'..='4') => {
+                let i = usize::from(c as u8 - b'1');
+                self.switch_timeline(Timeline::ALL[i]).await;
+            }
+            KeyCode::Enter => {
+                if let Some(event_id) = self.selected_post().map(|post| post.event_id) {
+                    self.load_thread(event_id).await;
+                }
+            }
+            KeyCode::Char('n') => self.open_composer(None),
+            KeyCode::Char('r') => {
+                if let Some(post) = self.selected_post() {
+                    let reply_to = ExternalEventId::new(post.author, post.event_id);
+                    self.open_composer(Some(reply_to));
+                }
+            }
+            KeyCode::Char('g') => self.refresh().await,
+            _ => {}
+        }
+    }
+
+    async fn handle_composer_key(&mut self, key: KeyEvent) {
+        let Some(composer) = &mut self.composer else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.composer = None,
+            KeyCode::Char('s') if ctrl => self.send().await,
+            KeyCode::Tab => composer.preview = !composer.preview,
+            _ if composer.preview => {}
+            KeyCode::Enter => composer.text.push('\n'),
+            KeyCode::Backspace => {
+                composer.text.pop();
+            }
+            KeyCode::Char(c) if !ctrl => composer.text.push(c),
+            _ => {}
+        }
+    }
+
+    async fn send(&mut self) {
+        let (Some(composer), Some(id_secret)) = (&self.composer, self.id_secret) else {
+            return;
+        };
+        if composer.text.trim().is_empty() {
+            self.status = Some("Nothing to post".to_owned());
+            return;
+        }
+        match self
+            .client
+            .social_post(
+                id_secret,
+                composer.text.clone(),
+                composer.reply_to,
+                Default::default(),
+            )
+            .await
+        {
+            Ok(_) => {
+                self.composer = None;
+                self.status = Some("Posted".to_owned());
+                self.refresh().await;
+            }
+            Err(err) => {
+                self.status = Some(format!("Posting failed: {err}"));
+            }
+        }
+    }
+}
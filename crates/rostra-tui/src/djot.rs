@@ -0,0 +1,250 @@
+//! Rendering of djot content as styled terminal text
+//!
+//! Keeps what reads well in a terminal: block layout, emphasis, code and link
+//! targets. Raw content is shown as its source.
+
+use jotup::{Container, Event, ListKind, Parser};
+use ratatui::style::{Modifier, Style, Stylize as _};
+use ratatui::text::{Line, Span};
+
+/// Render djot content as lines of styled text
+pub fn render(djot_content: &str) -> Vec<Line<'static>> {
+    let mut renderer = Renderer::default();
+    for event in Parser::new(djot_content) {
+        renderer.event(event);
+    }
+    renderer.finish()
+}
+
+fn code_style() -> Style {
+    Style::default().fg(ratatui::style::Color::Yellow)
+}
+
+/// A container being rendered
+enum Open {
+    Blockquote,
+    /// A list, with the number of its next item if ordered
+    List {
+        next: Option<u64>,
+    },
+    CodeBlock,
+    Heading,
+    /// Any other block container
+    Block,
+    /// An inline container styling its text
+    Inline(Style),
+    /// A link, followed by its target once closed
+    Link(String),
+}
+
+impl Open {
+    fn is_block(&self) -> bool {
+        !matches!(self, Open::Inline(_) | Open::Link(_))
+    }
+}
+
+#[derive(Default)]
+struct Renderer {
+    lines: Vec<Line<'static>>,
+    /// Spans of the line being rendered
+    line: Vec<Span<'static>>,
+    /// Open containers, innermost last
+    open: Vec<Open>,
+    /// Whether to put an empty line before the next block
+    gap: bool,
+}
+
+impl Renderer {
+    fn style(&self) -> Style {
+        self.open
+            .iter()
+            .fold(Style::default(), |style, open| match open {
+                Open::Heading => style.add_modifier(Modifier::BOLD),
+                Open::CodeBlock => style.patch(code_style()),
+                Open::Inline(inline) => style.patch(*inline),
+                Open::Link(_) => style.add_modifier(Modifier::UNDERLINED),
+                _ => style,
+            })
+    }
+
+    fn in_list(&self) -> bool {
+        self.open
+            .iter()
+            .any(|open| matches!(open, Open::List { .. }))
+    }
+
+    fn push(&mut self, text: impl Into<String>) {
+        let style = self.style();
+        self.line.push(Span::styled(text.into(), style));
+    }
+
+    fn flush_line(&mut self) {
+        let quote_depth = self
+            .open
+            .iter()
+            .filter(|open| matches!(open, Open::Blockquote))
+            .count();
+        let mut spans = Vec::with_capacity(self.line.len() + 1);
+        if 0 < quote_depth {
+            spans.push(Span::raw("> ".repeat(quote_depth)).dim());
+        }
+        spans.append(&mut self.line);
+        self.lines.push(Line::from(spans));
+    }
+
+    fn start_block(&mut self) {
+        if self.gap && !self.lines.is_empty() {
+            self.lines.push(Line::default());
+        }
+        self.gap = false;
+    }
+
+    fn start(&mut self, container: Container<'_>) {
+        let open = match container {
+            Container::Paragraph | Container::Div { .. } => {
+                // List items start their first paragraph on the bullet line
+                if self.line.is_empty() {
+                    self.start_block();
+                }
+                Open::Block
+            }
+            Container::Heading { level, .. } => {
+                self.start_block();
+                self.open.push(Open::Heading);
+                self.push(format!("{} ", "#".repeat(usize::from(level))));
+                return;
+            }
+            Container::CodeBlock { .. } | Container::RawBlock { .. } => {
+                self.start_block();
+                Open::CodeBlock
+            }
+            Container::Blockquote => {
+                self.start_block();
+                Open::Blockquote
+            }
+            Container::List { kind, .. } => {
+                if !self.in_list() {
+                    self.start_block();
+                }
+                Open::List {
+                    next: match kind {
+                        ListKind::Ordered { start, .. } => Some(start),
+                        ListKind::Unordered(_) | ListKind::Task(_) => None,
+                    },
+                }
+            }
+            Container::ListItem | Container::TaskListItem { .. } => {
+                if !self.line.is_empty() {
+                    self.flush_line();
+                }
+                let depth = self
+                    .open
+                    .iter()
+                    .filter(|open| matches!(open, Open::List { .. }))
+                    .count();
+                let bullet = match self.open.iter_mut().rev().find_map(|open| match open {
+                    Open::List { next } => Some(next),
+                    _ => None,
+                }) {
+                    Some(Some(next)) => {
+                        *next += 1;
+                        format!("{}. ", *next - 1)
+                    }
+                    _ => "- ".to_owned(),
+                };
+                let bullet = match container {
+                    Container::TaskListItem { checked: true } => format!("{bullet}[x] "),
+                    Container::TaskListItem { checked: false } => format!("{bullet}[ ] "),
+                    _ => bullet,
+                };
+                self.line.push(Span::raw(format!(
+                    "{}{bullet}",
+                    "  ".repeat(depth.saturating_sub(1))
+                )));
+                Open::Block
+            }
+            Container::Strong => Open::Inline(Style::default().add_modifier(Modifier::BOLD)),
+            Container::Emphasis => Open::Inline(Style::default().add_modifier(Modifier::ITALIC)),
+            Container::Delete => Open::Inline(Style::default().add_modifier(Modifier::CROSSED_OUT)),
+            Container::Insert => Open::Inline(Style::default().add_modifier(Modifier::UNDERLINED)),
+            Container::Mark => Open::Inline(Style::default().add_modifier(Modifier::REVERSED)),
+            Container::Verbatim | Container::RawInline { .. } | Container::Math { .. } => {
+                Open::Inline(code_style())
+            }
+            Container::Link(target, _) | Container::Image(target, _) => {
+                Open::Link(target.to_string())
+            }
+            Container::Span | Container::Subscript | Container::Superscript => {
+                Open::Inline(Style::default())
+            }
+            _ => Open::Block,
+        };
+        self.open.push(open);
+    }
+
+    fn end(&mut self) {
+        let Some(open) = self.open.pop() else {
+            return;
+        };
+        if let Open::Link(target) = &open {
+            if !target.is_empty() {
+                self.line.push(Span::raw(format!(" <{target}>")).dim());
+            }
+        }
+        if open.is_block() {
+            if !self.line.is_empty() {
+                self.flush_line();
+            }
+            self.gap = !self.in_list();
+        }
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(container, _) => self.start(container),
+            Event::End => self.end(),
+            Event::Str(text) => {
+                if matches!(self.open.last(), Some(Open::CodeBlock)) {
+                    for (i, part) in text.split('\n').enumerate() {
+                        if 0 < i {
+                            self.flush_line();
+                        }
+                        if !part.is_empty() {
+                            self.push(part);
+                        }
+                    }
+                } else {
+                    self.push(text.to_string());
+                }
+            }
+            Event::Softbreak | Event::NonBreakingSpace => self.push(" "),
+            Event::Hardbreak => self.flush_line(),
+            Event::LeftSingleQuote => self.push("\u{2018}"),
+            Event::RightSingleQuote => self.push("\u{2019}"),
+            Event::LeftDoubleQuote => self.push("\u{201C}"),
+            Event::RightDoubleQuote => self.push("\u{201D}"),
+            Event::Ellipsis => self.push("\u{2026}"),
+            Event::EnDash => self.push("\u{2013}"),
+            Event::EmDash => self.push("\u{2014}"),
+            Event::Symbol(symbol) => self.push(format!(":{symbol}:")),
+            Event::FootnoteReference(label) => self.push(format!("[^{label}]")),
+            Event::ThematicBreak(_) => {
+                self.start_block();
+                self.lines
+                    .push(Line::from("\u{2014}\u{2014}\u{2014}").dim());
+                self.gap = true;
+            }
+            Event::Escape | Event::Blankline | Event::Attributes(_) => {}
+        }
+    }
+
+    fn finish(mut self) -> Vec<Line<'static>> {
+        if !self.line.is_empty() {
+            self.flush_line();
+        }
+        self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests;
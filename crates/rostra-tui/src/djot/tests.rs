@@ -0,0 +1,54 @@
+use ratatui::style::Modifier;
+
+use super::render;
+
+fn plain(djot_content: &str) -> Vec<String> {
+    render(djot_content)
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn blocks_are_separated_by_empty_lines() {
+    assert_eq!(
+        plain("# Title\n\nFirst\nline\n\nSecond\n"),
+        vec!["# Title", "", "First line", "", "Second"]
+    );
+}
+
+#[test]
+fn lists_quotes_and_code_keep_their_layout() {
+    assert_eq!(
+        plain("- a\n- b\n\n1. one\n2. two\n\n> quoted\n\n``` rust\nfn x() {}\nlet y;\n```\n"),
+        vec![
+            "- a",
+            "- b",
+            "",
+            "1. one",
+            "2. two",
+            "",
+            "> quoted",
+            "",
+            "fn x() {}",
+            "let y;",
+        ]
+    );
+}
+
+#[test]
+fn inline_styles_and_link_targets_are_kept() {
+    let lines = render("*bold* and [a link](https://rostra.me)\n");
+    let spans = &lines[0].spans;
+    assert_eq!(spans[0].content, "bold");
+    assert!(spans[0].style.add_modifier.contains(Modifier::BOLD));
+    assert_eq!(
+        plain("*bold* and [a link](https://rostra.me)\n"),
+        vec!["bold and a link <https://rostra.me>"]
+    );
+}
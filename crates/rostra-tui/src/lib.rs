@@ -0,0 +1,88 @@
+//! Terminal UI of Rostra
+//!
+//! Renders the same timelines and threads as the web UI, from the database of
+//! a running [`Client`], and lets the user compose posts when the identity's
+//! secret is available. New posts show up as the client receives them.
+
+mod app;
+pub mod djot;
+mod ui;
+
+use std::io;
+use std::sync::Arc;
+use std::thread;
+
+use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use rostra_client::Client;
+use rostra_core::id::RostraIdSecretKey;
+use snafu::{ResultExt as _, Snafu};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::app::App;
+
+pub const LOG_TARGET: &str = "rostra::tui";
+
+#[derive(Debug, Snafu)]
+pub enum TuiError {
+    #[snafu(display("Terminal error"))]
+    Terminal { source: io::Error },
+}
+
+pub type TuiResult<T> = std::result::Result<T, TuiError>;
+
+/// Run the terminal UI of `client` until the user quits
+///
+/// Without `id_secret` the UI is read-only.
+pub async fn run_tui(client: Arc<Client>, id_secret: Option<RostraIdSecretKey>) -> TuiResult<()> {
+    let mut terminal = ratatui::init();
+    let res = run(&mut terminal, client, id_secret).await;
+    ratatui::restore();
+    res
+}
+
+async fn run(
+    terminal: &mut ratatui::DefaultTerminal,
+    client: Arc<Client>,
+    id_secret: Option<RostraIdSecretKey>,
+) -> TuiResult<()> {
+    let mut new_posts = client.new_posts_subscribe();
+    let mut app = App::new(client, id_secret);
+    app.reload_timeline().await;
+
+    // Crossterm input is blocking, so it is read on its own thread
+    let (input_tx, mut input_rx) = mpsc::channel(16);
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            if input_tx.blocking_send(event).is_err() {
+                break;
+            }
+        }
+    });
+
+    while !app.should_quit() {
+        terminal
+            .draw(|frame| ui::draw(frame, &mut app))
+            .context(TerminalSnafu)?;
+
+        tokio::select! {
+            event = input_rx.recv() => {
+                let Some(event) = event else {
+                    break;
+                };
+                if let Event::Key(key) = event {
+                    if key.kind == KeyEventKind::Press {
+                        app.handle_key(key).await;
+                    }
+                }
+            }
+            res = new_posts.recv() => {
+                match res {
+                    Ok((_, post)) => app.handle_new_post(&post).await,
+                    Err(broadcast::error::RecvError::Lagged(_)) => app.refresh().await,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        }
+    }
+    Ok(())
+}
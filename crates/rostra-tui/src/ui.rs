@@ -0,0 +1,230 @@
+//! Drawing of the terminal UI
+
+use ratatui::Frame;
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style, Stylize as _};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListItem, Paragraph, Tabs, Wrap};
+use rostra_client::timeline::Timeline;
+use rostra_core::Timestamp;
+
+use crate::app::{App, Composer, PostRecord};
+use crate::djot;
+
+/// Number of content lines shown per post in a timeline
+const TIMELINE_POST_LINES: usize = 8;
+
+/// Height of the composer, including its border
+const COMPOSER_HEIGHT: u16 = 12;
+
+pub(crate) fn draw(frame: &mut Frame, app: &mut App) {
+    let composer_height = if app.composer.is_some() {
+        COMPOSER_HEIGHT
+    } else {
+        0
+    };
+    let [tabs_area, main_area, composer_area, status_area] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(3),
+        Constraint::Length(composer_height),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+
+    draw_tabs(frame, app, tabs_area);
+    if app.thread.is_some() {
+        draw_thread(frame, app, main_area);
+    } else {
+        draw_timeline(frame, app, main_area);
+    }
+    if let Some(composer) = &app.composer {
+        draw_composer(frame, composer, composer_area);
+    }
+    draw_status(frame, app, status_area);
+}
+
+fn draw_tabs(frame: &mut Frame, app: &App, area: Rect) {
+    let selected = Timeline::ALL
+        .iter()
+        .position(|timeline| *timeline == app.timeline);
+    let tabs = Tabs::new(
+        Timeline::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, timeline)| format!("{} {}", i + 1, timeline.name())),
+    )
+    .select(selected)
+    .highlight_style(Style::default().add_modifier(Modifier::BOLD | Modifier::REVERSED));
+    frame.render_widget(tabs, area);
+}
+
+fn draw_timeline(frame: &mut Frame, app: &mut App, area: Rect) {
+    let width = content_width(area);
+    let items: Vec<_> = app
+        .posts
+        .iter()
+        .map(|post| post_item(app, post, width, Some(TIMELINE_POST_LINES)))
+        .collect();
+    let title = if items.is_empty() {
+        format!(" {} (empty) ", app.timeline.name())
+    } else {
+        format!(" {} ", app.timeline.name())
+    };
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_symbol("\u{258C}");
+    frame.render_stateful_widget(list, area, &mut app.list_state);
+}
+
+fn draw_thread(frame: &mut Frame, app: &mut App, area: Rect) {
+    let Some(thread) = &app.thread else {
+        return;
+    };
+    let width = content_width(area);
+    let items: Vec<_> = [&thread.post]
+        .into_iter()
+        .chain(&thread.replies)
+        .map(|post| post_item(app, post, width, None))
+        .collect();
+    let title = format!(" Thread, {} replies ", thread.replies.len());
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_symbol("\u{258C}");
+    let Some(thread) = &mut app.thread else {
+        return;
+    };
+    frame.render_stateful_widget(list, area, &mut thread.list_state);
+}
+
+fn draw_composer(frame: &mut Frame, composer: &Composer, area: Rect) {
+    let title = match (composer.reply_to.is_some(), composer.preview) {
+        (false, false) => " New post ",
+        (true, false) => " Reply ",
+        (false, true) => " New post (preview) ",
+        (true, true) => " Reply (preview) ",
+    };
+    let text = if composer.preview {
+        Text::from(djot::render(&composer.text))
+    } else {
+        Text::from(format!("{}\u{2588}", composer.text))
+    };
+    let paragraph = Paragraph::new(text)
+        .wrap(Wrap { trim: false })
+        .block(Block::bordered().title(title));
+    frame.render_widget(paragraph, area);
+}
+
+fn draw_status(frame: &mut Frame, app: &App, area: Rect) {
+    let help = if app.composer.is_some() {
+        "Ctrl-S send  Tab preview  Esc cancel"
+    } else if app.thread.is_some() {
+        "j/k move  Enter open  r reply  n new  g reload  Esc back  q quit"
+    } else {
+        "1-4/Tab timeline  j/k move  Enter thread  r reply  n new  g reload  q quit"
+    };
+    let mut spans = vec![];
+    if app.is_read_only() {
+        spans.push(Span::raw("[read-only] ").yellow());
+    }
+    match &app.status {
+        Some(status) => spans.push(Span::raw(status.clone()).bold()),
+        None => spans.push(Span::raw(help).dim()),
+    }
+    frame.render_widget(Line::from(spans), area);
+}
+
+/// Width available to post content inside a bordered list
+fn content_width(area: Rect) -> usize {
+    usize::from(area.width.saturating_sub(3)).max(1)
+}
+
+fn post_item(
+    app: &App,
+    post: &PostRecord,
+    width: usize,
+    max_lines: Option<usize>,
+) -> ListItem<'static> {
+    let mut header = vec![
+        Span::raw(app.display_name(post.author)).bold(),
+        Span::raw(format!(" \u{b7} {}", format_ts(post.ts))).dim(),
+    ];
+    if post.reply_to.is_some() {
+        header.push(Span::raw(" \u{b7} reply").dim());
+    }
+    if 0 < post.reply_count {
+        header.push(Span::raw(format!(" \u{b7} {} replies", post.reply_count)).dim());
+    }
+
+    let content = if let Some(reaction) = post.content.get_reaction() {
+        vec![Line::from(reaction.to_owned())]
+    } else {
+        match post.content.djot_content.as_deref() {
+            Some("") | None => vec![Line::from("(deleted)").dim()],
+            Some(djot_content) => djot::render(djot_content),
+        }
+    };
+    let mut lines: Vec<_> = content
+        .into_iter()
+        .flat_map(|line| wrap_line(line, width))
+        .collect();
+    if let Some(max_lines) = max_lines {
+        if max_lines < lines.len() {
+            lines.truncate(max_lines);
+            lines.push(Line::from("\u{2026}").dim());
+        }
+    }
+
+    let mut text = vec![Line::from(header)];
+    text.extend(lines);
+    text.push(Line::default());
+    ListItem::new(text)
+}
+
+/// Split `line` into lines of at most `width` characters
+fn wrap_line(line: Line<'static>, width: usize) -> Vec<Line<'static>> {
+    if line
+        .spans
+        .iter()
+        .map(|span| span.content.chars().count())
+        .sum::<usize>()
+        <= width
+    {
+        return vec![line];
+    }
+    let mut lines = vec![];
+    let mut current = vec![];
+    let mut current_width = 0;
+    for span in line.spans {
+        let mut chunk = String::new();
+        for c in span.content.chars() {
+            if current_width == width {
+                current.push(Span::styled(std::mem::take(&mut chunk), span.style));
+                lines.push(Line::from(std::mem::take(&mut current)));
+                current_width = 0;
+            }
+            chunk.push(c);
+            current_width += 1;
+        }
+        if !chunk.is_empty() {
+            current.push(Span::styled(chunk, span.style));
+        }
+    }
+    if !current.is_empty() {
+        lines.push(Line::from(current));
+    }
+    lines
+}
+
+fn format_ts(ts: Timestamp) -> String {
+    match ts.to_offset_date_time() {
+        Some(ts) => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            ts.year(),
+            u8::from(ts.month()),
+            ts.day(),
+            ts.hour(),
+            ts.minute()
+        ),
+        None => u64::from(ts).to_string(),
+    }
+}
@@ -0,0 +1,371 @@
+//! Scoped API tokens, letting programs use the `-managed` API endpoints
+//! without ever handling the identity's recovery phrase.
+//!
+//! Minting a token generates a fresh device key and delegates signing to it
+//! (see [`rostra_client::Client::delegate_device`]). The device key is stored
+//! here, next to the sessions in `webui.redb`, together with the scopes and
+//! limits of the token. Requests authenticated with the token sign with the
+//! device key. Revoking the token deletes the key and revokes the delegation,
+//! so even a leaked copy of the key stops being accepted by other nodes. The
+//! delegation of a token with an expiry expires together with it, and the
+//! device key is deleted the first time the expired token is used.
+//!
+//! Unlike the root secrets in [`crate::secrets::SecretStore`], device keys
+//! are persisted, so tokens survive restarts. Only a hash of the token itself
+//! is stored: it is shown once, when minted.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bincode::{Decode, Encode};
+use data_encoding::HEXLOWER;
+use rand::Rng as _;
+use redb_bincode::TableDefinition;
+use rostra_core::Timestamp;
+use rostra_core::id::{RostraId, RostraIdSecretKey};
+use snafu::{ResultExt as _, Snafu};
+
+/// Prefix of the tokens, making them easy to recognize, e.g. by secret
+/// scanners
+const TOKEN_PREFIX: &str = "rostra-api-";
+
+/// Length of the window requests are counted in for rate limits
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Table of API tokens.
+///
+/// Key: identity and id of the token
+/// Value: the token's device key, scopes and limits
+const API_TOKENS_TABLE: TableDefinition<(RostraId, ApiTokenId), ApiTokenRecord> =
+    TableDefinition::new("rostra_web_ui::api_tokens");
+
+/// What an API token is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+pub enum ApiScope {
+    /// Publish posts and polls, and vote in polls
+    Post,
+    /// Follow and unfollow identities
+    Follow,
+    /// Read notifications
+    ReadNotifications,
+}
+
+impl ApiScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Post => "post",
+            Self::Follow => "follow",
+            Self::ReadNotifications => "read-notifications",
+        }
+    }
+}
+
+impl fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Id of an API token, derived from the token by hashing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+pub struct ApiTokenId([u8; 16]);
+
+impl ApiTokenId {
+    pub const ZERO: Self = Self([0; 16]);
+    pub const MAX: Self = Self([0xff; 16]);
+}
+
+impl fmt::Display for ApiTokenId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&HEXLOWER.encode(&self.0))
+    }
+}
+
+impl FromStr for ApiTokenId {
+    type Err = InvalidApiTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HEXLOWER
+            .decode(s.as_bytes())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(Self)
+            .ok_or(InvalidApiTokenError)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ApiTokenId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// An API token, as presented by a client
+#[derive(Clone, Copy)]
+pub struct ApiToken([u8; 32]);
+
+impl ApiToken {
+    pub fn generate() -> Self {
+        Self(rand::rng().random())
+    }
+
+    pub fn id(&self) -> ApiTokenId {
+        let hash = blake3::hash(&self.0);
+        ApiTokenId(hash.as_bytes()[..16].try_into().expect("Can't fail"))
+    }
+}
+
+impl fmt::Display for ApiToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{TOKEN_PREFIX}{}", HEXLOWER.encode(&self.0))
+    }
+}
+
+impl FromStr for ApiToken {
+    type Err = InvalidApiTokenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.strip_prefix(TOKEN_PREFIX)
+            .and_then(|hex| HEXLOWER.decode(hex.as_bytes()).ok())
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(Self)
+            .ok_or(InvalidApiTokenError)
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[snafu(display("Invalid API token"))]
+pub struct InvalidApiTokenError;
+
+/// A stored API token
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ApiTokenRecord {
+    /// Name given by the user, e.g. the program using the token
+    pub label: String,
+    /// Key the identity delegated to
+    pub device: RostraId,
+    /// Secret of `device`, used to sign on behalf of the identity
+    ///
+    /// `None` once the token expired and the key was deleted.
+    pub device_secret: Option<RostraIdSecretKey>,
+    pub scopes: BTreeSet<ApiScope>,
+    pub created_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+    /// Maximum number of requests per minute
+    pub rate_limit_per_minute: Option<u32>,
+}
+
+impl ApiTokenRecord {
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+#[derive(Debug, Snafu)]
+pub enum ApiTokenError {
+    #[snafu(display("Unknown or revoked API token"))]
+    UnknownToken,
+    #[snafu(display("API token expired"))]
+    Expired,
+    #[snafu(display("API token lacks the `{scope}` scope"))]
+    MissingScope { scope: ApiScope },
+    #[snafu(display("API token rate limit exceeded"))]
+    RateLimited,
+    #[snafu(display("API token storage error: {source}"))]
+    Storage { source: Box<redb::Error> },
+}
+
+pub type ApiTokenResult<T> = Result<T, ApiTokenError>;
+
+type ApiTokensTable<'txn> = redb_bincode::Table<'txn, (RostraId, ApiTokenId), ApiTokenRecord>;
+type ApiTokensReadOnlyTable = redb_bincode::ReadOnlyTable<(RostraId, ApiTokenId), ApiTokenRecord>;
+
+fn boxed(err: impl Into<redb::Error>) -> Box<redb::Error> {
+    Box::new(err.into())
+}
+
+/// Persistent storage of API tokens, with in-memory rate limiting.
+pub struct ApiTokenStore {
+    db: Arc<redb_bincode::Database>,
+    /// Start of the current rate limit window, and the number of requests in
+    /// it, per token
+    usage: Mutex<HashMap<ApiTokenId, (Instant, u32)>>,
+}
+
+impl ApiTokenStore {
+    /// Create the store, initializing its table if it doesn't exist.
+    ///
+    /// This performs blocking I/O.
+    pub fn new(db: Arc<redb_bincode::Database>) -> Result<Self, Box<redb::Error>> {
+        let write_txn = db.begin_write().map_err(boxed)?;
+        let _ = write_txn.open_table(&API_TOKENS_TABLE).map_err(boxed)?;
+        write_txn.commit().map_err(boxed)?;
+
+        Ok(Self {
+            db,
+            usage: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn read_with<T>(
+        &self,
+        f: impl FnOnce(&ApiTokensReadOnlyTable) -> Result<T, redb::StorageError> + Send + 'static,
+    ) -> ApiTokenResult<T>
+    where
+        T: Send + 'static,
+    {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            let read_txn = db.begin_read().map_err(boxed)?;
+            let table = read_txn.open_table(&API_TOKENS_TABLE).map_err(boxed)?;
+            f(&table).map_err(boxed)
+        })
+        .await
+        .expect("spawn_blocking panicked")
+        .context(StorageSnafu)
+    }
+
+    async fn write_with<T>(
+        &self,
+        f: impl FnOnce(&mut ApiTokensTable<'_>) -> Result<T, redb::StorageError> + Send + 'static,
+    ) -> ApiTokenResult<T>
+    where
+        T: Send + 'static,
+    {
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || {
+            let write_txn = db.begin_write().map_err(boxed)?;
+            let res = {
+                let mut table = write_txn.open_table(&API_TOKENS_TABLE).map_err(boxed)?;
+                f(&mut table).map_err(boxed)?
+            };
+            write_txn.commit().map_err(boxed)?;
+            Ok(res)
+        })
+        .await
+        .expect("spawn_blocking panicked")
+        .context(StorageSnafu)
+    }
+
+    /// Store a newly minted token of `rostra_id`
+    pub async fn insert(
+        &self,
+        rostra_id: RostraId,
+        token: ApiToken,
+        record: ApiTokenRecord,
+    ) -> ApiTokenResult<()> {
+        let key = (rostra_id, token.id());
+        self.write_with(move |table| {
+            table.insert(&key, &record)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// List the tokens of `rostra_id`, ordered by id
+    pub async fn list(
+        &self,
+        rostra_id: RostraId,
+    ) -> ApiTokenResult<Vec<(ApiTokenId, ApiTokenRecord)>> {
+        self.read_with(move |table| {
+            let mut tokens = vec![];
+            for entry in
+                table.range(&(rostra_id, ApiTokenId::ZERO)..=&(rostra_id, ApiTokenId::MAX))?
+            {
+                let (key, record) = entry?;
+                tokens.push((key.value().1, record.value()));
+            }
+            Ok(tokens)
+        })
+        .await
+    }
+
+    /// Remove a token of `rostra_id`, returning it if it existed
+    pub async fn remove(
+        &self,
+        rostra_id: RostraId,
+        id: ApiTokenId,
+    ) -> ApiTokenResult<Option<ApiTokenRecord>> {
+        self.usage.lock().expect("Locking failed").remove(&id);
+        self.write_with(move |table| Ok(table.remove(&(rostra_id, id))?.map(|g| g.value())))
+            .await
+    }
+
+    /// Check that `token` of `rostra_id` may be used for `scope` now, and
+    /// return the key to sign with
+    ///
+    /// Every successful call counts towards the token's rate limit.
+    pub async fn authorize(
+        &self,
+        rostra_id: RostraId,
+        token: ApiToken,
+        scope: ApiScope,
+    ) -> ApiTokenResult<RostraIdSecretKey> {
+        let id = token.id();
+        let record = self
+            .read_with(move |table| Ok(table.get(&(rostra_id, id))?.map(|g| g.value())))
+            .await?
+            .ok_or(ApiTokenError::UnknownToken)?;
+
+        let device_secret = match record.device_secret {
+            Some(device_secret) if !record.is_expired(Timestamp::now()) => device_secret,
+            Some(_) => {
+                self.wipe_device_secret(rostra_id, id).await?;
+                return Err(ApiTokenError::Expired);
+            }
+            None => return Err(ApiTokenError::Expired),
+        };
+        if !record.scopes.contains(&scope) {
+            return Err(ApiTokenError::MissingScope { scope });
+        }
+        if let Some(limit) = record.rate_limit_per_minute {
+            self.count_request(id, limit)?;
+        }
+
+        Ok(device_secret)
+    }
+
+    /// Delete the device key of an expired token, keeping the rest of it
+    /// listed
+    async fn wipe_device_secret(&self, rostra_id: RostraId, id: ApiTokenId) -> ApiTokenResult<()> {
+        self.write_with(move |table| {
+            let record = table.get(&(rostra_id, id))?.map(|g| g.value());
+            if let Some(record) = record {
+                table.insert(
+                    &(rostra_id, id),
+                    &ApiTokenRecord {
+                        device_secret: None,
+                        ..record
+                    },
+                )?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    fn count_request(&self, id: ApiTokenId, limit: u32) -> ApiTokenResult<()> {
+        let now = Instant::now();
+        let mut usage = self.usage.lock().expect("Locking failed");
+        let (window_start, count) = usage.entry(id).or_insert((now, 0));
+        if RATE_LIMIT_WINDOW <= now.duration_since(*window_start) {
+            *window_start = now;
+            *count = 0;
+        }
+        if limit <= *count {
+            return Err(ApiTokenError::RateLimited);
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
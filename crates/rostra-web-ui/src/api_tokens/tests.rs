@@ -0,0 +1,70 @@
+use rostra_client::Client;
+use rostra_client::error::PostError;
+use rostra_client_db::Database;
+
+use super::*;
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn expired_token_device_can_not_publish() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let db = redb::Database::builder()
+        .create(dir.path().join("webui.redb"))
+        .expect("database");
+    let store = ApiTokenStore::new(Arc::new(redb_bincode::Database::from(db))).expect("store");
+
+    let secret = RostraIdSecretKey::generate();
+    let client = Client::builder(secret.id())
+        .db(Database::new_in_memory(secret.id())
+            .await
+            .expect("in-memory database"))
+        .start_request_handler(false)
+        .start_background_tasks(false)
+        .build()
+        .await
+        .expect("test client");
+
+    // Minted the way the settings page does, expiring right away
+    let now = Timestamp::now();
+    let device = RostraIdSecretKey::generate();
+    client
+        .delegate_device_until(secret, device.id(), now)
+        .await
+        .expect("delegation published");
+    let token = ApiToken::generate();
+    store
+        .insert(
+            secret.id(),
+            token,
+            ApiTokenRecord {
+                label: "bot".to_owned(),
+                device: device.id(),
+                device_secret: Some(device),
+                scopes: BTreeSet::from([ApiScope::Post]),
+                created_at: now,
+                expires_at: Some(now),
+                rate_limit_per_minute: None,
+            },
+        )
+        .await
+        .expect("token stored");
+
+    assert!(matches!(
+        store.authorize(secret.id(), token, ApiScope::Post).await,
+        Err(ApiTokenError::Expired)
+    ));
+    let tokens = store.list(secret.id()).await.expect("tokens listed");
+    assert_eq!(tokens.len(), 1, "Expired tokens stay listed");
+    assert!(tokens[0].1.device_secret.is_none(), "Device key is wiped");
+    assert!(matches!(
+        store.authorize(secret.id(), token, ApiScope::Post).await,
+        Err(ApiTokenError::Expired)
+    ));
+
+    // Even a copy of the device key taken before the expiry is useless
+    assert!(matches!(
+        client
+            .social_post(device, "Expired".to_owned(), None, BTreeSet::new())
+            .await,
+        Err(PostError::NotDelegated)
+    ));
+}
@@ -73,8 +73,8 @@ pub enum UnlockError {
     #[snafu(display("RostraId or secret key is required"))]
     PublicKeyMissing,
     #[snafu(visibility(pub(crate)))]
-    #[snafu(display("RostraId does not match secret key"))]
-    IdMismatch,
+    #[snafu(display("Secret key is not of RostraId or a device it delegated to"))]
+    NotDelegated,
     #[snafu(transparent)]
     Io { source: io::Error },
     #[snafu(display("Failed to open database"))]
@@ -137,14 +137,26 @@ impl From<UiStateClientError> for RequestError {
     }
 }
 
-/// Route `PostError::Validation` through `UserRequestError` so it's
-/// discoverable by the error-chain walk in `IntoResponse`.
+/// Route `PostError::Validation`, poll, succession, device key and image
+/// format errors through `UserRequestError`
+/// so they're discoverable by the error-chain walk in `IntoResponse`.
 impl From<PostError> for RequestError {
     fn from(source: PostError) -> Self {
         match source {
             PostError::Validation { source } => RequestError::User {
                 source: source.into(),
             },
+            other @ (PostError::PollUnavailable
+            | PostError::PollClosed
+            | PostError::InvalidPollOption
+            | PostError::NoSuccessor
+            | PostError::NotDelegated
+            | PostError::RootSecretRequired
+            | PostError::UnsupportedImage { .. }) => RequestError::User {
+                source: UserRequestError::BadRequest {
+                    message: other.to_string(),
+                },
+            },
             other => RequestError::Other {
                 source: Box::new(other),
             },
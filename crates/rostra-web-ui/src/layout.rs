@@ -196,7 +196,8 @@ impl UiState {
         }
     }
 
-    /// Renders the top navigation bar with Home, Support, and Settings links
+    /// Renders the top navigation bar with Home, Search, Messages, Private,
+    /// Support, and Settings links
     pub fn render_top_nav(&self) -> Markup {
         html! {
             div ."o-topNav" {
@@ -204,6 +205,18 @@ impl UiState {
                     span ."o-topNav__icon -home" {}
                     "Home"
                 }
+                a ."o-topNav__item" href="/search" {
+                    span ."o-topNav__icon -search" {}
+                    "Search"
+                }
+                a ."o-topNav__item" href="/messages" {
+                    span ."o-topNav__icon -messages" {}
+                    "Messages"
+                }
+                a ."o-topNav__item" href="/private" {
+                    span ."o-topNav__icon -private" {}
+                    "Private"
+                }
                 a ."o-topNav__item" href="https://github.com/dpc/rostra/discussions" {
                     span ."o-topNav__icon -support" {}
                     "Support"
@@ -1,3 +1,4 @@
+mod api_tokens;
 mod error;
 pub mod html_utils;
 mod layout;
@@ -20,7 +21,7 @@ use axum::http::header::{ACCEPT, CONTENT_TYPE};
 use axum::http::{HeaderName, HeaderValue, Method};
 use axum::{Router, middleware};
 use axum_dpc_static_assets::{StaticAssetService, StaticAssets};
-use error::{IdMismatchSnafu, UnlockError, UnlockResult};
+use error::{NotDelegatedSnafu, UnlockError, UnlockResult};
 use listenfd::ListenFd;
 use rostra_client::error::IdSecretReadError;
 use rostra_client::multiclient::MultiClient;
@@ -127,6 +128,8 @@ pub struct UiState {
     secrets: secrets::SecretStore,
     /// Session store for checking session validity during GC.
     session_store: RedbSessionStore,
+    /// API tokens minted by the identities, stored next to the sessions.
+    api_tokens: api_tokens::ApiTokenStore,
     /// External origin URL (from `--origin`) for absolute links in meta tags.
     origin_url: Option<url::Url>,
     /// Whether permanent credentials may be shown over the configured
@@ -202,6 +205,10 @@ impl UiState {
         self.secrets.gc(session_token, &self.session_store).await;
     }
 
+    pub(crate) fn api_tokens(&self) -> &api_tokens::ApiTokenStore {
+        &self.api_tokens
+    }
+
     /// Load a client for read-only access (no secret key).
     ///
     /// Use this for default_profile or read-only unlock.
@@ -212,7 +219,10 @@ impl UiState {
 
     /// Unlock a client with optional secret key.
     ///
-    /// This loads the client and unlocks it if a secret is provided.
+    /// This loads the client and unlocks it if the secret of `rostra_id` is
+    /// provided. The secret of a device it delegated to is only checked: it
+    /// can sign events, but the client's background tasks need the identity's
+    /// own secret.
     /// The caller is responsible for storing the secret in the session
     /// after the session has been saved (so session.id() is available).
     pub async fn unlock(
@@ -221,9 +231,18 @@ impl UiState {
         secret_id: Option<RostraIdSecretKey>,
     ) -> UnlockResult<Option<RostraIdSecretKey>> {
         if let Some(secret_id) = secret_id {
-            ensure!(secret_id.id() == rostra_id, IdMismatchSnafu);
-            let client = self.clients.load(secret_id.id()).await?;
-            client.unlock_active(secret_id).await?;
+            let client = self.clients.load(rostra_id).await?;
+            if secret_id.id() == rostra_id {
+                client.unlock_active(secret_id).await?;
+            } else {
+                ensure!(
+                    client
+                        .db()
+                        .is_device_delegate(rostra_id, secret_id.id())
+                        .await,
+                    NotDelegatedSnafu
+                );
+            }
             Ok(Some(secret_id))
         } else {
             self.clients.load(rostra_id).await?;
@@ -269,6 +288,10 @@ pub enum WebUiServerError {
     SessionStore {
         source: SessionStoreError,
     },
+
+    ApiTokenStore {
+        source: Box<redb::Error>,
+    },
 }
 
 pub type ServerResult<T> = std::result::Result<T, WebUiServerError>;
@@ -363,6 +386,8 @@ async fn build_state_and_session(
     .context(SessionStoreSnafu)?;
 
     let session_store = RedbSessionStore::new(session_db.clone()).context(SessionStoreSnafu)?;
+    let api_tokens =
+        api_tokens::ApiTokenStore::new(session_db.clone()).context(ApiTokenStoreSnafu)?;
 
     let loopback_bind = matches!(&opts.listen, BindAddr::Tcp(addr) if addr.ip().is_loopback());
     let https_origin = opts
@@ -385,6 +410,7 @@ async fn build_state_and_session(
         welcome_redirect: opts.welcome_redirect.clone(),
         secrets: secrets::SecretStore::new(),
         session_store: session_store.clone(),
+        api_tokens,
         origin_url: opts.origin.clone(),
         recovery_transport_secure: loopback_http || https_origin,
     });
@@ -7,14 +7,18 @@ mod debug;
 mod feeds;
 pub mod fragment;
 mod media;
+mod messages;
 mod new_post;
 mod post;
+mod private_posts;
 mod profile;
+mod profile_mentions;
 pub(crate) mod profile_self;
 mod recovery;
 mod search;
 mod settings;
 mod shoutbox;
+mod tag;
 mod timeline;
 pub(crate) mod unlock;
 mod url;
@@ -195,7 +199,15 @@ pub fn route_handler(state: SharedState) -> Router<Arc<UiState>> {
             "/profile/{id}/follow",
             get(profile::get_follow_dialog).post(profile::post_follow),
         )
+        .route(
+            "/profile/{id}/accept-succession",
+            post(profile::post_accept_succession),
+        )
         .route("/profile/{id}/avatar", get(avatar::get))
+        .route(
+            "/profile/{id}/mentions",
+            get(profile_mentions::get_profile_mentions),
+        )
         .route("/media/{author}/{event_id}", get(media::get))
         .route("/media/{author}/list", get(media::list))
         .route(
@@ -209,6 +221,8 @@ pub fn route_handler(state: SharedState) -> Router<Arc<UiState>> {
             post(post::fetch_missing_post).get(post::fetch_missing_post),
         )
         .route("/post/{author}/{event}/delete", post(post::delete_post))
+        .route("/post/{author}/{event}/boost", post(post::boost_post))
+        .route("/post/{author}/{event}/poll_vote", post(post::vote_poll))
         .route(
             "/post/{author}/{event}/edit",
             get(post::get_edit_post).post(post::post_edit_post),
@@ -219,6 +233,7 @@ pub fn route_handler(state: SharedState) -> Router<Arc<UiState>> {
         )
         .route("/post/edit_preview", post(post::post_edit_post_preview))
         .route("/post", post(new_post::post_new_post))
+        .route("/post/draft", post(new_post::post_new_post_draft))
         .route(
             "/post/new_post_preview",
             post(new_post::get_new_post_preview),
@@ -237,6 +252,28 @@ pub fn route_handler(state: SharedState) -> Router<Arc<UiState>> {
             post(new_post::post_inline_reply_preview),
         )
         .route("/followee", post(add_followee::add_followee))
+        .route("/messages", get(messages::get_messages))
+        .route("/messages/new", get(messages::get_new_conversation))
+        .route(
+            "/messages/{id}",
+            get(messages::get_conversation).post(messages::post_direct_message),
+        )
+        .route(
+            "/private",
+            get(private_posts::get_private_posts).post(private_posts::post_private_post),
+        )
+        .route(
+            "/private/audience",
+            get(private_posts::get_private_audience),
+        )
+        .route(
+            "/private/audience/add",
+            post(private_posts::post_add_private_audience_member),
+        )
+        .route(
+            "/private/audience/remove",
+            post(private_posts::post_remove_private_audience_member),
+        )
         .route("/shoutbox", get(shoutbox::get_shoutbox))
         .route("/shoutbox/post", post(shoutbox::post_shoutbox))
         .route("/shoutbox/preview", post(shoutbox::post_shoutbox_preview))
@@ -248,9 +285,19 @@ pub fn route_handler(state: SharedState) -> Router<Arc<UiState>> {
             get(timeline::get_post_replies),
         )
         .route("/self/edit", post(profile_self::post_self_account_edit))
+        .route("/search", get(search::get_search))
         .route("/search/profiles", get(search::search_profiles))
+        .route("/tag/{name}", get(tag::get_tag))
         .route("/settings", get(settings::get_settings))
         .route("/settings/identity", get(settings::get_settings_identity))
+        .route(
+            "/settings/api-tokens",
+            get(settings::get_settings_api_tokens).post(settings::post_settings_api_tokens),
+        )
+        .route(
+            "/settings/api-tokens/{id}/revoke",
+            post(settings::post_settings_api_token_revoke),
+        )
         .route(
             "/settings/profile",
             get(settings::get_settings_profile).post(settings::post_settings_profile),
@@ -259,8 +306,22 @@ pub fn route_handler(state: SharedState) -> Router<Arc<UiState>> {
             "/settings/profile/preview",
             post(settings::post_settings_profile_preview),
         )
+        .route("/settings/drafts", get(settings::get_settings_drafts))
+        .route("/settings/drafts/{id}", post(settings::post_settings_draft))
+        .route(
+            "/settings/drafts/{id}/publish",
+            post(settings::post_settings_draft_publish),
+        )
+        .route(
+            "/settings/drafts/{id}/delete",
+            post(settings::post_settings_draft_delete),
+        )
         .route("/settings/following", get(settings::get_settings_following))
         .route("/settings/followers", get(settings::get_settings_followers))
+        .route(
+            "/settings/filters",
+            get(settings::get_settings_filters).post(settings::post_settings_filters),
+        )
         .route("/settings/events", get(settings::get_settings_events))
         .route(
             "/settings/events/content/{event_id}",
@@ -1,20 +1,33 @@
 use std::collections::{BTreeSet, HashMap};
+use std::convert::Infallible;
 use std::sync::Arc;
 
-use axum::extract::{FromRequestParts, Path, Query, State};
-use axum::http::StatusCode;
+use axum::extract::{FromRequestParts, OptionalFromRequestParts, Path, Query, State};
 use axum::http::request::Parts;
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::response::sse::{self, KeepAlive, Sse};
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use rostra_client_db::social::{EventPaginationCursor, ReceivedAtPaginationCursor};
+use futures::Stream;
+use rostra_client::Client;
+use rostra_client::error::PostError;
+use rostra_client_db::Database;
+use rostra_client_db::content_filters::ContentFilters;
+use rostra_client_db::drafts::{PostDraft, PostDraftId};
+use rostra_client_db::social::{
+    EventPaginationCursor, ReceivedAtPaginationCursor, SocialPostRecord,
+};
+use rostra_core::event::content_kind::{self, SocialPoll};
 use rostra_core::event::{
-    Event, EventContentRaw, EventSignature, PersonaTag, PersonasTagsSelector, SignedEvent,
-    SocialPost, VerifiedEvent, VerifiedEventContent,
+    Event, EventContentRaw, EventKind, EventSignature, PersonaTag, PersonasTagsSelector,
+    SignedEvent, SocialPost, VerifiedEvent, VerifiedEventContent,
 };
-use rostra_core::id::{ExternalEventId, RostraId, RostraIdSecretKey};
+use rostra_core::id::{ExternalEventId, RostraId, RostraIdSecretKey, ToShort as _};
 use rostra_core::{ShortEventId, Timestamp};
 use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
 
+use crate::api_tokens::{ApiScope, ApiToken, ApiTokenError};
 use crate::{SharedState, UiState};
 
 const API_VERSION_HEADER: &str = "x-rostra-api-version";
@@ -102,6 +115,98 @@ impl FromRequestParts<Arc<UiState>> for ApiIdSecret {
     }
 }
 
+/// Credentials of a request: the identity's secret from the
+/// `X-Rostra-Id-Secret` header, or an API token minted in the web UI from the
+/// `Authorization: Bearer` header.
+enum ApiAuth {
+    IdSecret(RostraIdSecretKey),
+    Token(ApiToken),
+}
+
+impl ApiAuth {
+    /// Check the credentials allow `scope` on behalf of `rostra_id`, and
+    /// return the secret to sign with.
+    ///
+    /// The identity's own secret allows everything.
+    async fn secret_for(
+        self,
+        state: &UiState,
+        rostra_id: RostraId,
+        scope: ApiScope,
+    ) -> ApiResult<RostraIdSecretKey> {
+        match self {
+            ApiAuth::IdSecret(id_secret) => {
+                if id_secret.id() != rostra_id {
+                    return Err(api_error(
+                        StatusCode::FORBIDDEN,
+                        "Secret key does not match the rostra_id",
+                    ));
+                }
+                Ok(id_secret)
+            }
+            ApiAuth::Token(token) => state
+                .api_tokens()
+                .authorize(rostra_id, token, scope)
+                .await
+                .map_err(|e| {
+                    let status = match e {
+                        ApiTokenError::UnknownToken | ApiTokenError::Expired => {
+                            StatusCode::UNAUTHORIZED
+                        }
+                        ApiTokenError::MissingScope { .. } => StatusCode::FORBIDDEN,
+                        ApiTokenError::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+                        ApiTokenError::Storage { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+                    };
+                    api_error(status, e.to_string())
+                }),
+        }
+    }
+}
+
+impl FromRequestParts<Arc<UiState>> for ApiAuth {
+    type Rejection = (StatusCode, Json<ApiErrorResponse>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<UiState>,
+    ) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(header::AUTHORIZATION) else {
+            let ApiIdSecret(id_secret) = ApiIdSecret::from_request_parts(parts, state).await?;
+            return Ok(ApiAuth::IdSecret(id_secret));
+        };
+        let token = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .and_then(|token| token.trim().parse().ok())
+            .ok_or_else(|| {
+                api_error(
+                    StatusCode::UNAUTHORIZED,
+                    "Invalid authorization header (expected `Bearer <API token>`)",
+                )
+            })?;
+        Ok(ApiAuth::Token(token))
+    }
+}
+
+impl OptionalFromRequestParts<Arc<UiState>> for ApiAuth {
+    type Rejection = (StatusCode, Json<ApiErrorResponse>);
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<UiState>,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        if !parts.headers.contains_key(header::AUTHORIZATION)
+            && !parts.headers.contains_key(API_SECRET_HEADER)
+        {
+            return Ok(None);
+        }
+        <Self as FromRequestParts<_>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
+    }
+}
+
 // -- Router --
 
 pub fn api_router() -> Router<Arc<UiState>> {
@@ -121,15 +226,56 @@ pub fn api_router() -> Router<Arc<UiState>> {
             post(publish_social_post_prepare),
         )
         .route("/{rostra_id}/publish", post(publish_signed_event))
+        .route(
+            "/{rostra_id}/publish-social-poll-managed",
+            post(publish_social_poll_managed),
+        )
+        .route("/{rostra_id}/poll-vote-managed", post(poll_vote_managed))
+        .route("/{rostra_id}/polls/{poll}", get(get_poll))
         .route("/{rostra_id}/follow-managed", post(follow_managed))
         .route("/{rostra_id}/unfollow-managed", post(unfollow_managed))
         .route("/{rostra_id}/followees", get(get_followees))
         .route("/{rostra_id}/followers", get(get_followers))
         .route("/{rostra_id}/notifications", get(get_notifications))
+        .route("/{rostra_id}/stream", get(stream_events))
         .route("/{rostra_id}/posts", get(get_posts_by_author))
         .route("/{rostra_id}/posts/{event_id}", get(get_single_post))
         .route("/{rostra_id}/following", get(get_following_timeline))
         .route("/{rostra_id}/network", get(get_network_timeline))
+        .route("/{rostra_id}/search", get(search_posts))
+        .route("/{rostra_id}/tags/{tag}", get(get_tag_timeline))
+        .route("/{rostra_id}/mentions/{id}", get(get_mentions_timeline))
+        .route(
+            "/{rostra_id}/publish-private-post-managed",
+            post(publish_private_post_managed),
+        )
+        .route("/{rostra_id}/private-posts", get(get_private_posts))
+        .route("/{rostra_id}/private-audience", get(get_private_audience))
+        .route(
+            "/{rostra_id}/private-audience/add",
+            post(add_private_audience_member),
+        )
+        .route(
+            "/{rostra_id}/private-audience/remove",
+            post(remove_private_audience_member),
+        )
+        .route(
+            "/{rostra_id}/content-filters",
+            get(get_content_filters).post(set_content_filters),
+        )
+        .route(
+            "/{rostra_id}/drafts",
+            get(get_post_drafts).post(create_post_draft),
+        )
+        .route("/{rostra_id}/drafts/{draft}", post(update_post_draft))
+        .route(
+            "/{rostra_id}/drafts/{draft}/publish",
+            post(publish_post_draft),
+        )
+        .route(
+            "/{rostra_id}/drafts/{draft}/delete",
+            post(delete_post_draft),
+        )
 }
 
 // -- Endpoints --
@@ -210,17 +356,11 @@ struct PublishSocialPostResponse {
 async fn publish_social_post_managed(
     State(state): State<SharedState>,
     _version: ApiVersion,
-    ApiIdSecret(id_secret): ApiIdSecret,
+    auth: ApiAuth,
     Path(rostra_id): Path<RostraId>,
     Json(req): Json<PublishSocialPostRequest>,
 ) -> ApiResult<Json<PublishSocialPostResponse>> {
-    // Verify secret matches the rostra_id
-    if id_secret.id() != rostra_id {
-        return Err(api_error(
-            StatusCode::FORBIDDEN,
-            "Secret key does not match the rostra_id",
-        ));
-    }
+    let id_secret = auth.secret_for(&state, rostra_id, ApiScope::Post).await?;
 
     // Load client (before unlock, so heads reflect the caller's view)
     state.load_client(rostra_id).await.map_err(|e| {
@@ -243,7 +383,7 @@ async fn publish_social_post_managed(
         )
     })?;
 
-    // Get current heads BEFORE unlock_active (which may create a
+    // Get current heads BEFORE unlocking (which may create a
     // node-announcement event and change heads). This ensures the
     // idempotence check matches what the caller saw via GET /heads.
     let current_heads = client_ref.db().get_heads_events_for_id(rostra_id).await;
@@ -274,12 +414,7 @@ async fn publish_social_post_managed(
     }
 
     // Unlock client (may create a node-announcement event)
-    client_ref.unlock_active(id_secret).await.map_err(|e| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Unlock error: {e}"),
-        )
-    })?;
+    unlock_if_own_secret(&client_ref, id_secret).await?;
 
     // Parse persona tags
     let persona_tags: BTreeSet<PersonaTag> = req
@@ -323,6 +458,218 @@ async fn publish_social_post_managed(
     }))
 }
 
+// -- Polls --
+
+#[derive(Deserialize)]
+struct PublishSocialPollRequest {
+    question: String,
+    options: Vec<String>,
+    closes_at: Option<Timestamp>,
+    #[serde(default)]
+    persona_tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PublishSocialPollResponse {
+    /// The post presenting the poll
+    event_id: String,
+    /// The poll itself, to vote in
+    poll: String,
+    heads: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PollVoteManagedRequest {
+    poll: ExternalEventId,
+    /// `null` retracts the vote
+    option: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct PollVoteManagedResponse {
+    event_id: String,
+    heads: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct PollOptionItem {
+    label: String,
+    votes: u64,
+}
+
+#[derive(Serialize)]
+struct PollResponse {
+    question: String,
+    options: Vec<PollOptionItem>,
+    closes_at: Option<u64>,
+    closed: bool,
+    /// Option voted for by `rostra_id`
+    self_vote: Option<u32>,
+}
+
+fn poll_post_error(e: PostError) -> (StatusCode, Json<ApiErrorResponse>) {
+    let status = match e {
+        PostError::Validation { .. }
+        | PostError::PollUnavailable
+        | PostError::PollClosed
+        | PostError::InvalidPollOption => StatusCode::BAD_REQUEST,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    api_error(status, format!("Failed to publish: {e}"))
+}
+
+async fn publish_social_poll_managed(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    auth: ApiAuth,
+    Path(rostra_id): Path<RostraId>,
+    Json(req): Json<PublishSocialPollRequest>,
+) -> ApiResult<Json<PublishSocialPollResponse>> {
+    let id_secret = auth.secret_for(&state, rostra_id, ApiScope::Post).await?;
+    let client = managed_client(&state, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    unlock_if_own_secret(&client_ref, id_secret).await?;
+
+    let persona_tags: BTreeSet<PersonaTag> = req
+        .persona_tags
+        .iter()
+        .filter_map(|s| PersonaTag::new(s).ok())
+        .collect();
+
+    let verified_event = client_ref
+        .social_poll(
+            id_secret,
+            SocialPoll {
+                question: req.question,
+                options: req.options,
+                closes_at: req.closes_at,
+            },
+            persona_tags,
+        )
+        .await
+        .map_err(poll_post_error)?;
+
+    let poll = client_ref
+        .db()
+        .get_social_post(verified_event.event_id.into())
+        .await
+        .and_then(|post| post.content.poll)
+        .ok_or_else(|| api_error(StatusCode::INTERNAL_SERVER_ERROR, "Published poll missing"))?;
+
+    let mut heads: Vec<String> = client_ref
+        .db()
+        .get_heads_events_for_id(rostra_id)
+        .await
+        .into_iter()
+        .map(|h| h.to_string())
+        .collect();
+    heads.sort();
+
+    Ok(Json(PublishSocialPollResponse {
+        event_id: ShortEventId::from(verified_event.event_id).to_string(),
+        poll: ExternalEventId::new(rostra_id, poll).to_string(),
+        heads,
+    }))
+}
+
+async fn poll_vote_managed(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    auth: ApiAuth,
+    Path(rostra_id): Path<RostraId>,
+    Json(req): Json<PollVoteManagedRequest>,
+) -> ApiResult<Json<PollVoteManagedResponse>> {
+    let id_secret = auth.secret_for(&state, rostra_id, ApiScope::Post).await?;
+    let client = managed_client(&state, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    unlock_if_own_secret(&client_ref, id_secret).await?;
+
+    let verified_event = client_ref
+        .set_social_poll_vote(id_secret, req.poll, req.option)
+        .await
+        .map_err(poll_post_error)?;
+
+    let mut heads: Vec<String> = client_ref
+        .db()
+        .get_heads_events_for_id(rostra_id)
+        .await
+        .into_iter()
+        .map(|h| h.to_string())
+        .collect();
+    heads.sort();
+
+    Ok(Json(PollVoteManagedResponse {
+        event_id: ShortEventId::from(verified_event.event_id).to_string(),
+        heads,
+    }))
+}
+
+async fn get_poll(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path((rostra_id, poll_str)): Path<(RostraId, String)>,
+) -> ApiResult<Json<PollResponse>> {
+    let poll_id: ExternalEventId = poll_str
+        .parse()
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid poll format"))?;
+
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    let client = state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let poll = client_ref
+        .db()
+        .get_social_poll(poll_id)
+        .await
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Poll not found"))?;
+    let tally = client_ref.db().get_social_poll_tally(poll_id).await;
+    let self_vote = client_ref.get_self_social_poll_vote(poll_id).await;
+
+    Ok(Json(PollResponse {
+        closed: poll.is_closed(Timestamp::now()),
+        closes_at: poll.closes_at.map(Timestamp::as_u64),
+        options: poll
+            .options
+            .into_iter()
+            .enumerate()
+            .map(|(index, label)| PollOptionItem {
+                label,
+                votes: tally.get(&(index as u32)).copied().unwrap_or_default(),
+            })
+            .collect(),
+        question: poll.question,
+        self_vote,
+    }))
+}
+
 // -- Update Social Profile --
 
 #[derive(Deserialize)]
@@ -380,12 +727,7 @@ async fn update_social_profile_managed(
         )
     })?;
 
-    client_ref.unlock_active(id_secret).await.map_err(|e| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Unlock error: {e}"),
-        )
-    })?;
+    unlock_if_own_secret(&client_ref, id_secret).await?;
 
     // Decode avatar if provided
     let avatar = match req.avatar {
@@ -411,10 +753,11 @@ async fn update_social_profile_managed(
         .post_social_profile_update(id_secret, req.display_name, req.bio, avatar)
         .await
         .map_err(|e| {
-            api_error(
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Failed to update profile: {e}"),
-            )
+            let status = match e {
+                PostError::UnsupportedImage { .. } => StatusCode::BAD_REQUEST,
+                _ => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            api_error(status, format!("Failed to update profile: {e}"))
         })?;
 
     // Get updated heads
@@ -559,25 +902,7 @@ async fn publish_signed_event(
         ));
     }
 
-    // Verify signature
-    let signed_event = SignedEvent::unverified(req.event, req.sig);
-    let verified_event = VerifiedEvent::verify_received_as_is(signed_event).map_err(|e| {
-        api_error(
-            StatusCode::BAD_REQUEST,
-            format!("Signature verification failed: {e}"),
-        )
-    })?;
-
-    // Verify content matches event hash/len
-    let verified_event_content = VerifiedEventContent::verify(verified_event, req.content)
-        .map_err(|e| {
-            api_error(
-                StatusCode::BAD_REQUEST,
-                format!("Content verification failed: {e}"),
-            )
-        })?;
-
-    // Load client and store
+    // Load client, to know the identity's delegated devices
     state.load_client(rostra_id).await.map_err(|e| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -598,6 +923,26 @@ async fn publish_signed_event(
         )
     })?;
 
+    // Verify signature
+    let delegates = client_ref.db().get_device_delegates(rostra_id).await;
+    let signed_event = SignedEvent::unverified(req.event, req.sig);
+    let verified_event = VerifiedEvent::verify_received_delegated(signed_event, &delegates)
+        .map_err(|e| {
+            api_error(
+                StatusCode::BAD_REQUEST,
+                format!("Signature verification failed: {e}"),
+            )
+        })?;
+
+    // Verify content matches event hash/len
+    let verified_event_content = VerifiedEventContent::verify(verified_event, req.content)
+        .map_err(|e| {
+            api_error(
+                StatusCode::BAD_REQUEST,
+                format!("Content verification failed: {e}"),
+            )
+        })?;
+
     client_ref
         .db()
         .try_process_event_with_content(&verified_event_content)
@@ -647,16 +992,11 @@ struct FollowManagedResponse {
 async fn follow_managed(
     State(state): State<SharedState>,
     _version: ApiVersion,
-    ApiIdSecret(id_secret): ApiIdSecret,
+    auth: ApiAuth,
     Path(rostra_id): Path<RostraId>,
     Json(req): Json<FollowManagedRequest>,
 ) -> ApiResult<Json<FollowManagedResponse>> {
-    if id_secret.id() != rostra_id {
-        return Err(api_error(
-            StatusCode::FORBIDDEN,
-            "Secret key does not match the rostra_id",
-        ));
-    }
+    let id_secret = auth.secret_for(&state, rostra_id, ApiScope::Follow).await?;
 
     let followee: RostraId = req
         .followee
@@ -694,12 +1034,7 @@ async fn follow_managed(
         )
     })?;
 
-    client_ref.unlock_active(id_secret).await.map_err(|e| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Unlock error: {e}"),
-        )
-    })?;
+    unlock_if_own_secret(&client_ref, id_secret).await?;
 
     let verified_event = client_ref
         .follow(id_secret, followee, selector)
@@ -734,16 +1069,11 @@ struct UnfollowManagedRequest {
 async fn unfollow_managed(
     State(state): State<SharedState>,
     _version: ApiVersion,
-    ApiIdSecret(id_secret): ApiIdSecret,
+    auth: ApiAuth,
     Path(rostra_id): Path<RostraId>,
     Json(req): Json<UnfollowManagedRequest>,
 ) -> ApiResult<Json<FollowManagedResponse>> {
-    if id_secret.id() != rostra_id {
-        return Err(api_error(
-            StatusCode::FORBIDDEN,
-            "Secret key does not match the rostra_id",
-        ));
-    }
+    let id_secret = auth.secret_for(&state, rostra_id, ApiScope::Follow).await?;
 
     let followee: RostraId = req
         .followee
@@ -770,12 +1100,7 @@ async fn unfollow_managed(
         )
     })?;
 
-    client_ref.unlock_active(id_secret).await.map_err(|e| {
-        api_error(
-            StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Unlock error: {e}"),
-        )
-    })?;
+    unlock_if_own_secret(&client_ref, id_secret).await?;
 
     let verified_event = client_ref
         .unfollow(id_secret, followee)
@@ -901,17 +1226,378 @@ async fn get_followers(
     Ok(Json(FollowersResponse { followers }))
 }
 
-// -- Notifications --
-
-#[derive(Deserialize)]
-struct NotificationsQuery {
-    ts: Option<Timestamp>,
-    seq: Option<u64>,
-}
+// -- Content filters --
 
-#[derive(Serialize)]
-struct NotificationItem {
-    event_id: String,
+/// Open the client of `rostra_id` for an endpoint managing its local settings
+async fn local_settings_client(
+    state: &SharedState,
+    id_secret: RostraIdSecretKey,
+    rostra_id: RostraId,
+) -> ApiResult<rostra_client::ClientHandle> {
+    if id_secret.id() != rostra_id {
+        return Err(api_error(
+            StatusCode::FORBIDDEN,
+            "Secret key does not match the rostra_id",
+        ));
+    }
+
+    managed_client(state, rostra_id).await
+}
+
+/// Open the client of `rostra_id` for a `-managed` endpoint, whose caller
+/// checked the credentials
+async fn managed_client(
+    state: &SharedState,
+    rostra_id: RostraId,
+) -> ApiResult<rostra_client::ClientHandle> {
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })
+}
+
+/// Unlock the client if `id_secret` is the identity's own secret
+///
+/// API tokens sign with the key of a delegated device instead, which
+/// doesn't activate the client: its background tasks need the identity's own
+/// secret.
+async fn unlock_if_own_secret(client: &Client, id_secret: RostraIdSecretKey) -> ApiResult<()> {
+    if id_secret.id() != client.rostra_id() {
+        return Ok(());
+    }
+    client.unlock_active(id_secret).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Unlock error: {e}"),
+        )
+    })
+}
+
+async fn get_content_filters(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+) -> ApiResult<Json<ContentFilters>> {
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    Ok(Json(client_ref.db().get_content_filters().await))
+}
+
+async fn set_content_filters(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+    Json(filters): Json<ContentFilters>,
+) -> ApiResult<Json<ContentFilters>> {
+    let filters = filters.normalized();
+    filters
+        .validate()
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e.to_string()))?;
+
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    client_ref
+        .db()
+        .set_content_filters(filters.clone())
+        .await
+        .map_err(|e| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to store content filters: {e}"),
+            )
+        })?;
+
+    Ok(Json(filters))
+}
+
+// -- Drafts --
+
+#[derive(Deserialize)]
+struct PostDraftRequest {
+    content: String,
+    reply_to: Option<String>,
+    #[serde(default)]
+    persona_tags: Vec<String>,
+    /// Unix timestamp (seconds) to publish the draft at
+    scheduled_at: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct PostDraftItem {
+    id: PostDraftId,
+    content: String,
+    reply_to: Option<String>,
+    persona_tags: Vec<String>,
+    scheduled_at: Option<u64>,
+    updated_at: u64,
+    last_error: Option<String>,
+}
+
+impl PostDraftItem {
+    fn new(id: PostDraftId, draft: PostDraft) -> Self {
+        Self {
+            id,
+            content: draft.content,
+            reply_to: draft.reply_to.map(|id| id.to_string()),
+            persona_tags: draft
+                .persona_tags
+                .into_iter()
+                .map(|t| t.to_string())
+                .collect(),
+            scheduled_at: draft.scheduled_at.map(Timestamp::as_u64),
+            updated_at: draft.updated_at.as_u64(),
+            last_error: draft.last_error,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct PostDraftsResponse {
+    drafts: Vec<PostDraftItem>,
+}
+
+impl PostDraftRequest {
+    fn into_draft(self) -> ApiResult<PostDraft> {
+        if self.content.trim().is_empty() {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "Post content cannot be empty",
+            ));
+        }
+        let reply_to: Option<ExternalEventId> = self
+            .reply_to
+            .as_deref()
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid reply_to format"))?;
+        let now = Timestamp::now();
+        let scheduled_at = self.scheduled_at.map(Timestamp::from);
+        if scheduled_at.is_some_and(|scheduled_at| scheduled_at <= now) {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "Scheduled time must be in the future",
+            ));
+        }
+        Ok(PostDraft {
+            content: self.content,
+            reply_to,
+            persona_tags: self
+                .persona_tags
+                .iter()
+                .filter_map(|s| PersonaTag::new(s).ok())
+                .collect(),
+            scheduled_at,
+            updated_at: now,
+            last_error: None,
+        })
+    }
+}
+
+async fn get_post_drafts(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+) -> ApiResult<Json<PostDraftsResponse>> {
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let drafts = client_ref
+        .db()
+        .list_post_drafts()
+        .await
+        .into_iter()
+        .map(|(id, draft)| PostDraftItem::new(id, draft))
+        .collect();
+
+    Ok(Json(PostDraftsResponse { drafts }))
+}
+
+async fn create_post_draft(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+    Json(req): Json<PostDraftRequest>,
+) -> ApiResult<Json<PostDraftItem>> {
+    let draft = req.into_draft()?;
+
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    // Scheduled drafts are published by the client only while it's unlocked
+    if draft.scheduled_at.is_some() {
+        unlock_if_own_secret(&client_ref, id_secret).await?;
+    }
+
+    let id = client_ref
+        .db()
+        .insert_post_draft(draft.clone())
+        .await
+        .map_err(|e| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to store draft: {e}"),
+            )
+        })?;
+
+    Ok(Json(PostDraftItem::new(id, draft)))
+}
+
+async fn update_post_draft(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path((rostra_id, id)): Path<(RostraId, PostDraftId)>,
+    Json(req): Json<PostDraftRequest>,
+) -> ApiResult<Json<PostDraftItem>> {
+    let draft = req.into_draft()?;
+
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    if draft.scheduled_at.is_some() {
+        unlock_if_own_secret(&client_ref, id_secret).await?;
+    }
+
+    let updated = client_ref
+        .db()
+        .update_post_draft(id, draft.clone())
+        .await
+        .map_err(|e| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to store draft: {e}"),
+            )
+        })?;
+    if !updated {
+        return Err(api_error(StatusCode::NOT_FOUND, "Draft not found"));
+    }
+
+    Ok(Json(PostDraftItem::new(id, draft)))
+}
+
+async fn publish_post_draft(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path((rostra_id, id)): Path<(RostraId, PostDraftId)>,
+) -> ApiResult<Json<PublishSocialPostResponse>> {
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    unlock_if_own_secret(&client_ref, id_secret).await?;
+
+    let verified_event =
+        client_ref
+            .publish_post_draft(id_secret, id)
+            .await
+            .map_err(|e| match e {
+                PostError::DraftNotFound => api_error(StatusCode::NOT_FOUND, "Draft not found"),
+                e => api_error(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    format!("Failed to publish: {e}"),
+                ),
+            })?;
+
+    let mut heads: Vec<String> = client_ref
+        .db()
+        .get_heads_events_for_id(rostra_id)
+        .await
+        .into_iter()
+        .map(|h| h.to_string())
+        .collect();
+    heads.sort();
+
+    Ok(Json(PublishSocialPostResponse {
+        event_id: ShortEventId::from(verified_event.event_id).to_string(),
+        heads,
+    }))
+}
+
+async fn delete_post_draft(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path((rostra_id, id)): Path<(RostraId, PostDraftId)>,
+) -> ApiResult<Json<PostDraftItem>> {
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let draft = client_ref
+        .db()
+        .remove_post_draft(id)
+        .await
+        .map_err(|e| {
+            api_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to remove draft: {e}"),
+            )
+        })?
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Draft not found"))?;
+
+    Ok(Json(PostDraftItem::new(id, draft)))
+}
+
+// -- Notifications --
+
+#[derive(Deserialize)]
+struct NotificationsQuery {
+    ts: Option<Timestamp>,
+    seq: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct NotificationItem {
+    event_id: String,
     author: String,
     ts: u64,
     content: Option<String>,
@@ -935,9 +1621,16 @@ struct NotificationsCursor {
 async fn get_notifications(
     State(state): State<SharedState>,
     _version: ApiVersion,
+    auth: Option<ApiAuth>,
     Path(rostra_id): Path<RostraId>,
     Query(query): Query<NotificationsQuery>,
 ) -> ApiResult<Json<NotificationsResponse>> {
+    // Public for now, but credentials, if sent, must allow reading
+    if let Some(auth) = auth {
+        auth.secret_for(&state, rostra_id, ApiScope::ReadNotifications)
+            .await?;
+    }
+
     state.load_client(rostra_id).await.map_err(|e| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -995,23 +1688,574 @@ async fn get_notifications(
         })
         .collect();
 
-    let next_cursor = next.map(|c| NotificationsCursor {
+    let next_cursor = next.map(|c| NotificationsCursor {
+        ts: c.ts.as_u64(),
+        seq: c.seq,
+    });
+
+    Ok(Json(NotificationsResponse {
+        notifications,
+        next_cursor,
+    }))
+}
+
+// -- Live stream --
+
+/// Posts read per query when catching up on missed posts
+const STREAM_CATCH_UP_BATCH: usize = 100;
+
+/// Where to resume a stream from, alternatively to `Last-Event-ID`
+#[derive(Deserialize)]
+struct StreamQuery {
+    ts: Option<Timestamp>,
+    seq: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct StreamPostItem {
+    event_id: String,
+    author: String,
+    ts: u64,
+    /// Djot content, `null` for reactions
+    content: Option<String>,
+    reaction: Option<String>,
+    reply_to: Option<String>,
+    persona_tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct StreamFollowItem {
+    author: String,
+    followee: String,
+    unfollow: bool,
+}
+
+#[derive(Serialize)]
+struct StreamHeadItem {
+    head: String,
+}
+
+#[derive(Serialize)]
+struct StreamLaggedItem {
+    missed: u64,
+}
+
+/// An update received while streaming
+///
+/// Lives only until handled, so its size doesn't matter.
+#[allow(clippy::large_enum_variant)]
+enum StreamUpdate {
+    Post(Result<(VerifiedEventContent, SocialPost), broadcast::error::RecvError>),
+    Head(Result<(RostraId, ShortEventId), broadcast::error::RecvError>),
+    Content(Result<VerifiedEventContent, broadcast::error::RecvError>),
+}
+
+/// Parse an event id of the stream, `{ts}-{seq}` of the post's reception
+fn parse_stream_event_id(id: &str) -> Option<ReceivedAtPaginationCursor> {
+    let (ts, seq) = id.split_once('-')?;
+    Some(ReceivedAtPaginationCursor {
+        ts: Timestamp::from(ts.parse::<u64>().ok()?),
+        seq: seq.parse().ok()?,
+    })
+}
+
+/// The first cursor after `cursor`, to resume from
+fn stream_cursor_after(cursor: ReceivedAtPaginationCursor) -> Option<ReceivedAtPaginationCursor> {
+    Some(ReceivedAtPaginationCursor {
+        ts: cursor.ts,
+        seq: cursor.seq.checked_add(1)?,
+    })
+}
+
+fn stream_event(name: &'static str, data: &impl Serialize) -> sse::Event {
+    sse::Event::default()
+        .event(name)
+        .json_data(data)
+        .expect("Can't fail")
+}
+
+/// Turn a post into a `reaction`, `mention` or `post` event
+async fn stream_post_event(
+    db: &Database,
+    self_id: RostraId,
+    cursor: Option<ReceivedAtPaginationCursor>,
+    post: SocialPostRecord<SocialPost>,
+) -> sse::Event {
+    let reaction = post.content.get_reaction().map(ToOwned::to_owned);
+    let name = if reaction.is_some() {
+        "reaction"
+    } else if post.author != self_id
+        && (post.reply_to.map(|ext_id| ext_id.rostra_id()) == Some(self_id)
+            || db.is_self_mention(post.event_id).await)
+    {
+        "mention"
+    } else {
+        "post"
+    };
+    let event = stream_event(
+        name,
+        &StreamPostItem {
+            event_id: post.event_id.to_string(),
+            author: post.author.to_string(),
+            ts: post.ts.as_u64(),
+            persona_tags: post
+                .content
+                .persona_tags()
+                .into_iter()
+                .map(|t| t.to_string())
+                .collect(),
+            content: if reaction.is_some() {
+                None
+            } else {
+                post.content.djot_content
+            },
+            reaction,
+            reply_to: post.reply_to.map(|r| r.to_string()),
+        },
+    );
+    match cursor {
+        Some(cursor) => event.id(format!("{}-{}", cursor.ts.as_u64(), cursor.seq)),
+        None => event,
+    }
+}
+
+/// Stream new posts, reactions, mentions, follows and heads as Server-Sent
+/// Events
+///
+/// Post events carry their reception cursor as the event id, so a client
+/// reconnecting with `Last-Event-ID` first gets all the posts received since.
+async fn stream_events(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    auth: Option<ApiAuth>,
+    Path(rostra_id): Path<RostraId>,
+    Query(query): Query<StreamQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Sse<impl Stream<Item = Result<sse::Event, Infallible>>>> {
+    // Public like notifications, but credentials, if sent, must allow reading
+    if let Some(auth) = auth {
+        auth.secret_for(&state, rostra_id, ApiScope::ReadNotifications)
+            .await?;
+    }
+
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    let client = state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let resume_from = match headers.get("last-event-id") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(parse_stream_event_id)
+                .ok_or_else(|| api_error(StatusCode::BAD_REQUEST, "Invalid Last-Event-ID"))?,
+        ),
+        None => query
+            .ts
+            .and_then(|ts| query.seq.map(|seq| ReceivedAtPaginationCursor { ts, seq })),
+    };
+    let catch_up_from = resume_from
+        .map(|cursor| {
+            stream_cursor_after(cursor)
+                .ok_or_else(|| api_error(StatusCode::BAD_REQUEST, "Invalid resume cursor"))
+        })
+        .transpose()?;
+
+    let db = client_ref.db().clone();
+    // Subscribe before catching up, so no post falls in between
+    let mut new_posts = client_ref.new_posts_subscribe();
+    let mut new_heads = client_ref.new_heads_subscribe();
+    let mut new_content = client_ref.new_content_subscribe();
+
+    let events = async_stream::stream! {
+        // Last post delivered, to skip live posts already caught up on
+        let mut last_delivered = resume_from;
+        let mut catch_up_from = catch_up_from;
+
+        loop {
+            if let Some(mut cursor) = catch_up_from.take() {
+                loop {
+                    let (posts, next) = db
+                        .paginate_social_posts_by_received_at(
+                            Some(cursor),
+                            STREAM_CATCH_UP_BATCH,
+                            |_| true,
+                        )
+                        .await;
+                    for post in posts {
+                        let post_cursor = db.get_social_post_received_at_cursor(post.event_id).await;
+                        last_delivered = last_delivered.max(post_cursor);
+                        yield Ok(stream_post_event(&db, rostra_id, post_cursor, post).await);
+                    }
+                    match next {
+                        Some(next) => cursor = next,
+                        None => break,
+                    }
+                }
+            }
+
+            let update = tokio::select! {
+                res = new_posts.recv() => StreamUpdate::Post(res),
+                res = new_heads.recv() => StreamUpdate::Head(res),
+                res = new_content.recv() => StreamUpdate::Content(res),
+            };
+
+            match update {
+                StreamUpdate::Post(Ok((event_content, social_post))) => {
+                    let event_id = event_content.event.event_id.to_short();
+                    let post_cursor = db.get_social_post_received_at_cursor(event_id).await;
+                    if post_cursor.is_some() && post_cursor <= last_delivered {
+                        continue;
+                    }
+                    last_delivered = last_delivered.max(post_cursor);
+                    // Caught up posts are filtered by the query, live ones here
+                    let author = event_content.event.event.author;
+                    if db.is_social_post_hidden(author, &social_post).await {
+                        continue;
+                    }
+                    let post = SocialPostRecord {
+                        ts: event_content.event.event.timestamp.into(),
+                        event_id,
+                        author,
+                        reply_to: social_post.reply_to,
+                        content: social_post,
+                        reply_count: 0,
+                    };
+                    yield Ok(stream_post_event(&db, rostra_id, post_cursor, post).await);
+                }
+                StreamUpdate::Post(Err(broadcast::error::RecvError::Lagged(missed))) => {
+                    // Missed posts can be looked up, missed heads and follows can't
+                    match last_delivered.and_then(stream_cursor_after) {
+                        Some(from) => catch_up_from = Some(from),
+                        None => yield Ok(stream_event("lagged", &StreamLaggedItem { missed })),
+                    }
+                }
+                StreamUpdate::Head(Ok((id, head))) => {
+                    if id == rostra_id {
+                        yield Ok(stream_event("head", &StreamHeadItem { head: head.to_string() }));
+                    }
+                }
+                StreamUpdate::Content(Ok(event_content)) => {
+                    let event = &event_content.event.event;
+                    if event.kind != EventKind::FOLLOW {
+                        continue;
+                    }
+                    let Ok(follow) = event_content.deserialize_cbor::<content_kind::Follow>() else {
+                        continue;
+                    };
+                    if event.author == rostra_id || follow.followee == rostra_id {
+                        yield Ok(stream_event(
+                            "follow",
+                            &StreamFollowItem {
+                                author: event.author.to_string(),
+                                followee: follow.followee.to_string(),
+                                unfollow: follow.is_unfollow(),
+                            },
+                        ));
+                    }
+                }
+                StreamUpdate::Head(Err(broadcast::error::RecvError::Lagged(missed)))
+                | StreamUpdate::Content(Err(broadcast::error::RecvError::Lagged(missed))) => {
+                    yield Ok(stream_event("lagged", &StreamLaggedItem { missed }));
+                }
+                StreamUpdate::Post(Err(broadcast::error::RecvError::Closed))
+                | StreamUpdate::Head(Err(broadcast::error::RecvError::Closed))
+                | StreamUpdate::Content(Err(broadcast::error::RecvError::Closed)) => break,
+            }
+        }
+    };
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+// -- Posts by author / Single post --
+
+async fn get_posts_by_author(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path(rostra_id): Path<RostraId>,
+    Query(query): Query<TimelineQuery>,
+) -> ApiResult<Json<TimelineResponse>> {
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    let client = state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let cursor = query.ts.and_then(|ts| {
+        query
+            .event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let author_id = rostra_id;
+
+    let (posts, next) = client_ref
+        .db()
+        .paginate_social_posts_rev(cursor, 20, move |post| post.author == author_id)
+        .await;
+
+    let posts = posts.into_iter().map(post_to_timeline_item).collect();
+
+    let next_cursor = next.map(|c| TimelineCursorResponse {
+        ts: c.ts.as_u64(),
+        event_id: c.event_id.to_string(),
+    });
+
+    Ok(Json(TimelineResponse { posts, next_cursor }))
+}
+
+async fn get_single_post(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path((rostra_id, event_id_str)): Path<(RostraId, String)>,
+) -> ApiResult<Json<TimelinePostItem>> {
+    let event_id: ShortEventId = event_id_str
+        .parse()
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid event_id format"))?;
+
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    let client = state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let post = client_ref
+        .db()
+        .get_social_post(event_id)
+        .await
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Post not found"))?;
+
+    if post.author != rostra_id {
+        return Err(api_error(
+            StatusCode::NOT_FOUND,
+            "Post not found for this author",
+        ));
+    }
+
+    Ok(Json(post_to_timeline_item(post)))
+}
+
+// -- Timeline endpoints (Following / Network) --
+
+#[derive(Deserialize)]
+struct TimelineQuery {
+    ts: Option<Timestamp>,
+    event_id: Option<ShortEventId>,
+}
+
+#[derive(Serialize)]
+struct TimelinePostItem {
+    event_id: String,
+    author: String,
+    ts: u64,
+    content: Option<String>,
+    reply_to: Option<String>,
+    persona_tags: Vec<String>,
+    reply_count: u64,
+    /// The poll presented by the post, if any
+    poll: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TimelineResponse {
+    posts: Vec<TimelinePostItem>,
+    next_cursor: Option<TimelineCursorResponse>,
+}
+
+#[derive(Serialize)]
+struct TimelineCursorResponse {
+    ts: u64,
+    event_id: String,
+}
+
+fn post_to_timeline_item(
+    post: rostra_client_db::social::SocialPostRecord<SocialPost>,
+) -> TimelinePostItem {
+    let persona_tags = post
+        .content
+        .persona_tags()
+        .into_iter()
+        .map(|t| t.to_string())
+        .collect();
+    TimelinePostItem {
+        event_id: post.event_id.to_string(),
+        author: post.author.to_string(),
+        ts: post.ts.as_u64(),
+        content: post.content.djot_content,
+        reply_to: post.reply_to.map(|r| r.to_string()),
+        persona_tags,
+        reply_count: post.reply_count,
+        poll: post
+            .content
+            .poll
+            .map(|poll| ExternalEventId::new(post.author, poll).to_string()),
+    }
+}
+
+async fn get_following_timeline(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path(rostra_id): Path<RostraId>,
+    Query(query): Query<TimelineQuery>,
+) -> ApiResult<Json<TimelineResponse>> {
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    let client = state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let cursor = query.ts.and_then(|ts| {
+        query
+            .event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let self_id = rostra_id;
+    let followees: HashMap<RostraId, PersonasTagsSelector> = client_ref
+        .db()
+        .get_followees(self_id)
+        .await
+        .into_iter()
+        .collect();
+
+    let (posts, next) = client_ref
+        .db()
+        .paginate_social_posts_rev(cursor, 20, move |post| {
+            post.author != self_id
+                && followees.get(&post.author).is_some_and(|selector| {
+                    let tags = post.content.persona_tags();
+                    if tags.is_empty() {
+                        return true;
+                    }
+                    selector.matches_tags(&tags)
+                })
+        })
+        .await;
+
+    let posts = posts.into_iter().map(post_to_timeline_item).collect();
+
+    let next_cursor = next.map(|c| TimelineCursorResponse {
+        ts: c.ts.as_u64(),
+        event_id: c.event_id.to_string(),
+    });
+
+    Ok(Json(TimelineResponse { posts, next_cursor }))
+}
+
+async fn get_network_timeline(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    Path(rostra_id): Path<RostraId>,
+    Query(query): Query<TimelineQuery>,
+) -> ApiResult<Json<TimelineResponse>> {
+    state.load_client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to load client: {e}"),
+        )
+    })?;
+
+    let client = state.client(rostra_id).await.map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client error: {e}"),
+        )
+    })?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    let cursor = query.ts.and_then(|ts| {
+        query
+            .event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let self_id = rostra_id;
+
+    let (posts, next) = client_ref
+        .db()
+        .paginate_social_posts_rev(cursor, 20, move |post| post.author != self_id)
+        .await;
+
+    let posts = posts.into_iter().map(post_to_timeline_item).collect();
+
+    let next_cursor = next.map(|c| TimelineCursorResponse {
         ts: c.ts.as_u64(),
-        seq: c.seq,
+        event_id: c.event_id.to_string(),
     });
 
-    Ok(Json(NotificationsResponse {
-        notifications,
-        next_cursor,
-    }))
+    Ok(Json(TimelineResponse { posts, next_cursor }))
 }
 
-// -- Posts by author / Single post --
+// -- Hashtags --
 
-async fn get_posts_by_author(
+async fn get_tag_timeline(
     State(state): State<SharedState>,
     _version: ApiVersion,
-    Path(rostra_id): Path<RostraId>,
+    Path((rostra_id, tag)): Path<(RostraId, String)>,
     Query(query): Query<TimelineQuery>,
 ) -> ApiResult<Json<TimelineResponse>> {
     state.load_client(rostra_id).await.map_err(|e| {
@@ -1040,11 +2284,12 @@ async fn get_posts_by_author(
             .map(|event_id| EventPaginationCursor { ts, event_id })
     });
 
-    let author_id = rostra_id;
-
+    let wot = client_ref.self_wot_subscribe().snapshot();
     let (posts, next) = client_ref
         .db()
-        .paginate_social_posts_rev(cursor, 20, move |post| post.author == author_id)
+        .paginate_social_posts_by_tag_rev(&tag, cursor, 20, move |post| {
+            wot.contains(post.author, rostra_id)
+        })
         .await;
 
     let posts = posts.into_iter().map(post_to_timeline_item).collect();
@@ -1057,15 +2302,14 @@ async fn get_posts_by_author(
     Ok(Json(TimelineResponse { posts, next_cursor }))
 }
 
-async fn get_single_post(
+// -- Mentions --
+
+async fn get_mentions_timeline(
     State(state): State<SharedState>,
     _version: ApiVersion,
-    Path((rostra_id, event_id_str)): Path<(RostraId, String)>,
-) -> ApiResult<Json<TimelinePostItem>> {
-    let event_id: ShortEventId = event_id_str
-        .parse()
-        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid event_id format"))?;
-
+    Path((rostra_id, mentioned_id)): Path<(RostraId, RostraId)>,
+    Query(query): Query<TimelineQuery>,
+) -> ApiResult<Json<TimelineResponse>> {
     state.load_client(rostra_id).await.map_err(|e| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -1086,92 +2330,188 @@ async fn get_single_post(
         )
     })?;
 
-    let post = client_ref
+    let cursor = query.ts.and_then(|ts| {
+        query
+            .event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let wot = client_ref.self_wot_subscribe().snapshot();
+    let (posts, next) = client_ref
         .db()
-        .get_social_post(event_id)
-        .await
-        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Post not found"))?;
+        .paginate_social_posts_mentioning_rev(mentioned_id, cursor, 20, move |post| {
+            wot.contains(post.author, rostra_id)
+        })
+        .await;
 
-    if post.author != rostra_id {
-        return Err(api_error(
-            StatusCode::NOT_FOUND,
-            "Post not found for this author",
-        ));
-    }
+    let posts = posts.into_iter().map(post_to_timeline_item).collect();
 
-    Ok(Json(post_to_timeline_item(post)))
+    let next_cursor = next.map(|c| TimelineCursorResponse {
+        ts: c.ts.as_u64(),
+        event_id: c.event_id.to_string(),
+    });
+
+    Ok(Json(TimelineResponse { posts, next_cursor }))
 }
 
-// -- Timeline endpoints (Following / Network) --
+// -- Private posts --
 
 #[derive(Deserialize)]
-struct TimelineQuery {
-    ts: Option<Timestamp>,
-    event_id: Option<ShortEventId>,
+struct PublishPrivatePostRequest {
+    content: String,
+    reply_to: Option<ExternalEventId>,
+    #[serde(default)]
+    persona_tags: Vec<String>,
 }
 
-#[derive(Serialize)]
-struct TimelinePostItem {
-    event_id: String,
-    author: String,
-    ts: u64,
-    content: Option<String>,
-    reply_to: Option<String>,
-    persona_tags: Vec<String>,
-    reply_count: u64,
+#[derive(Deserialize)]
+struct PrivateAudienceMemberRequest {
+    member: String,
 }
 
 #[derive(Serialize)]
-struct TimelineResponse {
-    posts: Vec<TimelinePostItem>,
-    next_cursor: Option<TimelineCursorResponse>,
+struct PrivateAudienceResponse {
+    /// Identities private posts are readable by, including `rostra_id`
+    members: Vec<String>,
 }
 
 #[derive(Serialize)]
-struct TimelineCursorResponse {
-    ts: u64,
+struct PrivateAudienceUpdateResponse {
     event_id: String,
+    heads: Vec<String>,
+    members: Vec<String>,
 }
 
-fn post_to_timeline_item(
-    post: rostra_client_db::social::SocialPostRecord<SocialPost>,
-) -> TimelinePostItem {
-    let persona_tags = post
-        .content
-        .persona_tags()
+fn private_post_error(e: PostError) -> (StatusCode, Json<ApiErrorResponse>) {
+    let status = match e {
+        PostError::Validation { .. } => StatusCode::BAD_REQUEST,
+        PostError::NoPrivateAudience => StatusCode::CONFLICT,
+        PostError::RootSecretRequired => StatusCode::FORBIDDEN,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    api_error(status, format!("Failed to publish: {e}"))
+}
+
+async fn publish_private_post_managed(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    auth: ApiAuth,
+    Path(rostra_id): Path<RostraId>,
+    Json(req): Json<PublishPrivatePostRequest>,
+) -> ApiResult<Json<PublishSocialPostResponse>> {
+    let id_secret = auth.secret_for(&state, rostra_id, ApiScope::Post).await?;
+    let client = managed_client(&state, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    unlock_if_own_secret(&client_ref, id_secret).await?;
+
+    let persona_tags: BTreeSet<PersonaTag> = req
+        .persona_tags
+        .iter()
+        .filter_map(|s| PersonaTag::new(s).ok())
+        .collect();
+
+    let verified_event = client_ref
+        .social_private_post(id_secret, req.content, req.reply_to, persona_tags)
+        .await
+        .map_err(private_post_error)?;
+
+    let mut heads: Vec<String> = client_ref
+        .db()
+        .get_heads_events_for_id(rostra_id)
+        .await
         .into_iter()
-        .map(|t| t.to_string())
+        .map(|h| h.to_string())
         .collect();
-    TimelinePostItem {
-        event_id: post.event_id.to_string(),
-        author: post.author.to_string(),
-        ts: post.ts.as_u64(),
-        content: post.content.djot_content,
-        reply_to: post.reply_to.map(|r| r.to_string()),
-        persona_tags,
-        reply_count: post.reply_count,
-    }
+    heads.sort();
+
+    Ok(Json(PublishSocialPostResponse {
+        event_id: ShortEventId::from(verified_event.event_id).to_string(),
+        heads,
+    }))
 }
 
-async fn get_following_timeline(
+async fn get_private_posts(
     State(state): State<SharedState>,
     _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
     Path(rostra_id): Path<RostraId>,
     Query(query): Query<TimelineQuery>,
 ) -> ApiResult<Json<TimelineResponse>> {
-    state.load_client(rostra_id).await.map_err(|e| {
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Failed to load client: {e}"),
+            format!("Client ref error: {e}"),
         )
     })?;
 
-    let client = state.client(rostra_id).await.map_err(|e| {
+    // Audience keys granted by others are unwrapped by the unlocked client
+    unlock_if_own_secret(&client_ref, id_secret).await?;
+
+    let cursor = query.ts.and_then(|ts| {
+        query
+            .event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let (posts, next) = client_ref.db().paginate_private_posts_rev(cursor, 20).await;
+
+    let posts = posts.into_iter().map(post_to_timeline_item).collect();
+
+    let next_cursor = next.map(|c| TimelineCursorResponse {
+        ts: c.ts.as_u64(),
+        event_id: c.event_id.to_string(),
+    });
+
+    Ok(Json(TimelineResponse { posts, next_cursor }))
+}
+
+async fn get_private_audience(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+) -> ApiResult<Json<PrivateAudienceResponse>> {
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
-            format!("Client error: {e}"),
+            format!("Client ref error: {e}"),
         )
     })?;
+
+    let members = client_ref
+        .db()
+        .get_private_audience()
+        .await
+        .map(|audience| audience.members)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| id.to_string())
+        .collect();
+
+    Ok(Json(PrivateAudienceResponse { members }))
+}
+
+async fn add_private_audience_member(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+    Json(req): Json<PrivateAudienceMemberRequest>,
+) -> ApiResult<Json<PrivateAudienceUpdateResponse>> {
+    let member: RostraId = req
+        .member
+        .parse()
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid member rostra_id"))?;
+
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
     let client_ref = client.client_ref().map_err(|e| {
         api_error(
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -1179,49 +2519,94 @@ async fn get_following_timeline(
         )
     })?;
 
-    let cursor = query.ts.and_then(|ts| {
-        query
-            .event_id
-            .map(|event_id| EventPaginationCursor { ts, event_id })
-    });
+    unlock_if_own_secret(&client_ref, id_secret).await?;
 
-    let self_id = rostra_id;
-    let followees: HashMap<RostraId, PersonasTagsSelector> = client_ref
+    let verified_event = client_ref
+        .add_private_audience_member(id_secret, member)
+        .await
+        .map_err(private_post_error)?;
+
+    Ok(Json(
+        private_audience_updated(&client_ref, rostra_id, verified_event).await,
+    ))
+}
+
+async fn remove_private_audience_member(
+    State(state): State<SharedState>,
+    _version: ApiVersion,
+    ApiIdSecret(id_secret): ApiIdSecret,
+    Path(rostra_id): Path<RostraId>,
+    Json(req): Json<PrivateAudienceMemberRequest>,
+) -> ApiResult<Json<PrivateAudienceUpdateResponse>> {
+    let member: RostraId = req
+        .member
+        .parse()
+        .map_err(|_| api_error(StatusCode::BAD_REQUEST, "Invalid member rostra_id"))?;
+
+    let client = local_settings_client(&state, id_secret, rostra_id).await?;
+    let client_ref = client.client_ref().map_err(|e| {
+        api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Client ref error: {e}"),
+        )
+    })?;
+
+    unlock_if_own_secret(&client_ref, id_secret).await?;
+
+    let verified_event = client_ref
+        .remove_private_audience_member(id_secret, member)
+        .await
+        .map_err(private_post_error)?;
+
+    Ok(Json(
+        private_audience_updated(&client_ref, rostra_id, verified_event).await,
+    ))
+}
+
+async fn private_audience_updated(
+    client: &Client,
+    rostra_id: RostraId,
+    grant: VerifiedEvent,
+) -> PrivateAudienceUpdateResponse {
+    let mut heads: Vec<String> = client
         .db()
-        .get_followees(self_id)
+        .get_heads_events_for_id(rostra_id)
         .await
         .into_iter()
+        .map(|h| h.to_string())
         .collect();
-
-    let (posts, next) = client_ref
+    heads.sort();
+    let members = client
         .db()
-        .paginate_social_posts_rev(cursor, 20, move |post| {
-            post.author != self_id
-                && followees.get(&post.author).is_some_and(|selector| {
-                    let tags = post.content.persona_tags();
-                    if tags.is_empty() {
-                        return true;
-                    }
-                    selector.matches_tags(&tags)
-                })
-        })
-        .await;
+        .get_private_audience()
+        .await
+        .map(|audience| audience.members)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|id| id.to_string())
+        .collect();
 
-    let posts = posts.into_iter().map(post_to_timeline_item).collect();
+    PrivateAudienceUpdateResponse {
+        event_id: ShortEventId::from(grant.event_id).to_string(),
+        heads,
+        members,
+    }
+}
 
-    let next_cursor = next.map(|c| TimelineCursorResponse {
-        ts: c.ts.as_u64(),
-        event_id: c.event_id.to_string(),
-    });
+// -- Search --
 
-    Ok(Json(TimelineResponse { posts, next_cursor }))
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    ts: Option<Timestamp>,
+    event_id: Option<ShortEventId>,
 }
 
-async fn get_network_timeline(
+async fn search_posts(
     State(state): State<SharedState>,
     _version: ApiVersion,
     Path(rostra_id): Path<RostraId>,
-    Query(query): Query<TimelineQuery>,
+    Query(query): Query<SearchQuery>,
 ) -> ApiResult<Json<TimelineResponse>> {
     state.load_client(rostra_id).await.map_err(|e| {
         api_error(
@@ -1249,11 +2634,9 @@ async fn get_network_timeline(
             .map(|event_id| EventPaginationCursor { ts, event_id })
     });
 
-    let self_id = rostra_id;
-
     let (posts, next) = client_ref
         .db()
-        .paginate_social_posts_rev(cursor, 20, move |post| post.author != self_id)
+        .search_social_posts(&query.q, cursor, 20)
         .await;
 
     let posts = posts.into_iter().map(post_to_timeline_item).collect();
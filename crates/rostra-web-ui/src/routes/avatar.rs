@@ -1,5 +1,5 @@
 use axum::body::Body;
-use axum::extract::{OriginalUri, Path, State};
+use axum::extract::{OriginalUri, Path, Query, State};
 use axum::http::{HeaderMap, HeaderValue, header};
 use axum::response::{IntoResponse, Response};
 use axum_dpc_static_assets::handle_etag;
@@ -9,6 +9,7 @@ use super::unlock::session::UserSession;
 use crate::SharedState;
 use crate::error::RequestResult;
 use crate::routes::url::{RostraPathId, avatar_path, redirect_to_canonical};
+use crate::util::thumbnail::{ThumbnailQuery, get_thumbnail};
 
 const DEFAULT_AVATAR_SVG: &[u8] = include_bytes!("../../assets/icons/circle-user.svg");
 const DEFAULT_AVATAR_ETAG: &str = "default-circle-user-svg";
@@ -32,29 +33,38 @@ async fn serve_avatar(
     session: &UserSession,
     req_headers: &HeaderMap,
     avatar_id: RostraId,
+    query: &ThumbnailQuery,
 ) -> RequestResult<Response<Body>> {
-    let Some(profile) = state
-        .client(session.id())
-        .await?
-        .client_ref()?
-        .db()
-        .get_social_profile(avatar_id)
-        .await
-    else {
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let Some(profile) = client_ref.db().get_social_profile(avatar_id).await else {
         return Ok(serve_default_avatar(req_headers));
     };
 
-    let Some(avatar) = profile.avatar else {
+    let Some(mut avatar) = profile.avatar else {
         return Ok(serve_default_avatar(req_headers));
     };
 
     let mut resp_headers = HeaderMap::new();
-    let etag = profile.event_id.to_string();
+    let etag = query.etag(profile.event_id.to_string());
 
     if let Some(response) = handle_etag(req_headers, &etag, &mut resp_headers) {
         return Ok(response.into_response());
     }
 
+    if let Some(size) = query.size
+        && let Some(thumbnail) = get_thumbnail(
+            client_ref.db(),
+            profile.event_id,
+            size,
+            &avatar.0,
+            &avatar.1,
+        )
+        .await
+    {
+        avatar = (thumbnail.mime, thumbnail.data);
+    }
+
     let Ok(mime) = HeaderValue::from_str(&avatar.0) else {
         return Ok(serve_default_avatar(req_headers));
     };
@@ -68,6 +78,7 @@ pub async fn get(
     req_headers: HeaderMap,
     OriginalUri(original_uri): OriginalUri,
     Path(avatar_id): Path<RostraPathId>,
+    Query(query): Query<ThumbnailQuery>,
 ) -> RequestResult<impl IntoResponse> {
     let client = state.client(session.id()).await?;
     let client_ref = client.client_ref()?;
@@ -77,5 +88,5 @@ pub async fn get(
     if let Some(response) = redirect_to_canonical(&original_uri, avatar_path(avatar_id)) {
         return Ok(response);
     }
-    serve_avatar(&state, &session, &req_headers, avatar_id).await
+    serve_avatar(&state, &session, &req_headers, avatar_id, &query).await
 }
@@ -12,7 +12,7 @@ use crate::UiState;
 
 mod filters;
 
-use filters::{PrismCodeBlocks, RostraMedia, RostraProfileLinks, SanitizeUrls};
+use filters::{PrismCodeBlocks, RostraHashtags, RostraMedia, RostraProfileLinks, SanitizeUrls};
 
 /// Extension trait for adding rostra-specific rendering transformations
 pub trait RostraRenderExt {
@@ -25,6 +25,14 @@ pub trait RostraRenderExt {
         RostraProfileLinks::new(self, client)
     }
 
+    /// Link `#hashtags` to the posts with them
+    fn rostra_hashtags(self) -> RostraHashtags<Self>
+    where
+        Self: Sized,
+    {
+        RostraHashtags::new(self)
+    }
+
     /// Transform media elements (rostra-media: links rendered based on mime
     /// type)
     fn rostra_media<'s, 'c>(
@@ -85,10 +93,11 @@ impl UiState {
         author_id: RostraId,
         content: &str,
     ) -> Markup {
-        // Compose filters: ProfileLinks -> Media -> (Prism + Sanitize via
+        // Compose filters: Hashtags -> ProfileLinks -> Media -> (Prism + Sanitize via
         // make_base_renderer)
         let renderer = make_base_renderer(
             jotup::html::tokio::Renderer::default()
+                .rostra_hashtags()
                 .rostra_profile_links(client.clone())
                 .rostra_media(client.clone(), author_id),
         );
@@ -4,12 +4,12 @@ use jotup::r#async::{AsyncRender, AsyncRenderOutput};
 use jotup::{AttributeKind, AttributeValue, Attributes, Container, Event};
 use rostra_client::ClientRef;
 use rostra_core::ShortEventId;
-use rostra_core::event::content_kind;
 use rostra_core::id::RostraId;
+use rostra_djot::hashtag::{container_can_have_hashtags, find_hashtags, normalize_hashtag};
 use rostra_djot::links::{RostraIdLink, extract_rostra_id_link_reference};
 
 use crate::UiState;
-use crate::routes::url::{media_url, profile_url};
+use crate::routes::url::{media_url, profile_url, tag_url};
 
 /// Escape HTML special characters for use in attributes and text
 fn escape_html(s: &str) -> String {
@@ -385,15 +385,13 @@ where
                             let html = if let Some(content) =
                                 self.client.db().get_event_content(event_id).await
                             {
-                                if let Ok(media) =
-                                    content.deserialize_cbor::<content_kind::SocialMedia>()
-                                {
-                                    if media.mime.starts_with("image/") {
+                                if let Some(mime) = crate::routes::media::media_mime(&content) {
+                                    if mime.starts_with("image/") {
                                         // Render as image
                                         format!(
-                                            r#"<span class="m-rostraMedia"><img src="{url_escaped}" alt="{alt_escaped}"/></span>"#
+                                            r#"<span class="m-rostraMedia"><a href="{url_escaped}"><img src="{url_escaped}?size=large" alt="{alt_escaped}"/></a></span>"#
                                         )
-                                    } else if media.mime.starts_with("video/") {
+                                    } else if mime.starts_with("video/") {
                                         // Render as video player - plays when visible via
                                         // IntersectionObserver
                                         format!(
@@ -546,6 +544,108 @@ where
     }
 }
 
+/// Filter that turns `#hashtags` in text into links to their posts
+pub(crate) struct RostraHashtags<R> {
+    inner: R,
+    /// Depth inside containers that can't have hashtags
+    skip_depth: usize,
+    /// Consecutive text, which the parser might split
+    text: String,
+}
+
+impl<R> RostraHashtags<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            skip_depth: 0,
+            text: String::new(),
+        }
+    }
+}
+
+impl<'s, R> RostraHashtags<R>
+where
+    R: AsyncRender<'s> + Send,
+{
+    async fn flush_text(&mut self) -> Result<(), R::Error> {
+        if self.text.is_empty() {
+            return Ok(());
+        }
+        let text = std::mem::take(&mut self.text);
+        let mut pos = 0;
+        for range in find_hashtags(&text) {
+            let Some(tag) = normalize_hashtag(&text[range.start + 1..range.end]) else {
+                continue;
+            };
+            if pos < range.start {
+                self.inner
+                    .emit(Event::Str(Cow::Owned(text[pos..range.start].to_owned())))
+                    .await?;
+            }
+            self.inner
+                .emit(Event::Start(
+                    Container::Link(
+                        Cow::Owned(tag_url(&tag)),
+                        jotup::LinkType::Span(jotup::SpanLinkType::Inline),
+                    ),
+                    Attributes::new(),
+                ))
+                .await?;
+            self.inner
+                .emit(Event::Str(Cow::Owned(text[range.clone()].to_owned())))
+                .await?;
+            self.inner.emit(Event::End).await?;
+            pos = range.end;
+        }
+        if pos < text.len() {
+            self.inner
+                .emit(Event::Str(Cow::Owned(text[pos..].to_owned())))
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<'s, R> AsyncRender<'s> for RostraHashtags<R>
+where
+    R: AsyncRender<'s> + Send,
+{
+    type Error = R::Error;
+
+    async fn emit(&mut self, event: Event<'s>) -> Result<(), Self::Error> {
+        match event {
+            Event::Str(s) if self.skip_depth == 0 => {
+                self.text.push_str(&s);
+                return Ok(());
+            }
+            Event::Start(ref container, _)
+                if 0 < self.skip_depth || !container_can_have_hashtags(container) =>
+            {
+                self.skip_depth += 1;
+            }
+            Event::End => {
+                self.skip_depth = self.skip_depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        self.flush_text().await?;
+        self.inner.emit(event).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<'s, R> AsyncRenderOutput<'s> for RostraHashtags<R>
+where
+    R: AsyncRenderOutput<'s> + Send,
+{
+    type Output = R::Output;
+
+    fn into_output(self) -> Self::Output {
+        self.inner.into_output()
+    }
+}
+
 /// Filter that adds Prism.js classes to code blocks for syntax highlighting
 pub(crate) struct PrismCodeBlocks<R> {
     inner: R,
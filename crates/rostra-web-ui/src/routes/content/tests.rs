@@ -192,6 +192,22 @@ async fn inline_code_not_affected_by_prism() {
     );
 }
 
+#[tokio::test]
+async fn hashtags_link_to_their_posts() {
+    let renderer = make_base_renderer(jotup::html::tokio::Renderer::default().rostra_hashtags());
+    let out = renderer
+        .render_into_document("Hello #Rust_lang! `#code` [#link](https://example.com) a#b")
+        .await
+        .expect("Rendering failed");
+    let html = String::from_utf8(out.into_inner()).expect("valid utf8");
+
+    assert_eq!(
+        html.trim(),
+        "<p>Hello <a href=\"/tag/rust_lang\">#Rust_lang</a>! <code>#code</code> \
+         <a href=\"https://example.com\">#link</a> a#b</p>"
+    );
+}
+
 /// Helper to render djot content and see raw djot events
 fn render_events(content: &str) -> Vec<jotup::Event<'_>> {
     jotup::Parser::new(content).collect()
@@ -46,6 +46,8 @@ pub fn button(
     onclick: Option<&str>,
     /// Optional form ID for buttons that submit external forms
     form: Option<&str>,
+    /// Optional URL to submit the form to instead of its action
+    formaction: Option<&str>,
     /// Optional data-value attribute
     data_value: Option<&str>,
     /// Optional title/tooltip
@@ -74,6 +76,7 @@ pub fn button(
             disabled[disabled]
             onclick=[onclick]
             form=[form]
+            formaction=[formaction]
             data-value=[data_value]
             title=[title]
             aria-label=[aria_label]
@@ -1,11 +1,18 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::ops::Range;
+use std::sync::Arc;
+
 use axum::body::Body;
 use axum::extract::{Multipart, OriginalUri, Path, Query, State};
 use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 use axum::response::{IntoResponse, Response};
 use axum_dpc_static_assets::handle_etag;
+use futures::stream::{self, Stream, StreamExt as _};
 use maud::{PreEscaped, html};
+use rostra_client_db::Database;
 use rostra_core::ShortEventId;
-use rostra_core::event::{EventExt as _, content_kind};
+use rostra_core::event::{EventContentRaw, EventExt as _, EventKind, content_kind};
 use rostra_core::id::ToShort as _;
 use serde::Deserialize;
 use snafu::ResultExt as _;
@@ -13,10 +20,11 @@ use snafu::ResultExt as _;
 use super::unlock::session::UserSession;
 use super::{Maud, fragment};
 use crate::SharedState;
-use crate::error::{OtherSnafu, ReadOnlyModeSnafu, RequestResult};
+use crate::error::{EventContentStorageSnafu, OtherSnafu, ReadOnlyModeSnafu, RequestResult};
 use crate::routes::url::{
     EventPathId, RostraPathId, media_list_url, media_url, redirect_to_canonical,
 };
+use crate::util::thumbnail::{ThumbnailQuery, ThumbnailSize, get_thumbnail};
 
 pub async fn get(
     state: State<SharedState>,
@@ -24,6 +32,7 @@ pub async fn get(
     req_headers: HeaderMap,
     OriginalUri(original_uri): OriginalUri,
     Path((author, event_id)): Path<(RostraPathId, EventPathId)>,
+    Query(query): Query<ThumbnailQuery>,
 ) -> RequestResult<Response<Body>> {
     let client_handle = state.client(session.id()).await?;
     let client_ref = client_handle.client_ref()?;
@@ -48,19 +57,80 @@ pub async fn get(
         return Ok(StatusCode::NOT_FOUND.into_response());
     };
 
-    // Deserialize as SocialMedia content
-    let media_content: content_kind::SocialMedia = match event_content.deserialize_cbor() {
-        Ok(content) => content,
-        Err(_) => return Ok(StatusCode::BAD_REQUEST.into_response()),
-    };
-
     let mut resp_headers = HeaderMap::new();
-    let etag = event_id.to_string();
+    let etag = query.etag(event_id.to_string());
 
     // Handle ETag and conditional request
     if let Some(response) = handle_etag(&req_headers, &etag, &mut resp_headers) {
         return Ok(response.into_response());
     }
+    resp_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    if event.kind() == EventKind::SOCIAL_MEDIA_MANIFEST {
+        let Ok(manifest) = event_content.deserialize_cbor::<content_kind::SocialMediaManifest>()
+        else {
+            return Ok(StatusCode::BAD_REQUEST.into_response());
+        };
+        let Ok(mime) = HeaderValue::from_str(&manifest.mime) else {
+            return Ok(StatusCode::BAD_REQUEST.into_response());
+        };
+        resp_headers.insert(header::CONTENT_TYPE, mime);
+
+        let Ok(range) = requested_range(&req_headers, manifest.len) else {
+            return Ok(range_not_satisfiable(manifest.len));
+        };
+
+        // Chunks usually replicate with the manifest, but fetch any that
+        // didn't yet, so the response does not stop midway
+        let mut followers_cache = BTreeMap::new();
+        let byte_range = range.clone().unwrap_or(0..manifest.len);
+        for index in manifest.chunks_in(byte_range.clone()) {
+            let chunk_id = manifest.chunks[index];
+            if client_ref.db().get_event(chunk_id).await.is_some()
+                && !client_ref.db().is_event_content_missing(chunk_id).await
+            {
+                continue;
+            }
+            if !client_ref
+                .fetch_event_content(author, chunk_id, &mut followers_cache)
+                .await
+                .context(EventContentStorageSnafu {
+                    author_id: author,
+                    event_id: chunk_id,
+                })?
+            {
+                return Ok(StatusCode::SERVICE_UNAVAILABLE.into_response());
+            }
+        }
+
+        let body = Body::from_stream(manifest_stream(
+            client_ref.db().clone(),
+            manifest.clone(),
+            byte_range.clone(),
+        ));
+        return Ok(range_response(resp_headers, range, manifest.len, body));
+    }
+
+    // Deserialize as SocialMedia content
+    let mut media_content: content_kind::SocialMedia = match event_content.deserialize_cbor() {
+        Ok(content) => content,
+        Err(_) => return Ok(StatusCode::BAD_REQUEST.into_response()),
+    };
+
+    // Serve a resized variant, if one was requested and makes sense
+    if let Some(size) = query.size
+        && let Some(thumbnail) = get_thumbnail(
+            client_ref.db(),
+            event_id,
+            size,
+            &media_content.mime,
+            &media_content.data,
+        )
+        .await
+    {
+        media_content.mime = thumbnail.mime;
+        media_content.data = thumbnail.data;
+    }
 
     // Set content type from the media's MIME type
     let Ok(mime) = HeaderValue::from_str(&media_content.mime) else {
@@ -69,7 +139,153 @@ pub async fn get(
     resp_headers.insert(header::CONTENT_TYPE, mime);
 
     // Return the media data
-    Ok((resp_headers, media_content.data).into_response())
+    let len = media_content.data.len() as u64;
+    let Ok(range) = requested_range(&req_headers, len) else {
+        return Ok(range_not_satisfiable(len));
+    };
+    let mut data = media_content.data;
+    if let Some(range) = range.as_ref() {
+        data.truncate(range.end as usize);
+        data.drain(..range.start as usize);
+    }
+    Ok(range_response(resp_headers, range, len, Body::from(data)))
+}
+
+/// Mime type of a `SocialMedia` or `SocialMediaManifest` content
+pub(crate) fn media_mime(content: &EventContentRaw) -> Option<String> {
+    content
+        .deserialize_cbor::<content_kind::SocialMediaManifest>()
+        .map(|manifest| manifest.mime)
+        .or_else(|_| {
+            content
+                .deserialize_cbor::<content_kind::SocialMedia>()
+                .map(|media| media.mime)
+        })
+        .ok()
+}
+
+/// The byte range of the `Range` header, if any
+///
+/// Only a single range is supported, and requests for multiple ranges get the
+/// whole file, as allowed by RFC 9110. Fails if the range is not satisfiable.
+fn requested_range(headers: &HeaderMap, len: u64) -> Result<Option<Range<u64>>, ()> {
+    let Some(value) = headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Ok(None);
+    };
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((start, end)) = spec.split_once('-') else {
+        return Ok(None);
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    let range = if start.is_empty() {
+        // Suffix range: the last `end` bytes
+        let Ok(suffix_len) = end.parse::<u64>() else {
+            return Ok(None);
+        };
+        if suffix_len == 0 {
+            return Err(());
+        }
+        len.saturating_sub(suffix_len)..len
+    } else {
+        let Ok(start) = start.parse::<u64>() else {
+            return Ok(None);
+        };
+        let end = if end.is_empty() {
+            len
+        } else {
+            let Ok(end) = end.parse::<u64>() else {
+                return Ok(None);
+            };
+            if end < start {
+                return Ok(None);
+            }
+            end.saturating_add(1).min(len)
+        };
+        start..end
+    };
+
+    if len <= range.start {
+        return Err(());
+    }
+    Ok(Some(range))
+}
+
+fn range_not_satisfiable(len: u64) -> Response<Body> {
+    (
+        StatusCode::RANGE_NOT_SATISFIABLE,
+        [(header::CONTENT_RANGE, format!("bytes */{len}"))],
+    )
+        .into_response()
+}
+
+/// Respond with `body`, being the whole file of `len` bytes or its `range`
+fn range_response(
+    mut headers: HeaderMap,
+    range: Option<Range<u64>>,
+    len: u64,
+    body: Body,
+) -> Response<Body> {
+    let Some(range) = range else {
+        headers.insert(header::CONTENT_LENGTH, HeaderValue::from(len));
+        return (headers, body).into_response();
+    };
+    headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from(range.end - range.start),
+    );
+    if let Ok(value) =
+        HeaderValue::from_str(&format!("bytes {}-{}/{len}", range.start, range.end - 1))
+    {
+        headers.insert(header::CONTENT_RANGE, value);
+    }
+    (StatusCode::PARTIAL_CONTENT, headers, body).into_response()
+}
+
+/// Stream the bytes `range` of a chunked media file, loading one chunk at a
+/// time
+fn manifest_stream(
+    db: Arc<Database>,
+    manifest: content_kind::SocialMediaManifest,
+    range: Range<u64>,
+) -> impl Stream<Item = io::Result<Vec<u8>>> {
+    stream::iter(manifest.chunks_in(range.clone())).then(move |index| {
+        let db = db.clone();
+        let chunk_id = manifest.chunks[index];
+        let chunk_range = manifest.chunk_range(index);
+        let range = range.clone();
+        async move {
+            let chunk = db
+                .get_event_content(chunk_id)
+                .await
+                .and_then(|content| {
+                    content
+                        .deserialize_cbor::<content_kind::SocialMediaChunk>()
+                        .ok()
+                })
+                .filter(|chunk| chunk.data.len() as u64 == chunk_range.end - chunk_range.start)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("Media chunk {chunk_id} not available"),
+                    )
+                })?;
+            let start = range.start.saturating_sub(chunk_range.start) as usize;
+            let end = (range.end.min(chunk_range.end) - chunk_range.start) as usize;
+            let mut data = chunk.data;
+            data.truncate(end);
+            data.drain(..start);
+            Ok(data)
+        }
+    })
 }
 
 pub async fn publish(
@@ -110,15 +326,9 @@ pub async fn publish(
                     .id_secret(session.session_token())
                     .ok_or_else(|| ReadOnlyModeSnafu.build())?;
 
-                // Create and publish SocialMedia event
-                let media_event = content_kind::SocialMedia {
-                    mime: content_type,
-                    data: data.to_vec(),
-                };
-
+                // Large files are published in chunks
                 let event = client_ref
-                    .publish_event(id_secret, media_event)
-                    .call()
+                    .publish_media(id_secret, content_type, data.to_vec())
                     .await?;
 
                 let event_id = event.event_id.to_short();
@@ -155,11 +365,23 @@ pub async fn publish(
 struct MediaInfo {
     event_id: ShortEventId,
     mime: String,
-    size: usize,
+    size: u64,
     is_image: bool,
     is_video: bool,
 }
 
+impl MediaInfo {
+    fn new(event_id: ShortEventId, mime: String, size: u64) -> Self {
+        Self {
+            event_id,
+            is_image: mime.starts_with("image/"),
+            is_video: mime.starts_with("video/"),
+            mime,
+            size,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct ListQuery {
     /// CSS selector for the textarea to insert media into
@@ -185,7 +407,11 @@ pub async fn list(
 
     let media_event_ids = client_ref
         .db()
-        .get_latest_singleton_events(author, rostra_core::event::EventKind::SOCIAL_MEDIA)
+        .get_latest_singleton_events(author, EventKind::SOCIAL_MEDIA)
+        .await;
+    let manifest_event_ids = client_ref
+        .db()
+        .get_latest_singleton_events(author, EventKind::SOCIAL_MEDIA_MANIFEST)
         .await;
 
     // Fetch media info for each event
@@ -194,15 +420,20 @@ pub async fn list(
         if let Some(event_content) = client_ref.db().get_event_content(event_id).await {
             if let Ok(media_content) = event_content.deserialize_cbor::<content_kind::SocialMedia>()
             {
-                let is_image = media_content.mime.starts_with("image/");
-                let is_video = media_content.mime.starts_with("video/");
-                media_items.push(MediaInfo {
+                media_items.push(MediaInfo::new(
                     event_id,
-                    mime: media_content.mime,
-                    size: media_content.data.len(),
-                    is_image,
-                    is_video,
-                });
+                    media_content.mime,
+                    media_content.data.len() as u64,
+                ));
+            }
+        }
+    }
+    for event_id in manifest_event_ids {
+        if let Some(event_content) = client_ref.db().get_event_content(event_id).await {
+            if let Ok(manifest) =
+                event_content.deserialize_cbor::<content_kind::SocialMediaManifest>()
+            {
+                media_items.push(MediaInfo::new(event_id, manifest.mime, manifest.len));
             }
         }
     }
@@ -224,7 +455,7 @@ pub async fn list(
                             {
                                 @if media.is_image {
                                     img
-                                        src=(media_url(author, media.event_id))
+                                        src=(format!("{}?size={}", media_url(author, media.event_id), ThumbnailSize::Small.as_str()))
                                         ."o-mediaList__thumbnail"
                                         loading="lazy"
                                         {}
@@ -242,7 +473,7 @@ pub async fn list(
                                         div ."o-mediaList__fileIcon" {}
                                         div ."o-mediaList__fileMeta" {
                                             div ."o-mediaList__fileMime" { (media.mime.as_str()) }
-                                            div ."o-mediaList__fileSize" { (rostra_util_fmt::format_bytes(media.size as u64)) }
+                                            div ."o-mediaList__fileSize" { (rostra_util_fmt::format_bytes(media.size)) }
                                         }
                                     }
                                 }
@@ -283,3 +514,6 @@ pub async fn list(
     })
     .into_response())
 }
+
+#[cfg(test)]
+mod tests;
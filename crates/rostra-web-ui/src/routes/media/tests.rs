@@ -0,0 +1,32 @@
+use axum::http::{HeaderMap, HeaderValue, header};
+
+use super::requested_range;
+
+fn range(value: &str, len: u64) -> Result<Option<std::ops::Range<u64>>, ()> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::RANGE,
+        HeaderValue::from_str(value).expect("valid header"),
+    );
+    requested_range(&headers, len)
+}
+
+#[test]
+fn range_header_parsing() {
+    assert_eq!(requested_range(&HeaderMap::new(), 100), Ok(None));
+
+    assert_eq!(range("bytes=0-9", 100), Ok(Some(0..10)));
+    assert_eq!(range("bytes=90-", 100), Ok(Some(90..100)));
+    assert_eq!(range("bytes=-10", 100), Ok(Some(90..100)));
+    assert_eq!(range("bytes=-1000", 100), Ok(Some(0..100)));
+    assert_eq!(range("bytes=50-1000", 100), Ok(Some(50..100)));
+
+    // Unsupported or malformed ranges get the whole file
+    assert_eq!(range("bytes=0-9,20-29", 100), Ok(None));
+    assert_eq!(range("items=0-9", 100), Ok(None));
+    assert_eq!(range("bytes=9-0", 100), Ok(None));
+
+    // Not satisfiable
+    assert_eq!(range("bytes=100-", 100), Err(()));
+    assert_eq!(range("bytes=-0", 100), Err(()));
+}
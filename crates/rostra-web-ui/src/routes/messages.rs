@@ -0,0 +1,381 @@
+use axum::Form;
+use axum::extract::{Path, State};
+use axum::response::{IntoResponse, Redirect};
+use maud::{Markup, PreEscaped, html};
+use rostra_client::ClientRef;
+use rostra_client_db::direct_messages::{DirectConversationRecord, DirectMessageRecord};
+use rostra_client_db::social::EventPaginationCursor;
+use rostra_core::event::content_kind::DirectMessageBody;
+use rostra_core::id::{RostraId, RostraIdSecretKey};
+use rostra_core::{ShortEventId, Timestamp};
+use serde::Deserialize;
+
+use super::super::SharedState;
+use super::super::error::{ReadOnlyModeSnafu, RequestResult, UserRequestError};
+use super::unlock::session::UserSession;
+use super::url::RostraPathId;
+use super::{Maud, fragment};
+use crate::UiState;
+use crate::error::RequestError;
+use crate::html_utils::re_typeset;
+use crate::layout::FeedLinks;
+use crate::routes::url::{messages_url, profile_url};
+use crate::util::extractors::AjaxRequest;
+use crate::util::time::format_timestamp;
+
+const DIRECT_MESSAGES_LIMIT: usize = 50;
+
+#[derive(Deserialize, Default)]
+pub struct DirectMessagesPaginationInput {
+    pub ts: Option<Timestamp>,
+    pub event_id: Option<ShortEventId>,
+}
+
+#[derive(Deserialize)]
+pub struct NewConversationInput {
+    pub id: String,
+}
+
+#[derive(Deserialize)]
+pub struct DirectMessagePostInput {
+    pub content: String,
+}
+
+async fn resolve_counterparty(
+    client_ref: &ClientRef<'_>,
+    id: RostraPathId,
+) -> RequestResult<RostraId> {
+    id.resolve(client_ref.db())
+        .await
+        .ok_or_else(|| RequestError::User {
+            source: UserRequestError::SomethingNotFound,
+        })
+}
+
+/// Inbox: all direct message conversations, most recently active first
+pub async fn get_messages(
+    state: State<SharedState>,
+    session: UserSession,
+) -> RequestResult<impl IntoResponse> {
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let conversations = client_ref.db().get_direct_conversations().await;
+
+    let content = html! {
+        div ."o-mainBarTimeline" {
+            (UiState::render_page_tab_bar("Messages"))
+            form ."o-directMessages__new"
+                action="/messages/new"
+                method="get"
+            {
+                input ."o-directMessages__newInput"
+                    type="text"
+                    name="id"
+                    placeholder="Rostra ID of the recipient"
+                    required
+                    autocomplete="off"
+                    {}
+                (fragment::button("o-directMessages__newButton", "Write").call())
+            }
+            @if conversations.is_empty() {
+                p ."o-directMessages__empty" { "No messages yet." }
+            }
+            @for conversation in &conversations {
+                (state.render_direct_conversation(&client_ref, conversation).await)
+            }
+        }
+    };
+
+    let navbar = state
+        .timeline_common_navbar()
+        .session(&session)
+        .call()
+        .await?;
+    let page_layout = state.render_page_layout(navbar, content);
+    Ok(Maud(
+        state
+            .render_html_page(
+                "Messages - Rostra",
+                page_layout,
+                None::<&FeedLinks>,
+                None,
+                None,
+                true,
+            )
+            .await?,
+    ))
+}
+
+/// Open the conversation with the identity entered in the inbox
+pub async fn get_new_conversation(
+    state: State<SharedState>,
+    session: UserSession,
+    Form(form): Form<NewConversationInput>,
+) -> RequestResult<impl IntoResponse> {
+    let id = form
+        .id
+        .trim()
+        .parse::<RostraPathId>()
+        .map_err(|_| RequestError::User {
+            source: UserRequestError::InvalidData,
+        })?;
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let counterparty = resolve_counterparty(&client_ref, id).await?;
+
+    Ok(Redirect::to(&messages_url(counterparty)))
+}
+
+/// A single conversation, oldest message at the bottom
+pub async fn get_conversation(
+    state: State<SharedState>,
+    session: UserSession,
+    AjaxRequest(is_ajax): AjaxRequest,
+    Path(counterparty): Path<RostraPathId>,
+    Form(form): Form<DirectMessagesPaginationInput>,
+) -> RequestResult<impl IntoResponse> {
+    let pagination = form.ts.and_then(|ts| {
+        form.event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let counterparty = resolve_counterparty(&client_ref, counterparty).await?;
+    let id_secret = state.id_secret(session.session_token());
+    let ro_mode = state.ro_mode(session.session_token());
+
+    let (mut messages, next_cursor) = client_ref
+        .db()
+        .paginate_direct_messages_rev(counterparty, pagination, DIRECT_MESSAGES_LIMIT)
+        .await;
+    // Chat order: oldest first
+    messages.reverse();
+
+    let load_older = html! {
+        @if let Some(cursor) = next_cursor {
+            a id="direct-messages-load-older"
+                ."o-directMessages__loadOlder"
+                href=(format!("{}?ts={}&event_id={}", messages_url(counterparty), cursor.ts, cursor.event_id))
+                x-target="direct-messages-load-older direct-messages-posts ajax-scripts"
+            { "Load older messages" }
+        } @else {
+            div id="direct-messages-load-older" {}
+        }
+    };
+
+    if is_ajax {
+        return Ok(Maud(html! {
+            (load_older)
+            div id="direct-messages-posts" x-merge="prepend" {
+                @for message in &messages {
+                    (state.render_direct_message(&client_ref, id_secret, counterparty, message).await)
+                }
+            }
+            div id="ajax-scripts" { (re_typeset()) }
+        }));
+    }
+
+    let profile = state.get_social_profile(counterparty, &client_ref).await;
+    let content = html! {
+        div ."o-directMessages" {
+            div ."o-mainBarTimeline__tabs" {
+                a ."o-mainBarTimeline__back" href="/messages" { "<" }
+                a ."-active" href=(profile_url(counterparty)) { (profile.display_name) }
+            }
+            div ."o-directMessages__messages" id="direct-messages" {
+                (load_older)
+                @if id_secret.is_none() {
+                    p ."o-directMessages__empty" { "Unlock your identity with its secret to read messages." }
+                } @else if messages.is_empty() {
+                    p ."o-directMessages__empty" { "No messages yet. Only you and the recipient can read them." }
+                }
+                div id="direct-messages-posts" ."o-directMessages__posts" x-merge="append" {
+                    @for message in &messages {
+                        (state.render_direct_message(&client_ref, id_secret, counterparty, message).await)
+                    }
+                }
+            }
+            @let form_ajax = fragment::AjaxLoadingAttrs::for_class("o-directMessages__submitButton");
+            form ."o-directMessages__form"
+                action=(messages_url(counterparty))
+                method="post"
+                "x-target.nofocus"="direct-messages-posts ajax-scripts"
+                "@ajax:before"=(form_ajax.before)
+                "@ajax:after"=(form_ajax.after)
+                "@ajax:success"="setTimeout(() => { const el = document.getElementById('direct-messages'); el.scrollTop = el.scrollHeight; }, 50)"
+            {
+                div ."o-directMessages__inputWrapper" {
+                    textarea ."o-directMessages__input"
+                        id="direct-message-input"
+                        name="content"
+                        placeholder="Write a message..."
+                        dir="auto"
+                        rows="1"
+                        disabled[ro_mode.to_disabled()]
+                        "@keydown.enter.prevent"="if (!$event.shiftKey) { $el.form.requestSubmit(); }"
+                        autocomplete="off"
+                        autofocus
+                        {}
+                    (fragment::button("o-directMessages__submitButton", "Send")
+                        .disabled(ro_mode.to_disabled())
+                        .call())
+                }
+            }
+        }
+        (re_typeset())
+        script {
+            (PreEscaped(r#"
+                (function() {
+                    const el = document.getElementById('direct-messages');
+                    if (el) el.scrollTop = el.scrollHeight;
+                })();
+            "#))
+        }
+    };
+
+    let navbar = state
+        .timeline_common_navbar()
+        .session(&session)
+        .call()
+        .await?;
+    let page_layout = state.render_page_layout(navbar, content);
+    let content = html! {
+        (page_layout)
+        div id="ajax-scripts" style="display: none;" {}
+    };
+    Ok(Maud(
+        state
+            .render_html_page(
+                &format!("Messages with {} - Rostra", profile.display_name),
+                content,
+                None::<&FeedLinks>,
+                None,
+                None,
+                true,
+            )
+            .await?,
+    ))
+}
+
+pub async fn post_direct_message(
+    state: State<SharedState>,
+    session: UserSession,
+    Path(counterparty): Path<RostraPathId>,
+    Form(form): Form<DirectMessagePostInput>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let counterparty = resolve_counterparty(&client_ref, counterparty).await?;
+
+    let body = DirectMessageBody {
+        djot_content: form.content.trim().to_owned(),
+    };
+    if body.validate().is_err() {
+        return Ok(Maud(html! {
+            div id="direct-messages-posts" x-merge="append" {}
+            div id="ajax-scripts" {
+                script {
+                    (PreEscaped(r#"
+                        window.dispatchEvent(new CustomEvent('notify', {
+                            detail: { type: 'error', message: 'Message must be between 1 and 10000 characters' }
+                        }));
+                    "#))
+                }
+            }
+        }));
+    }
+
+    let event = client_ref
+        .send_direct_message(id_secret, counterparty, body.djot_content)
+        .await?;
+    let (messages, _) = client_ref
+        .db()
+        .paginate_direct_messages_rev(
+            counterparty,
+            Some(EventPaginationCursor {
+                ts: event.event.timestamp.into(),
+                event_id: event.event_id.into(),
+            }),
+            1,
+        )
+        .await;
+
+    Ok(Maud(html! {
+        div id="direct-messages-posts" x-merge="append" {
+            @for message in &messages {
+                (state.render_direct_message(&client_ref, Some(id_secret), counterparty, message).await)
+            }
+        }
+        div id="ajax-scripts" {
+            (re_typeset())
+            script {
+                (PreEscaped(r#"
+                    (function() {
+                        const input = document.getElementById('direct-message-input');
+                        if (input) input.value = '';
+                    })()
+                "#))
+            }
+        }
+    }))
+}
+
+impl UiState {
+    async fn render_direct_conversation(
+        &self,
+        client: &ClientRef<'_>,
+        conversation: &DirectConversationRecord,
+    ) -> Markup {
+        let profile = self
+            .get_social_profile(conversation.counterparty, client)
+            .await;
+
+        html! {
+            a ."o-directMessages__conversation" href=(messages_url(conversation.counterparty)) {
+                (fragment::avatar("o-directMessages__avatar", self.avatar_url(conversation.counterparty, profile.event_id), "Avatar"))
+                span ."o-directMessages__author" { (profile.display_name) }
+                span ."o-directMessages__timestamp" { (format_timestamp(conversation.last_ts)) }
+            }
+        }
+    }
+
+    async fn render_direct_message(
+        &self,
+        client: &ClientRef<'_>,
+        id_secret: Option<RostraIdSecretKey>,
+        counterparty: RostraId,
+        message: &DirectMessageRecord,
+    ) -> Markup {
+        let Some(id_secret) = id_secret else {
+            return html! {};
+        };
+        let profile = self.get_social_profile(message.author, client).await;
+
+        html! {
+            div ."o-directMessages__post" {
+                (fragment::avatar("o-directMessages__avatar", self.avatar_url(message.author, profile.event_id), "Avatar"))
+                div ."o-directMessages__postBody" {
+                    div ."o-directMessages__postMeta" {
+                        a ."o-directMessages__author" href=(profile_url(message.author)) {
+                            (profile.display_name)
+                        }
+                        span ."o-directMessages__timestamp" {
+                            (format_timestamp(message.ts))
+                        }
+                    }
+                    div ."o-directMessages__postContent" {
+                        @match message.content.open(id_secret, counterparty) {
+                            Ok(body) => (self.render_content(client, message.author, &body.djot_content).await),
+                            Err(_) => p ."o-directMessages__undecryptable" { "This message could not be decrypted." },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -5,15 +5,20 @@ use axum::http::HeaderMap;
 use axum::response::IntoResponse;
 use axum_extra::extract::Form;
 use maud::{Markup, PreEscaped, html};
+use rostra_client_db::drafts::PostDraft;
 use rostra_core::event::PersonaTag;
+use rostra_core::event::content_kind::{EventContentKind as _, SocialPoll};
 use rostra_core::id::{RostraId, ToShort as _};
-use rostra_core::{ExternalEventId, ShortEventId};
+use rostra_core::{ExternalEventId, ShortEventId, Timestamp};
 use serde::Deserialize;
+use snafu::ResultExt as _;
 use tower_cookies::Cookies;
 use url::Url;
 
 use super::super::SharedState;
-use super::super::error::{BadRequestSnafu, ReadOnlyModeSnafu, RequestError, RequestResult};
+use super::super::error::{
+    BadRequestSnafu, OtherSnafu, ReadOnlyModeSnafu, RequestError, RequestResult,
+};
 use super::cookies::CookiesExt as _;
 use super::post::{
     post_inline_reply_added_html_id, post_inline_reply_form_html_id,
@@ -25,6 +30,7 @@ use crate::UiState;
 use crate::html_utils::re_typeset;
 use crate::routes::url::media_list_url;
 use crate::util::extractors::ajax_request::AjaxRequest;
+use crate::util::time::parse_datetime_local;
 
 #[derive(Deserialize)]
 pub struct PostInput {
@@ -36,13 +42,22 @@ pub struct PostInput {
     news: bool,
     title: Option<String>,
     url: Option<String>,
+    /// Poll options, one per line, turning the post into a poll
+    poll_options: Option<String>,
+    /// For how many days the poll accepts votes
+    poll_days: Option<u64>,
+    /// When to publish a draft (`datetime-local` input value)
+    scheduled_at: Option<String>,
+    /// Browser's `Date.getTimezoneOffset()`, for `scheduled_at`
+    #[serde(default)]
+    tz_offset: i32,
     /// For inline reply mode: the post thread context ID
     post_thread_id: Option<ShortEventId>,
     /// Where to redirect after posting (no-JS fallback)
     redirect: Option<String>,
 }
 
-fn bad_request(message: impl Into<String>) -> RequestError {
+pub(crate) fn bad_request(message: impl Into<String>) -> RequestError {
     RequestError::User {
         source: BadRequestSnafu {
             message: message.into(),
@@ -73,6 +88,78 @@ fn parse_news_url(url: Option<&str>) -> RequestResult<Option<Url>> {
         .map_err(|_| bad_request("News URL is invalid"))
 }
 
+/// Build the poll the post content is the question of, if any options were
+/// given
+fn parse_poll(form: &PostInput) -> RequestResult<Option<SocialPoll>> {
+    let options: Vec<String> = form
+        .poll_options
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|option| !option.is_empty())
+        .map(ToOwned::to_owned)
+        .collect();
+    if options.is_empty() {
+        return Ok(None);
+    }
+    if form.news || form.reply_to.is_some() {
+        return Err(bad_request("Only new top-level posts can be polls"));
+    }
+    let poll = SocialPoll {
+        question: form.content.trim().to_owned(),
+        options,
+        closes_at: form
+            .poll_days
+            .filter(|days| 0 < *days)
+            .map(|days| Timestamp::now().saturating_add_secs(days.saturating_mul(24 * 60 * 60))),
+    };
+    poll.validate()
+        .map_err(|err| bad_request(err.public_message))?;
+    Ok(Some(poll))
+}
+
+/// Parse the time to publish a draft at, if any
+pub(crate) fn parse_scheduled_at(
+    value: Option<&str>,
+    tz_offset: i32,
+) -> RequestResult<Option<Timestamp>> {
+    let value = value.unwrap_or_default().trim();
+    if value.is_empty() {
+        return Ok(None);
+    }
+    let scheduled_at = parse_datetime_local(value, tz_offset)
+        .ok_or_else(|| bad_request("Scheduled time is invalid"))?;
+    if scheduled_at <= Timestamp::now() {
+        return Err(bad_request("Scheduled time is in the past"));
+    }
+    Ok(Some(scheduled_at))
+}
+
+/// Inputs to save the post as a draft, or schedule it, from the preview
+fn draft_inputs() -> Markup {
+    html! {
+        label ."o-previewDialog__scheduleLabel" {
+            "Publish at (optional) "
+            input ."o-previewDialog__scheduleInput" type="datetime-local" name="scheduled_at" {}
+        }
+        input type="hidden" name="tz_offset" value="0"
+            x-init="$el.value = new Date().getTimezoneOffset()" {}
+    }
+}
+
+/// Hidden inputs carrying the poll fields through the preview
+fn poll_hidden_inputs(form: &PostInput) -> Markup {
+    html! {
+        @if let Some(poll_options) = form.poll_options.as_ref() {
+            input type="hidden" name="poll_options" value=(poll_options) {}
+        }
+        @if let Some(poll_days) = form.poll_days {
+            input type="hidden" name="poll_days" value=(poll_days) {}
+        }
+    }
+}
+
 fn focus_on_new_post_content_input() -> Markup {
     html! {
         script {
@@ -146,6 +233,7 @@ pub async fn post_new_post(
     };
 
     let redirect_to = form.redirect.clone();
+    let poll = parse_poll(&form)?;
 
     let event = if form.news {
         client_ref
@@ -156,6 +244,10 @@ pub async fn post_new_post(
                 news_title.clone(),
             )
             .await?
+    } else if let Some(poll) = poll {
+        client_ref
+            .social_poll(id_secret, poll, persona_tags.clone())
+            .await?
     } else {
         client_ref
             .social_post(
@@ -311,6 +403,108 @@ pub async fn post_new_post(
     }))
 }
 
+/// Save the post as a draft, or schedule it if `scheduled_at` is set
+pub async fn post_new_post_draft(
+    state: State<SharedState>,
+    session: UserSession,
+    mut cookies: Cookies,
+    AjaxRequest(is_ajax): AjaxRequest,
+    Form(form): Form<PostInput>,
+) -> RequestResult<impl IntoResponse> {
+    if state.id_secret(session.session_token()).is_none() {
+        return Err(ReadOnlyModeSnafu.build());
+    }
+    if form.news || parse_poll(&form)?.is_some() {
+        return Err(bad_request("Only plain posts can be saved for later"));
+    }
+    if form.content.trim().is_empty() {
+        return Err(bad_request("Post content cannot be empty"));
+    }
+    let scheduled_at = parse_scheduled_at(form.scheduled_at.as_deref(), form.tz_offset)?;
+
+    let client_handle = state.client(session.id()).await?;
+    let client_ref = client_handle.client_ref()?;
+
+    let persona_tags: BTreeSet<PersonaTag> = form
+        .persona_tags
+        .iter()
+        .filter_map(|s| PersonaTag::new(s).ok())
+        .collect();
+    if !persona_tags.is_empty() {
+        cookies.save_persona_tags(client_ref.rostra_id(), &persona_tags);
+    }
+
+    client_ref
+        .db()
+        .insert_post_draft(PostDraft {
+            content: form.content.clone(),
+            reply_to: form.reply_to,
+            persona_tags,
+            scheduled_at,
+            updated_at: Timestamp::now(),
+            last_error: None,
+        })
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+
+    // No-JS: show the saved draft
+    if !is_ajax {
+        return Ok(Maud(html! {
+            (maud::DOCTYPE)
+            html {
+                head {
+                    meta http-equiv="refresh" content="0;url=/settings/drafts" {}
+                }
+                body {
+                    p { "Post saved. Redirecting..." }
+                    a href="/settings/drafts" { "Click here if not redirected." }
+                }
+            }
+        }));
+    }
+
+    let message = if scheduled_at.is_some() {
+        "Post scheduled"
+    } else {
+        "Draft saved"
+    };
+    let notify = html! {
+        div id="ajax-scripts" {
+            script {
+                (PreEscaped(format!(r#"
+                    window.dispatchEvent(new CustomEvent('notify', {{
+                        detail: {{ type: 'success', message: '{message}' }}
+                    }}));
+                "#)))
+            }
+        }
+    };
+
+    if let (Some(post_thread_id), Some(reply_to)) = (form.post_thread_id, form.reply_to) {
+        let reply_to_id = reply_to.event_id().to_short();
+        return Ok(Maud(html! {
+            div id=(post_inline_reply_form_html_id(post_thread_id, reply_to_id)) {}
+            div id=(post_inline_reply_preview_html_id(post_thread_id, reply_to_id)) {}
+            div id=(post_inline_reply_added_html_id(post_thread_id, reply_to_id)) {}
+            div id="post-preview-dialog" ."o-previewDialog" {}
+            (notify)
+        }));
+    }
+
+    Ok(Maud(html! {
+        (state.new_post_form_inner(
+            state.ro_mode(session.session_token()),
+            Some(client_ref.rostra_id()),
+            true,
+        ))
+        div id="post-preview-dialog" ."o-previewDialog" {}
+        div id="new-post-preview" ."o-mainBarTimeline__item -preview -empty" {}
+        div id="new-post-added" {}
+        (notify)
+    }))
+}
+
 pub async fn post_post_preview_dialog(
     state: State<SharedState>,
     session: UserSession,
@@ -343,6 +537,9 @@ pub async fn post_post_preview_dialog(
         }
         (None, None, None)
     };
+    let poll = parse_poll(&form)?;
+    // Drafts keep plain posts and replies only
+    let can_draft = !form.news && poll.is_none();
 
     let mut persona_tags_for_id = client_ref.db().get_persona_tags_for_id(self_id).await;
     persona_tags_for_id.extend(PersonaTag::defaults());
@@ -365,6 +562,16 @@ pub async fn post_post_preview_dialog(
         .ro(state.ro_mode(session.session_token()))
         .call()
         .await?;
+    let preview_content = html! {
+        (preview_content)
+        @if let Some(poll) = poll.as_ref() {
+            ol ."m-poll -preview" {
+                @for option in &poll.options {
+                    li ."m-poll__option" { (option) }
+                }
+            }
+        }
+    };
 
     // AJAX path: return the dialog overlay fragment
     if is_ajax {
@@ -408,6 +615,7 @@ pub async fn post_post_preview_dialog(
                                     input type="hidden" name="url" value=(url.as_str()) {}
                                 }
                             }
+                            (poll_hidden_inputs(&form))
                             @if let Some(reply_to) = form.reply_to {
                                 input type="hidden" name="reply_to" value=(reply_to) {}
                             }
@@ -426,12 +634,24 @@ pub async fn post_post_preview_dialog(
                                     }
                                 }
 
+                                @if can_draft {
+                                    div ."o-previewDialog__scheduleContainer" {
+                                        (draft_inputs())
+                                    }
+                                }
+
                                 div ."o-previewDialog__actionButtons" {
                                     (fragment::button("o-previewDialog__cancelButton", "Back")
                                         .button_type("button")
                                         .onclick("document.querySelector('.o-previewDialog').classList.remove('-active')")
                                         .call())
 
+                                    @if can_draft {
+                                        (fragment::button("o-previewDialog__draftButton", "Save for later")
+                                            .formaction("/post/draft")
+                                            .call())
+                                    }
+
                                     (fragment::button("o-previewDialog__submitButton", "Post").call())
                                 }
                             }
@@ -460,6 +680,7 @@ pub async fn post_post_preview_dialog(
                         input type="hidden" name="url" value=(url.as_str()) {}
                     }
                 }
+                (poll_hidden_inputs(&form))
                 @if let Some(ref redirect) = redirect_to {
                     input type="hidden" name="redirect" value=(redirect) {}
                 }
@@ -481,6 +702,15 @@ pub async fn post_post_preview_dialog(
                         }
                     }
 
+                    @if can_draft {
+                        div ."o-previewDialog__scheduleContainer" {
+                            (draft_inputs())
+                        }
+                        (fragment::button("o-previewDialog__draftButton", "Save for later")
+                            .formaction("/post/draft")
+                            .call())
+                    }
+
                     (fragment::button("o-previewDialog__submitButton", "Post").call())
                 }
             }
@@ -1252,6 +1482,23 @@ impl UiState {
                     }
                 }
 
+                details ."m-newPostForm__poll" {
+                    summary ."m-newPostForm__pollToggle" { "Poll" }
+                    textarea
+                        ."m-newPostForm__pollOptions"
+                        name="poll_options"
+                        placeholder="Options, one per line"
+                        rows="3"
+                        disabled[ro.to_disabled()]
+                        {}
+                    select ."m-newPostForm__pollDays" name="poll_days" disabled[ro.to_disabled()] {
+                        option value="" { "No deadline" }
+                        option value="1" { "Open for 1 day" }
+                        option value="3" { "Open for 3 days" }
+                        option value="7" { "Open for 7 days" }
+                    }
+                }
+
                 div ."m-newPostForm__footer" {
                     div ."m-newPostForm__footerRow m-newPostForm__footerRow--main" {
                         a ."m-newPostForm__helpButton"
@@ -25,8 +25,9 @@ use crate::error::{
 use crate::html_utils::re_typeset;
 use crate::layout::OpenGraphMeta;
 use crate::routes::url::{
-    EventPathId, RostraPathId, post_delete_url, post_edit_cancel_url, post_edit_url,
-    post_fetch_url, post_url, profile_url, redirect_to_canonical,
+    EventPathId, RostraPathId, post_boost_url, post_delete_url, post_edit_cancel_url,
+    post_edit_url, post_fetch_url, post_poll_vote_url, post_url, profile_url,
+    redirect_to_canonical,
 };
 use crate::util::extractors::AjaxRequest;
 use crate::util::time::{format_timestamp, format_timestamp_iso};
@@ -55,6 +56,16 @@ pub fn post_html_id(post_thread_id: ShortEventId, event_id: ShortEventId) -> Str
     format!("post-{post_thread_id}-{event_id}")
 }
 
+/// Generate HTML ID for the boost button of a post.
+pub fn post_boost_html_id(post_thread_id: ShortEventId, event_id: ShortEventId) -> String {
+    format!("post-boost-{post_thread_id}-{event_id}")
+}
+
+/// Generate HTML ID for the poll of a post.
+pub fn post_poll_html_id(post_thread_id: ShortEventId, event_id: ShortEventId) -> String {
+    format!("post-poll-{post_thread_id}-{event_id}")
+}
+
 /// Generate HTML ID for inline reply form container.
 pub fn post_inline_reply_form_html_id(
     post_thread_id: ShortEventId,
@@ -842,6 +853,99 @@ pub async fn post_edit_post(
     }))
 }
 
+#[derive(Deserialize)]
+pub struct BoostInput {
+    post_thread_id: ShortEventId,
+}
+
+/// Boost a post, or undo the boost if already boosted
+pub async fn boost_post(
+    state: State<SharedState>,
+    session: UserSession,
+    Path((author_id, event_id)): Path<(PostAuthorId, EventPathId)>,
+    Form(form): Form<BoostInput>,
+) -> RequestResult<impl IntoResponse> {
+    let client_handle = state.client(session.id()).await?;
+    let client = client_handle.client_ref()?;
+    let author_id = author_id
+        .resolve(client.db())
+        .await
+        .ok_or_else(post_not_found)?;
+    let event_id = event_id
+        .resolve(client.db())
+        .await
+        .ok_or_else(post_not_found)?;
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let post_id = ExternalEventId::new(author_id, event_id);
+    if let Some(repost) = client.db().get_self_repost(post_id).await {
+        client.social_unrepost(id_secret, post_id, repost).await?;
+    } else {
+        client.social_repost(id_secret, post_id, None).await?;
+    }
+
+    Ok(Maud(
+        state
+            .render_boost_button(&client, post_id, form.post_thread_id)
+            .ro(state.ro_mode(session.session_token()))
+            .call()
+            .await,
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct PollVoteInput {
+    post_thread_id: ShortEventId,
+    /// Missing to retract the vote
+    #[serde(default)]
+    option: Option<u32>,
+}
+
+/// Vote in the poll of a post
+pub async fn vote_poll(
+    state: State<SharedState>,
+    session: UserSession,
+    Path((author_id, event_id)): Path<(PostAuthorId, EventPathId)>,
+    Form(form): Form<PollVoteInput>,
+) -> RequestResult<impl IntoResponse> {
+    let client_handle = state.client(session.id()).await?;
+    let client = client_handle.client_ref()?;
+    let author_id = author_id
+        .resolve(client.db())
+        .await
+        .ok_or_else(post_not_found)?;
+    let event_id = event_id
+        .resolve(client.db())
+        .await
+        .ok_or_else(post_not_found)?;
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let post = client
+        .db()
+        .get_social_post(event_id)
+        .await
+        .filter(|post| post.author == author_id)
+        .ok_or_else(post_not_found)?;
+    let poll = post.content.poll.ok_or_else(post_not_found)?;
+    let poll_id = ExternalEventId::new(author_id, poll);
+    client
+        .set_social_poll_vote(id_secret, poll_id, form.option)
+        .await?;
+
+    Ok(Maud(
+        state
+            .render_poll(&client, ExternalEventId::new(author_id, event_id), poll)
+            .post_thread_id(form.post_thread_id)
+            .ro(state.ro_mode(session.session_token()))
+            .call()
+            .await,
+    ))
+}
+
 pub async fn fetch_missing_post(
     state: State<SharedState>,
     session: UserSession,
@@ -1060,6 +1164,118 @@ impl UiState {
         })
     }
 
+    /// Render the boost button of a post, with the number of boosts
+    #[builder]
+    pub async fn render_boost_button(
+        &self,
+        #[builder(start_fn)] client: &ClientRef<'_>,
+        #[builder(start_fn)] post_id: ExternalEventId,
+        #[builder(start_fn)] post_thread_id: ShortEventId,
+        ro: RoMode,
+    ) -> Markup {
+        let author = post_id.rostra_id();
+        let event_id = post_id.event_id();
+        let count = client.db().get_social_repost_count(post_id).await;
+        let boosted = client.db().get_self_repost(post_id).await.is_some();
+        let boost_id = post_boost_html_id(post_thread_id, event_id);
+        let label = match (boosted, count) {
+            (true, count) => format!("Boosted ({count})"),
+            (false, 0) => "Boost".to_string(),
+            (false, count) => format!("Boost ({count})"),
+        };
+        html! {
+            div #(boost_id) ."m-postView__boost" {
+                (fragment::ajax_button(
+                    &post_boost_url(author, event_id),
+                    "post",
+                    &boost_id,
+                    "m-postView__boostButton",
+                    &label,
+                )
+                .disabled(ro.to_disabled())
+                .hidden_inputs(html! {
+                    input type="hidden" name="post_thread_id" value=(post_thread_id) {}
+                })
+                .call())
+            }
+        }
+    }
+
+    /// Render the poll attached to a post, with the current tally and the
+    /// voting buttons
+    #[builder]
+    pub async fn render_poll(
+        &self,
+        #[builder(start_fn)] client: &ClientRef<'_>,
+        #[builder(start_fn)] post_id: ExternalEventId,
+        #[builder(start_fn)] poll: ShortEventId,
+        post_thread_id: ShortEventId,
+        ro: RoMode,
+    ) -> Markup {
+        let author = post_id.rostra_id();
+        let event_id = post_id.event_id();
+        let poll_html_id = post_poll_html_id(post_thread_id, event_id);
+        let poll_id = ExternalEventId::new(author, poll);
+        let Some(poll) = client.db().get_social_poll(poll_id).await else {
+            return html! {
+                div #(poll_html_id) ."m-poll -unavailable" {
+                    "Poll not available yet"
+                }
+            };
+        };
+        let tally = client.db().get_social_poll_tally(poll_id).await;
+        let self_vote = client.get_self_social_poll_vote(poll_id).await;
+        let closed = poll.is_closed(Timestamp::now());
+        let total: u64 = (0..poll.options.len() as u32)
+            .filter_map(|option| tally.get(&option))
+            .sum();
+        let vote_url = post_poll_vote_url(author, event_id);
+
+        html! {
+            div #(poll_html_id) ."m-poll" ."-closed"[closed] {
+                @for (index, option) in poll.options.iter().enumerate() {
+                    @let index = index as u32;
+                    @let count = tally.get(&index).copied().unwrap_or_default();
+                    @let percent = (count * 100).checked_div(total).unwrap_or_default();
+                    @let selected = self_vote == Some(index);
+                    div ."m-poll__option" ."-selected"[selected]
+                        style=(format!("--m-poll-percent: {percent}%"))
+                    {
+                        (fragment::ajax_button(
+                            &vote_url,
+                            "post",
+                            &poll_html_id,
+                            "m-poll__optionButton",
+                            &format!("{option} · {count} ({percent}%)"),
+                        )
+                        .disabled(ro.is_ro() || closed)
+                        .hidden_inputs(html! {
+                            input type="hidden" name="post_thread_id" value=(post_thread_id) {}
+                            @if !selected {
+                                input type="hidden" name="option" value=(index) {}
+                            }
+                        })
+                        .call())
+                    }
+                }
+                div ."m-poll__summary" {
+                    (if total == 1 { "1 vote".to_string() } else { format!("{total} votes") })
+                    @if let Some(closes_at) = poll.closes_at {
+                        " · "
+                        @if closed {
+                            "Closed "
+                        } @else {
+                            "Closes "
+                        }
+                        time datetime=(format_timestamp_iso(closes_at)) {
+                            (format_timestamp(closes_at))
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     /// Render post without its parents and comments, but with the buttons
     /// etc.)
     #[allow(clippy::too_many_arguments)]
@@ -1163,6 +1379,20 @@ impl UiState {
         } else {
             None
         };
+        let poll = match (
+            external_event_id,
+            post_thread_id,
+            fetched_post.as_ref().and_then(|post| post.content.poll),
+        ) {
+            (Some(post_id), Some(ctx), Some(poll)) => Some(
+                self.render_poll(client, post_id, poll)
+                    .post_thread_id(ctx)
+                    .ro(ro)
+                    .call()
+                    .await,
+            ),
+            _ => None,
+        };
 
         let display_name = if let Some(ref profile) = user_profile {
             profile.display_name.clone()
@@ -1280,10 +1510,24 @@ impl UiState {
                         p { "Post missing" }
                     }
                 }
+                @if let Some(poll) = poll {
+                    (poll)
+                }
             }
 
         };
 
+        let boost_button = if let (Some(post_id), Some(ctx)) = (external_event_id, post_thread_id) {
+            Some(
+                self.render_boost_button(client, post_id, ctx)
+                    .ro(ro)
+                    .call()
+                    .await,
+            )
+        } else {
+            None
+        };
+
         let button_bar = html! {
             @if let Some(ext_event_id) = external_event_id {
                 div ."m-postView__buttonBar" {
@@ -1325,6 +1569,9 @@ impl UiState {
                                 ).call())
                             }
                         }
+                        @if let Some(boost_button) = boost_button {
+                            (boost_button)
+                        }
                         // Reply button only available when we have a thread context
                         @if let Some(ctx) = post_thread_id {
                             // Target the replies container (placeholders are rendered inside when expanded)
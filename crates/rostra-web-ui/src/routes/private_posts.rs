@@ -0,0 +1,354 @@
+use std::collections::BTreeSet;
+
+use axum::Form;
+use axum::extract::State;
+use axum::response::{IntoResponse, Redirect};
+use maud::{Markup, html};
+use rostra_client::ClientRef;
+use rostra_client_db::social::{EventPaginationCursor, SocialPostRecord};
+use rostra_core::event::content_kind::SocialPost;
+use rostra_core::id::RostraId;
+use rostra_core::{ShortEventId, Timestamp};
+use serde::Deserialize;
+
+use super::super::SharedState;
+use super::super::error::{ReadOnlyModeSnafu, RequestResult, UserRequestError};
+use super::unlock::session::UserSession;
+use super::url::{RostraPathId, profile_url};
+use super::{Maud, fragment};
+use crate::UiState;
+use crate::error::RequestError;
+use crate::html_utils::re_typeset;
+use crate::layout::FeedLinks;
+use crate::util::extractors::AjaxRequest;
+use crate::util::time::format_timestamp;
+
+/// Number of posts per page of private posts
+const PRIVATE_POSTS_LIMIT: usize = 20;
+
+#[derive(Deserialize)]
+pub struct PrivatePostsPaginationInput {
+    ts: Option<Timestamp>,
+    event_id: Option<ShortEventId>,
+}
+
+#[derive(Deserialize)]
+pub struct PrivatePostInput {
+    content: String,
+}
+
+#[derive(Deserialize)]
+pub struct PrivateAudienceMemberInput {
+    id: String,
+}
+
+/// Private posts of the identity and of the audiences it is a member of,
+/// newest first, with a form to write one
+pub async fn get_private_posts(
+    state: State<SharedState>,
+    session: UserSession,
+    AjaxRequest(is_ajax): AjaxRequest,
+    Form(form): Form<PrivatePostsPaginationInput>,
+) -> RequestResult<impl IntoResponse> {
+    let pagination = form.ts.and_then(|ts| {
+        form.event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let id_secret = state.id_secret(session.session_token());
+    let ro_mode = state.ro_mode(session.session_token());
+
+    // Like direct messages, decrypted posts are only shown with the secret
+    let (posts, cursor) = if id_secret.is_some() {
+        client_ref
+            .db()
+            .paginate_private_posts_rev(pagination, PRIVATE_POSTS_LIMIT)
+            .await
+    } else {
+        (vec![], None)
+    };
+    let no_posts = pagination.is_none() && posts.is_empty();
+    let posts = html! {
+        div id="private-posts" x-merge="append" {
+            @for post in &posts {
+                (state.render_private_post(&client_ref, post).await)
+            }
+        }
+        @if let Some(cursor) = cursor {
+            // Infinite scroll, same as in timelines
+            a
+                id="private-load-more" ."o-mainBarTimeline__rest -empty"
+                "href"=(format!("/private?ts={}&event_id={}", cursor.ts, cursor.event_id))
+                x-init="new IntersectionObserver((entries, obs) => { if (entries[0].isIntersecting) { obs.disconnect(); $ajax($el.href, { targets: ['private-load-more', 'private-posts'] }); } }, { root: document.body, rootMargin: '0px 0px 250% 0px' }).observe($el)"
+            { "More posts" }
+        } @else {
+            div id="private-load-more" ."o-mainBarTimeline__rest -empty" {}
+        }
+    };
+    if is_ajax {
+        return Ok(Maud(html! {
+            (posts)
+            (re_typeset())
+        }));
+    }
+
+    let audience = client_ref.db().get_private_audience().await;
+    let member_count = audience.as_ref().map_or(0, |audience| {
+        audience
+            .members
+            .iter()
+            .filter(|id| **id != session.id())
+            .count()
+    });
+    let can_post = !ro_mode.is_ro() && audience.is_some();
+    let content = html! {
+        div ."o-mainBarTimeline" {
+            (UiState::render_page_tab_bar("Private"))
+            form ."o-privatePosts__form"
+                action="/private"
+                method="post"
+            {
+                textarea ."o-privatePosts__input"
+                    name="content"
+                    placeholder="Write a post only your audience can read..."
+                    dir="auto"
+                    required
+                    disabled[!can_post]
+                    {}
+                div ."o-privatePosts__formFooter" {
+                    a href="/private/audience" {
+                        @match member_count {
+                            0 => "No audience yet, add members",
+                            1 => "Audience: 1 member",
+                            count => (format!("Audience: {count} members")),
+                        }
+                    }
+                    (fragment::button("o-privatePosts__submitButton", "Post")
+                        .disabled(!can_post)
+                        .call())
+                }
+            }
+            @if id_secret.is_none() {
+                p ."o-privatePosts__note" { "Unlock your identity with its secret to read private posts." }
+            } @else if no_posts {
+                p ."o-privatePosts__note" { "No private posts yet." }
+            }
+            (posts)
+        }
+    };
+
+    let navbar = state
+        .timeline_common_navbar()
+        .session(&session)
+        .call()
+        .await?;
+    let page_layout = state.render_page_layout(navbar, content);
+    let content = html! {
+        (page_layout)
+        (re_typeset())
+    };
+    Ok(Maud(
+        state
+            .render_html_page(
+                "Private posts - Rostra",
+                content,
+                None::<&FeedLinks>,
+                None,
+                None,
+                true,
+            )
+            .await?,
+    ))
+}
+
+pub async fn post_private_post(
+    state: State<SharedState>,
+    session: UserSession,
+    Form(form): Form<PrivatePostInput>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    client_ref
+        .social_private_post(id_secret, form.content, None, BTreeSet::new())
+        .await?;
+
+    Ok(Redirect::to("/private"))
+}
+
+/// Members of the audience of private posts, with forms to add and remove
+/// them
+pub async fn get_private_audience(
+    state: State<SharedState>,
+    session: UserSession,
+) -> RequestResult<impl IntoResponse> {
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    // Granting the audience key needs the identity's own secret
+    let can_manage = state
+        .id_secret(session.session_token())
+        .is_some_and(|id_secret| id_secret.id() == session.id());
+
+    let members: Vec<_> = client_ref
+        .db()
+        .get_private_audience()
+        .await
+        .map(|audience| audience.members)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| *id != session.id())
+        .collect();
+
+    let content = html! {
+        div ."o-mainBarTimeline" {
+            div ."o-mainBarTimeline__tabs" {
+                a ."o-mainBarTimeline__back" href="/private" { "<" }
+                a ."-active" href="/private/audience" { "Audience" }
+            }
+            form ."o-privatePosts__add"
+                action="/private/audience/add"
+                method="post"
+            {
+                input ."o-privatePosts__addInput"
+                    type="text"
+                    name="id"
+                    placeholder="Rostra ID of the new member"
+                    required
+                    autocomplete="off"
+                    disabled[!can_manage]
+                    {}
+                (fragment::button("o-privatePosts__addButton", "Add")
+                    .disabled(!can_manage)
+                    .call())
+            }
+            @if !can_manage {
+                p ."o-privatePosts__note" { "Unlock your identity with its own secret to manage the audience." }
+            } @else if members.is_empty() {
+                p ."o-privatePosts__note" { "No members yet. Only members can read your private posts." }
+            } @else {
+                p ."o-privatePosts__note" { "Members removed from the audience can't read your private posts published afterwards." }
+            }
+            @for member in &members {
+                @let profile = state.get_social_profile(*member, &client_ref).await;
+                form ."o-privatePosts__member"
+                    action="/private/audience/remove"
+                    method="post"
+                {
+                    (fragment::avatar("o-privatePosts__avatar", state.avatar_url(*member, profile.event_id), "Avatar"))
+                    a ."o-privatePosts__author" href=(profile_url(*member)) { (profile.display_name) }
+                    input type="hidden" name="id" value=(member) {}
+                    (fragment::button("o-privatePosts__removeButton", "Remove")
+                        .disabled(!can_manage)
+                        .call())
+                }
+            }
+        }
+    };
+
+    let navbar = state
+        .timeline_common_navbar()
+        .session(&session)
+        .call()
+        .await?;
+    let page_layout = state.render_page_layout(navbar, content);
+    Ok(Maud(
+        state
+            .render_html_page(
+                "Private audience - Rostra",
+                page_layout,
+                None::<&FeedLinks>,
+                None,
+                None,
+                true,
+            )
+            .await?,
+    ))
+}
+
+pub async fn post_add_private_audience_member(
+    state: State<SharedState>,
+    session: UserSession,
+    Form(form): Form<PrivateAudienceMemberInput>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let member = resolve_member(&client_ref, &form.id).await?;
+    client_ref
+        .add_private_audience_member(id_secret, member)
+        .await?;
+
+    Ok(Redirect::to("/private/audience"))
+}
+
+pub async fn post_remove_private_audience_member(
+    state: State<SharedState>,
+    session: UserSession,
+    Form(form): Form<PrivateAudienceMemberInput>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let member = resolve_member(&client_ref, &form.id).await?;
+    client_ref
+        .remove_private_audience_member(id_secret, member)
+        .await?;
+
+    Ok(Redirect::to("/private/audience"))
+}
+
+async fn resolve_member(client_ref: &ClientRef<'_>, id: &str) -> RequestResult<RostraId> {
+    let id = id
+        .trim()
+        .parse::<RostraPathId>()
+        .map_err(|_| RequestError::User {
+            source: UserRequestError::InvalidData,
+        })?;
+    id.resolve(client_ref.db())
+        .await
+        .ok_or_else(|| RequestError::User {
+            source: UserRequestError::SomethingNotFound,
+        })
+}
+
+impl UiState {
+    async fn render_private_post(
+        &self,
+        client: &ClientRef<'_>,
+        post: &SocialPostRecord<SocialPost>,
+    ) -> Markup {
+        let profile = self.get_social_profile(post.author, client).await;
+
+        html! {
+            div ."o-privatePosts__post" {
+                (fragment::avatar("o-privatePosts__avatar", self.avatar_url(post.author, profile.event_id), "Avatar"))
+                div ."o-privatePosts__postBody" {
+                    div ."o-privatePosts__postMeta" {
+                        a ."o-privatePosts__author" href=(profile_url(post.author)) {
+                            (profile.display_name)
+                        }
+                        span ."o-privatePosts__timestamp" {
+                            (format_timestamp(post.ts))
+                        }
+                    }
+                    div ."o-privatePosts__postContent" {
+                        @if let Some(djot_content) = post.content.djot_content.as_deref() {
+                            (self.render_content(client, post.author, djot_content).await)
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
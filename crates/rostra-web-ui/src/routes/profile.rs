@@ -14,7 +14,10 @@ use super::unlock::session::{RoMode, UserSession};
 use super::{Maud, fragment};
 use crate::error::{ReadOnlyModeSnafu, RequestResult, UserRequestError};
 use crate::layout::{OpenGraphMeta, truncate_at_word_boundary};
-use crate::routes::url::{RostraPathId, profile_follow_url, profile_url, redirect_to_canonical};
+use crate::routes::url::{
+    RostraPathId, profile_accept_succession_url, profile_follow_url, profile_mentions_url,
+    profile_url, redirect_to_canonical,
+};
 use crate::util::extractors::AjaxRequest;
 use crate::{SharedState, UiState};
 
@@ -126,6 +129,8 @@ pub async fn get_follow_dialog(
         None
     };
 
+    let blocked = client_ref.db().is_self_blocked(profile_id).await;
+
     // Determine current follow type and selected tags
     let (follow_type, selected_tags) = match &current_selector {
         Some(PersonasTagsSelector::Except { ids }) => ("follow_all", ids.clone()),
@@ -171,6 +176,12 @@ pub async fn get_follow_dialog(
                                 value="follow_only"
                                 selected[follow_type == "follow_only"]
                             { "Follow Only (selected)" }
+
+                            @if blocked {
+                                option value="unblock" { "Unblock" }
+                            } @else {
+                                option value="block" { "Block" }
+                            }
                         }
                     }
 
@@ -228,6 +239,12 @@ pub async fn post_follow(
         "unfollow" => {
             client_ref.unfollow(id_secret, profile_id).await?;
         }
+        "block" => {
+            client_ref.block(id_secret, profile_id).await?;
+        }
+        "unblock" => {
+            client_ref.unblock(id_secret, profile_id).await?;
+        }
         "follow_all" | "follow_only" => {
             let ids: std::collections::BTreeSet<PersonaTag> = form
                 .personas
@@ -270,6 +287,33 @@ pub async fn post_follow(
     }))
 }
 
+/// Move the follow of a profile to its latest successor
+pub async fn post_accept_succession(
+    state: State<SharedState>,
+    session: UserSession,
+    Path(profile_id): Path<RostraPathId>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let profile_id = profile_id.resolve(client_ref.db()).await.ok_or_else(|| {
+        crate::error::RequestError::User {
+            source: UserRequestError::SomethingNotFound,
+        }
+    })?;
+
+    client_ref.accept_succession(id_secret, profile_id).await?;
+
+    Ok(Maud(
+        state
+            .render_profile_summary(profile_id, &session, state.ro_mode(session.session_token()))
+            .await?,
+    ))
+}
+
 impl UiState {
     pub async fn render_navbar(
         &self,
@@ -297,12 +341,21 @@ impl UiState {
         let client = self.client(session.id()).await?;
         let client_ref = client.client_ref()?;
         let profile = self.get_social_profile(profile_id, &client_ref).await;
-        let following = client
-            .db()?
-            .get_followees(session.id())
+        let followees = client.db()?.get_followees(session.id()).await;
+        let following = followees.iter().any(|(id, _)| id == &profile_id);
+        let successor = match client_ref
+            .db()
+            .get_succession_chain(profile_id)
             .await
-            .iter()
-            .any(|(id, _)| id == &profile_id);
+            .last()
+        {
+            Some(&successor) => Some((
+                successor,
+                self.get_social_profile(successor, &client_ref).await,
+                followees.iter().any(|(id, _)| id == &successor),
+            )),
+            None => None,
+        };
         let rendered_bio = self.render_bio(client_ref, &profile.bio).await;
         Ok(html! {
             div id="profile-summary" ."m-profileSummary" {
@@ -321,6 +374,12 @@ impl UiState {
                             .onclick("copyIdToClipboard(event)")
                             .aria_label("Copy RostraId")
                             .call())
+                        a ."m-profileSummary__mentionsLink u-button"
+                            href=(profile_mentions_url(profile_id))
+                        {
+                            span ."m-profileSummary__mentionsLinkIcon u-buttonIcon" {}
+                            "Mentions"
+                        }
                         @if session.id() != profile_id {
                             @let label = if following { "Following..." } else { "Follow..." };
                             (fragment::ajax_button(
@@ -339,6 +398,28 @@ impl UiState {
                     }
                 }
 
+                @if let Some((successor, successor_profile, following_successor)) = successor {
+                    div ."m-profileSummary__moved" {
+                        "This account moved to "
+                        a ."m-profileSummary__movedLink u-displayName"
+                            href=(profile_url(successor))
+                        {
+                            (successor_profile.display_name)
+                        }
+                        @if following && !following_successor {
+                            (fragment::ajax_button(
+                                &profile_accept_succession_url(profile_id),
+                                "post",
+                                "profile-summary",
+                                "m-profileSummary__acceptSuccessionButton",
+                                "Follow new account",
+                            )
+                            .disabled(ro.to_disabled())
+                            .call())
+                        }
+                    }
+                }
+
                 div ."m-profileSummary__bio" { (rendered_bio) }
             }
 
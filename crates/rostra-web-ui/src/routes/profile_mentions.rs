@@ -0,0 +1,153 @@
+use axum::Form;
+use axum::extract::{OriginalUri, Path, State};
+use axum::response::IntoResponse;
+use maud::{Markup, html};
+use rostra_client_db::social::EventPaginationCursor;
+use rostra_core::id::RostraId;
+
+use super::Maud;
+use super::timeline::TimelinePaginationInput;
+use super::unlock::session::UserSession;
+use super::url::{RostraPathId, profile_mentions_url, redirect_to_canonical};
+use crate::error::{RequestError, RequestResult, UserRequestError};
+use crate::html_utils::re_typeset;
+use crate::util::extractors::AjaxRequest;
+use crate::{SharedState, UiState};
+
+/// Number of posts per page of a mentions timeline
+const MENTION_POSTS_LIMIT: usize = 20;
+
+pub async fn get_profile_mentions(
+    state: State<SharedState>,
+    session: UserSession,
+    AjaxRequest(is_ajax): AjaxRequest,
+    OriginalUri(original_uri): OriginalUri,
+    Path(profile_id): Path<RostraPathId>,
+    Form(form): Form<TimelinePaginationInput>,
+) -> RequestResult<impl IntoResponse> {
+    let pagination = form.ts.and_then(|ts| {
+        form.event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let profile_id =
+        profile_id
+            .resolve(client_ref.db())
+            .await
+            .ok_or_else(|| RequestError::User {
+                source: UserRequestError::SomethingNotFound,
+            })?;
+    if let Some(response) = redirect_to_canonical(&original_uri, profile_mentions_url(profile_id)) {
+        return Ok(response);
+    }
+
+    let posts = state
+        .render_mention_posts(&session, profile_id, pagination)
+        .await?;
+    if is_ajax {
+        return Ok(Maud(posts).into_response());
+    }
+
+    let profile = state.get_social_profile(profile_id, &client_ref).await;
+    let title = format!("Mentions of {}", profile.display_name);
+    let navbar = state.render_navbar(profile_id, &session).await?;
+    let main_content = html! {
+        div ."o-mainBarTimeline" {
+            (UiState::render_page_tab_bar(&title))
+            (posts)
+        }
+    };
+    let content = html! {
+        (state.render_page_layout(navbar, main_content))
+        (re_typeset())
+    };
+    Ok(Maud(
+        state
+            .render_html_page(
+                &format!("{title} - Rostra"),
+                content,
+                None,
+                None,
+                None,
+                true,
+            )
+            .await?,
+    )
+    .into_response())
+}
+
+impl UiState {
+    /// Render posts @mentioning `profile_id` by identities in the web of
+    /// trust
+    async fn render_mention_posts(
+        &self,
+        session: &UserSession,
+        profile_id: RostraId,
+        pagination: Option<EventPaginationCursor>,
+    ) -> RequestResult<Markup> {
+        let client = self.client(session.id()).await?;
+        let client_ref = client.client_ref()?;
+
+        let self_id = client_ref.rostra_id();
+        let wot = client_ref.self_wot_subscribe().snapshot();
+        let (posts, cursor) = client_ref
+            .db()
+            .paginate_social_posts_mentioning_rev(
+                profile_id,
+                pagination,
+                MENTION_POSTS_LIMIT,
+                move |post| wot.contains(post.author, self_id),
+            )
+            .await;
+
+        Ok(html! {
+            div id="mention-posts" x-merge="append" {
+                @if pagination.is_none() && posts.is_empty() && cursor.is_none() {
+                    div ."o-postSearch__empty" { "No posts mentioning this identity yet." }
+                }
+                @for post in &posts {
+                    @if let Some(djot_content) = post.content.djot_content.as_ref() {
+                        div ."o-mainBarTimeline__item"
+                            ."-reply"[post.reply_to.is_some()]
+                            ."-post"[post.reply_to.is_none()]
+                        {
+                            (self.render_post_context(&client_ref, post.author)
+                                .persona_tags(&post.content.persona_tags())
+                                .maybe_reply_to(post.reply_to.map(|reply_to| {
+                                    (reply_to.rostra_id(), reply_to.event_id(), None)
+                                }))
+                                .event_id(post.event_id)
+                                .post_thread_id(post.event_id)
+                                .content(djot_content)
+                                .maybe_url(post.content.url.as_ref())
+                                .maybe_title(post.content.title.as_deref())
+                                .reply_count(post.reply_count)
+                                .timestamp(post.ts)
+                                .ro(self.ro_mode(session.session_token()))
+                                .call()
+                                .await?)
+                        }
+                    }
+                }
+            }
+            @if let Some(cursor) = cursor {
+                // Infinite scroll, same as in timelines
+                @let href = format!(
+                    "{}?ts={}&event_id={}",
+                    profile_mentions_url(profile_id),
+                    cursor.ts,
+                    cursor.event_id
+                );
+                a
+                    id="mention-load-more" ."o-mainBarTimeline__rest -empty"
+                    "href"=(href)
+                    x-init="new IntersectionObserver((entries, obs) => { if (entries[0].isIntersecting) { obs.disconnect(); $ajax($el.href, { targets: ['mention-load-more', 'mention-posts'] }); } }, { root: document.body, rootMargin: '0px 0px 250% 0px' }).observe($el)"
+                { "More posts" }
+            } @else {
+                div id="mention-load-more" ."o-mainBarTimeline__rest -empty" {}
+            }
+        })
+    }
+}
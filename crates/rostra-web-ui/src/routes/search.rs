@@ -1,12 +1,21 @@
-use axum::Json;
 use axum::extract::{Query, State};
 use axum::response::IntoResponse;
+use axum::{Form, Json};
+use maud::{Markup, html};
+use rostra_client_db::social::EventPaginationCursor;
 use rostra_core::id::{RostraId, ShortRostraId, ToShort as _};
+use rostra_core::{ShortEventId, Timestamp};
 use serde::{Deserialize, Serialize, Serializer};
 
+use super::Maud;
 use super::unlock::session::UserSession;
-use crate::SharedState;
 use crate::error::RequestResult;
+use crate::html_utils::re_typeset;
+use crate::util::extractors::AjaxRequest;
+use crate::{SharedState, UiState};
+
+/// Number of posts per page of post search results
+const POST_SEARCH_LIMIT: usize = 20;
 
 /// Subsequence fuzzy match: each query char must appear in order in text.
 /// Returns score > 0 on match, 0 on no match. Rewards consecutive matches
@@ -153,5 +162,135 @@ pub async fn search_profiles(
     Ok(Json(order_and_limit_results(scored)))
 }
 
+#[derive(Deserialize)]
+pub struct PostSearchInput {
+    #[serde(default)]
+    q: String,
+    ts: Option<Timestamp>,
+    event_id: Option<ShortEventId>,
+}
+
+pub async fn get_search(
+    state: State<SharedState>,
+    session: UserSession,
+    AjaxRequest(is_ajax): AjaxRequest,
+    Form(form): Form<PostSearchInput>,
+) -> RequestResult<impl IntoResponse> {
+    let pagination = form.ts.and_then(|ts| {
+        form.event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+
+    let results = state
+        .render_post_search_results(&session, &form.q, pagination)
+        .await?;
+    if is_ajax {
+        return Ok(Maud(results));
+    }
+
+    let navbar = state
+        .timeline_common_navbar()
+        .session(&session)
+        .call()
+        .await?;
+    let main_content = html! {
+        div ."o-mainBarTimeline" {
+            (UiState::render_page_tab_bar("Search"))
+            form ."o-postSearch" action="/search" method="get" {
+                input ."o-postSearch__input"
+                    type="search"
+                    name="q"
+                    value=(form.q)
+                    placeholder="Search posts..."
+                    autocomplete="off"
+                    autofocus
+                    {}
+                button ."o-postSearch__button u-button" type="submit" { "Search" }
+            }
+            (results)
+        }
+    };
+    let content = html! {
+        (state.render_page_layout(navbar, main_content))
+        (re_typeset())
+    };
+    Ok(Maud(
+        state
+            .render_html_page("Search - Rostra", content, None, None, None, true)
+            .await?,
+    ))
+}
+
+impl UiState {
+    async fn render_post_search_results(
+        &self,
+        session: &UserSession,
+        query: &str,
+        pagination: Option<EventPaginationCursor>,
+    ) -> RequestResult<Markup> {
+        let client = self.client(session.id()).await?;
+        let client_ref = client.client_ref()?;
+
+        let (posts, cursor) = client_ref
+            .db()
+            .search_social_posts(query, pagination, POST_SEARCH_LIMIT)
+            .await;
+
+        Ok(html! {
+            div id="search-results" x-merge="append" {
+                @if pagination.is_none() && posts.is_empty() && cursor.is_none() {
+                    div ."o-postSearch__empty" {
+                        @if query.trim().is_empty() {
+                            "Search posts by the words they contain."
+                        } @else {
+                            "No posts found."
+                        }
+                    }
+                }
+                @for post in &posts {
+                    @if let Some(djot_content) = post.content.djot_content.as_ref() {
+                        div ."o-mainBarTimeline__item"
+                            ."-reply"[post.reply_to.is_some()]
+                            ."-post"[post.reply_to.is_none()]
+                        {
+                            (self.render_post_context(&client_ref, post.author)
+                                .persona_tags(&post.content.persona_tags())
+                                .maybe_reply_to(post.reply_to.map(|reply_to| {
+                                    (reply_to.rostra_id(), reply_to.event_id(), None)
+                                }))
+                                .event_id(post.event_id)
+                                .post_thread_id(post.event_id)
+                                .content(djot_content)
+                                .maybe_url(post.content.url.as_ref())
+                                .maybe_title(post.content.title.as_deref())
+                                .reply_count(post.reply_count)
+                                .timestamp(post.ts)
+                                .ro(self.ro_mode(session.session_token()))
+                                .call()
+                                .await?)
+                        }
+                    }
+                }
+            }
+            @if let Some(cursor) = cursor {
+                // Infinite scroll, same as in timelines
+                @let href = format!(
+                    "/search?q={}&ts={}&event_id={}",
+                    urlencoding::encode(query),
+                    cursor.ts,
+                    cursor.event_id
+                );
+                a
+                    id="search-load-more" ."o-mainBarTimeline__rest -empty"
+                    "href"=(href)
+                    x-init="new IntersectionObserver((entries, obs) => { if (entries[0].isIntersecting) { obs.disconnect(); $ajax($el.href, { targets: ['search-load-more', 'search-results'] }); } }, { root: document.body, rootMargin: '0px 0px 250% 0px' }).observe($el)"
+                { "More results" }
+            } @else {
+                div id="search-load-more" ."o-mainBarTimeline__rest -empty" {}
+            }
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::FromStr as _;
 
 use axum::Form;
@@ -7,20 +7,28 @@ use axum::response::{IntoResponse, Redirect, Response};
 use maud::{Markup, PreEscaped, html};
 use rostra_client::id::IdResolvedData;
 use rostra_client::{IdP2PState, NodeP2PState};
+use rostra_client_db::content_filters::ContentFilters;
+use rostra_client_db::drafts::{PostDraft, PostDraftId};
 use rostra_client_db::{EventContentState, EventRecord, IdsDataUsageRecord, IrohNodeRecord};
-use rostra_core::event::IrohNodeId;
-use rostra_core::id::RostraId;
+use rostra_core::event::{IrohNodeId, PersonaTag};
+use rostra_core::id::{RostraId, RostraIdSecretKey};
 use rostra_core::{ShortEventId, Timestamp};
 use serde::Deserialize;
+use snafu::ResultExt as _;
 
+use super::new_post::{bad_request, parse_scheduled_at};
 use super::profile_self::extractor;
 use super::unlock::session::UserSession;
 use super::{Maud, fragment, recovery};
-use crate::error::{ReadOnlyModeSnafu, RequestResult};
+use crate::api_tokens::{ApiScope, ApiToken, ApiTokenId, ApiTokenRecord};
+use crate::error::{OtherSnafu, ReadOnlyModeSnafu, RequestResult};
 use crate::routes::url::{
-    EventPathId, profile_follow_url, profile_url, redirect_to_canonical, settings_event_content_url,
+    EventPathId, post_url, profile_follow_url, profile_url, redirect_to_canonical,
+    settings_api_token_url, settings_draft_url, settings_event_content_url,
+};
+use crate::util::time::{
+    format_datetime_local_utc, format_timestamp, format_timestamp_iso, format_timestamp_utc,
 };
-use crate::util::time::{format_timestamp, format_timestamp_iso};
 use crate::{SharedState, UiState};
 
 /// dpc's (Rostra author) RostraId as a string.
@@ -186,6 +194,458 @@ pub async fn get_settings_followers(
     ))
 }
 
+pub async fn get_settings_filters(
+    state: State<SharedState>,
+    session: UserSession,
+) -> RequestResult<impl IntoResponse> {
+    let filters = state
+        .client(session.id())
+        .await?
+        .client_ref()?
+        .db()
+        .get_content_filters()
+        .await;
+
+    let navbar = state.render_settings_navbar(&session, "filters").await?;
+    let content = state.render_filters_settings(&session, &filters);
+
+    Ok(Maud(
+        state
+            .render_settings_page(&session, navbar, "Filters", content)
+            .await?,
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct ContentFiltersInput {
+    muted_words: String,
+    muted_patterns: String,
+    muted_persona_tags: String,
+    muted_domains: String,
+    hide_reactions: Option<String>,
+    hide_reposts: Option<String>,
+}
+
+impl ContentFiltersInput {
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(ToOwned::to_owned).collect()
+    }
+
+    fn into_filters(self) -> Result<ContentFilters, &'static str> {
+        let muted_persona_tags = self
+            .muted_persona_tags
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(PersonaTag::new)
+            .collect::<Result<_, _>>()
+            .map_err(|_| "Invalid persona tag")?;
+        let filters = ContentFilters {
+            muted_words: Self::lines(&self.muted_words),
+            muted_patterns: Self::lines(&self.muted_patterns),
+            muted_persona_tags,
+            muted_domains: Self::lines(&self.muted_domains),
+            hide_reactions: self.hide_reactions.is_some(),
+            hide_reposts: self.hide_reposts.is_some(),
+        }
+        .normalized();
+        filters.validate().map_err(|_| "Invalid muted pattern")?;
+        Ok(filters)
+    }
+}
+
+pub async fn post_settings_filters(
+    state: State<SharedState>,
+    session: UserSession,
+    Form(form): Form<ContentFiltersInput>,
+) -> RequestResult<impl IntoResponse> {
+    if state.id_secret(session.session_token()).is_none() {
+        return Err(ReadOnlyModeSnafu.build());
+    }
+
+    let filters = match form.into_filters() {
+        Ok(filters) => filters,
+        Err(message) => {
+            return Ok(Maud(html! {
+                div id="ajax-scripts" {
+                    script {
+                        (PreEscaped(format!(r#"
+                            window.dispatchEvent(new CustomEvent('notify', {{
+                                detail: {{ type: 'error', message: '{message}' }}
+                            }}));
+                        "#)))
+                    }
+                }
+            }));
+        }
+    };
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    client_ref
+        .db()
+        .set_content_filters(filters.clone())
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+
+    Ok(Maud(html! {
+        (state.render_filters_settings(&session, &filters))
+        div id="ajax-scripts" {
+            script {
+                (PreEscaped(r#"
+                    window.dispatchEvent(new CustomEvent('notify', {
+                        detail: { type: 'success', message: 'Filters saved' }
+                    }));
+                "#))
+            }
+        }
+    }))
+}
+
+pub async fn get_settings_drafts(
+    state: State<SharedState>,
+    session: UserSession,
+) -> RequestResult<impl IntoResponse> {
+    let drafts = state
+        .client(session.id())
+        .await?
+        .client_ref()?
+        .db()
+        .list_post_drafts()
+        .await;
+
+    let navbar = state.render_settings_navbar(&session, "drafts").await?;
+    let content = state.render_drafts_settings(&session, &drafts);
+
+    Ok(Maud(
+        state
+            .render_settings_page(&session, navbar, "Drafts", content)
+            .await?,
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct DraftInput {
+    content: String,
+    scheduled_at: Option<String>,
+    #[serde(default)]
+    tz_offset: i32,
+}
+
+/// Render the drafts after a change, with a notification
+async fn render_drafts_update(
+    state: &SharedState,
+    session: &UserSession,
+    message: &str,
+) -> RequestResult<Markup> {
+    let drafts = state
+        .client(session.id())
+        .await?
+        .client_ref()?
+        .db()
+        .list_post_drafts()
+        .await;
+    Ok(html! {
+        (state.render_drafts_settings(session, &drafts))
+        div id="ajax-scripts" {
+            script {
+                (PreEscaped(format!(r#"
+                    window.dispatchEvent(new CustomEvent('notify', {{
+                        detail: {{ type: 'success', message: '{message}' }}
+                    }}));
+                "#)))
+            }
+        }
+    })
+}
+
+pub async fn post_settings_draft(
+    state: State<SharedState>,
+    session: UserSession,
+    Path(id): Path<PostDraftId>,
+    Form(form): Form<DraftInput>,
+) -> RequestResult<impl IntoResponse> {
+    if state.id_secret(session.session_token()).is_none() {
+        return Err(ReadOnlyModeSnafu.build());
+    }
+    if form.content.trim().is_empty() {
+        return Err(bad_request("Post content cannot be empty"));
+    }
+    let scheduled_at = parse_scheduled_at(form.scheduled_at.as_deref(), form.tz_offset)?;
+
+    let client = state.client(session.id()).await?;
+    let client_ref = client.client_ref()?;
+    let draft = client_ref
+        .db()
+        .get_post_draft(id)
+        .await
+        .ok_or_else(|| bad_request("Draft not found"))?;
+    client_ref
+        .db()
+        .update_post_draft(
+            id,
+            PostDraft {
+                content: form.content,
+                scheduled_at,
+                updated_at: Timestamp::now(),
+                last_error: None,
+                ..draft
+            },
+        )
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+
+    Ok(Maud(
+        render_drafts_update(&state, &session, "Draft saved").await?,
+    ))
+}
+
+pub async fn post_settings_draft_publish(
+    state: State<SharedState>,
+    session: UserSession,
+    Path(id): Path<PostDraftId>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+
+    let client = state.client(session.id()).await?;
+    client
+        .client_ref()?
+        .publish_post_draft(id_secret, id)
+        .await?;
+
+    Ok(Maud(
+        render_drafts_update(&state, &session, "Post published").await?,
+    ))
+}
+
+pub async fn post_settings_draft_delete(
+    state: State<SharedState>,
+    session: UserSession,
+    Path(id): Path<PostDraftId>,
+) -> RequestResult<impl IntoResponse> {
+    if state.id_secret(session.session_token()).is_none() {
+        return Err(ReadOnlyModeSnafu.build());
+    }
+
+    let client = state.client(session.id()).await?;
+    client
+        .client_ref()?
+        .db()
+        .remove_post_draft(id)
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+
+    Ok(Maud(
+        render_drafts_update(&state, &session, "Draft deleted").await?,
+    ))
+}
+
+pub async fn get_settings_api_tokens(
+    state: State<SharedState>,
+    session: UserSession,
+) -> RequestResult<impl IntoResponse> {
+    let tokens = state
+        .api_tokens()
+        .list(session.id())
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+
+    let navbar = state.render_settings_navbar(&session, "api-tokens").await?;
+    let content = state.render_api_tokens_settings(&session, &tokens, None);
+
+    Ok(Maud(
+        state
+            .render_settings_page(&session, navbar, "API Tokens", content)
+            .await?,
+    ))
+}
+
+/// The secret of the session, which must be the identity's own secret to
+/// manage API tokens
+fn api_tokens_root_secret(
+    state: &SharedState,
+    session: &UserSession,
+) -> RequestResult<RostraIdSecretKey> {
+    let id_secret = state
+        .id_secret(session.session_token())
+        .ok_or_else(|| ReadOnlyModeSnafu.build())?;
+    if id_secret.id() != session.id() {
+        return Err(bad_request(
+            "Managing API tokens requires signing in with the recovery phrase",
+        ));
+    }
+    Ok(id_secret)
+}
+
+/// Render the API tokens after a change, with a notification
+async fn render_api_tokens_update(
+    state: &SharedState,
+    session: &UserSession,
+    new_token: Option<ApiToken>,
+    message: &str,
+) -> RequestResult<Markup> {
+    let tokens = state
+        .api_tokens()
+        .list(session.id())
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+    Ok(html! {
+        (state.render_api_tokens_settings(session, &tokens, new_token))
+        div id="ajax-scripts" {
+            script {
+                (PreEscaped(format!(r#"
+                    window.dispatchEvent(new CustomEvent('notify', {{
+                        detail: {{ type: 'success', message: '{message}' }}
+                    }}));
+                "#)))
+            }
+        }
+    })
+}
+
+#[derive(Deserialize)]
+pub struct ApiTokenInput {
+    label: String,
+    scope_post: Option<String>,
+    scope_follow: Option<String>,
+    scope_read_notifications: Option<String>,
+    #[serde(default)]
+    expires_in_days: String,
+    #[serde(default)]
+    rate_limit_per_minute: String,
+}
+
+impl ApiTokenInput {
+    fn scopes(&self) -> BTreeSet<ApiScope> {
+        [
+            (ApiScope::Post, &self.scope_post),
+            (ApiScope::Follow, &self.scope_follow),
+            (ApiScope::ReadNotifications, &self.scope_read_notifications),
+        ]
+        .into_iter()
+        .filter(|(_, checked)| checked.is_some())
+        .map(|(scope, _)| scope)
+        .collect()
+    }
+
+    /// Parse an optional positive number field
+    fn positive<T: std::str::FromStr<Err = std::num::ParseIntError> + Default + PartialOrd>(
+        value: &str,
+        message: &str,
+    ) -> RequestResult<Option<T>> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Ok(None);
+        }
+        match value.parse::<T>() {
+            Ok(value) if T::default() < value => Ok(Some(value)),
+            _ => Err(bad_request(message)),
+        }
+    }
+}
+
+pub async fn post_settings_api_tokens(
+    state: State<SharedState>,
+    session: UserSession,
+    Form(form): Form<ApiTokenInput>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = api_tokens_root_secret(&state, &session)?;
+
+    let label = form.label.trim().to_owned();
+    if label.is_empty() {
+        return Err(bad_request("Token name cannot be empty"));
+    }
+    let scopes = form.scopes();
+    if scopes.is_empty() {
+        return Err(bad_request("Select at least one permission"));
+    }
+    let expires_in_days: Option<u64> =
+        ApiTokenInput::positive(&form.expires_in_days, "Invalid expiry")?;
+    let rate_limit_per_minute: Option<u32> =
+        ApiTokenInput::positive(&form.rate_limit_per_minute, "Invalid rate limit")?;
+
+    let now = Timestamp::now();
+    let expires_at =
+        expires_in_days.map(|days| now.saturating_add_secs(days.saturating_mul(24 * 60 * 60)));
+
+    // The delegation expires with the token, so other nodes stop accepting
+    // the device key even if nothing is around to revoke it
+    let device = RostraIdSecretKey::generate();
+    let client = state.client(session.id()).await?;
+    let client = client.client_ref()?;
+    match expires_at {
+        Some(expires_at) => {
+            client
+                .delegate_device_until(id_secret, device.id(), expires_at)
+                .await?
+        }
+        None => {
+            client
+                .delegate_device(id_secret, device.id(), false)
+                .await?
+        }
+    };
+
+    let token = ApiToken::generate();
+    state
+        .api_tokens()
+        .insert(
+            session.id(),
+            token,
+            ApiTokenRecord {
+                label,
+                device: device.id(),
+                device_secret: Some(device),
+                scopes,
+                created_at: now,
+                expires_at,
+                rate_limit_per_minute,
+            },
+        )
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+
+    Ok(Maud(
+        render_api_tokens_update(&state, &session, Some(token), "API token created").await?,
+    ))
+}
+
+pub async fn post_settings_api_token_revoke(
+    state: State<SharedState>,
+    session: UserSession,
+    Path(id): Path<ApiTokenId>,
+) -> RequestResult<impl IntoResponse> {
+    let id_secret = api_tokens_root_secret(&state, &session)?;
+
+    let removed = state
+        .api_tokens()
+        .remove(session.id(), id)
+        .await
+        .boxed()
+        .context(OtherSnafu)?;
+    // The device key is gone with the token, but other nodes would still
+    // accept events signed with a leaked copy of it
+    if let Some(record) = removed {
+        let client = state.client(session.id()).await?;
+        client
+            .client_ref()?
+            .delegate_device(id_secret, record.device, true)
+            .await?;
+    }
+
+    Ok(Maud(
+        render_api_tokens_update(&state, &session, None, "API token revoked").await?,
+    ))
+}
+
 #[derive(Deserialize)]
 pub struct EventExplorerQuery {
     id: Option<String>,
@@ -425,6 +885,12 @@ impl UiState {
                         {
                             "Identity"
                         }
+                        a ."o-settingsNav__item"
+                            ."-active"[active_category == "api-tokens"]
+                            href="/settings/api-tokens"
+                        {
+                            "API Tokens"
+                        }
                     }
                     div ."o-settingsNav__group" {
                         h3 ."o-settingsNav__groupHeader" { "Social" }
@@ -446,6 +912,18 @@ impl UiState {
                         {
                             "Followers"
                         }
+                        a ."o-settingsNav__item"
+                            ."-active"[active_category == "filters"]
+                            href="/settings/filters"
+                        {
+                            "Filters"
+                        }
+                        a ."o-settingsNav__item"
+                            ."-active"[active_category == "drafts"]
+                            href="/settings/drafts"
+                        {
+                            "Drafts"
+                        }
                     }
 
                     div ."o-settingsNav__group" {
@@ -634,6 +1112,322 @@ impl UiState {
         })
     }
 
+    pub fn render_filters_settings(
+        &self,
+        session: &UserSession,
+        filters: &ContentFilters,
+    ) -> Markup {
+        let ro = self.ro_mode(session.session_token());
+        let ajax_attrs = fragment::AjaxLoadingAttrs::for_class("m-profileSettings__saveButton");
+        let persona_tags = filters
+            .muted_persona_tags
+            .iter()
+            .map(|tag| tag.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        html! {
+            form id="filters-settings-form" ."m-profileSettings"
+                action="/settings/filters"
+                method="post"
+                x-target="filters-settings-form ajax-scripts"
+                "@ajax:before"=(ajax_attrs.before)
+                "@ajax:after"=(ajax_attrs.after)
+            {
+                p { "Muted content is hidden locally, and never shared with anyone." }
+
+                div ."m-profileSettings__field" {
+                    label ."m-profileSettings__label" for="filters-words" { "Muted words (one per line)" }
+                    textarea id="filters-words" ."m-profileSettings__textarea"
+                        rows="4"
+                        name="muted_words"
+                    {
+                        (filters.muted_words.join("\n"))
+                    }
+                }
+
+                div ."m-profileSettings__field" {
+                    label ."m-profileSettings__label" for="filters-patterns" { "Muted regular expressions (one per line)" }
+                    textarea id="filters-patterns" ."m-profileSettings__textarea"
+                        rows="3"
+                        name="muted_patterns"
+                    {
+                        (filters.muted_patterns.join("\n"))
+                    }
+                }
+
+                div ."m-profileSettings__field" {
+                    label ."m-profileSettings__label" for="filters-domains" { "Muted link domains (one per line)" }
+                    textarea id="filters-domains" ."m-profileSettings__textarea"
+                        rows="3"
+                        name="muted_domains"
+                    {
+                        (filters.muted_domains.join("\n"))
+                    }
+                }
+
+                div ."m-profileSettings__field" {
+                    label ."m-profileSettings__label" for="filters-persona-tags" { "Muted persona tags (comma separated)" }
+                    input # "filters-persona-tags" ."m-profileSettings__input"
+                        type="text"
+                        name="muted_persona_tags"
+                        value=(persona_tags)
+                    {}
+                }
+
+                div ."m-profileSettings__field" {
+                    label ."m-profileSettings__label" {
+                        input type="checkbox"
+                            name="hide_reactions"
+                            value="true"
+                            checked[filters.hide_reactions]
+                        {}
+                        " Hide reactions"
+                    }
+                }
+
+                div ."m-profileSettings__field" {
+                    label ."m-profileSettings__label" {
+                        input type="checkbox"
+                            name="hide_reposts"
+                            value="true"
+                            checked[filters.hide_reposts]
+                        {}
+                        " Hide boosts"
+                    }
+                }
+
+                div ."m-profileSettings__actions" {
+                    (fragment::button("m-profileSettings__saveButton", "Save")
+                        .disabled(ro.to_disabled())
+                        .call())
+                }
+            }
+        }
+    }
+
+    pub fn render_drafts_settings(
+        &self,
+        session: &UserSession,
+        drafts: &[(PostDraftId, PostDraft)],
+    ) -> Markup {
+        let ro = self.ro_mode(session.session_token());
+        // Show the scheduled time in the browser's time zone, and send its
+        // offset along
+        let to_local_time = r#"
+            const ts = $el.dataset.ts;
+            if (ts) {
+                const d = new Date(ts * 1000);
+                $el.value = new Date(d.getTime() - d.getTimezoneOffset() * 60000).toISOString().slice(0, 16);
+            }
+        "#;
+
+        html! {
+            div id="drafts-settings" ."m-draftList" {
+                @if drafts.is_empty() {
+                    p { "No drafts. Use \"Save for later\" in the post preview to keep a post here, or schedule it." }
+                }
+                @for (id, draft) in drafts {
+                    @let url = settings_draft_url(*id);
+                    @let ajax_attrs = fragment::AjaxLoadingAttrs::for_class("m-draftList__saveButton");
+                    form ."m-draftList__item" ."m-profileSettings"
+                        action=(url)
+                        method="post"
+                        x-target="drafts-settings ajax-scripts"
+                        "@ajax:before"=(ajax_attrs.before)
+                        "@ajax:after"=(ajax_attrs.after)
+                    {
+                        div ."m-draftList__status" {
+                            @if let Some(scheduled_at) = draft.scheduled_at {
+                                "Scheduled for "
+                                time datetime=(format_timestamp_iso(scheduled_at)) {
+                                    (format_timestamp_utc(scheduled_at))
+                                }
+                            } @else {
+                                "Draft"
+                            }
+                            @if let Some(reply_to) = draft.reply_to {
+                                " · "
+                                a href=(post_url(reply_to.rostra_id(), reply_to.event_id())) { "Reply" }
+                            }
+                            @if !draft.persona_tags.is_empty() {
+                                " · "
+                                (draft.persona_tags.iter().map(|tag| tag.as_str()).collect::<Vec<_>>().join(", "))
+                            }
+                        }
+                        @if let Some(error) = draft.last_error.as_ref() {
+                            p ."m-draftList__error" { "Publishing failed: " (error) }
+                        }
+                        textarea ."m-profileSettings__textarea"
+                            rows="4"
+                            name="content"
+                            dir="auto"
+                            disabled[ro.to_disabled()]
+                        {
+                            (draft.content)
+                        }
+                        div ."m-profileSettings__field" {
+                            label ."m-profileSettings__label" {
+                                "Publish at (optional) "
+                                input ."m-profileSettings__input"
+                                    type="datetime-local"
+                                    name="scheduled_at"
+                                    value=[draft.scheduled_at.and_then(format_datetime_local_utc)]
+                                    data-ts=[draft.scheduled_at.map(Timestamp::as_u64)]
+                                    x-init=(to_local_time)
+                                    disabled[ro.to_disabled()]
+                                {}
+                            }
+                            input type="hidden" name="tz_offset" value="0"
+                                x-init="$el.value = new Date().getTimezoneOffset()" {}
+                        }
+                        div ."m-profileSettings__actions" {
+                            (fragment::button("m-draftList__saveButton", "Save")
+                                .disabled(ro.to_disabled())
+                                .call())
+                            (fragment::button("m-draftList__publishButton", "Publish now")
+                                .formaction(&format!("{url}/publish"))
+                                .disabled(ro.to_disabled())
+                                .call())
+                            (fragment::button("m-draftList__deleteButton", "Delete")
+                                .formaction(&format!("{url}/delete"))
+                                .variant("--danger")
+                                .disabled(ro.to_disabled())
+                                .call())
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn render_api_tokens_settings(
+        &self,
+        session: &UserSession,
+        tokens: &[(ApiTokenId, ApiTokenRecord)],
+        new_token: Option<ApiToken>,
+    ) -> Markup {
+        let ro = self.ro_mode(session.session_token());
+        let ajax_attrs = fragment::AjaxLoadingAttrs::for_class("m-profileSettings__saveButton");
+        let now = Timestamp::now();
+
+        html! {
+            div id="api-tokens-settings" ."m-apiTokens" {
+                p {
+                    "API tokens let programs, like bots, post or follow on your behalf through the "
+                    a href="https://github.com/dpc/rostra/blob/master/docs/web-api.md" { "Web API" }
+                    ", without your recovery phrase. Each token can do only what you allow, "
+                    "and can be revoked at any time."
+                }
+
+                @if let Some(token) = new_token {
+                    div ."m-apiTokens__new" {
+                        h3 ."o-settingsContent__sectionHeader" { "New token" }
+                        p { "Copy the token now. It will not be shown again." }
+                        input ."m-profileSettings__input m-apiTokens__token"
+                            type="text"
+                            readonly
+                            value=(token.to_string())
+                            onfocus="this.select()"
+                        {}
+                    }
+                }
+
+                form ."m-profileSettings"
+                    action="/settings/api-tokens"
+                    method="post"
+                    x-target="api-tokens-settings ajax-scripts"
+                    "@ajax:before"=(ajax_attrs.before)
+                    "@ajax:after"=(ajax_attrs.after)
+                {
+                    h3 ."o-settingsContent__sectionHeader" { "Create a token" }
+                    div ."m-profileSettings__field" {
+                        label ."m-profileSettings__label" for="api-token-label" { "Name" }
+                        input # "api-token-label" ."m-profileSettings__input"
+                            type="text"
+                            name="label"
+                            placeholder="e.g. rostra-bot"
+                            required
+                        {}
+                    }
+                    div ."m-profileSettings__field" {
+                        span ."m-profileSettings__label" { "Permissions" }
+                        label {
+                            input type="checkbox" name="scope_post" value="true" {}
+                            " Publish posts and polls, and vote"
+                        }
+                        label {
+                            input type="checkbox" name="scope_follow" value="true" {}
+                            " Follow and unfollow"
+                        }
+                        label {
+                            input type="checkbox" name="scope_read_notifications" value="true" {}
+                            " Read notifications"
+                        }
+                    }
+                    div ."m-profileSettings__field" {
+                        label ."m-profileSettings__label" for="api-token-expiry" { "Expires after days (optional)" }
+                        input # "api-token-expiry" ."m-profileSettings__input"
+                            type="number"
+                            min="1"
+                            name="expires_in_days"
+                        {}
+                    }
+                    div ."m-profileSettings__field" {
+                        label ."m-profileSettings__label" for="api-token-rate-limit" { "Requests per minute (optional)" }
+                        input # "api-token-rate-limit" ."m-profileSettings__input"
+                            type="number"
+                            min="1"
+                            name="rate_limit_per_minute"
+                        {}
+                    }
+                    div ."m-profileSettings__actions" {
+                        (fragment::button("m-profileSettings__saveButton", "Create")
+                            .disabled(ro.to_disabled())
+                            .call())
+                    }
+                }
+
+                div ."m-apiTokens__list" {
+                    @if tokens.is_empty() {
+                        p { "No API tokens." }
+                    }
+                    @for (id, record) in tokens {
+                        form ."m-apiTokens__item"
+                            action=(format!("{}/revoke", settings_api_token_url(*id)))
+                            method="post"
+                            x-target="api-tokens-settings ajax-scripts"
+                        {
+                            div {
+                                strong { (record.label) }
+                                div ."m-apiTokens__details" {
+                                    (record.scopes.iter().map(|scope| scope.as_str()).collect::<Vec<_>>().join(", "))
+                                    " · created "
+                                    (format_timestamp_utc(record.created_at))
+                                    @if let Some(expires_at) = record.expires_at {
+                                        @if record.is_expired(now) {
+                                            " · expired "
+                                        } @else {
+                                            " · expires "
+                                        }
+                                        (format_timestamp_utc(expires_at))
+                                    }
+                                    @if let Some(limit) = record.rate_limit_per_minute {
+                                        " · " (limit) " requests per minute"
+                                    }
+                                }
+                            }
+                            (fragment::button("m-apiTokens__revokeButton", "Revoke")
+                                .variant("--danger")
+                                .disabled(ro.to_disabled())
+                                .call())
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     pub async fn render_following_settings(
         &self,
         session: &UserSession,
@@ -0,0 +1,162 @@
+use axum::Form;
+use axum::extract::{Path, State};
+use axum::response::IntoResponse;
+use maud::{Markup, html};
+use rostra_client_db::social::EventPaginationCursor;
+use rostra_core::{ShortEventId, Timestamp};
+use rostra_djot::hashtag::normalize_hashtag;
+use serde::Deserialize;
+
+use super::Maud;
+use super::unlock::session::UserSession;
+use super::url::tag_url;
+use crate::error::RequestResult;
+use crate::html_utils::re_typeset;
+use crate::util::extractors::AjaxRequest;
+use crate::{SharedState, UiState};
+
+/// Number of posts per page of a hashtag timeline
+const TAG_POSTS_LIMIT: usize = 20;
+
+#[derive(Deserialize)]
+pub struct TagPaginationInput {
+    ts: Option<Timestamp>,
+    event_id: Option<ShortEventId>,
+}
+
+pub async fn get_tag(
+    state: State<SharedState>,
+    session: UserSession,
+    AjaxRequest(is_ajax): AjaxRequest,
+    Path(name): Path<String>,
+    Form(form): Form<TagPaginationInput>,
+) -> RequestResult<impl IntoResponse> {
+    let pagination = form.ts.and_then(|ts| {
+        form.event_id
+            .map(|event_id| EventPaginationCursor { ts, event_id })
+    });
+    let tag = normalize_hashtag(name.trim_start_matches('#'));
+
+    let posts = state
+        .render_tag_posts(&session, tag.as_deref(), pagination)
+        .await?;
+    if is_ajax {
+        return Ok(Maud(posts));
+    }
+
+    let title = match tag.as_deref() {
+        Some(tag) => format!("#{tag}"),
+        None => "Hashtag".to_owned(),
+    };
+    let navbar = state
+        .timeline_common_navbar()
+        .session(&session)
+        .call()
+        .await?;
+    let main_content = html! {
+        div ."o-mainBarTimeline" {
+            (UiState::render_page_tab_bar(&title))
+            (posts)
+        }
+    };
+    let content = html! {
+        (state.render_page_layout(navbar, main_content))
+        (re_typeset())
+    };
+    Ok(Maud(
+        state
+            .render_html_page(
+                &format!("{title} - Rostra"),
+                content,
+                None,
+                None,
+                None,
+                true,
+            )
+            .await?,
+    ))
+}
+
+impl UiState {
+    /// Render posts with the hashtag `tag` by identities in the web of trust
+    async fn render_tag_posts(
+        &self,
+        session: &UserSession,
+        tag: Option<&str>,
+        pagination: Option<EventPaginationCursor>,
+    ) -> RequestResult<Markup> {
+        let client = self.client(session.id()).await?;
+        let client_ref = client.client_ref()?;
+
+        let (posts, cursor) = match tag {
+            Some(tag) => {
+                let self_id = client_ref.rostra_id();
+                let wot = client_ref.self_wot_subscribe().snapshot();
+                client_ref
+                    .db()
+                    .paginate_social_posts_by_tag_rev(
+                        tag,
+                        pagination,
+                        TAG_POSTS_LIMIT,
+                        move |post| wot.contains(post.author, self_id),
+                    )
+                    .await
+            }
+            None => (vec![], None),
+        };
+
+        Ok(html! {
+            div id="tag-posts" x-merge="append" {
+                @if pagination.is_none() && posts.is_empty() && cursor.is_none() {
+                    div ."o-postSearch__empty" {
+                        @if tag.is_some() {
+                            "No posts with this hashtag yet."
+                        } @else {
+                            "Not a valid hashtag."
+                        }
+                    }
+                }
+                @for post in &posts {
+                    @if let Some(djot_content) = post.content.djot_content.as_ref() {
+                        div ."o-mainBarTimeline__item"
+                            ."-reply"[post.reply_to.is_some()]
+                            ."-post"[post.reply_to.is_none()]
+                        {
+                            (self.render_post_context(&client_ref, post.author)
+                                .persona_tags(&post.content.persona_tags())
+                                .maybe_reply_to(post.reply_to.map(|reply_to| {
+                                    (reply_to.rostra_id(), reply_to.event_id(), None)
+                                }))
+                                .event_id(post.event_id)
+                                .post_thread_id(post.event_id)
+                                .content(djot_content)
+                                .maybe_url(post.content.url.as_ref())
+                                .maybe_title(post.content.title.as_deref())
+                                .reply_count(post.reply_count)
+                                .timestamp(post.ts)
+                                .ro(self.ro_mode(session.session_token()))
+                                .call()
+                                .await?)
+                        }
+                    }
+                }
+            }
+            @if let (Some(tag), Some(cursor)) = (tag, cursor) {
+                // Infinite scroll, same as in timelines
+                @let href = format!(
+                    "{}?ts={}&event_id={}",
+                    tag_url(tag),
+                    cursor.ts,
+                    cursor.event_id
+                );
+                a
+                    id="tag-load-more" ."o-mainBarTimeline__rest -empty"
+                    "href"=(href)
+                    x-init="new IntersectionObserver((entries, obs) => { if (entries[0].isIntersecting) { obs.disconnect(); $ajax($el.href, { targets: ['tag-load-more', 'tag-posts'] }); } }, { root: document.body, rootMargin: '0px 0px 250% 0px' }).observe($el)"
+                { "More posts" }
+            } @else {
+                div id="tag-load-more" ."o-mainBarTimeline__rest -empty" {}
+            }
+        })
+    }
+}
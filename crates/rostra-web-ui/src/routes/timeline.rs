@@ -9,6 +9,7 @@ use maud::{Markup, html};
 use rostra_client::ClientRef;
 use rostra_client_db::IdSocialProfileRecord;
 use rostra_client_db::news::NewsRankPaginationCursor;
+use rostra_client_db::reposts::{SocialRepostRecord, SocialTimelineRecord};
 use rostra_client_db::social::{
     EventPaginationCursor, ReceivedAtPaginationCursor, SocialPostRecord,
 };
@@ -834,11 +835,12 @@ impl UiState {
             .client(session.id())
             .await?
             .db()?
-            .get_posts_by_id(
-                filtered_posts
-                    .iter()
-                    .flat_map(|post| post.reply_to.map(|ext_id| ext_id.event_id().to_short())),
-            )
+            .get_posts_by_id(filtered_posts.iter().flat_map(|record| {
+                record
+                    .post()
+                    .reply_to
+                    .map(|ext_id| ext_id.event_id().to_short())
+            }))
             .await;
 
         // Persona tags are embedded in post content, no batch lookup needed.
@@ -942,7 +944,9 @@ impl UiState {
                 div id="new-post-preview" ."o-mainBarTimeline__item -preview -empty" x-sync { }
                 div id="new-post-added" x-merge="after" {}
                 div id="timeline-posts" x-merge="append" {
-                    @for post in &filtered_posts {
+                    @for record in &filtered_posts {
+                        @let post = record.post();
+                        @let repost = record.repost();
                         @if let Some(djot_content) = post.content.djot_content.as_ref() {
                             @let effective_tags = post.content.persona_tags();
                             @let post_id = ExternalEventId::new(post.author, post.event_id);
@@ -959,7 +963,11 @@ impl UiState {
                             div ."o-mainBarTimeline__item"
                             ."-reply"[post.reply_to.is_some()]
                             ."-post"[post.reply_to.is_none()]
+                            ."-boost"[repost.is_some()]
                             {
+                                @if let Some(repost) = repost {
+                                    (self.render_boost_attribution(&client_ref, repost).await)
+                                }
                                 (
                                     self.render_post_context(
                                         &client_ref,
@@ -974,7 +982,7 @@ impl UiState {
                                             )
                                         )
                                         .event_id(post.event_id)
-                                        .post_thread_id(post.event_id)
+                                        .post_thread_id(repost.map_or(post.event_id, |repost| repost.event_id))
                                         .content(djot_content)
                                         .maybe_url(post.content.url.as_ref())
                                         .maybe_title(post.content.title.as_deref())
@@ -1006,6 +1014,28 @@ impl UiState {
         })
     }
 
+    /// Render the "boosted by" line of a repost, with its quote comment
+    async fn render_boost_attribution(
+        &self,
+        client: &ClientRef<'_>,
+        repost: &SocialRepostRecord,
+    ) -> Markup {
+        let profile = self.get_social_profile_opt(repost.author, client).await;
+        let comment = match repost.comment.as_deref() {
+            Some(comment) => Some(self.render_content(client, repost.author, comment).await),
+            None => None,
+        };
+        html! {
+            div ."o-mainBarTimeline__boostedBy" {
+                span ."o-mainBarTimeline__boostedByLabel" { "🔁 boosted by" }
+                (self.render_user_handle(Some(repost.event_id), repost.author, profile.as_ref()))
+            }
+            @if let Some(comment) = comment {
+                div ."o-mainBarTimeline__boostComment" { (comment) }
+            }
+        }
+    }
+
     pub(crate) fn render_news_vote_controls(
         &self,
         post_id: ExternalEventId,
@@ -1157,7 +1187,7 @@ impl TimelineMode {
         self,
         client: &ClientRef<'_>,
         pagination: Option<TimelineCursor>,
-    ) -> (Vec<SocialPostRecord<SocialPost>>, Option<TimelineCursor>) {
+    ) -> (Vec<SocialTimelineRecord>, Option<TimelineCursor>) {
         let filter_fn = self.to_filter_fn(client).await;
 
         if matches!(self, Self::News) {
@@ -1170,7 +1200,10 @@ impl TimelineMode {
                 .paginate_news_posts_by_rank_rev(cursor, 20)
                 .await;
             return (
-                posts.into_iter().map(|record| record.post).collect(),
+                posts
+                    .into_iter()
+                    .map(|record| SocialTimelineRecord::Post(record.post))
+                    .collect(),
                 next.map(TimelineCursor::NewsRank),
             );
         }
@@ -1186,20 +1219,47 @@ impl TimelineMode {
                 .db()
                 .paginate_social_posts_by_received_at_rev(cursor, 20, filter_fn)
                 .await;
-            (posts, next.map(TimelineCursor::ReceivedTime))
+            (
+                posts.into_iter().map(SocialTimelineRecord::Post).collect(),
+                next.map(TimelineCursor::ReceivedTime),
+            )
         } else {
             let cursor = pagination.and_then(|c| match c {
                 TimelineCursor::EventTime(c) => Some(c),
                 _ => None,
             });
+            let repost_filter_fn = self.to_repost_filter_fn(client).await;
             let (posts, next) = client
                 .db()
-                .paginate_social_posts_rev(cursor, 20, filter_fn)
+                .paginate_social_posts_with_reposts_rev(cursor, 20, filter_fn, repost_filter_fn)
                 .await;
             (posts, next.map(TimelineCursor::EventTime))
         }
     }
 
+    /// Which reposts (boosts) to show in the timeline, by their reposter
+    #[allow(clippy::type_complexity)]
+    async fn to_repost_filter_fn(
+        self,
+        client: &ClientRef<'_>,
+    ) -> Box<dyn Fn(&SocialRepostRecord) -> bool + Send + Sync + 'static> {
+        let self_id = client.rostra_id();
+        match self {
+            TimelineMode::Followees => {
+                let followees: HashMap<RostraId, PersonasTagsSelector> = client
+                    .db()
+                    .get_followees(self_id)
+                    .await
+                    .into_iter()
+                    .collect();
+                Box::new(move |repost: &SocialRepostRecord| followees.contains_key(&repost.author))
+            }
+            TimelineMode::Network => Box::new(move |repost| repost.author != self_id),
+            TimelineMode::News | TimelineMode::Notifications => Box::new(|_| false),
+            TimelineMode::Profile(rostra_id) => Box::new(move |repost| repost.author == rostra_id),
+        }
+    }
+
     #[allow(clippy::type_complexity)]
     async fn to_filter_fn(
         self,
@@ -3,10 +3,13 @@ use std::str::FromStr;
 use axum::http::Uri;
 use axum::response::{IntoResponse, Redirect, Response};
 use rostra_client_db::Database;
+use rostra_client_db::drafts::PostDraftId;
 use rostra_core::id::{RostraId, ShortRostraId, ToShort as _};
 use rostra_core::{EventId, ShortEventId};
 use serde::{Deserialize, Deserializer};
 
+use crate::api_tokens::ApiTokenId;
+
 /// An identity path component in either legacy full or canonical short form.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum RostraPathId {
@@ -116,9 +119,10 @@ pub(crate) fn profile_url(id: RostraId) -> String {
     format!("/profile/{}", id.to_short())
 }
 
-/// Return the canonical relative URL for a profile avatar.
+/// Return the canonical relative URL for a profile avatar, resized for
+/// display next to content.
 pub(crate) fn avatar_url(id: RostraId, event_id: ShortEventId) -> String {
-    format!("{}?v={event_id}", avatar_path(id))
+    format!("{}?v={event_id}&size=small", avatar_path(id))
 }
 
 /// Return the canonical relative path for a profile avatar.
@@ -131,11 +135,21 @@ pub(crate) fn profile_feed_url(id: RostraId) -> String {
     format!("{}/atom.xml", profile_url(id))
 }
 
+/// Return the canonical relative URL for the posts mentioning a profile.
+pub(crate) fn profile_mentions_url(id: RostraId) -> String {
+    format!("{}/mentions", profile_url(id))
+}
+
 /// Return the canonical relative URL for a profile follow action.
 pub(crate) fn profile_follow_url(id: RostraId) -> String {
     format!("{}/follow", profile_url(id))
 }
 
+/// Return the canonical relative URL for accepting a profile's succession.
+pub(crate) fn profile_accept_succession_url(id: RostraId) -> String {
+    format!("{}/accept-succession", profile_url(id))
+}
+
 /// Return the canonical relative URL for a media resource.
 pub(crate) fn media_url(author: RostraId, event_id: ShortEventId) -> String {
     format!("/media/{}/{event_id}", author.to_short())
@@ -146,6 +160,18 @@ pub(crate) fn media_list_url(author: RostraId) -> String {
     format!("/media/{}/list", author.to_short())
 }
 
+/// Return the relative URL for a direct message conversation.
+///
+/// Uses the full identity, as the counterparty might not be a known identity.
+pub(crate) fn messages_url(counterparty: RostraId) -> String {
+    format!("/messages/{counterparty}")
+}
+
+/// Return the relative URL for the posts with a (normalized) hashtag.
+pub(crate) fn tag_url(tag: &str) -> String {
+    format!("/tag/{}", urlencoding::encode(tag))
+}
+
 /// Return the canonical relative URL for a post.
 pub(crate) fn post_url(author: RostraId, event_id: ShortEventId) -> String {
     format!("/post/{}/{event_id}", author.to_short())
@@ -161,6 +187,16 @@ pub(crate) fn post_delete_url(author: RostraId, event_id: ShortEventId) -> Strin
     format!("{}/delete", post_url(author, event_id))
 }
 
+/// Return the canonical relative URL for boosting or unboosting a post.
+pub(crate) fn post_boost_url(author: RostraId, event_id: ShortEventId) -> String {
+    format!("{}/boost", post_url(author, event_id))
+}
+
+/// Return the canonical relative URL for voting in the poll of a post.
+pub(crate) fn post_poll_vote_url(author: RostraId, event_id: ShortEventId) -> String {
+    format!("{}/poll_vote", post_url(author, event_id))
+}
+
 /// Return the canonical relative URL for cancelling a post edit.
 pub(crate) fn post_edit_cancel_url(author: RostraId, event_id: ShortEventId) -> String {
     format!("{}/edit_cancel", post_url(author, event_id))
@@ -188,5 +224,13 @@ pub(crate) fn settings_event_content_url(event_id: ShortEventId) -> String {
     format!("/settings/events/content/{event_id}")
 }
 
+pub(crate) fn settings_draft_url(id: PostDraftId) -> String {
+    format!("/settings/drafts/{id}")
+}
+
+pub(crate) fn settings_api_token_url(id: ApiTokenId) -> String {
+    format!("/settings/api-tokens/{id}")
+}
+
 #[cfg(test)]
 mod tests;
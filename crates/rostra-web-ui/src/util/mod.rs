@@ -1,2 +1,3 @@
 pub mod extractors;
+pub mod thumbnail;
 pub mod time;
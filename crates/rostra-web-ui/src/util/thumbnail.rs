@@ -0,0 +1,160 @@
+//! Resized variants of images, for `?size=` requests.
+//!
+//! Thumbnails are generated on first request and cached in the database as
+//! [`DerivedAsset`]s of the event the image was published in. Images that
+//! can't be decoded, or are already small enough, are served as they are.
+
+use std::io::Cursor;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use rostra_client_db::Database;
+use rostra_client_db::derived_assets::DerivedAsset;
+use rostra_core::ShortEventId;
+use rostra_util_error::FmtCompact as _;
+use serde::Deserialize;
+use tracing::debug;
+
+use crate::LOG_TARGET;
+
+/// JPEG quality of the generated thumbnails
+const JPEG_QUALITY: u8 = 80;
+
+/// Maximum width and height of images to generate thumbnails of
+const MAX_SOURCE_DIMENSION: u32 = 8192;
+
+/// Maximum memory to allocate while decoding an image
+const MAX_DECODE_ALLOC: u64 = 256 * 1024 * 1024;
+
+/// Requested image size preset
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl ThumbnailSize {
+    /// Maximum width and height in pixels
+    pub fn max_dimension(self) -> u32 {
+        match self {
+            ThumbnailSize::Small => 128,
+            ThumbnailSize::Medium => 640,
+            ThumbnailSize::Large => 1280,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ThumbnailSize::Small => "small",
+            ThumbnailSize::Medium => "medium",
+            ThumbnailSize::Large => "large",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct ThumbnailQuery {
+    pub size: Option<ThumbnailSize>,
+}
+
+impl ThumbnailQuery {
+    /// ETag of the requested variant of a resource with `etag`
+    pub fn etag(&self, etag: String) -> String {
+        match self.size {
+            Some(size) => format!("{etag}-{}", size.as_str()),
+            None => etag,
+        }
+    }
+}
+
+/// The `size` variant of the image of `mime` type published in `event_id`
+///
+/// Returns `None` if the original should be served instead.
+pub async fn get_thumbnail(
+    db: &Database,
+    event_id: ShortEventId,
+    size: ThumbnailSize,
+    mime: &str,
+    data: &[u8],
+) -> Option<DerivedAsset> {
+    // Only formats that are decodable, and not animated
+    let format = match mime {
+        "image/jpeg" | "image/jpg" => ImageFormat::Jpeg,
+        "image/png" => ImageFormat::Png,
+        "image/webp" => ImageFormat::WebP,
+        _ => return None,
+    };
+    let max_dimension = size.max_dimension();
+    if let Some(asset) = db.get_derived_asset(event_id, max_dimension).await {
+        return Some(asset);
+    }
+
+    let data = data.to_vec();
+    let asset =
+        tokio::task::spawn_blocking(move || generate_thumbnail(format, &data, max_dimension))
+            .await
+            .ok()??;
+    if let Err(err) = db
+        .insert_derived_asset(event_id, max_dimension, asset.clone())
+        .await
+    {
+        debug!(target: LOG_TARGET, %event_id, err = %err.fmt_compact(), "Failed to cache thumbnail");
+    }
+    Some(asset)
+}
+
+/// Decoding limits, as the images come from untrusted peers
+fn decode_limits() -> Limits {
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_DIMENSION);
+    limits.max_image_height = Some(MAX_SOURCE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_ALLOC);
+    limits
+}
+
+/// Resize an image to fit `max_dimension`, re-encoding it as JPEG, or PNG if
+/// it has transparency
+///
+/// Returns `None` if the image can't be decoded, exceeds the decoding
+/// limits, or already fits.
+pub fn generate_thumbnail(
+    format: ImageFormat,
+    data: &[u8],
+    max_dimension: u32,
+) -> Option<DerivedAsset> {
+    let reader = ImageReader::with_format(Cursor::new(data), format);
+    let (width, height) = reader.into_dimensions().ok()?;
+    if width <= max_dimension && height <= max_dimension {
+        return None;
+    }
+    if MAX_SOURCE_DIMENSION < width || MAX_SOURCE_DIMENSION < height {
+        return None;
+    }
+
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    reader.limits(decode_limits());
+    let image = reader
+        .decode()
+        .ok()?
+        .resize(max_dimension, max_dimension, FilterType::Lanczos3);
+
+    let mut out = Cursor::new(vec![]);
+    let mime = if image.color().has_alpha() {
+        image.write_to(&mut out, ImageFormat::Png).ok()?;
+        "image/png"
+    } else {
+        let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY);
+        rgb.write_with_encoder(encoder).ok()?;
+        "image/jpeg"
+    };
+    Some(DerivedAsset {
+        mime: mime.to_owned(),
+        data: out.into_inner(),
+    })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,61 @@
+use std::io::Cursor;
+
+use image::{DynamicImage, GenericImageView as _, ImageFormat, RgbImage, RgbaImage};
+
+use super::*;
+
+fn encode(image: DynamicImage, format: ImageFormat) -> Vec<u8> {
+    let mut out = Cursor::new(vec![]);
+    image.write_to(&mut out, format).expect("can encode");
+    out.into_inner()
+}
+
+fn decode(asset: &DerivedAsset) -> DynamicImage {
+    image::load_from_memory(&asset.data).expect("valid thumbnail")
+}
+
+#[test]
+fn opaque_images_are_resized_to_jpeg() {
+    let png = encode(
+        DynamicImage::ImageRgb8(RgbImage::new(400, 200)),
+        ImageFormat::Png,
+    );
+
+    let asset = generate_thumbnail(ImageFormat::Png, &png, 128).expect("resized");
+    assert_eq!(asset.mime, "image/jpeg");
+    assert_eq!(decode(&asset).dimensions(), (128, 64));
+}
+
+#[test]
+fn transparent_images_stay_png() {
+    let png = encode(
+        DynamicImage::ImageRgba8(RgbaImage::new(200, 400)),
+        ImageFormat::Png,
+    );
+
+    let asset = generate_thumbnail(ImageFormat::Png, &png, 128).expect("resized");
+    assert_eq!(asset.mime, "image/png");
+    assert_eq!(decode(&asset).dimensions(), (64, 128));
+}
+
+#[test]
+fn small_and_invalid_images_are_kept() {
+    let png = encode(
+        DynamicImage::ImageRgb8(RgbImage::new(100, 100)),
+        ImageFormat::Png,
+    );
+    assert_eq!(generate_thumbnail(ImageFormat::Png, &png, 128), None);
+    assert_eq!(
+        generate_thumbnail(ImageFormat::Jpeg, b"not an image", 128),
+        None
+    );
+}
+
+#[test]
+fn oversized_images_are_kept() {
+    let png = encode(
+        DynamicImage::ImageRgb8(RgbImage::new(MAX_SOURCE_DIMENSION + 1, 1)),
+        ImageFormat::Png,
+    );
+    assert_eq!(generate_thumbnail(ImageFormat::Png, &png, 128), None);
+}
@@ -51,6 +51,63 @@ fn format_timestamp_date(timestamp: Timestamp) -> String {
     )
 }
 
+/// Format a valid timestamp as a UTC date and time, like
+/// `2024-07-01 00:00 UTC`, or return `Invalid date`.
+pub fn format_timestamp_utc(timestamp: Timestamp) -> String {
+    let Some(datetime) = timestamp_to_datetime(timestamp) else {
+        return INVALID_DATE.to_string();
+    };
+
+    format!(
+        "{}-{:02}-{:02} {:02}:{:02} UTC",
+        datetime.year(),
+        datetime.month() as u8,
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute()
+    )
+}
+
+/// Format a valid timestamp as the value of a `datetime-local` input, in UTC
+pub fn format_datetime_local_utc(timestamp: Timestamp) -> Option<String> {
+    let datetime = timestamp_to_datetime(timestamp)?;
+    Some(format!(
+        "{}-{:02}-{:02}T{:02}:{:02}",
+        datetime.year(),
+        datetime.month() as u8,
+        datetime.day(),
+        datetime.hour(),
+        datetime.minute()
+    ))
+}
+
+/// Parse the value of a `datetime-local` input (`YYYY-MM-DDTHH:MM`, with
+/// optional seconds)
+///
+/// `utc_offset_minutes` is the browser's `Date.getTimezoneOffset()`, i.e. the
+/// minutes to add to the local time to get UTC.
+pub fn parse_datetime_local(value: &str, utc_offset_minutes: i32) -> Option<Timestamp> {
+    let (date, time) = value.trim().split_once('T')?;
+    let mut date = date.splitn(3, '-').map(str::parse::<u32>);
+    let (year, month, day) = (date.next()?.ok()?, date.next()?.ok()?, date.next()?.ok()?);
+    let mut time = time.splitn(3, ':').map(str::parse::<u8>);
+    let (hour, minute) = (time.next()?.ok()?, time.next()?.ok()?);
+    let second = time.next().transpose().ok()?.unwrap_or_default();
+
+    let date = time::Date::from_calendar_date(
+        i32::try_from(year).ok()?,
+        time::Month::try_from(u8::try_from(month).ok()?).ok()?,
+        u8::try_from(day).ok()?,
+    )
+    .ok()?;
+    let time = time::Time::from_hms(hour, minute, second).ok()?;
+    let datetime = time::PrimitiveDateTime::new(date, time).assume_utc()
+        + time::Duration::minutes(i64::from(utc_offset_minutes));
+    u64::try_from(datetime.unix_timestamp())
+        .ok()
+        .map(Timestamp::from)
+}
+
 fn timestamp_to_datetime(timestamp: Timestamp) -> Option<time::OffsetDateTime> {
     i64::try_from(timestamp.as_u64())
         .ok()
@@ -94,6 +151,38 @@ mod tests {
         assert_eq!(format_timestamp_iso(Timestamp::MAX), "Invalid date");
     }
 
+    #[test]
+    fn parses_datetime_local_values() {
+        use super::{format_datetime_local_utc, format_timestamp_utc, parse_datetime_local};
+
+        assert_eq!(
+            parse_datetime_local("2024-07-01T00:00", 0),
+            Some(Timestamp::from(1_719_792_000))
+        );
+        // UTC+2 has an offset of -120
+        assert_eq!(
+            parse_datetime_local("2024-07-01T02:00:30", -120),
+            Some(Timestamp::from(1_719_792_030))
+        );
+        assert_eq!(
+            format_datetime_local_utc(Timestamp::from(1_719_792_030)).as_deref(),
+            Some("2024-07-01T00:00")
+        );
+        assert_eq!(
+            format_timestamp_utc(Timestamp::from(1_719_792_030)),
+            "2024-07-01 00:00 UTC"
+        );
+        for invalid in [
+            "",
+            "2024-07-01",
+            "2024-13-01T00:00",
+            "2024-07-01T25:00",
+            "1969-12-31T00:00",
+        ] {
+            assert_eq!(parse_datetime_local(invalid, 0), None, "{invalid}");
+        }
+    }
+
     #[test]
     fn unrepresentable_timestamp_is_invalid() {
         assert_eq!(super::format_timestamp(Timestamp::MAX), "Invalid date");
@@ -1,7 +1,8 @@
 mod common;
 
 use common::TestServer;
-use rostra_core::id::RostraIdSecretKey;
+use rostra_core::event::{Event, VerifiedEvent, VerifiedEventContent, content_kind};
+use rostra_core::id::{RostraId, RostraIdSecretKey};
 
 /// Helper: generate an identity via the API and return (rostra_id, secret).
 async fn generate_identity(driver: &common::UiDriver) -> (String, String) {
@@ -1020,3 +1021,733 @@ async fn followees_empty_for_fresh_identity() {
     let body: serde_json::Value = resp.json().await.unwrap();
     assert!(body["followees"].as_array().unwrap().is_empty());
 }
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn search_finds_posts_by_all_words() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (first, heads) =
+        publish_post(&driver, &id_a, &secret_a, None, "Rust sync engine", None).await;
+    let (second, _) = publish_post(
+        &driver,
+        &id_a,
+        &secret_a,
+        Some(&heads[0]),
+        "Gardening notes",
+        None,
+    )
+    .await;
+
+    let resp = driver.api_get(&format!("/api/{id_a}/search?q=rust")).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let posts = body["posts"].as_array().unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["event_id"].as_str().unwrap(), first);
+    assert!(body["next_cursor"].is_null());
+
+    let resp = driver
+        .api_get(&format!("/api/{id_a}/search?q=gardening+NOTES"))
+        .await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let posts = body["posts"].as_array().unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["event_id"].as_str().unwrap(), second);
+
+    let resp = driver
+        .api_get(&format!("/api/{id_a}/search?q=rust+gardening"))
+        .await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["posts"].as_array().unwrap().is_empty());
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn tag_timeline_lists_posts_by_hashtag() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (first, heads) =
+        publish_post(&driver, &id_a, &secret_a, None, "Learning #Rust", None).await;
+    let (second, _) = publish_post(
+        &driver,
+        &id_a,
+        &secret_a,
+        Some(&heads[0]),
+        "More #rust and `#code`",
+        None,
+    )
+    .await;
+
+    let resp = driver.api_get(&format!("/api/{id_a}/tags/RUST")).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    // Both posts are likely published within the same second, so their
+    // order is not checked
+    let mut posts: Vec<&str> = body["posts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|post| post["event_id"].as_str().unwrap())
+        .collect();
+    posts.sort_unstable();
+    let mut expected = vec![first.as_str(), second.as_str()];
+    expected.sort_unstable();
+    assert_eq!(posts, expected);
+    assert!(body["next_cursor"].is_null());
+
+    let resp = driver.api_get(&format!("/api/{id_a}/tags/code")).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["posts"].as_array().unwrap().is_empty());
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn mentions_timeline_lists_posts_mentioning_identity() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (id_b, _secret_b) = generate_identity(&driver).await;
+    let (post, _) = publish_post(
+        &driver,
+        &id_a,
+        &secret_a,
+        None,
+        &format!("Hi <rostra:{id_b}>, from <rostra:{id_a}>"),
+        None,
+    )
+    .await;
+
+    let resp = driver
+        .api_get(&format!("/api/{id_a}/mentions/{id_b}"))
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let posts = body["posts"].as_array().unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["event_id"], post.as_str());
+    assert!(body["next_cursor"].is_null());
+
+    let resp = driver
+        .api_get(&format!("/api/{id_a}/mentions/{id_a}"))
+        .await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["posts"].as_array().unwrap().is_empty());
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn content_filters_round_trip() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (_id_b, secret_b) = generate_identity(&driver).await;
+    let path = format!("/api/{id_a}/content-filters");
+
+    let resp = driver.api_get_with_secret(&path, &secret_a).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["muted_words"].as_array().unwrap().is_empty());
+    assert_eq!(body["hide_reactions"], false);
+
+    let resp = driver
+        .api_post_json(
+            &path,
+            Some(&secret_a),
+            &serde_json::json!({
+                "muted_words": ["  Spoilers ", ""],
+                "muted_domains": ["Example.COM"],
+                "hide_reactions": true,
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = driver.api_get_with_secret(&path, &secret_a).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["muted_words"], serde_json::json!(["spoilers"]));
+    assert_eq!(body["muted_domains"], serde_json::json!(["example.com"]));
+    assert_eq!(body["hide_reactions"], true);
+
+    let resp = driver
+        .api_post_json(
+            &path,
+            Some(&secret_a),
+            &serde_json::json!({ "muted_patterns": ["(unclosed"] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = driver.api_get_with_secret(&path, &secret_b).await;
+    assert_eq!(resp.status(), 403);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn private_posts_and_audience_round_trip() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (id_b, secret_b) = generate_identity(&driver).await;
+
+    let publish = |content: &'static str| {
+        let driver = &driver;
+        let path = format!("/api/{id_a}/publish-private-post-managed");
+        let secret = secret_a.clone();
+        async move {
+            driver
+                .api_post_json(
+                    &path,
+                    Some(&secret),
+                    &serde_json::json!({ "content": content }),
+                )
+                .await
+        }
+    };
+    let resp = publish("Nobody to read this").await;
+    assert_eq!(resp.status(), 409, "Private posts need an audience");
+
+    let resp = driver
+        .api_post_json(
+            &format!("/api/{id_a}/private-audience/add"),
+            Some(&secret_a),
+            &serde_json::json!({ "member": id_b }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let members = body["members"].as_array().unwrap();
+    assert!(members.contains(&serde_json::json!(id_a)));
+    assert!(members.contains(&serde_json::json!(id_b)));
+
+    let resp = publish("Only for friends").await;
+    assert_eq!(resp.status(), 200);
+
+    let path = format!("/api/{id_a}/private-posts");
+    let resp = driver.api_get_with_secret(&path, &secret_a).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let posts = body["posts"].as_array().unwrap();
+    assert_eq!(posts.len(), 1);
+    assert_eq!(posts[0]["content"], "Only for friends");
+    assert_eq!(posts[0]["author"], id_a.as_str());
+
+    // Private posts are neither readable by others, nor public
+    let resp = driver.api_get_with_secret(&path, &secret_b).await;
+    assert_eq!(resp.status(), 403);
+    let resp = driver.api_get(&path).await;
+    assert_eq!(resp.status(), 401);
+    let resp = driver.api_get(&format!("/api/{id_a}/posts")).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["posts"].as_array().unwrap().is_empty());
+
+    let resp = driver
+        .api_post_json(
+            &format!("/api/{id_a}/private-audience/remove"),
+            Some(&secret_a),
+            &serde_json::json!({ "member": id_b }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let resp = driver
+        .api_get_with_secret(&format!("/api/{id_a}/private-audience"), &secret_a)
+        .await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["members"], serde_json::json!([id_a]));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn poll_publish_vote_and_tally() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+
+    let resp = driver
+        .api_post_json(
+            &format!("/api/{id_a}/publish-social-poll-managed"),
+            Some(&secret_a),
+            &serde_json::json!({
+                "question": "Lunch?",
+                "options": ["Pizza", "Sushi"],
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200, "Poll should publish");
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let post_id = body["event_id"].as_str().unwrap().to_string();
+    let poll = body["poll"].as_str().unwrap().to_string();
+
+    let resp = driver
+        .api_get(&format!("/api/{id_a}/posts/{post_id}"))
+        .await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["poll"].as_str(), Some(poll.as_str()));
+
+    let vote = |option: serde_json::Value| {
+        let driver = &driver;
+        let path = format!("/api/{id_a}/poll-vote-managed");
+        let body = serde_json::json!({ "poll": poll, "option": option });
+        let secret = secret_a.clone();
+        async move { driver.api_post_json(&path, Some(&secret), &body).await }
+    };
+
+    assert_eq!(vote(serde_json::json!(1)).await.status(), 200);
+    let resp = driver.api_get(&format!("/api/{id_a}/polls/{poll}")).await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["question"], "Lunch?");
+    assert_eq!(body["options"][0]["votes"], 0);
+    assert_eq!(body["options"][1]["votes"], 1);
+    assert_eq!(body["self_vote"], 1);
+    assert_eq!(body["closed"], false);
+
+    // Options out of range are rejected
+    assert_eq!(vote(serde_json::json!(2)).await.status(), 400);
+
+    // Retracting the vote removes it from the tally. Votes are ordered by
+    // their timestamps, which have a resolution of a second.
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    assert_eq!(vote(serde_json::Value::Null).await.status(), 200);
+    let resp = driver.api_get(&format!("/api/{id_a}/polls/{poll}")).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["options"][1]["votes"], 0);
+    assert!(body["self_vote"].is_null());
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn drafts_are_kept_until_published() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (_id_b, secret_b) = generate_identity(&driver).await;
+    let path = format!("/api/{id_a}/drafts");
+
+    let resp = driver
+        .api_post_json(
+            &path,
+            Some(&secret_a),
+            &serde_json::json!({ "content": "" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 400, "Empty drafts should be rejected");
+
+    let resp = driver
+        .api_post_json(
+            &path,
+            Some(&secret_a),
+            &serde_json::json!({ "content": "Soon", "scheduled_at": 1 }),
+        )
+        .await;
+    assert_eq!(resp.status(), 400, "Past schedules should be rejected");
+
+    let resp = driver
+        .api_post_json(
+            &path,
+            Some(&secret_a),
+            &serde_json::json!({ "content": "First take" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let draft_id = body["id"].as_u64().unwrap();
+
+    let resp = driver
+        .api_post_json(
+            &format!("{path}/{draft_id}"),
+            Some(&secret_a),
+            &serde_json::json!({ "content": "Second take" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = driver.api_get_with_secret(&path, &secret_a).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["drafts"].as_array().unwrap().len(), 1);
+    assert_eq!(body["drafts"][0]["content"], "Second take");
+    assert!(body["drafts"][0]["scheduled_at"].is_null());
+
+    // Drafts are not visible to others
+    let resp = driver.api_get_with_secret(&path, &secret_b).await;
+    assert_eq!(resp.status(), 403);
+    let resp = driver.api_get(&format!("/api/{id_a}/posts")).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["posts"].as_array().unwrap().is_empty());
+
+    let resp = driver
+        .api_post_json(
+            &format!("{path}/{draft_id}/publish"),
+            Some(&secret_a),
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status(), 200, "Draft should publish");
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let event_id = body["event_id"].as_str().unwrap().to_string();
+
+    let resp = driver
+        .api_get(&format!("/api/{id_a}/posts/{event_id}"))
+        .await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["content"], "Second take");
+
+    // Published drafts are gone
+    let resp = driver.api_get_with_secret(&path, &secret_a).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body["drafts"].as_array().unwrap().is_empty());
+    let resp = driver
+        .api_post_json(
+            &format!("{path}/{draft_id}/delete"),
+            Some(&secret_a),
+            &serde_json::json!({}),
+        )
+        .await;
+    assert_eq!(resp.status(), 404);
+}
+
+// -- API token tests --
+
+/// Helper: mint an API token in the settings of the logged in identity.
+async fn mint_api_token(driver: &common::UiDriver, form: &[(&str, &str)]) -> String {
+    let resp = driver.ajax_post_form("/settings/api-tokens", form).await;
+    assert_eq!(resp.status(), 200, "Minting should succeed");
+    let html = resp.text().await.unwrap();
+    let start = html.find("rostra-api-").expect("New token should be shown");
+    html[start..start + "rostra-api-".len() + 64].to_string()
+}
+
+/// Helper: current first head of an identity, if any.
+async fn first_head(driver: &common::UiDriver, rostra_id: &str) -> Option<String> {
+    let resp = driver.api_get(&format!("/api/{rostra_id}/heads")).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    body["heads"][0].as_str().map(ToOwned::to_owned)
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn api_token_posts_until_revoked() {
+    let server = TestServer::start().await;
+    let owner = server.driver();
+    let (id, _secret) = owner.login_new_identity().await;
+    let token = mint_api_token(&owner, &[("label", "test-bot"), ("scope_post", "true")]).await;
+
+    // The bot never sees the secret, only the token
+    let bot = server.driver();
+    let parent_head_id = first_head(&bot, &id.to_string()).await;
+    let resp = bot
+        .api_post_json_with_token(
+            &format!("/api/{id}/publish-social-post-managed"),
+            &token,
+            &serde_json::json!({
+                "parent_head_id": parent_head_id,
+                "content": "Posted by a bot",
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200, "Token with the post scope should post");
+    let body: serde_json::Value = resp.json().await.unwrap();
+    let event_id = body["event_id"].as_str().unwrap();
+    let resp = bot.api_get(&format!("/api/{id}/posts/{event_id}")).await;
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["content"], "Posted by a bot");
+    assert_eq!(body["author"], id.to_string());
+
+    // Scopes not granted are refused
+    let followee = RostraIdSecretKey::generate().id().to_string();
+    let resp = bot
+        .api_post_json_with_token(
+            &format!("/api/{id}/follow-managed"),
+            &token,
+            &serde_json::json!({ "followee": followee }),
+        )
+        .await;
+    assert_eq!(resp.status(), 403, "Token without the follow scope");
+
+    // The token is only valid for its identity
+    let (other_id, _) = generate_identity(&bot).await;
+    let resp = bot
+        .api_post_json_with_token(
+            &format!("/api/{other_id}/publish-social-post-managed"),
+            &token,
+            &serde_json::json!({ "parent_head_id": null, "content": "Elsewhere" }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    // Revoke it
+    let html = owner
+        .get("/settings/api-tokens")
+        .await
+        .text()
+        .await
+        .unwrap();
+    assert!(html.contains("test-bot"));
+    assert!(!html.contains(&token), "Tokens are only shown when minted");
+    let start = html.find("/settings/api-tokens/").unwrap();
+    let revoke_path = &html[start..start + html[start..].find('"').unwrap()];
+    let resp = owner.ajax_post_form(revoke_path, &[]).await;
+    assert_eq!(resp.status(), 200);
+    let html = resp.text().await.unwrap();
+    assert!(!html.contains("test-bot"));
+
+    let parent_head_id = first_head(&bot, &id.to_string()).await;
+    let resp = bot
+        .api_post_json_with_token(
+            &format!("/api/{id}/publish-social-post-managed"),
+            &token,
+            &serde_json::json!({
+                "parent_head_id": parent_head_id,
+                "content": "After revocation",
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 401, "Revoked token should be rejected");
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn api_token_scopes_and_rate_limit() {
+    let server = TestServer::start().await;
+    let owner = server.driver();
+    let (id, _secret) = owner.login_new_identity().await;
+    let token = mint_api_token(
+        &owner,
+        &[
+            ("label", "follower"),
+            ("scope_follow", "true"),
+            ("scope_read_notifications", "true"),
+            ("expires_in_days", "30"),
+            ("rate_limit_per_minute", "2"),
+        ],
+    )
+    .await;
+
+    let bot = server.driver();
+    let resp = bot
+        .api_get_with_token(&format!("/api/{id}/notifications"), &token)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let followee = RostraIdSecretKey::generate().id().to_string();
+    let resp = bot
+        .api_post_json_with_token(
+            &format!("/api/{id}/follow-managed"),
+            &token,
+            &serde_json::json!({ "followee": followee }),
+        )
+        .await;
+    assert_eq!(
+        resp.status(),
+        200,
+        "Token with the follow scope should follow"
+    );
+
+    let resp = bot
+        .api_get_with_token(&format!("/api/{id}/notifications"), &token)
+        .await;
+    assert_eq!(
+        resp.status(),
+        429,
+        "Third request in a minute is rate limited"
+    );
+
+    // Malformed tokens are rejected, not treated as missing
+    let resp = bot
+        .api_get_with_token(&format!("/api/{id}/notifications"), "not-a-token")
+        .await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn api_token_minting_requires_secret() {
+    let server = TestServer::start().await;
+    let owner = server.driver();
+    let (id, _secret) = generate_identity(&owner).await;
+    owner.login_readonly(id.parse().unwrap()).await;
+
+    let resp = owner
+        .ajax_post_form(
+            "/settings/api-tokens",
+            &[("label", "test-bot"), ("scope_post", "true")],
+        )
+        .await;
+    assert!(!resp.status().is_success());
+    let html = resp.text().await.unwrap();
+    assert!(!html.contains("rostra-api-"));
+}
+
+/// Helper: take the first Server-Sent Event named `name`, reading more of
+/// the stream if needed, and return its id and data.
+///
+/// Other events are kept in `buf`, as events of different kinds don't arrive
+/// in a fixed order.
+async fn next_sse_event(
+    resp: &mut reqwest::Response,
+    buf: &mut String,
+    name: &str,
+) -> (Option<String>, serde_json::Value) {
+    loop {
+        let mut start = 0;
+        while let Some(len) = buf[start..].find("\n\n") {
+            let end = start + len + 2;
+            let (mut event, mut id, mut data) = (None, None, None);
+            for line in buf[start..end].lines() {
+                if let Some(v) = line.strip_prefix("event: ") {
+                    event = Some(v.to_owned());
+                } else if let Some(v) = line.strip_prefix("id: ") {
+                    id = Some(v.to_owned());
+                } else if let Some(v) = line.strip_prefix("data: ") {
+                    data = Some(serde_json::from_str(v).unwrap());
+                }
+            }
+            if event.as_deref() == Some(name) {
+                buf.replace_range(start..end, "");
+                return (id, data.expect("Event should have data"));
+            }
+            start = end;
+        }
+        let chunk = tokio::time::timeout(std::time::Duration::from_secs(10), resp.chunk())
+            .await
+            .unwrap_or_else(|_| panic!("Timed out waiting for a `{name}` event"))
+            .unwrap()
+            .expect("Stream should not end");
+        buf.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn stream_delivers_posts_heads_and_follows() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    let (id_a, secret_a) = generate_identity(&driver).await;
+    let (id_b, _secret_b) = generate_identity(&driver).await;
+
+    let mut stream = driver
+        .api_stream(&format!("/api/{id_a}/stream"), None)
+        .await;
+    assert_eq!(stream.status(), 200);
+    assert!(
+        stream.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream")
+    );
+    let mut buf = String::new();
+
+    let (post, heads) = publish_post(&driver, &id_a, &secret_a, None, "Live!", None).await;
+    let (id, data) = next_sse_event(&mut stream, &mut buf, "post").await;
+    assert!(id.is_some(), "Posts should carry a resume cursor");
+    assert_eq!(data["event_id"], post.as_str());
+    assert_eq!(data["author"], id_a.as_str());
+    assert_eq!(data["content"], "Live!");
+    assert!(heads.contains(&post));
+    // Publishing may also announce the node first, which is a head too
+    loop {
+        let (_, data) = next_sse_event(&mut stream, &mut buf, "head").await;
+        if data["head"] == post.as_str() {
+            break;
+        }
+    }
+
+    let resp = driver
+        .api_post_json(
+            &format!("/api/{id_a}/follow-managed"),
+            Some(&secret_a),
+            &serde_json::json!({ "followee": id_b }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+    let (_, data) = next_sse_event(&mut stream, &mut buf, "follow").await;
+    assert_eq!(data["author"], id_a.as_str());
+    assert_eq!(data["followee"], id_b.as_str());
+    assert_eq!(data["unfollow"], false);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn stream_resumes_from_last_event_id() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    let (id, secret) = generate_identity(&driver).await;
+
+    let mut stream = driver.api_stream(&format!("/api/{id}/stream"), None).await;
+    let mut buf = String::new();
+    let mut head = None;
+    let mut posts = vec![];
+    for content in ["First", "Second", "Third"] {
+        let (post, heads) =
+            publish_post(&driver, &id, &secret, head.as_deref(), content, None).await;
+        head = heads.into_iter().next();
+        let (event_id, data) = next_sse_event(&mut stream, &mut buf, "post").await;
+        assert_eq!(data["event_id"], post.as_str());
+        posts.push((post, event_id.unwrap()));
+    }
+    drop(stream);
+
+    // Reconnecting after the first post catches up on the rest
+    let mut stream = driver
+        .api_stream(&format!("/api/{id}/stream"), Some(&posts[0].1))
+        .await;
+    assert_eq!(stream.status(), 200);
+    let mut buf = String::new();
+    for (post, event_id) in &posts[1..] {
+        let (id, data) = next_sse_event(&mut stream, &mut buf, "post").await;
+        assert_eq!(data["event_id"], post.as_str());
+        assert_eq!(id.as_ref(), Some(event_id));
+    }
+
+    let resp = driver
+        .api_stream(&format!("/api/{id}/stream"), Some("garbage"))
+        .await;
+    assert_eq!(resp.status(), 400);
+    let resp = driver
+        .api_stream(
+            &format!("/api/{id}/stream"),
+            Some(&format!("1-{}", u64::MAX)),
+        )
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn stream_hides_filtered_live_posts() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    let (id, secret) = generate_identity(&driver).await;
+    let resp = driver
+        .api_post_json(
+            &format!("/api/{id}/content-filters"),
+            Some(&secret),
+            &serde_json::json!({ "muted_words": ["spoiler"] }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let mut stream = driver.api_stream(&format!("/api/{id}/stream"), None).await;
+    let mut buf = String::new();
+
+    // Posts of others, as received from the network
+    let other = RostraIdSecretKey::generate();
+    let client = server.client(id.parse::<RostraId>().unwrap()).await;
+    for text in ["A spoiler!", "Nothing to hide"] {
+        let (event, content) = Event::builder(&content_kind::SocialPost::new(
+            text.to_owned(),
+            None,
+            Default::default(),
+        ))
+        .author(other.id())
+        .build()
+        .expect("valid post");
+        let event =
+            VerifiedEvent::verify_received_as_is(event.signed_by(other)).expect("event verifies");
+        client
+            .db()
+            .process_event_with_content(
+                &VerifiedEventContent::verify(event, content).expect("content verifies"),
+            )
+            .await;
+    }
+
+    let (_, data) = next_sse_event(&mut stream, &mut buf, "post").await;
+    assert_eq!(data["content"], "Nothing to hide");
+}
@@ -312,6 +312,17 @@ impl UiDriver {
             .expect("API GET request failed")
     }
 
+    /// Send a GET to an API endpoint with the version and secret headers.
+    pub async fn api_get_with_secret(&self, path: &str, secret: &str) -> reqwest::Response {
+        self.client
+            .get(self.url(path))
+            .header("x-rostra-api-version", "0")
+            .header("x-rostra-id-secret", secret)
+            .send()
+            .await
+            .expect("API GET request failed")
+    }
+
     /// Send a GET without the version header (for negative tests).
     pub async fn api_get_no_version(&self, path: &str) -> reqwest::Response {
         self.client
@@ -340,6 +351,49 @@ impl UiDriver {
         req.send().await.expect("API POST request failed")
     }
 
+    /// Send a POST to an API endpoint with JSON body, version header, and
+    /// an API token.
+    pub async fn api_post_json_with_token(
+        &self,
+        path: &str,
+        token: &str,
+        body: &serde_json::Value,
+    ) -> reqwest::Response {
+        self.client
+            .post(self.url(path))
+            .header("x-rostra-api-version", "0")
+            .bearer_auth(token)
+            .json(body)
+            .send()
+            .await
+            .expect("API POST request failed")
+    }
+
+    /// Send a GET to an API endpoint with the version header and an API
+    /// token.
+    pub async fn api_get_with_token(&self, path: &str, token: &str) -> reqwest::Response {
+        self.client
+            .get(self.url(path))
+            .header("x-rostra-api-version", "0")
+            .bearer_auth(token)
+            .send()
+            .await
+            .expect("API GET request failed")
+    }
+
+    /// Open an event stream of an API endpoint, optionally resuming after
+    /// `last_event_id`.
+    pub async fn api_stream(&self, path: &str, last_event_id: Option<&str>) -> reqwest::Response {
+        let mut req = self
+            .client
+            .get(self.url(path))
+            .header("x-rostra-api-version", "0");
+        if let Some(id) = last_event_id {
+            req = req.header("last-event-id", id);
+        }
+        req.send().await.expect("API stream request failed")
+    }
+
     /// Send a GET to an API endpoint with a custom version header value.
     pub async fn api_get_with_version(&self, path: &str, version: &str) -> reqwest::Response {
         self.client
@@ -2,7 +2,7 @@ mod common;
 
 use common::TestServer;
 use reqwest::header;
-use rostra_core::event::{Event, EventKind, VerifiedEvent};
+use rostra_core::event::{Event, EventKind, VerifiedEvent, VerifiedEventContent, content_kind};
 use rostra_core::id::{RostraId, RostraIdSecretKey, ToShort as _};
 use rostra_core::{EventId, ShortEventId};
 use scraper::{ElementRef, Html, Selector};
@@ -1230,3 +1230,170 @@ fn all_mention_composers_use_the_shared_short_id_selection_contract() {
         );
     }
 }
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn hashtags_link_to_tag_pages() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    let (author, secret) = driver.login_new_identity().await;
+
+    let resp = driver.api_get(&format!("/api/{author}/heads")).await;
+    let heads: serde_json::Value = resp.json().await.unwrap();
+    let head = heads["heads"][0].as_str().unwrap();
+    let resp = driver
+        .api_post_json(
+            &format!("/api/{author}/publish-social-post-managed"),
+            Some(&secret.to_string()),
+            &json!({
+                "parent_head_id": head,
+                "content": "Hello #Gardening friends",
+            }),
+        )
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = driver.get("/tag/gardening").await;
+    assert_eq!(resp.status(), 200);
+    let body = resp.text().await.unwrap();
+    assert!(
+        body.contains("<a href=\"/tag/gardening\">#Gardening</a>"),
+        "hashtags should link to their tag page, body:\n{body}"
+    );
+
+    let resp = driver.get("/tag/cooking").await;
+    assert_eq!(resp.status(), 200);
+    let body = resp.text().await.unwrap();
+    assert!(body.contains("No posts with this hashtag yet."));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn profile_links_to_its_mentions_page() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    let (author, _) = driver.login_new_identity().await;
+
+    let response = driver
+        .ajax_post_form("/post", &[("content", &format!("Hi <rostra:{author}>"))])
+        .await;
+    assert_eq!(response.status(), 200);
+
+    let mentions_url = format!("/profile/{}/mentions", author.to_short());
+    let resp = driver.get(&format!("/profile/{}", author.to_short())).await;
+    assert_eq!(resp.status(), 200);
+    let body = resp.text().await.unwrap();
+    assert!(
+        body.contains(&format!("href=\"{mentions_url}\"")),
+        "profile should link to its mentions, body:\n{body}"
+    );
+
+    // Posts mentioning their own author are not listed
+    let resp = driver.get(&mentions_url).await;
+    assert_eq!(resp.status(), 200);
+    let body = resp.text().await.unwrap();
+    assert!(body.contains("No posts mentioning this identity yet."));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn moved_profile_offers_following_its_successor() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    let (self_id, self_secret) = driver.login_new_identity().await;
+    let client = server.client(self_id).await;
+
+    let old = RostraIdSecretKey::generate();
+    let new = RostraIdSecretKey::generate();
+    let (event, content) = Event::builder(&content_kind::Succession::new(old.id(), new))
+        .author(old.id())
+        .build()
+        .expect("valid succession");
+    let event = VerifiedEvent::verify_received_as_is(event.signed_by(old)).expect("event verifies");
+    client
+        .db()
+        .process_event_with_content(
+            &VerifiedEventContent::verify(event, content).expect("content verifies"),
+        )
+        .await;
+    // Followed earlier, as the unfollow must win over the follow by timestamp
+    let (event, content) = Event::builder(&content_kind::Follow {
+        followee: old.id(),
+        persona: None,
+        selector: None,
+        persona_tags_selector: Some(Default::default()),
+    })
+    .author(self_id)
+    .timestamp(time::OffsetDateTime::now_utc() - time::Duration::HOUR)
+    .build()
+    .expect("valid follow");
+    let event =
+        VerifiedEvent::verify_received_as_is(event.signed_by(self_secret)).expect("event verifies");
+    client
+        .db()
+        .process_event_with_content(
+            &VerifiedEventContent::verify(event, content).expect("content verifies"),
+        )
+        .await;
+
+    let profile_url = format!("/profile/{}", old.id().to_short());
+    let accept_url = format!("{profile_url}/accept-succession");
+    let body = driver.get(&profile_url).await.text().await.unwrap();
+    assert!(
+        body.contains("This account moved to")
+            && body.contains(&format!("href=\"/profile/{}\"", new.id().to_short()))
+            && body.contains(&accept_url),
+        "profile should link to its successor, body:\n{body}"
+    );
+
+    let response = driver.ajax_post_form(&accept_url, &[]).await;
+    assert_eq!(response.status(), 200);
+    let followees: Vec<RostraId> = client
+        .db()
+        .get_followees(self_id)
+        .await
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(followees, vec![new.id()]);
+
+    let body = driver.get(&profile_url).await.text().await.unwrap();
+    assert!(body.contains("This account moved to") && !body.contains(&accept_url));
+}
+
+#[test_log::test(tokio::test(flavor = "multi_thread"))]
+async fn private_posts_page_composes_and_lists_posts() {
+    let server = TestServer::start().await;
+    let driver = server.driver();
+    driver.login_new_identity().await;
+
+    let body = driver.get("/private").await.text().await.unwrap();
+    assert!(
+        body.contains("No audience yet, add members"),
+        "composing should wait for an audience, body:\n{body}"
+    );
+
+    let member = RostraIdSecretKey::generate().id();
+    let resp = driver
+        .post_form("/private/audience/add", &[("id", &member.to_string())])
+        .await;
+    assert_eq!(resp.status(), 303);
+    assert_eq!(
+        resp.headers().get(header::LOCATION).unwrap(),
+        "/private/audience"
+    );
+    let resp = driver.get("/private/audience").await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = driver
+        .post_form("/private", &[("content", "Only for my audience")])
+        .await;
+    assert_eq!(resp.status(), 303);
+    let body = driver.get("/private").await.text().await.unwrap();
+    assert!(
+        body.contains("Only for my audience"),
+        "private post should be listed, body:\n{body}"
+    );
+
+    let resp = driver
+        .post_form("/private/audience/add", &[("id", "not-an-id")])
+        .await;
+    assert_eq!(resp.status(), 400);
+}
@@ -1,8 +1,12 @@
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
+use std::time::Duration;
 
-use clap::{Args, Parser, Subcommand};
-use rostra_core::id::RostraId;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rostra_client_db::ContentPruningPolicy;
+use rostra_core::ShortEventId;
+use rostra_core::event::{PersonaTag, PersonasTagsSelector};
+use rostra_core::id::{ExternalEventId, RostraId};
 use rostra_util_bind_addr::BindAddr;
 
 /// Command line options for the Rostra CLI application
@@ -50,16 +54,41 @@ pub enum OptsCmd {
     Serve {
         /// Default profile to use for users who haven't logged in yet
         /// (read-only mode)
-        #[arg(long, group = "id", env = "ROSTRA_ID")]
+        #[arg(long, env = "ROSTRA_ID", required_unless_present = "secret_file")]
         id: Option<RostraId>,
 
         /// Path to the secret file for authentication
-        #[arg(long, group = "id")]
+        #[arg(long)]
         secret_file: Option<PathBuf>,
+
+        /// Serve the local control API on a Unix socket at this path
+        ///
+        /// Only the user running the node can connect. See
+        /// `docs/control-api.md`.
+        #[arg(long, env = "ROSTRA_CONTROL_SOCKET")]
+        control_socket: Option<PathBuf>,
+
+        #[command(flatten)]
+        pruning: ContentPruningOpts,
     },
     /// Start web-ui
     WebUi(WebUiOpts),
 
+    /// Start the terminal UI
+    ///
+    /// Logs go to `tui.log` in the data dir, as the UI takes over the
+    /// terminal.
+    Tui {
+        /// Identity to browse as, read-only
+        #[arg(long, env = "ROSTRA_ID", required_unless_present = "secret_file")]
+        id: Option<RostraId>,
+
+        /// Path to the secret file, to also post as the identity (or as a
+        /// device delegated by `--id`)
+        #[arg(long)]
+        secret_file: Option<PathBuf>,
+    },
+
     /// Development and debugging commands
     #[command(subcommand)]
     Dev(DevCmd),
@@ -74,6 +103,295 @@ pub enum OptsCmd {
         #[arg(long)]
         secret_file: PathBuf,
     },
+
+    /// Reply to a post
+    Reply {
+        /// Post to reply to, as `<rostra-id>-<event-id>`
+        #[arg(long)]
+        to: ExternalEventId,
+
+        /// Message body of the reply
+        #[arg(long)]
+        body: String,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// React to a post with an emoji
+    React {
+        /// Post to react to, as `<rostra-id>-<event-id>`
+        #[arg(long)]
+        to: ExternalEventId,
+
+        /// A single emoji
+        #[arg(long)]
+        emoji: String,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Upvote or downvote a post, or retract a vote
+    Vote {
+        /// Post to vote on, as `<rostra-id>-<event-id>`
+        #[arg(long)]
+        post: ExternalEventId,
+
+        #[arg(long, value_enum)]
+        vote: VoteArg,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Replace the content of an own post
+    Edit {
+        /// Event id of the post
+        #[arg(long)]
+        event_id: ShortEventId,
+
+        /// New message body
+        #[arg(long)]
+        body: String,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Delete an own post
+    Delete {
+        /// Event id of the post
+        #[arg(long)]
+        event_id: ShortEventId,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Follow an identity, or update the persona tags followed
+    Follow {
+        /// Identity to follow
+        #[arg(long)]
+        id: RostraId,
+
+        #[command(flatten)]
+        selector: PersonaTagsSelectorOpts,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Unfollow an identity
+    Unfollow {
+        /// Identity to unfollow
+        #[arg(long)]
+        id: RostraId,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Update the profile, keeping the fields not given
+    UpdateProfile {
+        #[arg(long)]
+        display_name: Option<String>,
+
+        #[arg(long)]
+        bio: Option<String>,
+
+        /// Path to the avatar image
+        #[arg(long, requires = "avatar_mime")]
+        avatar: Option<PathBuf>,
+
+        /// Mime type of the avatar image, e.g. `image/png`
+        #[arg(long)]
+        avatar_mime: Option<String>,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// Upload a media file, to be embedded in posts
+    UploadMedia {
+        /// Path to the file
+        path: PathBuf,
+
+        /// Mime type of the file, e.g. `image/png`
+        #[arg(long)]
+        mime: String,
+
+        #[command(flatten)]
+        secret: SecretOpts,
+    },
+
+    /// List the posts of a timeline, newest first
+    Timeline {
+        /// Identity whose local database to read
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+
+        #[arg(value_enum)]
+        timeline: TimelineArg,
+
+        /// Maximum number of posts to list
+        #[arg(long, default_value = "20")]
+        limit: usize,
+    },
+
+    /// Show a post and its replies
+    Thread {
+        /// Identity whose local database to read
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+
+        /// Event id of the post
+        event_id: ShortEventId,
+
+        /// Maximum number of replies to list
+        #[arg(long, default_value = "100")]
+        limit: usize,
+    },
+
+    /// Announce a new identity as the successor of the current one
+    ///
+    /// Followers of the current identity can then move their follow to the
+    /// successor.
+    AnnounceSuccessor {
+        /// Path to the secret file of the current identity
+        #[arg(long)]
+        secret_file: PathBuf,
+
+        /// Path to the secret file of the successor identity, which signs the
+        /// announcement too
+        #[arg(long)]
+        successor_secret_file: PathBuf,
+    },
+
+    /// Delegate signing events to a device key, or revoke a delegation
+    DelegateDevice {
+        /// Path to the secret file of the identity
+        #[arg(long)]
+        secret_file: PathBuf,
+
+        /// Public key of the device
+        #[arg(long)]
+        device: RostraId,
+
+        /// Revoke the delegation instead
+        #[arg(long)]
+        revoke: bool,
+    },
+
+    /// Export stored events and their content to an archive
+    Export {
+        /// Identity whose local database to export from
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+
+        /// Export the events of the whole web of trust, not only of
+        /// `rostra_id`
+        #[arg(long)]
+        wot: bool,
+
+        /// Path of the archive to write
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+
+    /// Import the events of an archive, verifying them
+    Import {
+        /// Identity whose local database to import into (created if missing)
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+
+        /// Path of the archive to read
+        #[arg(long, short)]
+        input: PathBuf,
+    },
+
+    /// Sync two database files of an identity offline, merging each into the
+    /// other
+    SyncDb {
+        /// Identity of both databases
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+
+        /// Path of the first database file
+        path_a: PathBuf,
+
+        /// Path of the second database file
+        path_b: PathBuf,
+    },
+}
+
+/// Secret to sign events with
+#[derive(Debug, Args)]
+pub struct SecretOpts {
+    /// Path to the secret file for authentication
+    #[arg(long)]
+    pub secret_file: PathBuf,
+
+    /// Identity to act for, if the secret is of one of its delegated devices
+    #[arg(long)]
+    pub rostra_id: Option<RostraId>,
+}
+
+/// Persona tags of posts to follow
+#[derive(Debug, Args)]
+pub struct PersonaTagsSelectorOpts {
+    /// Only follow posts with one of these tags (can be repeated)
+    #[arg(long = "only-tag", conflicts_with = "except_tags")]
+    pub only_tags: Vec<PersonaTag>,
+
+    /// Follow all posts except the ones with one of these tags (can be
+    /// repeated)
+    #[arg(long = "except-tag")]
+    pub except_tags: Vec<PersonaTag>,
+}
+
+impl PersonaTagsSelectorOpts {
+    pub fn selector(&self) -> PersonasTagsSelector {
+        if self.only_tags.is_empty() {
+            PersonasTagsSelector::Except {
+                ids: self.except_tags.iter().cloned().collect(),
+            }
+        } else {
+            PersonasTagsSelector::Only {
+                ids: self.only_tags.iter().cloned().collect(),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum VoteArg {
+    Up,
+    Down,
+    /// Retract a previous vote
+    None,
+}
+
+impl VoteArg {
+    pub fn upvote(self) -> Option<bool> {
+        match self {
+            VoteArg::Up => Some(true),
+            VoteArg::Down => Some(false),
+            VoteArg::None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum TimelineArg {
+    /// Posts of followed identities
+    Following,
+    /// Posts of the whole network
+    Network,
+    /// News posts, by rank
+    News,
+    /// Replies to and mentions of own posts, by time received
+    Notifications,
 }
 
 #[derive(Debug, Args)]
@@ -128,6 +446,55 @@ pub struct WebUiOpts {
     /// welcome page
     #[arg(long, env = "ROSTRA_WELCOME_REDIRECT")]
     pub welcome_redirect: Option<String>,
+
+    #[command(flatten)]
+    pub pruning: ContentPruningOpts,
+}
+
+/// Local content pruning options
+///
+/// Pruning discards payloads only; events themselves are kept, unless header
+/// pruning is enabled as well.
+#[derive(Debug, Args)]
+pub struct ContentPruningOpts {
+    /// Prune content of events older than this many days
+    #[arg(long, env = "ROSTRA_PRUNE_MAX_AGE_DAYS")]
+    pub prune_max_age_days: Option<u64>,
+
+    /// Prune content of identities further away in the web of trust (0 = only
+    /// self, 1 = direct followees, 2 = followees of followees)
+    #[arg(long, env = "ROSTRA_PRUNE_MAX_WOT_DISTANCE")]
+    pub prune_max_wot_distance: Option<u8>,
+
+    /// Maximum bytes of content to keep per identity, oldest pruned first
+    #[arg(long, env = "ROSTRA_PRUNE_MAX_CONTENT_SIZE_PER_ID")]
+    pub prune_max_content_size_per_id: Option<u64>,
+
+    /// Apply pruning to our own content as well
+    #[arg(long, env = "ROSTRA_PRUNE_SELF")]
+    pub prune_self: bool,
+
+    /// Prune whole events older than this many days of identities that are
+    /// not directly followed
+    #[arg(long, env = "ROSTRA_PRUNE_HEADERS_MAX_AGE_DAYS")]
+    pub prune_headers_max_age_days: Option<u64>,
+}
+
+impl ContentPruningOpts {
+    pub fn policy(&self) -> Option<ContentPruningPolicy> {
+        let policy = ContentPruningPolicy {
+            keep_self: !self.prune_self,
+            max_age: self
+                .prune_max_age_days
+                .map(|days| Duration::from_secs(days.saturating_mul(24 * 60 * 60))),
+            max_wot_distance: self.prune_max_wot_distance,
+            max_content_size_per_id: self.prune_max_content_size_per_id,
+            max_header_age: self
+                .prune_headers_max_age_days
+                .map(|days| Duration::from_secs(days.saturating_mul(24 * 60 * 60))),
+        };
+        (!policy.is_noop()).then_some(policy)
+    }
 }
 
 pub fn make_web_opts(data_dir: &Path, opts: &WebUiOpts) -> rostra_web_ui::Opts {
@@ -174,4 +541,22 @@ pub enum DevCmd {
         #[arg(long)]
         rostra_id: RostraId,
     },
+    /// List the heads of an identity's event DAG
+    Heads {
+        /// Identity whose local database to read
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+        /// Identity whose heads to list (defaults to `rostra_id`)
+        #[arg(long)]
+        of: Option<RostraId>,
+    },
+    /// List events of an identity known to be missing
+    MissingEvents {
+        /// Identity whose local database to read
+        #[arg(long, env = "ROSTRA_ID")]
+        rostra_id: RostraId,
+        /// Identity whose missing events to list (defaults to `rostra_id`)
+        #[arg(long)]
+        of: Option<RostraId>,
+    },
 }
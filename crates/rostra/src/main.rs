@@ -1,7 +1,11 @@
 mod cli;
+mod social;
 
+use std::fs;
 use std::io;
 use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Mutex;
 use std::time::Duration;
 
 use clap::Parser;
@@ -11,10 +15,14 @@ use futures::future::pending;
 use rostra_client::Client;
 use rostra_client::error::{ConnectError, IdResolveError, IdSecretReadError, InitError, PostError};
 use rostra_client::multiclient::MultiClient;
+use rostra_client_db::archive::ArchiveError;
 use rostra_client_db::{Database, DbError};
+use rostra_control::{ControlError, run_control};
+use rostra_core::event::SocialPost;
 use rostra_core::id::RostraIdSecretKey;
 use rostra_p2p::RpcError;
 use rostra_p2p::connection::Connection;
+use rostra_tui::{TuiError, run_tui};
 use rostra_util_bind_addr::BindAddr;
 use rostra_util_error::{BoxedError, FmtCompact as _};
 use rostra_web_ui::{WebUiServerError, run_ui};
@@ -23,6 +31,7 @@ use tokio::time::Instant;
 use tracing::level_filters::LevelFilter;
 use tracing::{info, warn};
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 pub const PROJECT_NAME: &str = "rostra";
 pub const LOG_TARGET: &str = "rostra::cli";
@@ -35,6 +44,10 @@ pub enum CliError {
     Init { source: InitError },
     #[snafu(display("WebUI Server error: {source}"))]
     WebUiServer { source: WebUiServerError },
+    #[snafu(display("Terminal UI error: {source}"))]
+    Tui { source: TuiError },
+    #[snafu(display("Control API error: {source}"))]
+    Control { source: ControlError },
     #[snafu(display("ID resolution error: {source}"))]
     Resolve { source: IdResolveError },
     #[snafu(display("Connection error: {source}"))]
@@ -51,6 +64,14 @@ pub enum CliError {
     DataDir { source: io::Error },
     #[snafu(display("Database error: {source}"))]
     Database { source: DbError },
+    #[snafu(display("Archive error: {source}"))]
+    Archive { source: ArchiveError },
+    #[snafu(display("Archive file error: {source}"))]
+    ArchiveFile { source: io::Error },
+    #[snafu(display("File read error: {source}"))]
+    ReadFile { source: io::Error },
+    #[snafu(display("Invalid input: {message}"))]
+    InvalidInput { message: String },
     #[snafu(display("Miscellaneous error: {source}"))]
     Other { source: BoxedError },
 }
@@ -90,9 +111,13 @@ fn spawn_open_browser(addr: SocketAddr) {
 #[snafu::report]
 #[tokio::main]
 async fn main() -> CliResult<()> {
-    init_logging().context(WhateverSnafu)?;
-
     let opts = Opts::parse();
+    let log_file = if let cli::OptsCmd::Tui { .. } = opts.cmd {
+        Some(opts.global.data_dir().join("tui.log"))
+    } else {
+        None
+    };
+    init_logging(log_file.as_deref()).context(WhateverSnafu)?;
     match handle_cmd(opts).await {
         Ok(v) => {
             println!("{}", serde_json::to_string_pretty(&v).expect("Can't fail"));
@@ -199,8 +224,32 @@ async fn handle_cmd(opts: Opts) -> CliResult<serde_json::Value> {
 
                 serde_json::to_value(serde_json::Value::Null).expect("Can't fail")
             }
+            cli::DevCmd::Heads { rostra_id, of } => {
+                let db = social::open_db(opts.global.data_dir(), rostra_id).await?;
+
+                let mut heads: Vec<_> = db
+                    .get_heads(of.unwrap_or(rostra_id))
+                    .await
+                    .into_iter()
+                    .collect();
+                heads.sort();
+
+                serde_json::to_value(heads).expect("Can't fail")
+            }
+            cli::DevCmd::MissingEvents { rostra_id, of } => {
+                let db = social::open_db(opts.global.data_dir(), rostra_id).await?;
+
+                let missing = db.get_missing_events_for_id(of.unwrap_or(rostra_id)).await;
+
+                serde_json::to_value(missing).expect("Can't fail")
+            }
         },
-        cli::OptsCmd::Serve { secret_file, id } => {
+        cli::OptsCmd::Serve {
+            secret_file,
+            id,
+            control_socket,
+            pruning,
+        } => {
             let (id, secret) = if let Some(secret_file) = secret_file {
                 let secret = Client::read_id_secret(&secret_file)
                     .await
@@ -209,13 +258,22 @@ async fn handle_cmd(opts: Opts) -> CliResult<serde_json::Value> {
             } else {
                 (id.expect("Must be set, enforced via clap"), None)
             };
-            let _client = Client::builder(id)
+            let client = Client::builder(id)
                 .maybe_secret(secret)
+                .maybe_content_pruning_policy(pruning.policy())
                 .build()
                 .await
                 .context(InitSnafu)?;
 
-            pending().await
+            match control_socket {
+                Some(path) => {
+                    run_control(client, secret, &path)
+                        .await
+                        .context(ControlSnafu)?;
+                    serde_json::Value::Null
+                }
+                None => pending().await,
+            }
         }
         cli::OptsCmd::WebUi(ref web_opts) => {
             let pkarr_client = Client::make_pkarr_client().context(InitSnafu)?;
@@ -224,7 +282,8 @@ async fn handle_cmd(opts: Opts) -> CliResult<serde_json::Value> {
                 web_opts.max_clients,
                 web_opts.public,
                 pkarr_client,
-            );
+            )
+            .with_content_pruning_policy(web_opts.pruning.policy());
             let ui_opts = make_web_opts(opts.global.data_dir(), web_opts);
 
             if !web_opts.skip_xdg_open {
@@ -238,6 +297,30 @@ async fn handle_cmd(opts: Opts) -> CliResult<serde_json::Value> {
 
             serde_json::Value::Null
         }
+        cli::OptsCmd::Tui { id, secret_file } => {
+            let secret = match secret_file {
+                Some(secret_file) => Some(
+                    Client::read_id_secret(&secret_file)
+                        .await
+                        .context(SecretSnafu)?,
+                ),
+                None => None,
+            };
+            let id = id
+                .or(secret.map(|secret| secret.id()))
+                .expect("Must be set, enforced via clap");
+            // A device key only signs events, it doesn't unlock the client
+            let client = Client::builder(id)
+                .db(social::open_db(opts.global.data_dir(), id).await?)
+                .maybe_secret(secret.filter(|secret| secret.id() == id))
+                .build()
+                .await
+                .context(InitSnafu)?;
+
+            run_tui(client, secret).await.context(TuiSnafu)?;
+
+            serde_json::Value::Null
+        }
         cli::OptsCmd::GenId => {
             let secret = RostraIdSecretKey::generate();
             let id = secret.id();
@@ -265,12 +348,312 @@ async fn handle_cmd(opts: Opts) -> CliResult<serde_json::Value> {
 
             serde_json::Value::Bool(true)
         }
+        cli::OptsCmd::Reply { to, body, secret } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+
+            let event = client
+                .social_post(id_secret, body, Some(to), Default::default())
+                .await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::React { to, emoji, secret } => {
+            if SocialPost::is_reaction(&Some(to), &emoji).is_none() {
+                return InvalidInputSnafu {
+                    message: "Reaction must be a single emoji",
+                }
+                .fail();
+            }
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+
+            let event = client
+                .social_post(id_secret, emoji, Some(to), Default::default())
+                .await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::Vote { post, vote, secret } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+
+            let event = client
+                .set_social_vote(id_secret, post, vote.upvote())
+                .await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::Edit {
+            event_id,
+            body,
+            secret,
+        } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+            let post = client
+                .db()
+                .get_social_post(event_id)
+                .await
+                .filter(|post| post.author == client.rostra_id())
+                .ok_or_else(|| {
+                    InvalidInputSnafu {
+                        message: "Own post not found",
+                    }
+                    .build()
+                })?;
+
+            let content = SocialPost::new_text(body, post.reply_to, post.content.persona_tags());
+            let content = if post.content.news {
+                content.with_news_fields(post.content.url, post.content.title)
+            } else {
+                content
+            };
+            let event = client
+                .publish_event(id_secret, content)
+                .replace(post.event_id)
+                .call()
+                .await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::Delete { event_id, secret } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+            let post = client
+                .db()
+                .get_social_post(event_id)
+                .await
+                .filter(|post| post.author == client.rostra_id())
+                .ok_or_else(|| {
+                    InvalidInputSnafu {
+                        message: "Own post not found",
+                    }
+                    .build()
+                })?;
+
+            // An empty post replacing the original one deletes it
+            let event = client
+                .publish_event(
+                    id_secret,
+                    SocialPost::new(String::new(), None, Default::default()),
+                )
+                .replace(post.event_id)
+                .call()
+                .await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::Follow {
+            id,
+            selector,
+            secret,
+        } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+
+            let event = client.follow(id_secret, id, selector.selector()).await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::Unfollow { id, secret } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+
+            let event = client.unfollow(id_secret, id).await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::UpdateProfile {
+            display_name,
+            bio,
+            avatar,
+            avatar_mime,
+            secret,
+        } => {
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+            let existing = client.db().get_social_profile(client.rostra_id()).await;
+
+            let avatar = match (avatar, avatar_mime) {
+                (Some(path), Some(mime)) => {
+                    Some((mime, tokio::fs::read(&path).await.context(ReadFileSnafu)?))
+                }
+                _ => existing.as_ref().and_then(|profile| profile.avatar.clone()),
+            };
+            let display_name = display_name
+                .or_else(|| {
+                    existing
+                        .as_ref()
+                        .map(|profile| profile.display_name.clone())
+                })
+                .unwrap_or_default();
+            let bio = bio
+                .or_else(|| existing.as_ref().map(|profile| profile.bio.clone()))
+                .unwrap_or_default();
+            let event = client
+                .post_social_profile_update(id_secret, display_name, bio, avatar)
+                .await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::UploadMedia { path, mime, secret } => {
+            let data = tokio::fs::read(&path).await.context(ReadFileSnafu)?;
+            let (client, id_secret) = social::open_client(opts.global.data_dir(), &secret).await?;
+
+            let event = client.publish_media(id_secret, mime, data).await?;
+
+            social::published_output(&event)
+        }
+        cli::OptsCmd::Timeline {
+            rostra_id,
+            timeline,
+            limit,
+        } => {
+            let db = social::open_db(opts.global.data_dir(), rostra_id).await?;
+
+            let posts = social::list_timeline(&db, rostra_id, timeline, limit).await;
+
+            serde_json::to_value(posts).expect("Can't fail")
+        }
+        cli::OptsCmd::Thread {
+            rostra_id,
+            event_id,
+            limit,
+        } => {
+            let db = social::open_db(opts.global.data_dir(), rostra_id).await?;
+            let post = db.get_social_post(event_id).await.ok_or_else(|| {
+                InvalidInputSnafu {
+                    message: "Post not found",
+                }
+                .build()
+            })?;
+
+            let (replies, _) = db
+                .paginate_social_post_comments_rev(event_id, None, limit)
+                .await;
+
+            serde_json::json!({
+                "post": social::PostOutput::from(post),
+                "replies": replies.into_iter().map(social::PostOutput::from).collect::<Vec<_>>(),
+            })
+        }
+        cli::OptsCmd::AnnounceSuccessor {
+            secret_file,
+            successor_secret_file,
+        } => {
+            let id_secret = Client::read_id_secret(&secret_file)
+                .await
+                .context(SecretSnafu)?;
+            let successor_secret = Client::read_id_secret(&successor_secret_file)
+                .await
+                .context(SecretSnafu)?;
+
+            let client = Client::builder(id_secret.id())
+                .start_request_handler(false)
+                .build()
+                .await
+                .context(InitSnafu)?;
+
+            client
+                .announce_successor(id_secret, successor_secret)
+                .await?;
+
+            serde_json::Value::Bool(true)
+        }
+        cli::OptsCmd::DelegateDevice {
+            secret_file,
+            device,
+            revoke,
+        } => {
+            let id_secret = Client::read_id_secret(&secret_file)
+                .await
+                .context(SecretSnafu)?;
+
+            let client = Client::builder(id_secret.id())
+                .start_request_handler(false)
+                .build()
+                .await
+                .context(InitSnafu)?;
+
+            client.delegate_device(id_secret, device, revoke).await?;
+
+            serde_json::Value::Bool(true)
+        }
+        cli::OptsCmd::Export {
+            rostra_id,
+            wot,
+            output,
+        } => {
+            let db_path = Database::mk_db_path(opts.global.data_dir(), rostra_id)
+                .await
+                .context(DataDirSnafu)?;
+            let db = Database::open(&db_path, rostra_id)
+                .await
+                .context(DatabaseSnafu)?;
+
+            let mut authors = vec![rostra_id];
+            if wot {
+                authors.extend(db.self_wot_subscribe().snapshot().iter_all());
+            }
+
+            let mut out =
+                io::BufWriter::new(std::fs::File::create(&output).context(ArchiveFileSnafu)?);
+            let stats = db
+                .export_archive(authors, &mut out)
+                .await
+                .context(ArchiveSnafu)?;
+
+            serde_json::to_value(stats).expect("Can't fail")
+        }
+        cli::OptsCmd::Import { rostra_id, input } => {
+            let db_path = Database::mk_db_path(opts.global.data_dir(), rostra_id)
+                .await
+                .context(DataDirSnafu)?;
+            let db = Database::open(&db_path, rostra_id)
+                .await
+                .context(DatabaseSnafu)?;
+
+            let mut input =
+                io::BufReader::new(std::fs::File::open(&input).context(ArchiveFileSnafu)?);
+            let stats = db.import_archive(&mut input).await.context(ArchiveSnafu)?;
+
+            serde_json::to_value(stats).expect("Can't fail")
+        }
+        cli::OptsCmd::SyncDb {
+            rostra_id,
+            path_a,
+            path_b,
+        } => {
+            let db_a = Database::open(&path_a, rostra_id)
+                .await
+                .context(DatabaseSnafu)?;
+            let db_b = Database::open(&path_b, rostra_id)
+                .await
+                .context(DatabaseSnafu)?;
+
+            let merged_into_a = db_a.merge_from(&db_b).await.context(DatabaseSnafu)?;
+            let merged_into_b = db_b.merge_from(&db_a).await.context(DatabaseSnafu)?;
+
+            serde_json::json!({
+                "a": merged_into_a,
+                "b": merged_into_b,
+            })
+        }
     })
 }
 
-pub fn init_logging() -> WhateverResult<()> {
+/// Initialize logging to stderr, or appending to `log_file` if given
+pub fn init_logging(log_file: Option<&Path>) -> WhateverResult<()> {
+    let (writer, ansi) = match log_file {
+        Some(log_file) => {
+            if let Some(dir) = log_file.parent() {
+                fs::create_dir_all(dir).whatever_context("Failed to create log dir")?;
+            }
+            let file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(log_file)
+                .whatever_context("Failed to open log file")?;
+            (BoxMakeWriter::new(Mutex::new(file)), false)
+        }
+        None => (BoxMakeWriter::new(io::stderr), true),
+    };
     tracing_subscriber::fmt()
-        .with_writer(io::stderr)
+        .with_writer(writer)
+        .with_ansi(ansi)
         .with_env_filter(
             EnvFilter::builder()
                 .with_default_directive(LevelFilter::INFO.into())
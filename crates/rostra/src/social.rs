@@ -0,0 +1,103 @@
+//! Helpers of the social commands, which act on the local database of an
+//! identity
+//!
+//! Events published by these commands are stored locally, and propagate to
+//! peers the next time `serve` or `web-ui` runs for the identity. The database
+//! can't be open in another process at the same time.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use rostra_client::Client;
+use rostra_client::timeline::Timeline;
+use rostra_client_db::Database;
+use rostra_client_db::social::SocialPostRecord;
+use rostra_core::event::{SocialPost, VerifiedEvent};
+use rostra_core::id::{ExternalEventId, RostraId, RostraIdSecretKey, ToShort as _};
+use rostra_core::{ShortEventId, Timestamp};
+use serde::Serialize;
+use snafu::ResultExt as _;
+
+use crate::cli::{SecretOpts, TimelineArg};
+use crate::{CliResult, DataDirSnafu, DatabaseSnafu, InitSnafu, SecretSnafu};
+
+/// A social post, as output by the commands
+#[derive(Serialize)]
+pub struct PostOutput {
+    pub event_id: ShortEventId,
+    pub author: RostraId,
+    pub ts: Timestamp,
+    pub reply_to: Option<ExternalEventId>,
+    pub content: Option<String>,
+    pub reaction: Option<String>,
+    pub reply_count: u64,
+}
+
+impl From<SocialPostRecord<SocialPost>> for PostOutput {
+    fn from(record: SocialPostRecord<SocialPost>) -> Self {
+        Self {
+            event_id: record.event_id,
+            author: record.author,
+            ts: record.ts,
+            reply_to: record.reply_to,
+            reaction: record.content.get_reaction().map(ToOwned::to_owned),
+            content: record.content.djot_content,
+            reply_count: record.reply_count,
+        }
+    }
+}
+
+/// Output of the commands publishing an event
+pub fn published_output(event: &VerifiedEvent) -> serde_json::Value {
+    serde_json::json!({
+        "event_id": event.event_id.to_short(),
+    })
+}
+
+pub async fn open_db(data_dir: &Path, id: RostraId) -> CliResult<Database> {
+    let db_path = Database::mk_db_path(data_dir, id)
+        .await
+        .context(DataDirSnafu)?;
+    Database::open(&db_path, id).await.context(DatabaseSnafu)
+}
+
+/// Open a client of the local database, without syncing with peers
+pub async fn open_client(
+    data_dir: &Path,
+    secret: &SecretOpts,
+) -> CliResult<(Arc<Client>, RostraIdSecretKey)> {
+    let id_secret = Client::read_id_secret(&secret.secret_file)
+        .await
+        .context(SecretSnafu)?;
+    let id = secret.rostra_id.unwrap_or_else(|| id_secret.id());
+
+    let client = Client::builder(id)
+        .db(open_db(data_dir, id).await?)
+        .start_request_handler(false)
+        .start_background_tasks(false)
+        .build()
+        .await
+        .context(InitSnafu)?;
+    Ok((client, id_secret))
+}
+
+/// List up to `limit` posts of `timeline` of `self_id`, newest first
+pub async fn list_timeline(
+    db: &Database,
+    self_id: RostraId,
+    timeline: TimelineArg,
+    limit: usize,
+) -> Vec<PostOutput> {
+    let timeline = match timeline {
+        TimelineArg::Following => Timeline::Following,
+        TimelineArg::Network => Timeline::Network,
+        TimelineArg::News => Timeline::News,
+        TimelineArg::Notifications => Timeline::Notifications,
+    };
+    timeline
+        .load(db, self_id, limit)
+        .await
+        .into_iter()
+        .map(PostOutput::from)
+        .collect()
+}